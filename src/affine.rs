@@ -0,0 +1,237 @@
+//! Exact affine relations between indices and values.
+//!
+//! After simplification, most coordinates reduce to a straight line between two data points:
+//! they can be stored as a start and a step instead of arrays. `Interp::affine` detects such
+//! coordinates and returns an `Affine`, which only keeps the two extreme data points and
+//! interpolates between them with the same schemes as `Interp`, hence with the same results.
+
+use std::cmp::Ordering;
+
+use crate::divop::Method;
+use crate::extended::F80;
+use crate::piecewise::InterpError;
+use crate::schemes::{Extended, Forward, Inverse};
+
+/// Structure for performing forward and inverse interpolation on a straight line between two
+/// data points, with the default settings of `Interp` (closed on both sides, out of bounds
+/// queries fail).
+#[derive(Clone, PartialEq, Debug)]
+pub struct Affine<X, F> {
+    x0: X,
+    x1: X,
+    f0: F,
+    f1: F,
+}
+
+impl<X, F> Affine<X, F>
+where
+    X: Forward<F>,
+    F: Inverse<X>,
+{
+    /// Creates a new Affine instance from its two extreme data points.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The first data point as an (index, value) pair.
+    /// * `end` - The last data point as an (index, value) pair.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the Affine.
+    /// Otherwise, returns `NotStrictlyIncreasing` if the indices are not strictly increasing.
+    pub fn new(start: (X, F), end: (X, F)) -> Result<Affine<X, F>, InterpError> {
+        let ((x0, f0), (x1, f1)) = (start, end);
+        if x0 >= x1 {
+            return Err(InterpError::NotStrictlyIncreasing);
+        }
+        Ok(Affine { x0, x1, f0, f1 })
+    }
+    /// Returns the first data point as an (index, value) pair.
+    pub fn start(&self) -> (X, F) {
+        (self.x0, self.f0.clone())
+    }
+    /// Returns the last data point as an (index, value) pair.
+    pub fn end(&self) -> (X, F) {
+        (self.x1, self.f1.clone())
+    }
+    /// Performs forward interpolation at the given index.
+    ///
+    /// # Arguments
+    ///
+    /// * `rhs` - The index for forward interpolation.
+    /// * `method` - The rounding method to use in case of inexact integer values.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the interpolated value.
+    /// Otherwise, returns `OutOfBounds` if the index is beyond the data points or `NotFound`
+    /// if the value is inexact with `Method::None`.
+    pub fn forward(&self, rhs: X, method: Method) -> Result<F, InterpError> {
+        if rhs == self.x0 {
+            Ok(self.f0.clone())
+        } else if rhs == self.x1 {
+            Ok(self.f1.clone())
+        } else if self.x0 < rhs && rhs < self.x1 {
+            rhs.forward(self.x0, self.x1, self.f0.clone(), self.f1.clone(), method)
+                .ok_or(InterpError::NotFound)
+        } else {
+            Err(InterpError::OutOfBounds)
+        }
+    }
+    /// Performs inverse interpolation at the given value, with the semantics of
+    /// `Interp::inverse`: the rounding methods refer to the direction of the indices and out of
+    /// bounds values are snapped to the extreme indices by the methods pointing inwards.
+    ///
+    /// # Arguments
+    ///
+    /// * `rhs` - The value for inverse interpolation.
+    /// * `method` - The rounding method to use in case of inexact matching.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the interpolated index.
+    /// Otherwise, returns `NotStrictlyMonotonic` if both values are equal or an error
+    /// indicating the reason for failure.
+    pub fn inverse(&self, rhs: F, method: Method) -> Result<X, InterpError> {
+        if rhs.partial_cmp(&rhs).is_none() {
+            return Err(InterpError::NotFound);
+        }
+        let increasing = match self.f0.partial_cmp(&self.f1) {
+            Some(Ordering::Less) => true,
+            Some(Ordering::Greater) => false,
+            _ => return Err(InterpError::NotStrictlyMonotonic),
+        };
+        let (before, after) = if increasing {
+            (rhs < self.f0, rhs > self.f1)
+        } else {
+            (rhs > self.f0, rhs < self.f1)
+        };
+        if rhs == self.f0 {
+            Ok(self.x0)
+        } else if rhs == self.f1 {
+            Ok(self.x1)
+        } else if before {
+            match method {
+                Method::None | Method::ForwardFill => Err(InterpError::OutOfBounds),
+                Method::Nearest | Method::BackwardFill => Ok(self.x0),
+            }
+        } else if after {
+            match method {
+                Method::None | Method::BackwardFill => Err(InterpError::OutOfBounds),
+                Method::Nearest | Method::ForwardFill => Ok(self.x1),
+            }
+        } else if increasing {
+            rhs.inverse(self.x0, self.x1, self.f0.clone(), self.f1.clone(), method)
+                .ok_or(InterpError::NotFound)
+        } else {
+            rhs.inverse(self.x1, self.x0, self.f1.clone(), self.f0.clone(), method)
+                .ok_or(InterpError::NotFound)
+        }
+    }
+}
+
+impl<X, F> Affine<X, F>
+where
+    X: Forward<F> + Extended,
+    F: Inverse<X> + Extended,
+{
+    /// Returns the slope of the line, in units of the values per index step, computed with
+    /// extended-precision floats. None if a value is not finite.
+    pub fn slope(&self) -> Option<F80> {
+        let rise = self.f1.to_extended()?.sub(&self.f0.to_extended()?);
+        Some(rise.div(&self.x1.to_extended()?.sub(&self.x0.to_extended()?)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::piecewise::Interp;
+
+    const METHODS: [Method; 4] = [
+        Method::None,
+        Method::Nearest,
+        Method::ForwardFill,
+        Method::BackwardFill,
+    ];
+
+    #[test]
+    fn test_agrees_with_interp() {
+        let interp = Interp::new(vec![0, 10, 25, 100], vec![5i64, 8, 12, 35]);
+        assert!(!interp.is_affine());
+        assert_eq!(interp.affine(), None);
+
+        let interp = Interp::new(vec![3, 10, 24, 101], vec![5i64, 19, 47, 201]);
+        let affine = interp.affine().unwrap();
+        assert_eq!((affine.start(), affine.end()), ((3, 5), (101, 201)));
+        assert_eq!(affine.slope(), Some(F80::try_from(2.0).unwrap()));
+        for x in 0..110 {
+            for method in METHODS {
+                assert_eq!(affine.forward(x, method), interp.forward_with(x, method));
+            }
+        }
+        for f in 0..210 {
+            for method in METHODS {
+                assert_eq!(affine.inverse(f, method), interp.inverse(f, method));
+            }
+        }
+
+        let interp = Interp::new(vec![0, 7], vec![100i64, 97]);
+        let affine = interp.affine().unwrap();
+        for x in 0..10 {
+            for method in METHODS {
+                assert_eq!(affine.forward(x, method), interp.forward_with(x, method));
+            }
+        }
+        for f in 90..110 {
+            for method in METHODS {
+                assert_eq!(affine.inverse(f, method), interp.inverse(f, method));
+            }
+        }
+    }
+
+    #[test]
+    fn test_float() {
+        let interp = Interp::new(vec![0, 4, 8], vec![1.0, 0.0, -1.0]);
+        let affine = interp.affine().unwrap();
+        assert_eq!(affine.slope(), Some(F80::try_from(-0.25).unwrap()));
+        for x in 0..10 {
+            assert_eq!(
+                affine.forward(x, Method::None),
+                interp.forward_with(x, Method::None)
+            );
+        }
+        assert_eq!(affine.inverse(0.5, Method::None), Ok(2));
+        assert_eq!(affine.inverse(0.6, Method::ForwardFill), Ok(1));
+        assert_eq!(
+            affine.inverse(f64::NAN, Method::None),
+            Err(InterpError::NotFound)
+        );
+        assert!(!Interp::new(vec![0, 4, 8], vec![1.0, 0.0, -1.5]).is_affine());
+        let affine = Affine::new((0, 0.0), (4, f64::INFINITY)).unwrap();
+        assert_eq!(affine.slope(), None);
+        assert_eq!(affine.forward(2, Method::None), Err(InterpError::NotFound));
+        assert_eq!(
+            affine.inverse(1.0, Method::None),
+            Err(InterpError::NotFound)
+        );
+    }
+
+    #[test]
+    fn test_new() {
+        assert_eq!(
+            Affine::new((5u64, 0i64), (5, 1)),
+            Err(InterpError::NotStrictlyIncreasing)
+        );
+        let constant = Affine::new((0u64, 1i64), (10, 1)).unwrap();
+        assert_eq!(constant.forward(5, Method::None), Ok(1));
+        assert_eq!(
+            constant.inverse(1, Method::None),
+            Err(InterpError::NotStrictlyMonotonic)
+        );
+        assert!(Interp::new(vec![0, 10], vec![1i64, 1]).is_affine());
+        assert!(!Interp::new(vec![0], vec![1i64]).is_affine());
+        assert!(!Interp::<u64, i64>::new(vec![], vec![]).is_affine());
+        assert!(!Interp::new(vec![10, 0], vec![1i64, 2]).is_affine());
+    }
+}
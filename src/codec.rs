@@ -0,0 +1,421 @@
+//! Compact binary serialization of data points.
+//!
+//! Millions of data points are too bulky for text formats such as JSON. The data points are
+//! stored in a small versioned little-endian format:
+//!
+//! | Offset | Size | Content                                                  |
+//! |--------|------|----------------------------------------------------------|
+//! | 0      | 4    | magic `XINT`                                             |
+//! | 4      | 1    | version (1)                                              |
+//! | 5      | 1    | dtype tag of the indices                                 |
+//! | 6      | 1    | dtype tag of the values                                  |
+//! | 7      | 1    | flags (bit 0: checksum present)                          |
+//! | 8      | 8    | number of data points                                    |
+//! | 16     |      | indices, then values, as raw little-endian arrays        |
+//! |        | 8    | optional FNV-1a 64-bit checksum of all the previous bytes |
+//!
+//! The dtype tags are 1 for u64, 2 for i64, 3 for f64 and 4 for F80 (stored as the 10 bytes of
+//! the x87 80-bit extended-precision layout).
+
+use std::error::Error;
+use std::fmt;
+
+use crate::extended::F80;
+use crate::piecewise::Interp;
+use crate::schemes::{Forward, Inverse};
+
+/// The first bytes of every buffer.
+pub const MAGIC: &[u8; 4] = b"XINT";
+/// The version of the format.
+pub const VERSION: u8 = 1;
+/// The size of the header preceding the arrays.
+const HEADER: usize = 16;
+/// The flag marking the presence of a checksum.
+const CHECKSUM: u8 = 1;
+
+/// Decoding Errors
+#[derive(PartialEq, Debug)]
+pub enum DecodeError {
+    /// The buffer is shorter than announced by its header.
+    Truncated,
+    /// The buffer is longer than announced by its header.
+    TrailingBytes,
+    /// The buffer does not start with the magic bytes.
+    BadMagic,
+    /// The version of the format is not supported.
+    UnsupportedVersion(u8),
+    /// The dtype tags do not match the expected ones (expected, found), indices first.
+    DtypeMismatch((u8, u8), (u8, u8)),
+    /// The checksum does not match the content.
+    ChecksumMismatch,
+    /// A value cannot be decoded (e.g. an infinite extended-precision value).
+    InvalidValue,
+    /// The indices are not strictly increasing.
+    NotStrictlyIncreasing,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::Truncated => write!(f, "truncated buffer"),
+            DecodeError::TrailingBytes => write!(f, "trailing bytes after the data points"),
+            DecodeError::BadMagic => write!(f, "not a buffer of data points"),
+            DecodeError::UnsupportedVersion(version) => {
+                write!(f, "unsupported format version {version}")
+            }
+            DecodeError::DtypeMismatch(expected, found) => write!(
+                f,
+                "dtype tags {found:?} do not match the expected {expected:?}"
+            ),
+            DecodeError::ChecksumMismatch => write!(f, "checksum mismatch"),
+            DecodeError::InvalidValue => write!(f, "invalid value"),
+            DecodeError::NotStrictlyIncreasing => write!(f, "xp must be strictly increasing"),
+        }
+    }
+}
+impl Error for DecodeError {}
+
+/// Implements the little-endian encoding of a dtype.
+pub trait Codec: Sized {
+    /// The tag identifying the dtype in the header.
+    const TAG: u8;
+    /// The number of bytes of each value.
+    const SIZE: usize;
+    /// Appends the bytes of the value.
+    fn encode(&self, out: &mut Vec<u8>);
+    /// Decodes a value from exactly `SIZE` bytes, None if invalid.
+    fn decode(bytes: &[u8]) -> Option<Self>;
+}
+impl Codec for u64 {
+    const TAG: u8 = 1;
+    const SIZE: usize = 8;
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.to_le_bytes());
+    }
+    fn decode(bytes: &[u8]) -> Option<u64> {
+        Some(u64::from_le_bytes(bytes.try_into().ok()?))
+    }
+}
+impl Codec for i64 {
+    const TAG: u8 = 2;
+    const SIZE: usize = 8;
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.to_le_bytes());
+    }
+    fn decode(bytes: &[u8]) -> Option<i64> {
+        Some(i64::from_le_bytes(bytes.try_into().ok()?))
+    }
+}
+impl Codec for f64 {
+    const TAG: u8 = 3;
+    const SIZE: usize = 8;
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.to_le_bytes());
+    }
+    fn decode(bytes: &[u8]) -> Option<f64> {
+        Some(f64::from_le_bytes(bytes.try_into().ok()?))
+    }
+}
+impl Codec for F80 {
+    const TAG: u8 = 4;
+    const SIZE: usize = 10;
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.to_bits().to_le_bytes()[..10]);
+    }
+    fn decode(bytes: &[u8]) -> Option<F80> {
+        let mut buffer = [0; 16];
+        buffer[..10].copy_from_slice(bytes);
+        F80::from_bits(u128::from_le_bytes(buffer))
+    }
+}
+
+impl<X, F> Interp<X, F>
+where
+    X: Forward<F> + Codec + Sync,
+    F: Inverse<X> + Codec,
+{
+    /// Serializes the data points with a checksum.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes_with(true)
+    }
+    /// Serializes the data points, optionally appending a checksum. Other settings (e.g. the
+    /// inclusivity of the extreme breakpoints) are not serialized.
+    ///
+    /// # Arguments
+    ///
+    /// * `checksum` - Whether to append a checksum verified when deserializing.
+    pub fn to_bytes_with(&self, checksum: bool) -> Vec<u8> {
+        let len = self.xp().len();
+        let mut out = Vec::with_capacity(HEADER + len * (X::SIZE + F::SIZE) + 8);
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(&[VERSION, X::TAG, F::TAG, if checksum { CHECKSUM } else { 0 }]);
+        out.extend_from_slice(&(len as u64).to_le_bytes());
+        for x in self.xp() {
+            x.encode(&mut out);
+        }
+        for f in self.fp() {
+            f.encode(&mut out);
+        }
+        if checksum {
+            let hash = fnv1a(&out);
+            out.extend_from_slice(&hash.to_le_bytes());
+        }
+        out
+    }
+    /// Computes a fingerprint of the data points, e.g. to key caches of derived products. It is
+    /// the FNV-1a hash of the serialization without checksum, which covers the dtype tags, the
+    /// number of data points and their raw little-endian bytes: it is stable across processes
+    /// and platforms. Other settings are not hashed.
+    pub fn content_hash(&self) -> u64 {
+        fnv1a(&self.to_bytes_with(false))
+    }
+    /// Deserializes data points serialized by `to_bytes`.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The serialized data points.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the Interp.
+    /// Otherwise, returns an error indicating the reason for failure: the buffer must have the
+    /// expected dtypes and length, a matching checksum if any and strictly increasing indices.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Interp<X, F>, DecodeError> {
+        let (len, arrays) = Self::split(bytes)?;
+        let (xp, fp) = arrays.split_at(len * X::SIZE);
+        let xp = xp
+            .chunks_exact(X::SIZE)
+            .map(X::decode)
+            .collect::<Option<Vec<X>>>()
+            .ok_or(DecodeError::InvalidValue)?;
+        let fp = fp
+            .chunks_exact(F::SIZE)
+            .map(F::decode)
+            .collect::<Option<Vec<F>>>()
+            .ok_or(DecodeError::InvalidValue)?;
+        if !xp.windows(2).all(|pair| pair[0] < pair[1]) {
+            return Err(DecodeError::NotStrictlyIncreasing);
+        }
+        Ok(Interp::new(xp, fp))
+    }
+    /// Computes the fingerprint of serialized data points without deserializing them. It is the
+    /// `content_hash` of the deserialized data points, further hashed with `unit` (e.g. the
+    /// datetime64 unit of the values) unless empty. Only the header, the length and the checksum
+    /// are validated, not the values.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The serialized data points.
+    /// * `unit` - The unit of the values, empty if none.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the fingerprint.
+    /// Otherwise, returns the error of `from_bytes` for a malformed buffer.
+    pub fn fingerprint(bytes: &[u8], unit: &str) -> Result<u64, DecodeError> {
+        let (_, arrays) = Self::split(bytes)?;
+        let mut header: [u8; HEADER] = bytes[..HEADER].try_into().unwrap();
+        header[7] &= !CHECKSUM;
+        let hash = fnv1a_with(fnv1a(&header), arrays);
+        Ok(fnv1a_with(hash, unit.as_bytes()))
+    }
+    /// Validates the header, the length and the checksum of serialized data points, returning
+    /// their number and the bytes of their arrays.
+    fn split(bytes: &[u8]) -> Result<(usize, &[u8]), DecodeError> {
+        let (header, body) = bytes
+            .split_at_checked(HEADER)
+            .ok_or(DecodeError::Truncated)?;
+        if &header[..4] != MAGIC {
+            return Err(DecodeError::BadMagic);
+        }
+        if header[4] != VERSION {
+            return Err(DecodeError::UnsupportedVersion(header[4]));
+        }
+        if (header[5], header[6]) != (X::TAG, F::TAG) {
+            return Err(DecodeError::DtypeMismatch(
+                (X::TAG, F::TAG),
+                (header[5], header[6]),
+            ));
+        }
+        let checksum = header[7] & CHECKSUM != 0;
+        let len = u64::from_le_bytes(header[8..16].try_into().unwrap());
+        let size = usize::try_from(len)
+            .ok()
+            .and_then(|len| len.checked_mul(X::SIZE + F::SIZE))
+            .and_then(|size| size.checked_add(if checksum { 8 } else { 0 }))
+            .ok_or(DecodeError::Truncated)?;
+        if body.len() < size {
+            return Err(DecodeError::Truncated);
+        }
+        if body.len() > size {
+            return Err(DecodeError::TrailingBytes);
+        }
+        let mut arrays = body;
+        if checksum {
+            let (content, hash) = bytes.split_at(bytes.len() - 8);
+            if fnv1a(content).to_le_bytes() != hash {
+                return Err(DecodeError::ChecksumMismatch);
+            }
+            arrays = &body[..body.len() - 8];
+        }
+        Ok((len as usize, arrays))
+    }
+}
+
+/// Reads the dtype tags of serialized data points, indices first, e.g. to choose the types to
+/// deserialize them with.
+pub fn peek_tags(bytes: &[u8]) -> Result<(u8, u8), DecodeError> {
+    let header = bytes.get(..HEADER).ok_or(DecodeError::Truncated)?;
+    if &header[..4] != MAGIC {
+        return Err(DecodeError::BadMagic);
+    }
+    Ok((header[5], header[6]))
+}
+
+/// Computes the 64-bit FNV-1a hash of the bytes.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    fnv1a_with(0xcbf29ce484222325, bytes)
+}
+
+/// Continues a 64-bit FNV-1a hash with more bytes.
+fn fnv1a_with(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::piecewise::Closed;
+
+    #[test]
+    fn test_round_trip() {
+        let interp = Interp::new(vec![0, 10, 20], vec![-5i64, 1_700_000_000_000_000_000, 7]);
+        let bytes = interp.to_bytes();
+        assert_eq!(bytes.len(), 16 + 3 * 16 + 8);
+        assert_eq!(Interp::from_bytes(&bytes), Ok(interp.clone()));
+        let bytes = interp.to_bytes_with(false);
+        assert_eq!(bytes.len(), 16 + 3 * 16);
+        assert_eq!(Interp::from_bytes(&bytes), Ok(interp));
+
+        let interp = Interp::new(vec![3u64, 4], vec![0.5, f64::NAN]);
+        let decoded: Interp<u64, f64> = Interp::from_bytes(&interp.to_bytes()).unwrap();
+        assert_eq!(decoded.fp()[0], 0.5);
+        assert!(decoded.fp()[1].is_nan());
+
+        let interp = Interp::new(vec![0, u64::MAX], vec![1u64, 2]);
+        assert_eq!(Interp::from_bytes(&interp.to_bytes()), Ok(interp));
+
+        let fp = vec![
+            F80::try_from(1.0)
+                .unwrap()
+                .div(&F80::try_from(3.0).unwrap()),
+            F80::try_from(-2.5).unwrap(),
+        ];
+        let interp = Interp::new(vec![0u64, 1], fp);
+        let bytes = interp.to_bytes();
+        assert_eq!(bytes.len(), 16 + 2 * 18 + 8);
+        assert_eq!(Interp::from_bytes(&bytes), Ok(interp));
+
+        let interp: Interp<u64, i64> = Interp::new(vec![], vec![]);
+        assert_eq!(Interp::from_bytes(&interp.to_bytes()), Ok(interp));
+    }
+
+    #[test]
+    fn test_corrupted() {
+        let interp = Interp::new(vec![0, 10, 20], vec![1i64, 2, 3]);
+        let bytes = interp.to_bytes();
+        for len in [0, 8, 16, 40, bytes.len() - 1] {
+            assert_eq!(
+                Interp::<u64, i64>::from_bytes(&bytes[..len]),
+                Err(DecodeError::Truncated)
+            );
+        }
+        let mut longer = bytes.clone();
+        longer.push(0);
+        assert_eq!(
+            Interp::<u64, i64>::from_bytes(&longer),
+            Err(DecodeError::TrailingBytes)
+        );
+        let mut flipped = bytes.clone();
+        flipped[30] ^= 1;
+        assert_eq!(
+            Interp::<u64, i64>::from_bytes(&flipped),
+            Err(DecodeError::ChecksumMismatch)
+        );
+        let mut magic = bytes.clone();
+        magic[0] = b'Y';
+        assert_eq!(
+            Interp::<u64, i64>::from_bytes(&magic),
+            Err(DecodeError::BadMagic)
+        );
+        let mut version = bytes.clone();
+        version[4] = 2;
+        assert_eq!(
+            Interp::<u64, i64>::from_bytes(&version),
+            Err(DecodeError::UnsupportedVersion(2))
+        );
+        assert_eq!(
+            Interp::<u64, f64>::from_bytes(&bytes),
+            Err(DecodeError::DtypeMismatch((1, 3), (1, 2)))
+        );
+        let mut huge = bytes.clone();
+        huge[8..16].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert_eq!(
+            Interp::<u64, i64>::from_bytes(&huge),
+            Err(DecodeError::Truncated)
+        );
+        let unsorted = Interp::new(vec![10, 0], vec![1i64, 2]).to_bytes();
+        assert_eq!(
+            Interp::<u64, i64>::from_bytes(&unsorted),
+            Err(DecodeError::NotStrictlyIncreasing)
+        );
+        assert_eq!(peek_tags(&bytes), Ok((1, 2)));
+        assert_eq!(DecodeError::Truncated.to_string(), "truncated buffer");
+    }
+
+    #[test]
+    fn test_content_hash() {
+        let interp = Interp::new(vec![0, 10, 20], vec![0.5, 1.5, 2.5]);
+        assert_eq!(interp.content_hash(), interp.clone().content_hash());
+        assert_eq!(interp.content_hash(), 10550901362686441908);
+        let next = f64::from_bits(1.5f64.to_bits() + 1);
+        let other = Interp::new(vec![0, 10, 20], vec![0.5, next, 2.5]);
+        assert_ne!(interp.content_hash(), other.content_hash());
+        let integers = Interp::new(vec![0, 10, 20], vec![0i64, 1, 2]);
+        let unsigned = Interp::new(vec![0, 10, 20], vec![0u64, 1, 2]);
+        assert_ne!(integers.content_hash(), unsigned.content_hash());
+        let shorter = Interp::new(vec![0, 10], vec![0.5, 1.5]);
+        assert_ne!(interp.content_hash(), shorter.content_hash());
+        let open = interp.clone().with_closed(Closed::Left);
+        assert_eq!(interp.content_hash(), open.content_hash());
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let interp = Interp::new(vec![0, 10, 20], vec![-5i64, 0, 7]);
+        for checksum in [true, false] {
+            let bytes = interp.to_bytes_with(checksum);
+            assert_eq!(
+                Interp::<u64, i64>::fingerprint(&bytes, ""),
+                Ok(interp.content_hash())
+            );
+        }
+        let bytes = interp.to_bytes();
+        let ns = Interp::<u64, i64>::fingerprint(&bytes, "datetime64[ns]").unwrap();
+        let us = Interp::<u64, i64>::fingerprint(&bytes, "datetime64[us]").unwrap();
+        assert_ne!(ns, interp.content_hash());
+        assert_ne!(ns, us);
+        let mut flipped = bytes.clone();
+        flipped[30] ^= 1;
+        assert_eq!(
+            Interp::<u64, i64>::fingerprint(&flipped, ""),
+            Err(DecodeError::ChecksumMismatch)
+        );
+        assert_eq!(
+            Interp::<u64, f64>::fingerprint(&bytes, ""),
+            Err(DecodeError::DtypeMismatch((1, 3), (1, 2)))
+        );
+    }
+}
@@ -0,0 +1,250 @@
+//! Parsing of ISO 8601 datetimes into nanoseconds since the Unix epoch.
+//!
+//! Datetimes are parsed with integer arithmetic only so that nanoseconds are never lost, which
+//! would happen going through a floating number of seconds. The accepted format is
+//! `YYYY-MM-DD[(T| )hh:mm[:ss[.fffffffff]]][offset]` where the fraction has one to nine digits
+//! and the offset is either `Z` or `±hh[[:]mm]`. Offsets are honored: the datetime is converted
+//! to UTC. Datetimes without offset are considered to be in UTC, as numpy does.
+
+/// Parsing Errors
+#[derive(PartialEq, Debug)]
+pub enum ParseError {
+    /// The text is not a valid ISO 8601 datetime.
+    Invalid,
+    /// The datetime cannot be represented by an i64 number of nanoseconds (before 1677-09-21
+    /// or after 2262-04-11).
+    OutOfRange,
+}
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::Invalid => write!(f, "invalid ISO 8601 datetime"),
+            ParseError::OutOfRange => write!(f, "datetime out of the nanosecond range"),
+        }
+    }
+}
+impl std::error::Error for ParseError {}
+
+/// Parses an ISO 8601 datetime.
+///
+/// # Arguments
+///
+/// * `text` - The datetime to parse.
+///
+/// # Returns
+///
+/// If successful, returns the number of nanoseconds since 1970-01-01T00:00:00Z.
+/// Otherwise, returns an error indicating the reason for failure.
+pub fn parse_datetime(text: &str) -> Result<i64, ParseError> {
+    let mut cursor = Cursor(text.as_bytes());
+    let year = cursor.number(4)?;
+    cursor.expect(b'-')?;
+    let month = cursor.number(2)?;
+    cursor.expect(b'-')?;
+    let day = cursor.number(2)?;
+    if !(1..=12).contains(&month) || !(1..=days_in_month(year, month)).contains(&day) {
+        return Err(ParseError::Invalid);
+    }
+    let (mut hour, mut minute, mut second, mut fraction) = (0, 0, 0, 0);
+    if cursor.accept(b'T') || cursor.accept(b' ') {
+        hour = cursor.number(2)?;
+        cursor.expect(b':')?;
+        minute = cursor.number(2)?;
+        if cursor.accept(b':') {
+            second = cursor.number(2)?;
+            if cursor.accept(b'.') {
+                let digits = cursor.digits();
+                if digits.is_empty() || digits.len() > 9 {
+                    return Err(ParseError::Invalid);
+                }
+                fraction = digits
+                    .iter()
+                    .chain(std::iter::repeat(&b'0'))
+                    .take(9)
+                    .fold(0, |acc, digit| 10 * acc + (digit - b'0') as i64);
+            }
+        }
+        if hour > 23 || minute > 59 || second > 59 {
+            return Err(ParseError::Invalid);
+        }
+    }
+    let offset = cursor.offset()?;
+    if !cursor.0.is_empty() {
+        return Err(ParseError::Invalid);
+    }
+    let seconds =
+        days_from_civil(year, month, day) * 86_400 + hour * 3_600 + minute * 60 + second - offset;
+    let nanoseconds = seconds as i128 * 1_000_000_000 + fraction as i128;
+    i64::try_from(nanoseconds).map_err(|_| ParseError::OutOfRange)
+}
+
+/// The remaining bytes to parse.
+struct Cursor<'a>(&'a [u8]);
+
+impl Cursor<'_> {
+    /// Consumes the given byte if it comes next.
+    fn accept(&mut self, byte: u8) -> bool {
+        match self.0.split_first() {
+            Some((first, rest)) if *first == byte => {
+                self.0 = rest;
+                true
+            }
+            _ => false,
+        }
+    }
+    /// Consumes the given byte, which must come next.
+    fn expect(&mut self, byte: u8) -> Result<(), ParseError> {
+        if self.accept(byte) {
+            Ok(())
+        } else {
+            Err(ParseError::Invalid)
+        }
+    }
+    /// Consumes the leading digits.
+    fn digits(&mut self) -> &[u8] {
+        let len = self
+            .0
+            .iter()
+            .take_while(|byte| byte.is_ascii_digit())
+            .count();
+        let (digits, rest) = self.0.split_at(len);
+        self.0 = rest;
+        digits
+    }
+    /// Consumes a number with exactly the given count of digits.
+    fn number(&mut self, count: usize) -> Result<i64, ParseError> {
+        let digits = self.0.get(..count).ok_or(ParseError::Invalid)?;
+        if !digits.iter().all(|byte| byte.is_ascii_digit()) {
+            return Err(ParseError::Invalid);
+        }
+        self.0 = &self.0[count..];
+        Ok(digits
+            .iter()
+            .fold(0, |acc, digit| 10 * acc + (digit - b'0') as i64))
+    }
+    /// Consumes the optional UTC offset and returns it in seconds.
+    fn offset(&mut self) -> Result<i64, ParseError> {
+        if self.accept(b'Z') {
+            return Ok(0);
+        }
+        let sign = if self.accept(b'+') {
+            1
+        } else if self.accept(b'-') {
+            -1
+        } else {
+            return Ok(0);
+        };
+        let hours = self.number(2)?;
+        let colon = self.accept(b':');
+        let minutes = if colon || !self.0.is_empty() {
+            self.number(2)?
+        } else {
+            0
+        };
+        if hours > 23 || minutes > 59 {
+            return Err(ParseError::Invalid);
+        }
+        Ok(sign * (hours * 3_600 + minutes * 60))
+    }
+}
+
+/// Whether the year is a leap year of the proleptic Gregorian calendar.
+fn is_leap(year: i64) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+/// The number of days of the month.
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if is_leap(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// The number of days since 1970-01-01 of a date of the proleptic Gregorian calendar.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_epoch() {
+        assert_eq!(parse_datetime("1970-01-01"), Ok(0));
+        assert_eq!(parse_datetime("1970-01-01T00:00:00Z"), Ok(0));
+        assert_eq!(parse_datetime("1970-01-02 00:00"), Ok(86_400_000_000_000));
+        assert_eq!(parse_datetime("2000-03-01"), Ok(951_868_800_000_000_000));
+    }
+
+    #[test]
+    fn test_subsecond() {
+        assert_eq!(
+            parse_datetime("2024-03-01T12:00:00.000000123"),
+            Ok(1_709_294_400_000_000_123)
+        );
+        assert_eq!(
+            parse_datetime("2024-03-01T12:00:00.5"),
+            Ok(1_709_294_400_500_000_000)
+        );
+        assert_eq!(
+            parse_datetime("2024-03-01T12:00:00.0000000001"),
+            Err(ParseError::Invalid)
+        );
+    }
+
+    #[test]
+    fn test_before_epoch() {
+        assert_eq!(parse_datetime("1969-12-31T23:59:59.5"), Ok(-500_000_000));
+        assert_eq!(parse_datetime("1900-01-01"), Ok(-2_208_988_800_000_000_000));
+        assert_eq!(
+            parse_datetime("1677-09-21T00:12:43.145224192"),
+            Ok(i64::MIN)
+        );
+        assert_eq!(
+            parse_datetime("1677-09-21T00:12:43.145224191"),
+            Err(ParseError::OutOfRange)
+        );
+        assert_eq!(
+            parse_datetime("2262-04-11T23:47:16.854775807"),
+            Ok(i64::MAX)
+        );
+    }
+
+    #[test]
+    fn test_offset() {
+        let utc = parse_datetime("2024-03-01T12:00:00").unwrap();
+        assert_eq!(parse_datetime("2024-03-01T13:30:00+01:30"), Ok(utc));
+        assert_eq!(parse_datetime("2024-03-01T13:30:00+0130"), Ok(utc));
+        assert_eq!(parse_datetime("2024-03-01T10:00:00-02"), Ok(utc));
+    }
+
+    #[test]
+    fn test_invalid() {
+        for text in [
+            "",
+            "2024",
+            "2024-3-01",
+            "2024-02-30",
+            "2023-02-29",
+            "2024-13-01",
+            "2024-03-01T24:00",
+            "2024-03-01T12",
+            "2024-03-01T12:00:00.",
+            "2024-03-01T12:00:00+1",
+            "2024-03-01T12:00:00 junk",
+        ] {
+            assert_eq!(parse_datetime(text), Err(ParseError::Invalid), "{text}");
+        }
+        assert!(parse_datetime("2024-02-29").is_ok());
+        assert_eq!(ParseError::Invalid.to_string(), "invalid ISO 8601 datetime");
+    }
+}
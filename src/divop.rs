@@ -1,7 +1,12 @@
 //! Integer division with different rounding rules
+//!
+//! The rules apply to the exact quotient in value space: `Nearest` rounds ties to the even
+//! integer whatever the signs of the operands, e.g. -1/2 gives 0 and -3/2 gives -2.
+
+use std::cmp::Ordering;
 
 /// Rounding methods for integer division.
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub enum Method {
     None,
     Nearest,
@@ -20,41 +25,48 @@ pub trait DivOp: Sized {
     ///
     /// # Returns
     ///
-    /// It returns None if `None` rounding is chosen and the division is inexact. Otherwise, it
-    /// returns the exact or rounded quotient.  
+    /// It returns None if the divisor is zero or if `None` rounding is chosen and the division
+    /// is inexact. Otherwise, it returns the exact or rounded quotient.
     fn div(self, rhs: Self, method: Method) -> Option<Self>;
 }
 
 impl DivOp for u128 {
     fn div(self, rhs: u128, method: Method) -> Option<u128> {
-        let div = self.div_euclid(rhs);
-        let rem = self.rem_euclid(rhs);
-        match method {
-            Method::None => {
-                if rem == 0 {
-                    Some(div)
-                } else {
-                    None
-                }
+        let div = self.checked_div_euclid(rhs)?;
+        let rem = self.checked_rem_euclid(rhs)?;
+        round_quotient(div, rem, rhs, method)
+    }
+}
+
+/// Rounds the quotient of a division given its integer part `div` and remainder `rem` by `rhs`
+/// (`rem < rhs`). Used where the quotient and remainder are maintained incrementally rather than
+/// computed by a division.
+pub fn round_quotient(div: u128, rem: u128, rhs: u128, method: Method) -> Option<u128> {
+    match method {
+        Method::None => {
+            if rem == 0 {
+                Some(div)
+            } else {
+                None
             }
-            Method::Nearest => {
-                if rem * 2 < rhs {
-                    Some(div)
-                } else if rem * 2 > rhs {
-                    Some(div + 1)
-                } else if div % 2 == 0 {
-                    Some(div)
-                } else {
-                    Some(div + 1)
-                }
+        }
+        Method::Nearest => {
+            if rem * 2 < rhs {
+                Some(div)
+            } else if rem * 2 > rhs {
+                Some(div + 1)
+            } else if div.is_multiple_of(2) {
+                Some(div)
+            } else {
+                Some(div + 1)
             }
-            Method::ForwardFill => Some(div),
-            Method::BackwardFill => {
-                if rem == 0 {
-                    Some(div)
-                } else {
-                    Some(div + 1)
-                }
+        }
+        Method::ForwardFill => Some(div),
+        Method::BackwardFill => {
+            if rem == 0 {
+                Some(div)
+            } else {
+                Some(div + 1)
             }
         }
     }
@@ -62,8 +74,8 @@ impl DivOp for u128 {
 
 impl DivOp for i128 {
     fn div(self, rhs: i128, method: Method) -> Option<i128> {
-        let div = self.div_euclid(rhs);
-        let rem = self.rem_euclid(rhs);
+        let div = self.checked_div_euclid(rhs)?;
+        let rem = self.checked_rem_euclid(rhs)?;
         match method {
             Method::None => {
                 if rem == 0 {
@@ -73,10 +85,14 @@ impl DivOp for i128 {
                 }
             }
             Method::Nearest => {
-                let sgn = self.signum() * rhs.signum();
-                self.unsigned_abs()
-                    .div(rhs.unsigned_abs(), Method::Nearest)
-                    .map(|div| sgn * div as i128)
+                // the exact quotient lies between div and its neighbor towards the sign of rhs,
+                // at a distance rem / |rhs| from div: ties go to the even one of the two
+                let (rem, rest) = (rem as u128, rhs.unsigned_abs() - rem as u128);
+                match rem.cmp(&rest) {
+                    Ordering::Less => Some(div),
+                    Ordering::Equal if div % 2 == 0 => Some(div),
+                    _ => div.checked_add(rhs.signum()),
+                }
             }
             Method::ForwardFill => Some(div),
             Method::BackwardFill => {
@@ -109,6 +125,55 @@ mod tests {
         assert_eq!((-2i128).div(3, Method::None), None);
     }
 
+    /// Rounds the quotient to the nearest integer, ties to even, by comparing the distances of
+    /// both candidates to the exact quotient.
+    fn nearest_reference(num: i128, den: i128) -> i128 {
+        let floor = (num as f64 / den as f64).floor() as i128;
+        let distance = |q: i128| (num - q * den).abs();
+        match distance(floor).cmp(&distance(floor + 1)) {
+            Ordering::Less => floor,
+            Ordering::Greater => floor + 1,
+            Ordering::Equal if floor % 2 == 0 => floor,
+            Ordering::Equal => floor + 1,
+        }
+    }
+
+    #[test]
+    fn test_div_round_signed() {
+        for den in (-8i128..=8).filter(|den| *den != 0) {
+            for num in -40i128..=40 {
+                let expected = nearest_reference(num, den);
+                assert_eq!(num.div(den, Method::Nearest), Some(expected), "{num}/{den}");
+                if num >= 0 && den > 0 {
+                    let unsigned = (num as u128).div(den as u128, Method::Nearest);
+                    assert_eq!(unsigned, Some(expected as u128));
+                }
+            }
+        }
+        assert_eq!((-1i128).div(2, Method::Nearest), Some(0));
+        assert_eq!(1i128.div(-2, Method::Nearest), Some(0));
+        assert_eq!((-3i128).div(2, Method::Nearest), Some(-2));
+        assert_eq!((-5i128).div(2, Method::Nearest), Some(-2));
+        assert_eq!(5i128.div(-2, Method::Nearest), Some(-2));
+        assert_eq!((-7i128).div(-2, Method::Nearest), Some(4));
+        assert_eq!(i128::MIN.div(i128::MAX, Method::Nearest), Some(-1));
+        assert_eq!(i128::MIN.div(2, Method::Nearest), Some(i128::MIN / 2));
+        assert_eq!(i128::MAX.div(1, Method::Nearest), Some(i128::MAX));
+    }
+
+    #[test]
+    fn test_div_zero() {
+        for method in [
+            Method::None,
+            Method::Nearest,
+            Method::ForwardFill,
+            Method::BackwardFill,
+        ] {
+            assert_eq!(1u128.div(0, method), None);
+            assert_eq!((-1i128).div(0, method), None);
+        }
+    }
+
     #[test]
     fn test_div_round() {
         assert_eq!(0u128.div(2, Method::Nearest), Some(0));
@@ -1,75 +1,136 @@
 //! Extended precision floating-point format that can accurately represent 64 bits integers.
 
+use crate::divop::Method;
 use astro_float::{BigFloat, RoundingMode, Sign};
 use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
+use std::sync::LazyLock;
+
+static ZERO: LazyLock<F80> = LazyLock::new(|| F80::from(0u64));
+static ONE: LazyLock<F80> = LazyLock::new(|| F80::from(1u64));
+static TWO: LazyLock<F80> = LazyLock::new(|| F80::from(2u64));
+static MAX_SAFE_U64: LazyLock<F80> = LazyLock::new(|| F80::from(u64::MAX));
+
+/// Errors of the exact conversions of F80 into integers and of f64 into F80.
+#[derive(PartialEq, Debug)]
+pub enum ConversionError {
+    /// The value is negative while the target type is unsigned.
+    Negative,
+    /// The magnitude of the value exceeds the range of the target type.
+    TooLarge,
+    /// The value has a fractional part.
+    NotInteger,
+    /// The value is NaN or infinite.
+    NotFinite,
+}
+impl std::fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConversionError::Negative => write!(f, "negative value"),
+            ConversionError::TooLarge => write!(f, "value out of range"),
+            ConversionError::NotInteger => write!(f, "value is not an integer"),
+            ConversionError::NotFinite => write!(f, "value is not finite"),
+        }
+    }
+}
+impl std::error::Error for ConversionError {}
 
 /// f80 floating-point format with 64 bits mantissa. It wraps astro-float BigFloat struct with
 /// imposed one word (64 bits) mantissa. It implements total ordering by only allowing finite
 /// values (no nan or inf). It expose some basic methods of BigFloat. Use the From/Into traits
-/// to initialize some instance of this struct from u64 or f64.  
-#[derive(Clone, PartialEq, Debug)]
+/// to initialize some instance of this struct from u64 or i64 and TryFrom from f64. Negative
+/// zeros are normalized to positive zero on construction so that equal values share a unique
+/// representation, which keeps equality, ordering and hashing consistent.
+#[derive(Clone, Debug)]
 pub struct F80 {
     value: BigFloat,
 }
 impl From<u64> for F80 {
     /// Converts a u64 into an F80.
     fn from(value: u64) -> F80 {
-        F80 {
-            value: BigFloat::from_u64(value, 64),
-        }
+        F80::new(BigFloat::from_u64(value, 64))
     }
 }
-impl From<f64> for F80 {
-    /// Converts an f64 into an F80. Panics if the input is NaN or infinity.
-    fn from(value: f64) -> F80 {
-        assert!(value.is_finite());
-        F80 {
-            value: BigFloat::from_f64(value, 64),
+impl From<i64> for F80 {
+    /// Converts an i64 into an F80.
+    fn from(value: i64) -> F80 {
+        F80::new(BigFloat::from_i64(value, 64))
+    }
+}
+impl TryFrom<f64> for F80 {
+    type Error = ConversionError;
+    /// Converts an f64 into an F80, failing for NaNs and infinities which F80 cannot represent.
+    fn try_from(value: f64) -> Result<F80, ConversionError> {
+        if !value.is_finite() {
+            return Err(ConversionError::NotFinite);
         }
+        if value.is_subnormal() {
+            // BigFloat halves subnormals, which are rebuilt as multiples of 2^-1074
+            let sign = if value < 0.0 { Sign::Neg } else { Sign::Pos };
+            let mantissa = value.to_bits() & ((1 << 52) - 1);
+            let zeros = mantissa.leading_zeros();
+            return Ok(F80::new(BigFloat::from_words(
+                &[mantissa << zeros],
+                sign,
+                64 - zeros as i32 - 1074,
+            )));
+        }
+        Ok(F80::new(BigFloat::from_f64(value, 64)))
     }
 }
 impl From<F80> for f64 {
-    /// Converts an F80 into an f64.
+    /// Converts an F80 into an f64, rounding to the nearest f64 with ties to even. Magnitudes
+    /// beyond the range of f64 round to infinity and the smallest ones to subnormals or zero.
     fn from(float: F80) -> f64 {
-        if float.value.is_zero() {
-            return 0.0;
-        }
-        let sign = float.value.sign().unwrap();
-        let exponent = float.value.exponent().unwrap();
-        let mantissa = float.value.mantissa_digits().unwrap()[0];
+        let (mantissa, exponent) = (float.mantissa(), float.exponent());
+        let sign = match float.sign() {
+            Sign::Pos => 0,
+            Sign::Neg => 1u64 << 63,
+        };
         if mantissa == 0 {
             return 0.0;
         }
-        let mut exponent: isize = exponent as isize + 0b1111111111;
-        let mut ret = 0;
-        if exponent >= 0b11111111111 {
-            match sign {
-                Sign::Pos => f64::INFINITY,
-                Sign::Neg => f64::NEG_INFINITY,
-            }
-        } else if exponent <= 0 {
-            let shift = -exponent;
-            if shift < 52 {
-                ret |= mantissa >> (shift + 12);
-                if sign == Sign::Neg {
-                    ret |= 0x8000000000000000u64;
-                }
-                f64::from_bits(ret)
+        if exponent > 1023 {
+            return f64::from_bits(sign | f64::INFINITY.to_bits());
+        }
+        // number of significant bits kept, fewer than 53 for subnormals
+        let precision = if exponent >= -1022 {
+            53
+        } else {
+            exponent + 1075
+        };
+        if precision < 0 {
+            return f64::from_bits(sign);
+        }
+        let shift = 64 - precision as u32;
+        let (kept, rest) = if shift == 64 {
+            (0, mantissa)
+        } else {
+            (mantissa >> shift, mantissa & ((1 << shift) - 1))
+        };
+        let half = 1 << (shift - 1);
+        let kept = if rest > half || (rest == half && kept % 2 == 1) {
+            kept + 1
+        } else {
+            kept
+        };
+        let bits = if exponent >= -1022 {
+            // the carry of the rounding may bump the exponent
+            let (kept, exponent) = if kept == 1 << 53 {
+                (kept >> 1, exponent + 1)
             } else {
-                0.0
+                (kept, exponent)
+            };
+            if exponent > 1023 {
+                return f64::from_bits(sign | f64::INFINITY.to_bits());
             }
+            (((exponent + 1023) as u64) << 52) | (kept & ((1 << 52) - 1))
         } else {
-            let mantissa = mantissa << 1;
-            exponent -= 1;
-            if sign == Sign::Neg {
-                ret |= 1;
-            }
-            ret <<= 11;
-            ret |= exponent as u64;
-            ret <<= 52;
-            ret |= mantissa >> 12;
-            f64::from_bits(ret)
-        }
+            // subnormals are multiples of 2^-1074, a carry reaching 2^52 gives the smallest
+            // normal
+            kept
+        };
+        f64::from_bits(sign | bits)
     }
 }
 impl From<F80> for u64 {
@@ -78,10 +139,9 @@ impl From<F80> for u64 {
         if float.value.is_zero() {
             return 0;
         }
-        let sign = float.value.sign().unwrap();
-        let exponent = float.value.exponent().unwrap();
-        let mantissa = float.value.mantissa_digits().unwrap()[0];
-        match sign {
+        let exponent = float.exponent() + 1;
+        let mantissa = float.mantissa();
+        match float.sign() {
             Sign::Pos => {
                 if exponent > 0 {
                     if exponent <= 64 {
@@ -99,11 +159,43 @@ impl From<F80> for u64 {
         }
     }
 }
+impl From<F80> for i64 {
+    /// Converts an F80 into an i64, truncating toward zero and saturating at the i64 bounds.
+    fn from(float: F80) -> i64 {
+        if float >= *F80::zero() {
+            let value: u64 = float.into();
+            value.min(i64::MAX as u64) as i64
+        } else {
+            let value: u64 = F80::zero().sub(&float).into();
+            if value >= i64::MIN.unsigned_abs() {
+                i64::MIN
+            } else {
+                -(value as i64)
+            }
+        }
+    }
+}
+impl PartialEq for F80 {
+    /// Whether two F80 are equal.
+    fn eq(&self, other: &F80) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
 impl Eq for F80 {}
+impl Hash for F80 {
+    /// Hashes the sign, exponent and mantissa of an F80, which are unique to its value.
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (self.sign() == Sign::Neg).hash(state);
+        self.exponent().hash(state);
+        self.mantissa().hash(state);
+    }
+}
 impl Ord for F80 {
     /// Compares two F80.
     fn cmp(&self, other: &F80) -> Ordering {
-        self.value.partial_cmp(&other.value).unwrap()
+        self.value
+            .partial_cmp(&other.value)
+            .expect("F80 values are finite")
     }
 }
 impl PartialOrd for F80 {
@@ -113,67 +205,406 @@ impl PartialOrd for F80 {
     }
 }
 impl F80 {
+    /// Zero. BigFloat values cannot be built in constant contexts, the constants of F80 are hence
+    /// lazily initialized statics returned by reference.
+    pub fn zero() -> &'static F80 {
+        &ZERO
+    }
+    /// One.
+    pub fn one() -> &'static F80 {
+        &ONE
+    }
+    /// Two.
+    pub fn two() -> &'static F80 {
+        &TWO
+    }
+    /// The largest u64 (2^64 - 1), exactly represented.
+    pub fn max_safe_u64() -> &'static F80 {
+        &MAX_SAFE_U64
+    }
+    /// Wraps a BigFloat, normalizing negative zero to positive zero.
+    fn new(value: BigFloat) -> F80 {
+        if value.is_zero() {
+            F80 {
+                value: BigFloat::from_u64(0, 64),
+            }
+        } else {
+            F80 { value }
+        }
+    }
     /// Adds two F80s.
     pub fn add(&self, rhs: &F80) -> F80 {
-        F80 {
-            value: self.value.add(&rhs.value, 64, RoundingMode::ToEven),
-        }
+        F80::new(self.value.add(&rhs.value, 64, RoundingMode::ToEven))
     }
     /// Subtracts two F80s.
     pub fn sub(&self, rhs: &F80) -> F80 {
-        F80 {
-            value: self.value.sub(&rhs.value, 64, RoundingMode::ToEven),
-        }
+        F80::new(self.value.sub(&rhs.value, 64, RoundingMode::ToEven))
     }
     /// Multiplies two F80s.
     pub fn mul(&self, rhs: &F80) -> F80 {
-        F80 {
-            value: self.value.mul(&rhs.value, 64, RoundingMode::ToEven),
+        F80::new(self.value.mul(&rhs.value, 64, RoundingMode::ToEven))
+    }
+    /// Computes `self * a + b` with a single rounding, the product being kept at full
+    /// precision.
+    pub fn mul_add(&self, a: &F80, b: &F80) -> F80 {
+        if self.value.is_zero() || a.value.is_zero() {
+            return b.clone();
         }
+        let product = self.value.mul_full_prec(&a.value);
+        F80::new(product.add(&b.value, 64, RoundingMode::ToEven))
     }
-    /// Divides two F80s.
+    /// Divides two F80s. Dividing by zero yields a value that is not finite, which breaks the
+    /// ordering of F80: use `checked_div` when the divisor may be zero.
     pub fn div(&self, rhs: &F80) -> F80 {
-        F80 {
-            value: self.value.div(&rhs.value, 64, RoundingMode::ToEven),
+        F80::new(self.value.div(&rhs.value, 64, RoundingMode::ToEven))
+    }
+    /// Divides two F80s, None if the divisor is zero.
+    pub fn checked_div(&self, rhs: &F80) -> Option<F80> {
+        (!rhs.value.is_zero()).then(|| self.div(rhs))
+    }
+    /// Adds two F80s, also returning whether the sum is exact (no rounding occurred). The
+    /// exactness is checked against a full precision computation (which mishandles zeros, hence
+    /// operations with zero are directly reported as exact).
+    pub fn add_checked(&self, rhs: &F80) -> (F80, bool) {
+        let out = self.add(rhs);
+        let exact = self.value.is_zero()
+            || rhs.value.is_zero()
+            || out.value == self.value.add_full_prec(&rhs.value);
+        (out, exact)
+    }
+    /// Multiplies two F80s, also returning whether the product is exact (no rounding occurred).
+    pub fn mul_checked(&self, rhs: &F80) -> (F80, bool) {
+        let out = self.mul(rhs);
+        let exact = self.value.is_zero()
+            || rhs.value.is_zero()
+            || out.value == self.value.mul_full_prec(&rhs.value);
+        (out, exact)
+    }
+    /// Divides two F80s, also returning whether the quotient is exact (no rounding occurred).
+    pub fn div_checked(&self, rhs: &F80) -> (F80, bool) {
+        let out = self.div(rhs);
+        let exact = self.value.is_zero() || out.value.mul_full_prec(&rhs.value) == self.value;
+        (out, exact)
+    }
+    /// Whether this value is exactly the weighted mean `(a * wa + b * wb) / (wa + wb)` of two
+    /// values, the products and sums being computed without rounding.
+    pub fn is_weighted_mean(&self, a: &F80, wa: u64, b: &F80, wb: u64) -> bool {
+        let (wa, wb) = (BigFloat::from_u64(wa, 64), BigFloat::from_u64(wb, 64));
+        let lhs = [self.value.mul_full_prec(&wa), self.value.mul_full_prec(&wb)];
+        let rhs = [a.value.mul_full_prec(&wa), b.value.mul_full_prec(&wb)];
+        match (sum_full_prec(lhs), sum_full_prec(rhs)) {
+            (None, None) => true,
+            (Some(lhs), Some(rhs)) => lhs.partial_cmp(&rhs) == Some(Ordering::Equal),
+            _ => false,
         }
     }
     /// Computes the remainder of division of two F80s.
     pub fn rem(&self, rhs: &F80) -> F80 {
-        F80 {
-            value: self.value.rem(&rhs.value),
+        F80::new(self.value.rem(&rhs.value))
+    }
+    /// Computes the quotient of the Euclidean division of two F80s, i.e. the largest integer `q`
+    /// such that `q * rhs <= self`. Quotients beyond 2^64 are rounded to the nearest F80.
+    ///
+    /// # Returns
+    ///
+    /// The quotient, None if `rhs` is not positive.
+    pub fn div_euclid(&self, rhs: &F80) -> Option<F80> {
+        let rem = self.rem_euclid(rhs)?;
+        // the exact quotient is an integer, recovered with extra precision before rounding
+        let num = self.value.sub(&rem.value, 192, RoundingMode::ToEven);
+        let half = BigFloat::from_f64(0.5, 64);
+        let quotient = num
+            .div(&rhs.value, 192, RoundingMode::ToEven)
+            .add(&half, 192, RoundingMode::ToEven)
+            .floor()
+            .add(&F80::zero().value, 64, RoundingMode::ToEven);
+        Some(F80::new(quotient))
+    }
+    /// Computes the remainder of the Euclidean division of two F80s, which always lies in
+    /// `[0, rhs)`. The remainder of a negative value is rounded down if it cannot be exactly
+    /// represented, so that it never reaches `rhs`.
+    ///
+    /// # Returns
+    ///
+    /// The remainder, None if `rhs` is not positive.
+    pub fn rem_euclid(&self, rhs: &F80) -> Option<F80> {
+        if *rhs <= *F80::zero() {
+            return None;
+        }
+        let rem = self.rem(rhs);
+        if rem < *F80::zero() {
+            Some(F80::new(rem.value.add(&rhs.value, 64, RoundingMode::Down)))
+        } else {
+            Some(F80::clone(F80::zero()).max(rem))
         }
     }
-    /// Rounds a F80  to its nearest integer using the round ties to even rule.
-    pub fn round(&self) -> F80 {
-        let floor = self.floor();
-        let ceil = self.ceil();
-        let mid = floor.add(&ceil).div(&F80::from(2));
-        match self.cmp(&mid) {
-            Ordering::Less => floor,
-            Ordering::Equal => match floor.rem(&F80::from(2)).eq(&F80::from(0)) {
-                true => floor,
-                false => ceil,
+    /// Rounds to a multiple of `step` offset by `origin`, i.e. to `origin + k * step` for some
+    /// integer k chosen according to `method` (ties to even k for `Nearest`, previous multiple
+    /// for `ForwardFill` and next one for `BackwardFill`). The number of steps is computed with
+    /// an Euclidean division and the multiple is computed with extra precision so that the result
+    /// is only rounded once.
+    ///
+    /// # Returns
+    ///
+    /// The rounded value, None if `step` is not positive or if `Method::None` is chosen and the
+    /// value is not a multiple.
+    pub fn round_to_multiple(&self, step: &F80, origin: &F80, method: Method) -> Option<F80> {
+        let offset = self.sub(origin);
+        let div = offset.div_euclid(step)?;
+        let rem = offset.rem_euclid(step)?;
+        let next = |div: F80| div.add(F80::one());
+        let count = match method {
+            Method::None if rem == *F80::zero() => div,
+            Method::None => return None,
+            Method::Nearest => match rem.mul(F80::two()).cmp(step) {
+                Ordering::Less => div,
+                Ordering::Greater => next(div),
+                Ordering::Equal if div.rem(F80::two()) == *F80::zero() => div,
+                Ordering::Equal => next(div),
             },
-            Ordering::Greater => ceil,
+            Method::ForwardFill => div,
+            Method::BackwardFill if rem == *F80::zero() => div,
+            Method::BackwardFill => next(div),
+        };
+        let multiple = count.value.mul(&step.value, 192, RoundingMode::ToEven);
+        Some(F80::new(origin.value.add(
+            &multiple,
+            64,
+            RoundingMode::ToEven,
+        )))
+    }
+    /// Rounds a F80  to its nearest integer using the round ties to even rule. The magnitude is
+    /// rounded directly on the mantissa (see `round_magnitude`) and the sign is kept.
+    pub fn round(&self) -> F80 {
+        if self.exponent() >= 63 {
+            return self.clone();
+        }
+        let integer = self
+            .round_magnitude(Method::Nearest)
+            .expect("magnitude below 2^63");
+        if integer == 0 {
+            return F80::clone(F80::zero());
+        }
+        let zeros = integer.leading_zeros();
+        F80::new(BigFloat::from_words(
+            &[integer << zeros],
+            self.sign(),
+            64 - zeros as i32,
+        ))
+    }
+    /// Rounds the magnitude of an F80 below 2^63 to an integer according to the given method:
+    /// the bits below the binary point are compared with one half, ties being rounded to the
+    /// even integer for `Nearest`, while `ForwardFill` and `BackwardFill` round down and up the
+    /// signed value. Larger magnitudes are integers and are returned as is.
+    ///
+    /// # Returns
+    ///
+    /// The rounded magnitude, None if `Method::None` is chosen and the value is not an integer or
+    /// if the magnitude is 2^64 or more.
+    fn round_magnitude(&self, method: Method) -> Option<u64> {
+        let (mantissa, exponent) = (self.mantissa(), self.exponent());
+        if mantissa == 0 || exponent == 63 {
+            return Some(mantissa);
+        } else if exponent > 63 {
+            return None;
+        }
+        let (integer, fraction, half) = if exponent < 0 {
+            // bits shifted out can only make a nonzero fraction below one half, kept nonzero
+            let shift = (-exponent - 1) as u32;
+            let fraction = if shift < 64 { mantissa >> shift } else { 0 };
+            (0, fraction.max(1), 1 << 63)
+        } else {
+            let shift = 63 - exponent as u32;
+            let fraction = (mantissa & ((1 << shift) - 1)) << (64 - shift);
+            (mantissa >> shift, fraction, 1 << 63)
+        };
+        let negative = self.sign() == Sign::Neg;
+        let up = match method {
+            Method::None if fraction != 0 => return None,
+            Method::None => false,
+            Method::Nearest => fraction > half || (fraction == half && integer % 2 == 1),
+            Method::ForwardFill => negative && fraction != 0,
+            Method::BackwardFill => !negative && fraction != 0,
+        };
+        Some(integer + u64::from(up))
+    }
+    /// Rounds to an integer according to the given method (ties to even for `Nearest`) and
+    /// converts it into a u64, rounding only once.
+    ///
+    /// # Returns
+    ///
+    /// The rounded integer, None if it does not fit in a u64 (negative or 2^64 or more) or if
+    /// `Method::None` is chosen and the value is not an integer.
+    pub fn to_u64(&self, method: Method) -> Option<u64> {
+        let magnitude = self.round_magnitude(method)?;
+        match self.sign() {
+            Sign::Neg if magnitude != 0 => None,
+            _ => Some(magnitude),
         }
     }
+    /// Converts an F80 into a u64 if it is exactly representable. The `From` conversion, which
+    /// saturates and truncates silently, prevents implementing `TryFrom`.
+    pub fn try_to_u64(&self) -> Result<u64, ConversionError> {
+        if *self < *F80::zero() {
+            Err(ConversionError::Negative)
+        } else if *self > *F80::max_safe_u64() {
+            Err(ConversionError::TooLarge)
+        } else if self.floor() != *self {
+            Err(ConversionError::NotInteger)
+        } else {
+            Ok(self.clone().into())
+        }
+    }
+    /// Converts an F80 into an i64 if it is exactly representable.
+    pub fn try_to_i64(&self) -> Result<i64, ConversionError> {
+        if *self < F80::from(i64::MIN) || *self > F80::from(i64::MAX) {
+            Err(ConversionError::TooLarge)
+        } else if self.floor() != *self {
+            Err(ConversionError::NotInteger)
+        } else {
+            Ok(self.clone().into())
+        }
+    }
+    /// Rounds to an integer according to the given method (ties to even for `Nearest`) and
+    /// converts it into a u64, saturating negative values to 0 and large values to u64::MAX.
+    ///
+    /// # Returns
+    ///
+    /// The rounded integer, None if `Method::None` is chosen and the value is not an integer.
+    pub fn to_u64_lossy(&self, method: Method) -> Option<u64> {
+        let rounded = match method {
+            Method::None if self.floor() == *self => self.clone(),
+            Method::None => return None,
+            Method::Nearest => self.round(),
+            Method::ForwardFill => self.floor(),
+            Method::BackwardFill => self.ceil(),
+        };
+        Some(rounded.into())
+    }
+    /// Converts an f64 into an F80, None for infinities and NaNs.
+    pub fn from_finite(value: f64) -> Option<F80> {
+        F80::try_from(value).ok()
+    }
+    /// Converts the x87 80-bit extended-precision layout (the long double of x86 platforms) into
+    /// an F80. The bits are the 64-bit mantissa with its explicit integer bit followed by the
+    /// 15-bit biased exponent and the sign bit, the upper 48 bits being ignored. The conversion
+    /// is lossless. Returns None for infinities and NaNs.
+    pub fn from_bits(bits: u128) -> Option<F80> {
+        let mantissa = bits as u64;
+        let exponent = ((bits >> 64) & 0x7FFF) as i32;
+        let sign = if (bits >> 79) & 1 == 1 {
+            Sign::Neg
+        } else {
+            Sign::Pos
+        };
+        if exponent == 0x7FFF {
+            return None;
+        }
+        if mantissa == 0 {
+            return Some(F80::clone(F80::zero()));
+        }
+        Some(F80::new(BigFloat::from_words(
+            &[mantissa],
+            sign,
+            exponent.max(1) - 16382,
+        )))
+    }
+    /// Converts an F80 into the x87 80-bit extended-precision layout (see `from_bits`). The
+    /// conversion is lossless for values in the range of the format, larger magnitudes
+    /// saturate to infinity and smaller ones are flushed to zero.
+    pub fn to_bits(&self) -> u128 {
+        let Some((words, _, sign, exponent, _)) = self.value.as_raw_parts() else {
+            return 0;
+        };
+        let mantissa = words.last().copied().unwrap_or(0);
+        let sign = match sign {
+            Sign::Pos => 0u128,
+            Sign::Neg => 1u128 << 79,
+        };
+        if mantissa == 0 {
+            return sign;
+        }
+        let biased = exponent as i64 + 16382;
+        if biased >= 0x7FFF {
+            sign | (0x7FFFu128 << 64) | (1u128 << 63)
+        } else if biased >= 1 {
+            sign | ((biased as u128) << 64) | mantissa as u128
+        } else if biased > -64 {
+            // The shift reaches 64 bits at the exponent below the smallest subnormal.
+            sign | (mantissa as u128) >> (1 - biased)
+        } else {
+            sign
+        }
+    }
+    /// Returns the binary exponent, such that the value is `1.f * 2^exponent`. Zero has a zero
+    /// exponent.
+    pub fn exponent(&self) -> i32 {
+        match self.value.as_raw_parts() {
+            Some((_, _, _, exponent, _)) if self.mantissa() != 0 => exponent - 1,
+            _ => 0,
+        }
+    }
+    /// Returns the 64-bit significand with its explicit integer bit (as in the x87 layout), such
+    /// that the value is `mantissa * 2^(exponent - 63)`. Zero has a zero mantissa.
+    pub fn mantissa(&self) -> u64 {
+        match self.value.as_raw_parts() {
+            Some((words, _, _, _, _)) => words.last().copied().unwrap_or(0),
+            None => 0,
+        }
+    }
+    /// Returns the sign. Zero is always positive.
+    pub fn sign(&self) -> Sign {
+        self.value.sign().unwrap_or(Sign::Pos)
+    }
+    /// Returns the magnitude of one unit in the last place at this value, i.e.
+    /// `2^(exponent - 63)`. The unit in the last place at zero is the smallest positive subnormal
+    /// of the x87 layout (2^-16445).
+    pub fn ulp(&self) -> F80 {
+        let exponent = if self.mantissa() == 0 {
+            -16445
+        } else {
+            self.exponent() - 63
+        };
+        F80::new(BigFloat::from_words(&[1 << 63], Sign::Pos, exponent + 1))
+    }
+    /// Whether two F80s are within the given number of units in the last place of each other,
+    /// the larger unit of the two values being used.
+    pub fn approx_eq(&self, other: &F80, ulps: u64) -> bool {
+        let distance = self
+            .clone()
+            .max(other.clone())
+            .sub(&self.clone().min(other.clone()));
+        let ulp = self.ulp().max(other.ulp());
+        distance <= ulp.mul(&F80::from(ulps))
+    }
     /// Floors a F80.
     pub fn floor(&self) -> F80 {
-        F80 {
-            value: self.value.floor(),
-        }
+        F80::new(self.value.floor())
     }
     /// Ceils a F80.
     pub fn ceil(&self) -> F80 {
-        F80 {
-            value: self.value.ceil(),
-        }
+        F80::new(self.value.ceil())
+    }
+    /// Returns the absolute value of a F80.
+    pub fn abs(&self) -> F80 {
+        F80::new(self.value.abs())
     }
 }
 
+/// Sums values without rounding, None if the sum is zero. Zeros are skipped as the full
+/// precision addition mishandles them.
+fn sum_full_prec(terms: [BigFloat; 2]) -> Option<BigFloat> {
+    let mut terms = terms.into_iter().filter(|term| !term.is_zero());
+    let first = terms.next()?;
+    let sum = terms.fold(first, |sum, term| sum.add_full_prec(&term));
+    (!sum.is_zero()).then_some(sum)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::piecewise::Interp;
+    use crate::rng::Rng;
 
     #[test]
     fn test_u64_conversion() {
@@ -184,20 +615,704 @@ mod tests {
         }
         let cases: [(f64, u64); 4] = [(-1.0, 0), (0.5, 0), (1.5, 1), (1e32, u64::MAX)];
         for (input, expected) in cases.iter() {
-            let result: u64 = F80::from(*input).into();
+            let result: u64 = F80::try_from(*input).unwrap().into();
+            assert_eq!(result, *expected);
+        }
+    }
+
+    #[test]
+    fn test_i64_conversion() {
+        let cases: [i64; 7] = [i64::MIN, i64::MIN + 1, -1, 0, 1, i64::MAX - 1, i64::MAX];
+        for expected in cases.iter() {
+            let result: i64 = F80::from(*expected).into();
+            assert_eq!(result, *expected);
+        }
+        let cases: [(f64, i64); 5] = [
+            (-1.5, -1),
+            (-0.5, 0),
+            (0.5, 0),
+            (-1e32, i64::MIN),
+            (1e32, i64::MAX),
+        ];
+        for (input, expected) in cases.iter() {
+            let result: i64 = F80::try_from(*input).unwrap().into();
             assert_eq!(result, *expected);
         }
     }
 
+    #[test]
+    fn test_try_conversion() {
+        let max: &F80 = F80::max_safe_u64();
+        assert_eq!(max.try_to_u64(), Ok(u64::MAX));
+        // u64::MAX + 0.5 is not representable and rounds to 2^64
+        let above = max.add(&F80::try_from(0.5).unwrap());
+        assert_eq!(above.try_to_u64(), Err(ConversionError::TooLarge));
+        assert_eq!(
+            F80::try_from(-0.5).unwrap().try_to_u64(),
+            Err(ConversionError::Negative)
+        );
+        assert_eq!(
+            F80::try_from(0.5).unwrap().try_to_u64(),
+            Err(ConversionError::NotInteger)
+        );
+        assert_eq!(F80::try_from(-0.0).unwrap().try_to_u64(), Ok(0));
+        assert_eq!(ConversionError::TooLarge.to_string(), "value out of range");
+        assert_eq!(F80::from(i64::MIN).try_to_i64(), Ok(i64::MIN));
+        assert_eq!(F80::from(i64::MAX).try_to_i64(), Ok(i64::MAX));
+        assert_eq!(
+            F80::from(i64::MAX).add(F80::one()).try_to_i64(),
+            Err(ConversionError::TooLarge)
+        );
+        assert_eq!(
+            F80::from(i64::MIN).sub(F80::one()).try_to_i64(),
+            Err(ConversionError::TooLarge)
+        );
+        assert_eq!(
+            F80::try_from(-0.5).unwrap().try_to_i64(),
+            Err(ConversionError::NotInteger)
+        );
+    }
+
+    #[test]
+    fn test_to_u64_lossy() {
+        let cases: [(f64, Method, Option<u64>); 8] = [
+            (2.5, Method::None, None),
+            (2.0, Method::None, Some(2)),
+            (2.5, Method::Nearest, Some(2)),
+            (3.5, Method::Nearest, Some(4)),
+            (2.5, Method::ForwardFill, Some(2)),
+            (2.5, Method::BackwardFill, Some(3)),
+            (-0.5, Method::Nearest, Some(0)),
+            (1e32, Method::Nearest, Some(u64::MAX)),
+        ];
+        for (value, method, expected) in cases {
+            assert_eq!(F80::try_from(value).unwrap().to_u64_lossy(method), expected);
+        }
+        let above = F80::clone(F80::max_safe_u64()).add(&F80::try_from(0.5).unwrap());
+        assert_eq!(above.to_u64_lossy(Method::ForwardFill), Some(u64::MAX));
+    }
+
+    #[test]
+    fn test_to_u64() {
+        let methods = [
+            Method::None,
+            Method::Nearest,
+            Method::ForwardFill,
+            Method::BackwardFill,
+        ];
+        let cases: [(f64, [Option<u64>; 4]); 10] = [
+            (0.0, [Some(0), Some(0), Some(0), Some(0)]),
+            (0.25, [None, Some(0), Some(0), Some(1)]),
+            (0.5, [None, Some(0), Some(0), Some(1)]),
+            (1.5, [None, Some(2), Some(1), Some(2)]),
+            (2.5, [None, Some(2), Some(2), Some(3)]),
+            (2.5000000000000004, [None, Some(3), Some(2), Some(3)]),
+            (3.0, [Some(3), Some(3), Some(3), Some(3)]),
+            (-0.25, [None, Some(0), None, Some(0)]),
+            (-0.5, [None, Some(0), None, Some(0)]),
+            (-1.5, [None, None, None, None]),
+        ];
+        for (value, expected) in cases {
+            for (method, expected) in methods.into_iter().zip(expected) {
+                assert_eq!(
+                    F80::try_from(value).unwrap().to_u64(method),
+                    expected,
+                    "{value} {method:?}"
+                );
+            }
+        }
+
+        // Ties and fractions near the top of the range, where the ulp is one half.
+        let top = F80::from((1u64 << 63) - 2);
+        let tie = top.add(&F80::try_from(0.5).unwrap());
+        assert_eq!(tie.to_u64(Method::Nearest), Some((1 << 63) - 2));
+        assert_eq!(tie.add(F80::one()).to_u64(Method::Nearest), Some(1 << 63));
+        assert_eq!(tie.to_u64(Method::BackwardFill), Some((1 << 63) - 1));
+        assert_eq!(tie.to_u64(Method::None), None);
+        let max: &F80 = F80::max_safe_u64();
+        for method in methods {
+            assert_eq!(max.to_u64(method), Some(u64::MAX));
+            assert_eq!(max.add(F80::one()).to_u64(method), None);
+            assert_eq!(F80::try_from(1e32).unwrap().to_u64(method), None);
+            assert_eq!(F80::try_from(-1e32).unwrap().to_u64(method), None);
+        }
+        // u64::MAX - 0.5 is not representable and rounds to an even integer first.
+        let below = max.sub(&F80::try_from(0.5).unwrap());
+        assert_eq!(below.to_u64(Method::None), Some(u64::MAX - 1));
+        let above = max.add(&F80::try_from(0.5).unwrap());
+        assert_eq!(above.to_u64(Method::ForwardFill), None);
+
+        // A single rounding agrees with rounding first and converting next in the range.
+        let mut rng = Rng(0x2545_F491_4F6C_DD1D);
+        for _ in 0..100_000 {
+            let state = rng.next();
+            let value = f64::from_bits(state >> 1);
+            if !value.is_finite() {
+                continue;
+            }
+            let in_range = value < 2.0f64.powi(64);
+            let value = F80::try_from(value).unwrap();
+            for method in methods {
+                let expected = value.to_u64_lossy(method).filter(|_| in_range);
+                assert_eq!(value.to_u64(method), expected);
+            }
+        }
+    }
+
     #[test]
     fn test_f64_conversion() {
         let cases: [f64; 11] = [
             0.0, 0.5, -0.5, 1.0, -1.0, 1.5, -1.5, 1e307, -1e307, 1e-307, -1e-307,
         ];
         for expected in cases.iter() {
-            let result: f64 = F80::from(*expected).into();
+            let result: f64 = F80::try_from(*expected).unwrap().into();
             assert_eq!(result, *expected);
+            assert_eq!(
+                F80::from_finite(*expected),
+                Some(F80::try_from(*expected).unwrap())
+            );
         }
+        for value in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            assert_eq!(F80::from_finite(value), None);
+            assert_eq!(F80::try_from(value), Err(ConversionError::NotFinite));
+        }
+    }
+
+    #[test]
+    fn test_f64_rounding() {
+        let pow2 =
+            |exponent: i32| F80::new(BigFloat::from_words(&[1 << 63], Sign::Pos, exponent + 1));
+        let convert = |value: F80| f64::from(value);
+        let one = F80::clone(F80::one());
+        // Ties on the first discarded bit round to even, anything beyond rounds up.
+        assert_eq!(convert(one.add(&pow2(-53))), 1.0);
+        assert_eq!(
+            convert(one.add(&pow2(-53)).add(&pow2(-63))),
+            1.0 + f64::EPSILON
+        );
+        assert_eq!(convert(one.add(&pow2(-54))), 1.0);
+        let odd = one.add(&pow2(-52));
+        assert_eq!(convert(odd.add(&pow2(-53))), 1.0 + 2.0 * f64::EPSILON);
+        assert_eq!(convert(odd.add(&pow2(-54))), 1.0 + f64::EPSILON);
+        assert_eq!(
+            convert(F80::zero().sub(&odd.add(&pow2(-53)))),
+            -1.0 - 2.0 * f64::EPSILON
+        );
+        // The carry bumps the exponent, up to infinity.
+        let below_two = F80::try_from(2.0 - f64::EPSILON).unwrap();
+        assert_eq!(convert(below_two.add(&pow2(-53))), 2.0);
+        let max = F80::try_from(f64::MAX).unwrap();
+        assert_eq!(convert(max.add(&pow2(970))), f64::INFINITY);
+        assert_eq!(convert(max.add(&pow2(969))), f64::MAX);
+        assert_eq!(
+            convert(F80::zero().sub(&max.add(&pow2(970)))),
+            f64::NEG_INFINITY
+        );
+        assert_eq!(convert(pow2(1024)), f64::INFINITY);
+        // Subnormals keep fewer bits and the smallest magnitudes flush to zero.
+        let tiny = f64::from_bits(1);
+        assert_eq!(F80::try_from(tiny).unwrap(), pow2(-1074));
+        assert_eq!(
+            F80::try_from(-3.0 * tiny).unwrap(),
+            F80::zero().sub(&pow2(-1073).add(&pow2(-1074)))
+        );
+        assert_eq!(convert(pow2(-1074)), tiny);
+        assert_eq!(convert(pow2(-1075)), 0.0);
+        assert_eq!(convert(pow2(-1075).add(&pow2(-1090))), tiny);
+        assert_eq!(convert(pow2(-1074).add(&pow2(-1075))), 2.0 * tiny);
+        assert_eq!(convert(pow2(-1073).add(&pow2(-1075))), 2.0 * tiny);
+        assert_eq!(convert(pow2(-1080)), 0.0);
+        let below_normal = F80::try_from(f64::MIN_POSITIVE - tiny).unwrap();
+        assert_eq!(convert(below_normal.add(&pow2(-1075))), f64::MIN_POSITIVE);
+        // Products needing more than 53 bits are correctly rounded.
+        let third = F80::from(3u64).div(&F80::from(10u64));
+        assert_eq!(convert(third), 0.3);
+    }
+
+    #[test]
+    fn test_f64_subnormals() {
+        let pow2 =
+            |exponent: i32| F80::new(BigFloat::from_words(&[1 << 63], Sign::Pos, exponent + 1));
+        let tiny = f64::from_bits(1);
+        let largest = f64::from_bits((1 << 52) - 1);
+        let cases = [
+            (F80::try_from(f64::MIN_POSITIVE).unwrap(), f64::MIN_POSITIVE),
+            (F80::try_from(largest).unwrap(), largest),
+            (F80::try_from(largest).unwrap().add(&pow2(-1076)), largest),
+            (
+                F80::try_from(largest).unwrap().add(&pow2(-1075)),
+                f64::MIN_POSITIVE,
+            ),
+            (F80::try_from(tiny).unwrap(), tiny),
+            // straddling the flush threshold at half the smallest subnormal
+            (pow2(-1075).sub(&pow2(-1140)), 0.0),
+            (pow2(-1075), 0.0),
+            (pow2(-1075).add(&pow2(-1138)), tiny),
+            (pow2(-1074).add(&pow2(-1075)), 2.0 * tiny),
+            (pow2(-1100), 0.0),
+            // near the subnormal threshold, where the leading bit matters
+            (pow2(-1023), f64::MIN_POSITIVE / 2.0),
+            (pow2(-1023).add(&pow2(-1076)), f64::MIN_POSITIVE / 2.0),
+            (
+                pow2(-1023).add(&pow2(-1073)),
+                f64::MIN_POSITIVE / 2.0 + 2.0 * tiny,
+            ),
+        ];
+        for (value, expected) in cases {
+            assert_eq!(f64::from(value.clone()), expected, "{value:?}");
+            let negative = f64::from(F80::zero().sub(&value));
+            assert_eq!(negative.to_bits(), (-expected).to_bits(), "{value:?}");
+        }
+    }
+
+    #[test]
+    fn test_f64_round_trip() {
+        let mut rng = Rng(0x853C_49E6_748F_EA9B);
+        for _ in 0..200_000 {
+            let state = rng.next();
+            let value = f64::from_bits(state);
+            if value.is_finite() {
+                assert_eq!(
+                    f64::from(F80::try_from(value).unwrap()).to_bits(),
+                    value.to_bits()
+                );
+            }
+            let subnormal = f64::from_bits(state >> 12);
+            assert_eq!(f64::from(F80::try_from(subnormal).unwrap()), subnormal);
+        }
+    }
+
+    #[test]
+    fn test_bits_conversion() {
+        let one = 0x3FFF_8000_0000_0000_0000u128;
+        assert_eq!(F80::from_bits(one), Some(F80::from(1u64)));
+        assert_eq!(F80::from(1u64).to_bits(), one);
+        let minus_three = 0xC000_C000_0000_0000_0000u128;
+        assert_eq!(F80::from_bits(minus_three), Some(F80::from(-3i64)));
+        assert_eq!(F80::from(-3i64).to_bits(), minus_three);
+        assert_eq!(F80::from_bits(0), Some(F80::from(0u64)));
+        assert_eq!(F80::from(0u64).to_bits(), 0);
+        assert_eq!(F80::from_bits(0x7FFF_8000_0000_0000_0000), None);
+        assert_eq!(F80::from_bits(0xFFFF_C000_0000_0000_0000), None);
+        let subnormal = 0x0000_0000_0000_0000_0001u128;
+        assert_eq!(F80::from_bits(subnormal).unwrap().to_bits(), subnormal);
+        // Half the smallest subnormal and below are flushed to zero, keeping the sign.
+        let half = F80::from_bits(subnormal).unwrap().div(F80::two());
+        assert_eq!(half.to_bits(), 0);
+        assert_eq!(F80::zero().sub(&half).to_bits(), 1u128 << 79);
+        assert_eq!(half.div(F80::two()).to_bits(), 0);
+        let cases: [u64; 5] = [1, 3, u64::MAX / 3, u64::MAX - 1, u64::MAX];
+        for value in cases {
+            let bits = F80::from(value).to_bits();
+            assert_eq!(F80::from_bits(bits), Some(F80::from(value)));
+        }
+        let cases: [f64; 6] = [0.5, -1.5, 1e-300, -1e300, 0.1, f64::MIN_POSITIVE];
+        for value in cases {
+            let bits = F80::try_from(value).unwrap().to_bits();
+            assert_eq!(F80::from_bits(bits), Some(F80::try_from(value).unwrap()));
+        }
+    }
+
+    #[test]
+    fn test_constants() {
+        assert_eq!(*F80::zero(), F80::from(0u64));
+        assert_eq!(*F80::one(), F80::from(1u64));
+        assert_eq!(*F80::two(), F80::from(2u64));
+        assert_eq!(*F80::max_safe_u64(), F80::from(u64::MAX));
+        assert_eq!(F80::one().add(F80::one()), *F80::two());
+        let max: u64 = F80::clone(F80::max_safe_u64()).into();
+        assert_eq!(max, u64::MAX);
+    }
+
+    #[test]
+    fn test_euclid() {
+        let cases: [(f64, f64, f64, f64); 8] = [
+            (7.0, 3.0, 2.0, 1.0),
+            (-7.0, 3.0, -3.0, 2.0),
+            (6.0, 3.0, 2.0, 0.0),
+            (-6.0, 3.0, -2.0, 0.0),
+            (0.0, 3.0, 0.0, 0.0),
+            (-0.75, 0.5, -2.0, 0.25),
+            (0.75, 0.5, 1.0, 0.25),
+            (-1e-20, 1.0, -1.0, 1.0 - 1e-20),
+        ];
+        for (value, rhs, quotient, remainder) in cases {
+            let (value, rhs) = (F80::try_from(value).unwrap(), F80::try_from(rhs).unwrap());
+            assert_eq!(
+                value.div_euclid(&rhs),
+                Some(F80::try_from(quotient).unwrap())
+            );
+            let rem = value.rem_euclid(&rhs).unwrap();
+            assert!(rem >= *F80::zero() && rem < rhs);
+            if remainder < 1.0 - 1e-10 {
+                assert_eq!(rem, F80::try_from(remainder).unwrap());
+            }
+        }
+        for rhs in [0.0, -3.0] {
+            assert_eq!(
+                F80::try_from(7.0)
+                    .unwrap()
+                    .div_euclid(&F80::try_from(rhs).unwrap()),
+                None
+            );
+            assert_eq!(
+                F80::try_from(-7.0)
+                    .unwrap()
+                    .rem_euclid(&F80::try_from(rhs).unwrap()),
+                None
+            );
+        }
+    }
+
+    #[test]
+    fn test_euclid_large_quotient() {
+        let value = F80::from(u64::MAX).mul(&F80::from(16u64));
+        let expected = F80::from(u64::MAX).mul(&F80::from(32u64));
+        assert_eq!(
+            value.div_euclid(&F80::try_from(0.5).unwrap()),
+            Some(expected)
+        );
+        assert_eq!(
+            value.rem_euclid(&F80::try_from(0.5).unwrap()),
+            Some(F80::clone(F80::zero()))
+        );
+        let value = F80::zero().sub(&value);
+        let seven = F80::from(7u64);
+        let expected = F80::zero().sub(&F80::from(10540996613548315209u64).mul(&F80::from(4u64)));
+        assert_eq!(value.div_euclid(&seven), Some(expected));
+        assert_eq!(value.rem_euclid(&seven), Some(F80::from(5u64)));
+    }
+
+    #[test]
+    fn test_signed_zero() {
+        let hash = |value: &F80| {
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            value.hash(&mut hasher);
+            hasher.finish()
+        };
+        let zero = F80::clone(F80::zero());
+        let zeros = [
+            F80::try_from(0.0).unwrap(),
+            F80::try_from(-0.0).unwrap(),
+            F80::from(0u64),
+            F80::from(0i64),
+            F80::try_from(1.5)
+                .unwrap()
+                .sub(&F80::try_from(1.5).unwrap()),
+            F80::try_from(-1.5)
+                .unwrap()
+                .sub(&F80::try_from(-1.5).unwrap()),
+            F80::try_from(-1.5)
+                .unwrap()
+                .mul(&F80::try_from(0.0).unwrap()),
+            F80::try_from(-0.0).unwrap().div(&F80::from(3u64)),
+            F80::from_bits(1 << 79).unwrap(),
+        ];
+        for value in zeros {
+            assert_eq!(value, zero);
+            assert_eq!(value.cmp(&zero), Ordering::Equal);
+            assert_eq!(hash(&value), hash(&zero));
+            assert_eq!(value.sign(), Sign::Pos);
+            assert_eq!(value.to_bits(), 0);
+            assert_eq!(f64::from(value).to_bits(), 0.0f64.to_bits());
+        }
+        assert!(F80::try_from(-1e-200).unwrap() < zero && zero < F80::try_from(1e-200).unwrap());
+        assert_ne!(
+            hash(&F80::try_from(1.5).unwrap()),
+            hash(&F80::try_from(-1.5).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_representation() {
+        let value = F80::from(-3i64);
+        assert_eq!(value.exponent(), 1);
+        assert_eq!(value.mantissa(), 0xC000_0000_0000_0000);
+        assert_eq!(value.sign(), Sign::Neg);
+        let value = F80::from(u64::MAX);
+        assert_eq!(value.exponent(), 63);
+        assert_eq!(value.mantissa(), u64::MAX);
+        assert_eq!(value.sign(), Sign::Pos);
+        assert_eq!(value.ulp(), *F80::one());
+        let zero = F80::clone(F80::zero());
+        assert_eq!((zero.exponent(), zero.mantissa()), (0, 0));
+        assert_eq!(zero.sign(), Sign::Pos);
+        assert!(zero.ulp() > zero);
+        assert_eq!(zero.ulp().to_bits(), 1);
+    }
+
+    #[test]
+    fn test_ulp() {
+        let cases: [f64; 6] = [1.0, 1.5, 0.1, 1e-200, 3e300, -7.25];
+        for value in cases {
+            let next = f64::from_bits(value.to_bits() + 1);
+            let ulp = (next - value).abs();
+            assert_eq!(
+                F80::try_from(value).unwrap().ulp(),
+                F80::try_from(ulp / 2048.0).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_approx_eq() {
+        let one = F80::clone(F80::one());
+        let next = one.add(&one.ulp());
+        assert!(one.approx_eq(&next, 1));
+        assert!(!one.approx_eq(&next, 0));
+        assert!(one.approx_eq(&one, 0));
+        let far = one.add(&one.ulp().mul(&F80::from(5u64)));
+        assert!(far.approx_eq(&one, 5));
+        assert!(!one.approx_eq(&far, 4));
+        let zero = F80::clone(F80::zero());
+        assert!(zero.approx_eq(&zero.ulp(), 1));
+        assert!(!zero.approx_eq(&one.ulp(), 1000));
+    }
+
+    #[test]
+    fn test_round_to_multiple() {
+        let (step, origin) = (F80::try_from(0.25).unwrap(), F80::try_from(1.0).unwrap());
+        let cases: [(f64, f64, f64, f64); 5] = [
+            (1.3, 1.25, 1.25, 1.5),
+            (1.4, 1.5, 1.25, 1.5),
+            (1.375, 1.5, 1.25, 1.5),
+            (1.125, 1.0, 1.0, 1.25),
+            (-0.1, 0.0, -0.25, 0.0),
+        ];
+        for (value, nearest, ffill, bfill) in cases {
+            let value = F80::try_from(value).unwrap();
+            let round = |method| value.round_to_multiple(&step, &origin, method);
+            assert_eq!(
+                round(Method::Nearest),
+                Some(F80::try_from(nearest).unwrap())
+            );
+            assert_eq!(
+                round(Method::ForwardFill),
+                Some(F80::try_from(ffill).unwrap())
+            );
+            assert_eq!(
+                round(Method::BackwardFill),
+                Some(F80::try_from(bfill).unwrap())
+            );
+            assert_eq!(round(Method::None), None);
+        }
+        let value = F80::try_from(1.75).unwrap();
+        assert_eq!(
+            value.round_to_multiple(&step, &origin, Method::None),
+            Some(value.clone())
+        );
+        let zero = F80::clone(F80::zero());
+        assert_eq!(
+            value.round_to_multiple(&zero, &origin, Method::Nearest),
+            None
+        );
+    }
+
+    #[test]
+    fn test_round_to_multiple_precision() {
+        let third = F80::one().div(&F80::from(3u64));
+        let zero = F80::clone(F80::zero());
+        let steps = F80::from(3_000_000_000_000u64);
+        let value = steps.add(&F80::try_from(0.1).unwrap());
+        let expected = steps.mul(&third);
+        assert_eq!(
+            value
+                .mul(&third)
+                .round_to_multiple(&third, &zero, Method::Nearest),
+            Some(expected.clone())
+        );
+        let origin = F80::from(1_700_000_000_000_000_000u64);
+        let value = origin
+            .add(&F80::from(1_000_000_000_000u64))
+            .add(&F80::try_from(0.4).unwrap());
+        assert_eq!(
+            value.round_to_multiple(F80::one(), &origin, Method::BackwardFill),
+            Some(origin.add(&F80::from(1_000_000_000_001u64)))
+        );
+        let step = F80::from(7u64).div(&F80::from(1_000_000_007u64));
+        let count = F80::from(123_456_789_012_345u64);
+        let value = count
+            .mul(&step)
+            .add(&step.mul(&F80::try_from(0.75).unwrap()));
+        let expected = F80 {
+            value: count.value.mul(&step.value, 192, RoundingMode::ToEven).add(
+                &step.value,
+                64,
+                RoundingMode::ToEven,
+            ),
+        };
+        assert_eq!(
+            value.round_to_multiple(&step, &zero, Method::Nearest),
+            Some(expected)
+        );
+    }
+
+    #[test]
+    fn test_checked() {
+        let (one, three) = (F80::clone(F80::one()), F80::from(3u64));
+        assert_eq!(one.add_checked(&three), (F80::from(4u64), true));
+        let max: &F80 = F80::max_safe_u64();
+        let (sum, exact) = max.add_checked(&F80::try_from(0.5).unwrap());
+        assert!(!exact);
+        assert_eq!(sum, F80::try_from(2.0f64.powi(64)).unwrap());
+        assert_eq!(max.sub(&one).add_checked(&one), (max.clone(), true));
+        assert_eq!(
+            three.mul_checked(&F80::from(5u64)),
+            (F80::from(15u64), true)
+        );
+        assert!(!max.mul_checked(&three).1);
+        assert!(max.mul_checked(&F80::try_from(0.25).unwrap()).1);
+        assert_eq!(
+            F80::from(6u64).div_checked(&three),
+            (F80::clone(F80::two()), true)
+        );
+        assert!(one.div_checked(&F80::from(4u64)).1);
+        assert!(!one.div_checked(&three).1);
+        assert!(max.div_checked(&F80::from(2u64)).1);
+        assert!(!max.div_checked(&F80::from(7u64)).1);
+        let zero = F80::clone(F80::zero());
+        assert_eq!(zero.mul_checked(&three), (zero.clone(), true));
+        assert_eq!(zero.add_checked(&three), (three.clone(), true));
+        assert_eq!(zero.div_checked(&three), (zero.clone(), true));
+        assert_eq!(
+            F80::from(6u64).checked_div(&three),
+            Some(F80::clone(F80::two()))
+        );
+        assert_eq!(zero.checked_div(&three), Some(zero.clone()));
+        assert_eq!(three.checked_div(&zero), None);
+        assert_eq!(zero.checked_div(&F80::try_from(-0.0).unwrap()), None);
+    }
+
+    /// Rounds as `F80::round` did before working on the mantissa, through floor and ceil.
+    fn round_reference(value: &F80) -> F80 {
+        let floor = value.floor();
+        let ceil = value.ceil();
+        let mid = floor.add(&ceil).div(F80::two());
+        match value.cmp(&mid) {
+            Ordering::Less => floor,
+            Ordering::Equal if floor.rem(F80::two()) == *F80::zero() => floor,
+            Ordering::Equal => ceil,
+            Ordering::Greater => ceil,
+        }
+    }
+
+    #[test]
+    fn test_round_agrees_with_reference() {
+        let mut values = Vec::new();
+        // Every multiple of 1/1024 within 64 on both sides, including all ties.
+        for k in -(1 << 16)..=(1 << 16) {
+            values.push(F80::try_from(k as f64 / 1024.0).unwrap());
+        }
+        // Random finite f64 over the whole range of exponents.
+        let mut rng = Rng(0x9E37_79B9_7F4A_7C15);
+        while values.len() < 300_000 {
+            let state = rng.next();
+            let value = f64::from_bits(state);
+            if value.is_finite() {
+                values.push(F80::try_from(value).unwrap());
+            }
+        }
+        // Values around 2^63 and 2^64, whose fractions need the full mantissa.
+        let half = F80::try_from(0.5).unwrap();
+        for base in [1u64 << 52, 1 << 62, 1 << 63, u64::MAX - 1, u64::MAX] {
+            for offset in [F80::clone(F80::zero()), half.clone()] {
+                let value = F80::from(base).add(&offset);
+                values.push(value.clone());
+                values.push(F80::zero().sub(&value));
+            }
+        }
+        let third = F80::one().div(&F80::from(3u64));
+        for exponent in [-1i64, 0, 1, 2, 31, 62] {
+            let scale = F80::try_from(2.0f64.powi(exponent as i32)).unwrap();
+            for value in [third.clone(), F80::one().sub(&third), half.clone()] {
+                let value = value.add(&F80::from(7u64)).mul(&scale);
+                values.push(value.clone());
+                values.push(F80::zero().sub(&value));
+            }
+        }
+        for value in values {
+            assert_eq!(value.round(), round_reference(&value), "{value:?}");
+        }
+    }
+
+    #[test]
+    #[ignore = "benchmark, run with --release --ignored"]
+    fn bench_round() {
+        let values: Vec<F80> = (0..1_000_000u64)
+            .map(|k| F80::try_from(k as f64 * 0.37 - 1e5).unwrap())
+            .collect();
+        let start = std::time::Instant::now();
+        let reference: Vec<F80> = values.iter().map(round_reference).collect();
+        let elapsed = start.elapsed();
+        let start = std::time::Instant::now();
+        let rounded: Vec<F80> = values.iter().map(F80::round).collect();
+        let round_elapsed = start.elapsed();
+        assert_eq!(rounded, reference);
+        assert!(round_elapsed < elapsed, "{round_elapsed:?} vs {elapsed:?}");
+
+        // The inverse of extended-precision values rounds each index with `round`.
+        let (x1, f0, f1) = (
+            F80::from(1u64 << 40),
+            F80::try_from(-1e5).unwrap(),
+            F80::try_from(3e5).unwrap(),
+        );
+        let interp = Interp::new(vec![0, 1 << 40], vec![f0.clone(), f1.clone()]);
+        let start = std::time::Instant::now();
+        let reference: Vec<u64> = values
+            .iter()
+            .map(|f| round_reference(&x1.mul(&f.sub(&f0)).div(&f1.sub(&f0))).into())
+            .collect();
+        let elapsed = start.elapsed();
+        let start = std::time::Instant::now();
+        let indices: Vec<u64> = values
+            .iter()
+            .map(|f| interp.inverse(f.clone(), Method::Nearest).unwrap())
+            .collect();
+        let inverse_elapsed = start.elapsed();
+        assert_eq!(indices, reference);
+        assert!(
+            inverse_elapsed < elapsed,
+            "{inverse_elapsed:?} vs {elapsed:?}"
+        );
+    }
+
+    #[test]
+    fn test_weighted_mean() {
+        let (one, three) = (F80::clone(F80::one()), F80::from(3u64));
+        assert!(F80::from(2u64).is_weighted_mean(&one, 1, &three, 1));
+        assert!(F80::try_from(1.5)
+            .unwrap()
+            .is_weighted_mean(&one, 3, &three, 1));
+        assert!(!F80::try_from(1.5)
+            .unwrap()
+            .is_weighted_mean(&one, 2, &three, 1));
+        assert!(one.is_weighted_mean(&one, 5, &three, 0));
+        assert!(F80::zero().is_weighted_mean(&F80::from(-3i64), 1, &three, 1));
+        assert!(!F80::zero().is_weighted_mean(&one, 1, &three, 1));
+        assert!(F80::zero().is_weighted_mean(F80::zero(), 1, F80::zero(), 1));
+        // 1/3 rounded to 64 bits is not the mean of 0 and 1 with weights 2 and 1.
+        let third = one.div(&three);
+        assert!(!third.is_weighted_mean(&one, 1, F80::zero(), 2));
+        let max = u64::MAX;
+        let half = F80::try_from(0.5).unwrap();
+        assert!(half.is_weighted_mean(F80::zero(), max, &one, max));
+        assert!(!half.is_weighted_mean(F80::zero(), max, &one, max - 1));
+    }
+
+    #[test]
+    fn test_mul_add() {
+        // (2^64 - 1)^2 = 2^128 - 2^65 + 1 rounds to 2^128 - 2^65 when the product is rounded
+        // before the sum.
+        let max = F80::from(u64::MAX);
+        let rounded = max.mul(&max);
+        let neg = F80::zero().sub(&rounded);
+        assert_eq!(max.mul(&max).add(&neg), *F80::zero());
+        assert_eq!(max.mul_add(&max, &neg), *F80::one());
+        assert_eq!(F80::zero().mul_add(&max, &neg), neg);
+        assert_eq!(
+            F80::from(3u64).mul_add(&F80::try_from(0.5).unwrap(), F80::one()),
+            F80::try_from(2.5).unwrap()
+        );
     }
 
     #[test]
@@ -218,7 +1333,7 @@ mod tests {
             (2.0, 2),
         ];
         for (input, expected) in cases {
-            let result: u64 = F80::from(input).round().into();
+            let result: u64 = F80::try_from(input).unwrap().round().into();
             assert_eq!(result, expected)
         }
     }
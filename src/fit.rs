@@ -0,0 +1,206 @@
+//! Continuous piecewise linear fitting of densely sampled values under an L∞ bound.
+//!
+//! Segments are greedily grown from left to right. For each segment, the set of admissible lines
+//! is tracked as a convex polygon in the (value at segment start, slope) plane: every sample adds
+//! a strip constraint that clips the polygon. The start value of a segment is only constrained
+//! to the range of end values reachable by the previous segment, so that noisy samples never pin
+//! a breakpoint. When the polygon becomes empty, the segment is closed on the previous sample.
+//! Once all segments are known, the breakpoint values are chosen backward so that consecutive
+//! segments join continuously.
+//!
+//! All computations are performed with extended-precision floats.
+
+use crate::extended::F80;
+
+/// A point in the (value at segment start, slope) plane.
+type Point = (F80, F80);
+
+/// A segment starting at index `start` and ending at index `end` with the polygon of its
+/// admissible lines.
+struct Segment {
+    start: usize,
+    end: usize,
+    polygon: Vec<Point>,
+}
+
+/// Fits a continuous piecewise linear function to samples located at consecutive indices so
+/// that every sample lies within `tolerance` of the fit.
+///
+/// # Arguments
+///
+/// * `samples` - The sampled values, at least two.
+/// * `tolerance` - The maximum absolute deviation, non-negative.
+///
+/// # Returns
+///
+/// The indices and values of the breakpoints.
+pub(crate) fn fit(samples: &[F80], tolerance: &F80) -> (Vec<u64>, Vec<F80>) {
+    let last = samples.len() - 1;
+    let mut segments: Vec<Segment> = Vec::new();
+    let mut range = (samples[0].sub(tolerance), samples[0].add(tolerance));
+    let mut start = 0;
+    while start < last {
+        let mut polygon = initial_polygon(&range, samples, start, tolerance);
+        let mut end = start + 1;
+        while end < last {
+            let candidate = clip_strip(&polygon, samples, start, end + 1, tolerance);
+            if candidate.is_empty() {
+                break;
+            }
+            polygon = candidate;
+            end += 1;
+        }
+        range = projection(&polygon, end - start);
+        segments.push(Segment {
+            start,
+            end,
+            polygon,
+        });
+        start = end;
+    }
+    let mut xp = vec![last as u64];
+    let mut fp = Vec::with_capacity(segments.len() + 1);
+    let mut target: Option<F80> = None;
+    for segment in segments.iter().rev() {
+        let distance = F80::from((segment.end - segment.start) as u64);
+        let (value, slope) = match &target {
+            None => centroid(&segment.polygon),
+            Some(target) => on_line(&segment.polygon, &distance, target),
+        };
+        if target.is_none() {
+            fp.push(value.add(&slope.mul(&distance)));
+        }
+        xp.push(segment.start as u64);
+        fp.push(value.clone());
+        target = Some(value);
+    }
+    xp.reverse();
+    fp.reverse();
+    (xp, fp)
+}
+
+/// Builds the polygon of the lines starting within `range` at `start` and passing within
+/// `tolerance` of the next sample.
+fn initial_polygon(
+    range: &(F80, F80),
+    samples: &[F80],
+    start: usize,
+    tolerance: &F80,
+) -> Vec<Point> {
+    let low = samples[start + 1].sub(tolerance);
+    let high = samples[start + 1].add(tolerance);
+    let (a0, a1) = range;
+    vec![
+        (a0.clone(), low.sub(a0)),
+        (a1.clone(), low.sub(a1)),
+        (a1.clone(), high.sub(a1)),
+        (a0.clone(), high.sub(a0)),
+    ]
+}
+
+/// Clips the polygon with the strip of lines passing within `tolerance` of the given sample.
+fn clip_strip(
+    polygon: &[Point],
+    samples: &[F80],
+    start: usize,
+    index: usize,
+    tolerance: &F80,
+) -> Vec<Point> {
+    let distance = F80::from((index - start) as u64);
+    let high = samples[index].add(tolerance);
+    let low = samples[index].sub(tolerance);
+    let zero: &F80 = F80::zero();
+    let below = clip(polygon, |point| {
+        point.0.add(&point.1.mul(&distance)).sub(&high)
+    });
+    clip(&below, |point| {
+        zero.sub(&point.0.add(&point.1.mul(&distance)).sub(&low))
+    })
+}
+
+/// Clips a convex polygon with the half-plane where `level` is non-positive.
+fn clip(polygon: &[Point], level: impl Fn(&Point) -> F80) -> Vec<Point> {
+    let zero: &F80 = F80::zero();
+    let mut out = Vec::with_capacity(polygon.len() + 1);
+    for (index, current) in polygon.iter().enumerate() {
+        let next = &polygon[(index + 1) % polygon.len()];
+        let current_level = level(current);
+        let next_level = level(next);
+        if current_level <= *zero {
+            out.push(current.clone());
+        }
+        if (current_level < *zero && next_level > *zero)
+            || (current_level > *zero && next_level < *zero)
+        {
+            let ratio = current_level.div(&current_level.sub(&next_level));
+            out.push((
+                current.0.add(&next.0.sub(&current.0).mul(&ratio)),
+                current.1.add(&next.1.sub(&current.1).mul(&ratio)),
+            ));
+        }
+    }
+    out
+}
+
+/// Computes the range of values reached at `distance` from the segment start by the lines of
+/// the polygon.
+fn projection(polygon: &[Point], distance: usize) -> (F80, F80) {
+    let distance = F80::from(distance as u64);
+    let values: Vec<F80> = polygon
+        .iter()
+        .map(|point| point.0.add(&point.1.mul(&distance)))
+        .collect();
+    let min = values.iter().min().expect("non-empty polygon").clone();
+    let max = values.iter().max().expect("non-empty polygon").clone();
+    (min, max)
+}
+
+/// Computes the vertex average of a polygon, which lies inside since it is convex.
+fn centroid(polygon: &[Point]) -> Point {
+    let count = F80::from(polygon.len() as u64);
+    let zero: &F80 = F80::zero();
+    let (a, s) = polygon
+        .iter()
+        .fold((zero.clone(), zero.clone()), |(a, s), point| {
+            (a.add(&point.0), s.add(&point.1))
+        });
+    (a.div(&count), s.div(&count))
+}
+
+/// Picks a line of the polygon reaching `target` at `distance` from the segment start. The
+/// middle of the chord is chosen. If rounding errors make the chord empty, the closest vertex
+/// is returned instead. Only the start value of the returned line is used, the end value being
+/// the target itself.
+fn on_line(polygon: &[Point], distance: &F80, target: &F80) -> Point {
+    let zero: &F80 = F80::zero();
+    let level = |point: &Point| point.0.add(&point.1.mul(distance)).sub(target);
+    let mut chord: Vec<Point> = Vec::new();
+    for (index, current) in polygon.iter().enumerate() {
+        let next = &polygon[(index + 1) % polygon.len()];
+        let current_level = level(current);
+        let next_level = level(next);
+        if current_level == *zero {
+            chord.push(current.clone());
+        } else if (current_level < *zero && next_level > *zero)
+            || (current_level > *zero && next_level < *zero)
+        {
+            let ratio = current_level.div(&current_level.sub(&next_level));
+            chord.push((
+                current.0.add(&next.0.sub(&current.0).mul(&ratio)),
+                current.1.add(&next.1.sub(&current.1).mul(&ratio)),
+            ));
+        }
+    }
+    if chord.is_empty() {
+        polygon
+            .iter()
+            .min_by_key(|point| {
+                let level = level(point);
+                level.clone().max(zero.sub(&level))
+            })
+            .expect("non-empty polygon")
+            .clone()
+    } else {
+        centroid(&chord)
+    }
+}
@@ -0,0 +1,299 @@
+//! Bilinear interpolation on grids of data points.
+//!
+//! Two-dimensional coordinates (e.g. positions varying along both channels and time) are
+//! compressed as a coarse grid of data points: strictly increasing row and column indices and
+//! the values at each of their crossings. Values are bilinearly interpolated within the cell of
+//! the grid containing the query, exactly for integers (see `Bilinear`).
+//!
+//! # Example
+//!
+//! ```ignore
+//! use xinterp::grid::Interp2D;
+//!
+//! let xi: Vec<u64> = vec![0, 10];
+//! let xj: Vec<u64> = vec![0, 100];
+//! let fp: Vec<i64> = vec![0, 1000, 10, 1010];
+//! let interp = Interp2D::new(xi, xj, fp).unwrap();
+//! assert_eq!(interp.forward(5, 50), Ok(505));
+//! ```
+
+use crate::divop::Method;
+use crate::piecewise::InterpError;
+use crate::schemes::{Bilinear, Distance, Forward};
+
+/// Structure for performing bilinear interpolation on a grid of data points. The values are
+/// stored row by row.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Interp2D<Xi, Xj, F> {
+    xi: Vec<Xi>,
+    xj: Vec<Xj>,
+    fp: Vec<F>,
+}
+
+impl<Xi, Xj, F> Interp2D<Xi, Xj, F>
+where
+    Xi: Copy + Ord + Into<u64>,
+    Xj: Copy + Ord + Into<u64>,
+    F: Bilinear + Clone,
+{
+    /// Creates a new Interp2D instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `xi` - The row indices of the data points, must be strictly increasing.
+    /// * `xj` - The column indices of the data points, must be strictly increasing.
+    /// * `fp` - The values of the data points, row by row.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the Interp2D.
+    /// Otherwise, returns `NotStrictlyIncreasing` if the row or column indices are not strictly
+    /// increasing or `LengthMismatch` with the size of the grid and the number of values if they
+    /// differ.
+    pub fn new(xi: Vec<Xi>, xj: Vec<Xj>, fp: Vec<F>) -> Result<Interp2D<Xi, Xj, F>, InterpError> {
+        if xi.len() * xj.len() != fp.len() {
+            return Err(InterpError::LengthMismatch(xi.len() * xj.len(), fp.len()));
+        }
+        let increasing = xi.windows(2).all(|pair| pair[0] < pair[1])
+            && xj.windows(2).all(|pair| pair[0] < pair[1]);
+        if !increasing {
+            return Err(InterpError::NotStrictlyIncreasing);
+        }
+        Ok(Interp2D { xi, xj, fp })
+    }
+    /// Returns the row indices of the data points.
+    pub fn xi(&self) -> &[Xi] {
+        &self.xi
+    }
+    /// Returns the column indices of the data points.
+    pub fn xj(&self) -> &[Xj] {
+        &self.xj
+    }
+    /// Returns the values of the data points, row by row.
+    pub fn fp(&self) -> &[F] {
+        &self.fp
+    }
+    /// Performs bilinear interpolation at the given pair of indices. Integer values are rounded
+    /// to the nearest integer. Queries on the edges of the grid are within bounds.
+    ///
+    /// # Arguments
+    ///
+    /// * `i` - The row index.
+    /// * `j` - The column index.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the interpolated value.
+    /// Otherwise, returns `OutOfBounds` if any index lies outside the grid or `Overflow` if the
+    /// integer computation overflows.
+    pub fn forward(&self, i: Xi, j: Xj) -> Result<F, InterpError> {
+        let (i0, i1, di, ni) = locate(&self.xi, i).ok_or(InterpError::OutOfBounds)?;
+        let (j0, j1, dj, nj) = locate(&self.xj, j).ok_or(InterpError::OutOfBounds)?;
+        let corners = [
+            [self.value(i0, j0), self.value(i0, j1)],
+            [self.value(i1, j0), self.value(i1, j1)],
+        ];
+        F::bilinear(corners, (di, ni), (dj, nj)).ok_or(InterpError::Overflow)
+    }
+    /// Removes the rows and the columns of data points that can be recovered by interpolation
+    /// within a tolerance. Rows and columns are pruned independently: a row is dropped if every
+    /// one of its values deviates from the linear interpolation between the kept neighboring
+    /// rows by at most `epsilon` (integers being rounded to the nearest), and so are columns.
+    /// Segments are greedily grown from the first row (or column), and the extreme rows and
+    /// columns are always kept. As both prunings add up, the values of the removed data points
+    /// deviate from the simplified grid by at most twice `epsilon`.
+    ///
+    /// # Arguments
+    ///
+    /// * `epsilon` - The maximum deviation of the values along each direction.
+    ///
+    /// # Returns
+    ///
+    /// The simplified Interp2D.
+    pub fn simplify(&self, epsilon: F::Output) -> Interp2D<Xi, Xj, F>
+    where
+        u64: Forward<F>,
+        F: Distance,
+    {
+        let (rows, cols) = (self.xi.len(), self.xj.len());
+        let kept_rows = prune(rows, |a, k, b| {
+            let x = [a, k, b].map(|r| self.xi[r].into());
+            (0..cols).all(|c| within(x, [a, k, b].map(|r| self.value(r, c)), &epsilon))
+        });
+        let kept_cols = prune(cols, |a, k, b| {
+            let x = [a, k, b].map(|c| self.xj[c].into());
+            (0..rows).all(|r| within(x, [a, k, b].map(|c| self.value(r, c)), &epsilon))
+        });
+        let fp = kept_rows
+            .iter()
+            .flat_map(|r| kept_cols.iter().map(move |c| self.value(*r, *c)))
+            .collect();
+        Interp2D {
+            xi: kept_rows.iter().map(|r| self.xi[*r]).collect(),
+            xj: kept_cols.iter().map(|c| self.xj[*c]).collect(),
+            fp,
+        }
+    }
+    /// Returns the value of the data point at the given row and column positions.
+    fn value(&self, row: usize, col: usize) -> F {
+        self.fp[row * self.xj.len() + col].clone()
+    }
+}
+
+/// Locates the segment of strictly increasing indices containing the given index. Returns the
+/// positions of its ends, the offset of the index from the first end and the length of the
+/// segment. An index matching a single data point lies in a segment of length one made of that
+/// data point only.
+fn locate<X: Copy + Ord + Into<u64>>(xp: &[X], x: X) -> Option<(usize, usize, u64, u64)> {
+    match xp.partition_point(|value| *value <= x) {
+        0 => None,
+        1 if xp.len() == 1 => (xp[0] == x).then_some((0, 0, 0, 1)),
+        len if len == xp.len() => {
+            let (x0, x1): (u64, u64) = (xp[len - 2].into(), xp[len - 1].into());
+            (xp[len - 1] == x).then_some((len - 2, len - 1, x1 - x0, x1 - x0))
+        }
+        index => {
+            let (x0, x1): (u64, u64) = (xp[index - 1].into(), xp[index].into());
+            Some((index - 1, index, x.into() - x0, x1 - x0))
+        }
+    }
+}
+
+/// Whether the middle of three data points deviates from the linear interpolation between the
+/// two others by at most `epsilon`.
+fn within<F>([x0, x, x1]: [u64; 3], [f0, f, f1]: [F; 3], epsilon: &F::Output) -> bool
+where
+    u64: Forward<F>,
+    F: Distance,
+{
+    x.forward(x0, x1, f0, f1, Method::Nearest)
+        .is_some_and(|value| value.distance(f) <= *epsilon)
+}
+
+/// Greedily selects the positions to keep among `len` ones, the first and the last being always
+/// kept. `within(a, k, b)` tells whether the position k can be dropped in between a and b.
+fn prune(len: usize, within: impl Fn(usize, usize, usize) -> bool) -> Vec<usize> {
+    if len < 3 {
+        return (0..len).collect();
+    }
+    let mut kept = vec![0];
+    let mut end = 2;
+    while end < len {
+        let start = *kept.last().unwrap();
+        if !(start + 1..end).all(|k| within(start, k, end)) {
+            kept.push(end - 1);
+        }
+        end += 1;
+    }
+    kept.push(len - 1);
+    kept
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extended::F80;
+
+    #[test]
+    fn test_forward() {
+        let xi: Vec<u64> = vec![0, 10, 20];
+        let xj: Vec<u64> = vec![0, 100];
+        let fp: Vec<i64> = vec![0, 1000, 10, 1010, 30, 1030];
+        let interp = Interp2D::new(xi, xj, fp).unwrap();
+        assert_eq!(interp.forward(0, 0), Ok(0));
+        assert_eq!(interp.forward(5, 50), Ok(505));
+        assert_eq!(interp.forward(15, 1), Ok(30));
+        assert_eq!(interp.forward(20, 100), Ok(1030));
+        assert_eq!(interp.forward(10, 33), Ok(340));
+        assert_eq!(interp.forward(21, 0), Err(InterpError::OutOfBounds));
+        assert_eq!(interp.forward(0, 101), Err(InterpError::OutOfBounds));
+
+        let fp = vec![1.0, 2.0, 3.0, 5.0];
+        let interp = Interp2D::new(vec![2u64, 4], vec![0u64, 4], fp).unwrap();
+        assert_eq!(interp.forward(3, 2), Ok(2.75));
+        assert_eq!(interp.forward(1, 2), Err(InterpError::OutOfBounds));
+    }
+
+    #[test]
+    fn test_forward_exact() {
+        let base = 1_700_000_000_000_000_000i64;
+        let xi: Vec<u64> = vec![0, 3];
+        let xj: Vec<u64> = vec![0, 7];
+        let fp = vec![base, base + 7, base + 3, base + 11];
+        let interp = Interp2D::new(xi, xj, fp).unwrap();
+        for i in 0..=3i128 {
+            for j in 0..=7i128 {
+                let num = 7 * (3 - i) * j + 3 * i * (7 - j) + 11 * i * j;
+                let expected = base + ((2 * num + 21) / 42) as i64;
+                assert_eq!(interp.forward(i as u64, j as u64), Ok(expected));
+            }
+        }
+        let fp: Vec<u64> = vec![0, u64::MAX, u64::MAX, 0];
+        let interp = Interp2D::new(vec![0u64, 2], vec![0u64, 2], fp).unwrap();
+        assert_eq!(interp.forward(1, 1), Ok(u64::MAX / 2 + 1));
+        let fp: Vec<u64> = vec![0, u64::MAX, u64::MAX, 0];
+        let interp = Interp2D::new(vec![0u64, u64::MAX], vec![0u64, u64::MAX], fp).unwrap();
+        assert_eq!(interp.forward(1, 1), Err(InterpError::Overflow));
+        let fp: Vec<F80> = vec![
+            F80::try_from(0.0).unwrap(),
+            F80::try_from(1.0).unwrap(),
+            F80::try_from(2.0).unwrap(),
+            F80::try_from(3.0).unwrap(),
+        ];
+        let interp = Interp2D::new(vec![0u64, 4], vec![0u64, 4], fp).unwrap();
+        assert_eq!(interp.forward(2, 2), Ok(F80::try_from(1.5).unwrap()));
+    }
+
+    #[test]
+    fn test_single() {
+        let interp = Interp2D::new(vec![5u64], vec![0u64, 10], vec![0i64, 100]).unwrap();
+        assert_eq!(interp.forward(5, 3), Ok(30));
+        assert_eq!(interp.forward(4, 3), Err(InterpError::OutOfBounds));
+        let interp = Interp2D::new(vec![5u64], vec![7u64], vec![1.0]).unwrap();
+        assert_eq!(interp.forward(5, 7), Ok(1.0));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            Interp2D::new(vec![0u64, 1], vec![0u64, 1], vec![0i64; 3]),
+            Err(InterpError::LengthMismatch(4, 3))
+        );
+        assert_eq!(
+            Interp2D::new(vec![1u64, 0], vec![0u64, 1], vec![0i64; 4]),
+            Err(InterpError::NotStrictlyIncreasing)
+        );
+        assert_eq!(
+            Interp2D::new(vec![0u64, 1], vec![1u64, 1], vec![0i64; 4]),
+            Err(InterpError::NotStrictlyIncreasing)
+        );
+        let interp = Interp2D::new(vec![0u64, 2], vec![0u64, 2], vec![0.0, 1.0, 2.0, f64::NAN]);
+        assert_eq!(interp.unwrap().forward(1, 1), Err(InterpError::Overflow));
+    }
+
+    #[test]
+    fn test_simplify() {
+        let xi: Vec<u64> = (0..5).map(|i| 10 * i).collect();
+        let xj: Vec<u64> = (0..4).map(|j| 100 * j).collect();
+        let mut fp: Vec<i64> = Vec::new();
+        for i in &xi {
+            for j in &xj {
+                fp.push(3 * *i as i64 + *j as i64 / 10);
+            }
+        }
+        fp[2 * 4 + 1] += 5;
+        let interp = Interp2D::new(xi, xj, fp).unwrap();
+        let simplified = interp.simplify(0);
+        assert_eq!(simplified.xi(), &[0, 10, 20, 30, 40]);
+        assert_eq!(simplified.xj(), &[0, 100, 200, 300]);
+        let simplified = interp.simplify(5);
+        assert_eq!(simplified.xi(), &[0, 40]);
+        assert_eq!(simplified.xj(), &[0, 300]);
+        for i in (0..=40).step_by(10) {
+            for j in (0..=300).step_by(100) {
+                let deviation = interp.forward(i, j).unwrap() - simplified.forward(i, j).unwrap();
+                assert!(deviation.abs() <= 10);
+            }
+        }
+    }
+}
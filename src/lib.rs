@@ -1,144 +1,2512 @@
+// pyo3 0.20 expands the constructors of classes into impl blocks nested in a function.
+#![allow(non_local_definitions)]
+
+pub mod affine;
+pub mod codec;
+pub mod datetime;
 pub mod divop;
 pub mod extended;
+mod fit;
+pub mod grid;
+pub mod monotonicity;
+pub mod multi;
+#[cfg(feature = "npy")]
+pub mod npy;
+pub mod outliers;
+pub mod packed;
 pub mod piecewise;
+pub mod rational;
+#[cfg(test)]
+mod rng;
 pub mod schemes;
+pub mod smooth;
+pub mod step;
+#[cfg(feature = "tracing")]
+mod trace;
+pub mod union;
+pub mod units;
 
+use crate::codec::{peek_tags, Codec, DecodeError};
+use crate::datetime::{parse_datetime, ParseError};
 use crate::divop::Method;
-use crate::piecewise::{Interp, InterpError};
-use numpy::ndarray::Array1;
-use numpy::{IntoPyArray, PyArray1, PyReadonlyArray1};
-use pyo3::exceptions::{PyIndexError, PyKeyError, PyValueError};
+use crate::extended::F80;
+use crate::grid::Interp2D;
+use crate::monotonicity::{check_strictly_increasing, Monotonicity, MonotonicityReport};
+use crate::multi::{forward_multi, inverse_multi, Pair};
+#[cfg(feature = "npy")]
+use crate::npy::{NpyArray, NpyError};
+use crate::outliers::detect_outliers;
+use crate::packed::Packed;
+use crate::piecewise::{
+    BatchError, Bounds, BuildError, Closed, Collision, GapPolicy, Interp, InterpError,
+    InverseStats, MaxGap, SidedBounds, Summary, Verification,
+};
+use crate::schemes::{Extended, Forward, Inverse};
+use crate::smooth::{smooth, Smoothing};
+use crate::units::{ns_to_seconds, parse_duration, seconds_to_ns, DurationError, UnitError};
+use numpy::ndarray::{Array1, Array2, ArrayView1};
+use numpy::{
+    dtype, Element, IntoPyArray, PyArray1, PyArray2, PyReadonlyArray1, PyReadonlyArray2,
+    PyUntypedArray,
+};
+#[cfg(feature = "npy")]
+use pyo3::exceptions::{PyFileNotFoundError, PyOSError};
+use pyo3::exceptions::{PyIndexError, PyKeyError, PyOverflowError, PyTypeError, PyValueError};
 use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict, PyIterator, PyList, PySequence, PySlice, PyString};
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::ops::Range;
+use std::os::raw::c_long;
+#[cfg(feature = "npy")]
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Rounding methods exposed to Python as `xinterp.Method`.
+#[pyclass(name = "Method")]
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum PyMethod {
+    #[pyo3(name = "NONE")]
+    None,
+    #[pyo3(name = "NEAREST")]
+    Nearest,
+    #[pyo3(name = "FFILL")]
+    ForwardFill,
+    #[pyo3(name = "BFILL")]
+    BackwardFill,
+}
+
+#[pymethods]
+impl PyMethod {
+    fn __hash__(&self) -> u64 {
+        *self as u64
+    }
+    /// Returns all the members in definition order.
+    #[staticmethod]
+    fn members() -> Vec<PyMethod> {
+        vec![
+            PyMethod::None,
+            PyMethod::Nearest,
+            PyMethod::ForwardFill,
+            PyMethod::BackwardFill,
+        ]
+    }
+}
+
+impl From<PyMethod> for Method {
+    fn from(method: PyMethod) -> Method {
+        match method {
+            PyMethod::None => Method::None,
+            PyMethod::Nearest => Method::Nearest,
+            PyMethod::ForwardFill => Method::ForwardFill,
+            PyMethod::BackwardFill => Method::BackwardFill,
+        }
+    }
+}
+
+impl From<Method> for PyMethod {
+    fn from(method: Method) -> PyMethod {
+        match method {
+            Method::None => PyMethod::None,
+            Method::Nearest => PyMethod::Nearest,
+            Method::ForwardFill => PyMethod::ForwardFill,
+            Method::BackwardFill => PyMethod::BackwardFill,
+        }
+    }
+}
+
+/// Python rounding method argument, either an `xinterp.Method` member or a string.
+#[derive(FromPyObject)]
+enum MethodArg<'a> {
+    Enum(PyMethod),
+    Str(&'a str),
+}
+
+/// Python bounds policy argument, either one string for both sides or a (left, right) pair.
+#[derive(FromPyObject)]
+enum BoundsArg<'a> {
+    Both(&'a str),
+    Sided(&'a str, &'a str),
+}
+
+/// Python array argument, either a one-dimensional numpy array of the expected dtype or any
+/// sequence of numbers (list, tuple, range...). Arrays of other dtypes are promoted if the
+/// conversion is lossless and arrays in non-native byte order are converted.
+enum ArrayArg<'py, T: Element> {
+    Array(PyReadonlyArray1<'py, T>),
+    Vec(Vec<T>),
+}
+
+impl<'py, T: Element> ArrayArg<'py, T> {
+    /// Returns a view of the underlying data.
+    fn as_array(&self) -> ArrayView1<'_, T> {
+        match self {
+            ArrayArg::Array(array) => array.as_array(),
+            ArrayArg::Vec(vec) => ArrayView1::from(vec.as_slice()),
+        }
+    }
+}
+
+impl<'py, T> ArrayArg<'py, T>
+where
+    T: Promote + for<'a> FromPyObject<'a>,
+{
+    /// Extracts the argument named `name`. Values that do not fit in `T` raise an OverflowError
+    /// identifying the argument, the position and the value, other invalid inputs raise a
+    /// TypeError.
+    fn extract(ob: &'py PyAny, name: &str) -> PyResult<Self> {
+        let ob = native_order(ob)?;
+        if let Ok(array) = ob.extract::<PyReadonlyArray1<'py, T>>() {
+            return Ok(ArrayArg::Array(array));
+        }
+        if let Ok(array) = ob.downcast::<PyUntypedArray>() {
+            if array.ndim() != 1 {
+                return Err(PyTypeError::new_err(format!(
+                    "argument '{name}': expected a one-dimensional array"
+                )));
+            }
+            return match T::promote(ob) {
+                Some(Ok(vec)) => Ok(ArrayArg::Vec(vec)),
+                Some(Err((position, value))) => Err(range_error::<T>(
+                    name,
+                    position,
+                    &value.to_string(),
+                    value < 0,
+                )),
+                None => Err(PyTypeError::new_err(format!(
+                    "argument '{name}': cannot safely cast array from dtype {} to dtype {}",
+                    array.dtype(),
+                    dtype::<T>(ob.py())
+                ))),
+            };
+        }
+        if ob.downcast::<PyIterator>().is_ok() {
+            return Err(PyTypeError::new_err(format!(
+                "argument '{name}': iterators and generators are not supported, pass a list \
+                 instead"
+            )));
+        }
+        let sequence = ob.downcast::<PySequence>().map_err(|_| {
+            PyTypeError::new_err(format!(
+                "argument '{name}': expected an array or a sequence of numbers"
+            ))
+        })?;
+        let mut out = Vec::with_capacity(sequence.len().unwrap_or(0));
+        for (position, item) in sequence.iter()?.enumerate() {
+            let item = item?;
+            match item.extract::<T>() {
+                Ok(value) => out.push(value),
+                Err(err) if err.is_instance_of::<PyOverflowError>(ob.py()) => {
+                    let negative = item.lt(0)?;
+                    return Err(range_error::<T>(
+                        name,
+                        position,
+                        &item.str()?.to_string(),
+                        negative,
+                    ));
+                }
+                Err(_) => {
+                    return Err(PyTypeError::new_err(format!(
+                        "argument '{name}': element {position} has an invalid type"
+                    )))
+                }
+            }
+        }
+        Ok(ArrayArg::Vec(out))
+    }
+}
+
+/// Python integer array argument of either signedness: uint64 numpy arrays are kept unsigned,
+/// anything else is extracted as int64.
+enum IntegerArg<'py> {
+    Signed(ArrayArg<'py, i64>),
+    Unsigned(ArrayArg<'py, u64>),
+}
+
+impl<'py> IntegerArg<'py> {
+    /// Extracts the argument named `name` (see `ArrayArg::extract`).
+    fn extract(ob: &'py PyAny, name: &str) -> PyResult<Self> {
+        let ob = native_order(ob)?;
+        if let Ok(array) = ob.extract::<PyReadonlyArray1<'py, u64>>() {
+            return Ok(IntegerArg::Unsigned(ArrayArg::Array(array)));
+        }
+        ArrayArg::extract(ob, name).map(IntegerArg::Signed)
+    }
+}
+
+/// Converts numpy arrays in non-native byte order (e.g. '>u8' on little-endian platforms) to a
+/// copy in native byte order, which numpy does not consider equivalent dtypes. Other objects are
+/// returned unchanged.
+fn native_order(ob: &PyAny) -> PyResult<&PyAny> {
+    match ob.downcast::<PyUntypedArray>() {
+        Ok(array) if array.dtype().is_native_byteorder() == Some(false) => {
+            let dtype = array.dtype().call_method1("newbyteorder", ("=",))?;
+            ob.call_method1("astype", (dtype,))
+        }
+        _ => Ok(ob),
+    }
+}
+
+/// Builds the error raised when the value at the given position of an argument does not fit in
+/// the expected type.
+fn range_error<T: Promote>(name: &str, position: usize, value: &str, negative: bool) -> PyErr {
+    let reason = match T::BOUNDS {
+        Some((0, _)) if negative => "cannot be negative".to_string(),
+        Some((min, _)) if negative => format!("is below the minimum of {min}"),
+        Some((_, max)) => format!("exceeds the maximum of {max}"),
+        None => "is out of range".to_string(),
+    };
+    PyOverflowError::new_err(format!("{name}[{position}] = {value} {reason}"))
+}
+
+/// Implements the lossless conversion of numpy arrays of other dtypes. Integers are cast if
+/// every value fits, floats only accept dtypes exactly representable as f64.
+trait Promote: Element {
+    /// The minimum and maximum of integer types.
+    const BOUNDS: Option<(i128, i128)>;
+    /// Converts the array if its dtype can be promoted, None otherwise. If some value does not
+    /// fit, returns its position and value.
+    fn promote(ob: &PyAny) -> Option<Result<Vec<Self>, (usize, i128)>>;
+}
+
+/// Converts an array of dtype `S` element-wise, None if the array is not of dtype `S`.
+fn convert<S, T>(ob: &PyAny, func: impl Fn(S) -> Option<T>) -> Option<Result<Vec<T>, (usize, i128)>>
+where
+    S: Element + Copy + Into<i128>,
+{
+    let array = ob.extract::<PyReadonlyArray1<'_, S>>().ok()?;
+    let array = array.as_array();
+    Some(
+        array
+            .iter()
+            .enumerate()
+            .map(|(position, value)| func(*value).ok_or((position, (*value).into())))
+            .collect(),
+    )
+}
+
+impl Promote for u64 {
+    const BOUNDS: Option<(i128, i128)> = Some((0, u64::MAX as i128));
+    fn promote(ob: &PyAny) -> Option<Result<Vec<u64>, (usize, i128)>> {
+        convert(ob, |value: u8| Some(value.into()))
+            .or_else(|| convert(ob, |value: u16| Some(value.into())))
+            .or_else(|| convert(ob, |value: u32| Some(value.into())))
+            .or_else(|| convert(ob, |value: i8| u64::try_from(value).ok()))
+            .or_else(|| convert(ob, |value: i16| u64::try_from(value).ok()))
+            .or_else(|| convert(ob, |value: i32| u64::try_from(value).ok()))
+            .or_else(|| convert(ob, |value: i64| u64::try_from(value).ok()))
+    }
+}
+
+impl Promote for i64 {
+    const BOUNDS: Option<(i128, i128)> = Some((i64::MIN as i128, i64::MAX as i128));
+    fn promote(ob: &PyAny) -> Option<Result<Vec<i64>, (usize, i128)>> {
+        convert(ob, |value: i8| Some(value.into()))
+            .or_else(|| convert(ob, |value: i16| Some(value.into())))
+            .or_else(|| convert(ob, |value: i32| Some(value.into())))
+            .or_else(|| convert(ob, |value: u8| Some(value.into())))
+            .or_else(|| convert(ob, |value: u16| Some(value.into())))
+            .or_else(|| convert(ob, |value: u32| Some(value.into())))
+            .or_else(|| convert(ob, |value: u64| i64::try_from(value).ok()))
+    }
+}
+
+impl Promote for f64 {
+    const BOUNDS: Option<(i128, i128)> = None;
+    fn promote(ob: &PyAny) -> Option<Result<Vec<f64>, (usize, i128)>> {
+        convert(ob, |value: i8| Some(value.into()))
+            .or_else(|| convert(ob, |value: i16| Some(value.into())))
+            .or_else(|| convert(ob, |value: i32| Some(value.into())))
+            .or_else(|| convert(ob, |value: u8| Some(value.into())))
+            .or_else(|| convert(ob, |value: u16| Some(value.into())))
+            .or_else(|| convert(ob, |value: u32| Some(value.into())))
+            .or_else(|| {
+                let array = ob.extract::<PyReadonlyArray1<'_, f32>>().ok()?;
+                Some(Ok(array
+                    .as_array()
+                    .iter()
+                    .map(|value| (*value).into())
+                    .collect()))
+            })
+    }
+}
+
+/// Parses ISO 8601 datetimes passed as a string, a sequence of strings or a numpy array of
+/// strings into nanoseconds since the epoch. Returns None if the argument holds no strings.
+fn parse_datetimes(ob: &PyAny, name: &str) -> PyResult<Option<Vec<i64>>> {
+    let parse = |text: &str| {
+        parse_datetime(text).map_err(|err| match err {
+            ParseError::Invalid => PyValueError::new_err(format!(
+                "argument '{name}': invalid ISO 8601 datetime '{text}'"
+            )),
+            ParseError::OutOfRange => PyValueError::new_err(format!(
+                "argument '{name}': datetime '{text}' out of the nanosecond range"
+            )),
+        })
+    };
+    if let Ok(text) = ob.downcast::<PyString>() {
+        return Ok(Some(vec![parse(text.to_str()?)?]));
+    }
+    let textual = match ob.downcast::<PyUntypedArray>() {
+        Ok(array) => array.ndim() == 1 && array.dtype().kind() == b'U',
+        Err(_) => ob
+            .downcast::<PySequence>()
+            .ok()
+            .and_then(|sequence| sequence.get_item(0).ok())
+            .is_some_and(|item| item.is_instance_of::<PyString>()),
+    };
+    if !textual {
+        return Ok(None);
+    }
+    ob.iter()?
+        .enumerate()
+        .map(|(position, item)| {
+            let item = item?;
+            let text = item.downcast::<PyString>().map_err(|_| {
+                PyTypeError::new_err(format!(
+                    "argument '{name}': element {position} has an invalid type"
+                ))
+            })?;
+            parse(text.to_str()?)
+        })
+        .collect::<PyResult<Vec<i64>>>()
+        .map(Some)
+}
+
+/// Parses the Python rounding method argument.
+fn parse_method(method: Option<MethodArg>) -> PyResult<Method> {
+    match method {
+        None => Ok(Method::None),
+        Some(MethodArg::Enum(method)) => Ok(method.into()),
+        Some(MethodArg::Str("nearest")) => Ok(Method::Nearest),
+        Some(MethodArg::Str("ffill")) => Ok(Method::ForwardFill),
+        Some(MethodArg::Str("bfill")) => Ok(Method::BackwardFill),
+        Some(MethodArg::Str(_)) => Err(PyValueError::new_err(
+            "method must be either None, 'nearest', 'ffill' or 'bfill'",
+        )),
+    }
+}
+
+/// Parses the Python bounds inclusivity argument.
+fn parse_closed(closed: &str) -> PyResult<Closed> {
+    match closed {
+        "both" => Ok(Closed::Both),
+        "left" => Ok(Closed::Left),
+        "right" => Ok(Closed::Right),
+        _ => Err(PyValueError::new_err(
+            "closed must be either 'both', 'left' or 'right'",
+        )),
+    }
+}
+
+/// Parses the Python bounds policy argument.
+fn parse_bounds(bounds: BoundsArg) -> PyResult<SidedBounds> {
+    let parse = |bounds| match bounds {
+        "raise" => Ok(Bounds::Error),
+        "clip" => Ok(Bounds::Clamp),
+        _ => Err(PyValueError::new_err(
+            "bounds must be either 'raise' or 'clip', or a pair of them",
+        )),
+    };
+    match bounds {
+        BoundsArg::Both(bounds) => Ok(parse(bounds)?.into()),
+        BoundsArg::Sided(left, right) => Ok(SidedBounds {
+            left: parse(left)?,
+            right: parse(right)?,
+        }),
+    }
+}
+
+/// Parses the Python duplicate indices policy argument.
+fn parse_duplicates(duplicates: &str) -> PyResult<Collision> {
+    match duplicates {
+        "raise" => Ok(Collision::Error),
+        "first" => Ok(Collision::KeepFirst),
+        _ => Err(PyValueError::new_err(
+            "duplicates must be either 'raise' or 'first'",
+        )),
+    }
+}
+
+/// Converts the errors of sorting data points into Python exceptions, the indices being
+/// strictly increasing once sorted unless duplicated.
+fn sort_error(err: BuildError) -> PyErr {
+    match err {
+        BuildError::NotStrictlyIncreasing => PyValueError::new_err("xp has duplicate values"),
+        BuildError::LengthMismatch(..) => PyValueError::new_err(err.to_string()),
+    }
+}
+
+/// Wraps packed values with the CF attributes.
+fn packed(interp: Interp<u64, i64>, scale_factor: f64, add_offset: f64) -> PyResult<Packed> {
+    Packed::new(interp, scale_factor, add_offset)
+        .map_err(|err| PyValueError::new_err(err.to_string()))
+}
+
+/// Parses the Python gap policy argument.
+fn parse_gaps(gaps: &str) -> PyResult<GapPolicy> {
+    match gaps {
+        "raise" => Ok(GapPolicy::Error),
+        "snap" => Ok(GapPolicy::Snap),
+        _ => Err(PyValueError::new_err(
+            "gaps must be either 'raise' or 'snap'",
+        )),
+    }
+}
+
+/// Parses the Python gap threshold arguments, resolving a threshold relative to the median
+/// steepness once for the whole batch. Returns None if gaps are not checked.
+fn parse_max_gap<F>(
+    interp: &Interp<u64, F>,
+    max_gap: Option<f64>,
+    max_gap_factor: Option<f64>,
+) -> PyResult<Option<MaxGap>>
+where
+    u64: Forward<F>,
+    F: Inverse<u64> + Extended,
+{
+    match (max_gap, max_gap_factor) {
+        (Some(_), Some(_)) => Err(PyValueError::new_err(
+            "max_gap and max_gap_factor cannot be both provided",
+        )),
+        (Some(max_gap), None) => Ok(Some(MaxGap::Absolute(max_gap))),
+        (None, Some(factor)) => Ok(interp.median_steepness().map(|median| {
+            let threshold = F80::from_finite(factor).map(|factor| median.mul(&factor).into());
+            MaxGap::Absolute(threshold.unwrap_or(factor))
+        })),
+        (None, None) => Ok(None),
+    }
+}
+
+/// Decimates the data points with either the Python step `k` or the maximum count `n`.
+fn parse_decimation<F>(
+    interp: &Interp<u64, F>,
+    k: Option<usize>,
+    n: Option<usize>,
+) -> PyResult<Interp<u64, F>>
+where
+    u64: Forward<F>,
+    F: Inverse<u64>,
+{
+    match (k, n) {
+        (Some(k), None) => interp.decimate(k).map_err(forward_error),
+        (None, Some(n)) => interp.decimate_to(n).map_err(forward_error),
+        _ => Err(PyValueError::new_err("either k or n must be provided")),
+    }
+}
+
+/// Parses the Python smoothing kind argument.
+fn parse_smoothing(kind: &str) -> PyResult<Smoothing> {
+    match kind {
+        "mean" => Ok(Smoothing::Mean),
+        "median" => Ok(Smoothing::Median),
+        _ => Err(PyValueError::new_err(
+            "kind must be either 'mean' or 'median'",
+        )),
+    }
+}
+
+/// Output dtypes of the binding functions, converted element-wise in the interpolation loop.
+#[derive(Clone, Copy, PartialEq)]
+enum Dtype {
+    Float32,
+    Float64,
+    Int64,
+    UInt64,
+}
+
+impl Dtype {
+    /// Returns the numpy name of the dtype.
+    fn name(self) -> &'static str {
+        match self {
+            Dtype::Float32 => "float32",
+            Dtype::Float64 => "float64",
+            Dtype::Int64 => "int64",
+            Dtype::UInt64 => "uint64",
+        }
+    }
+}
+
+/// Parses the Python output dtype argument among the supported ones.
+fn parse_dtype(dtype: &str, supported: &[Dtype]) -> PyResult<Dtype> {
+    match supported.iter().find(|supported| supported.name() == dtype) {
+        Some(dtype) => Ok(*dtype),
+        None => {
+            let names: Vec<&str> = supported.iter().map(|dtype| dtype.name()).collect();
+            Err(PyValueError::new_err(format!(
+                "dtype must be either {}",
+                names.join(" or ")
+            )))
+        }
+    }
+}
+
+/// Rounds a float64 to the nearest float32 (ties to even), failing with `Overflow` instead of
+/// saturating to infinity beyond the float32 range.
+fn to_f32(value: f64) -> Result<f32, InterpError> {
+    let narrow = value as f32;
+    if narrow.is_infinite() && value.is_finite() {
+        Err(InterpError::Overflow)
+    } else {
+        Ok(narrow)
+    }
+}
+
+/// Converts an index to int64, failing with `Overflow` beyond the int64 range.
+fn to_i64(index: u64) -> Result<i64, InterpError> {
+    i64::try_from(index).map_err(|_| InterpError::Overflow)
+}
+
+/// Converts the indices of a batch to a numpy array of the given dtype.
+fn indices_to_py(py: Python<'_>, indices: Vec<u64>, dtype: Dtype) -> PyResult<PyObject> {
+    match dtype {
+        Dtype::Int64 => {
+            let indices = indices
+                .into_iter()
+                .map(to_i64)
+                .collect::<Result<Vec<i64>, InterpError>>()
+                .map_err(inverse_error)?;
+            Ok(indices.into_pyarray(py).into_py(py))
+        }
+        _ => Ok(indices.into_pyarray(py).into_py(py)),
+    }
+}
+
+/// Parses the Python error handling argument. Returns whether failing elements are masked.
+fn parse_errors(errors: &str) -> PyResult<bool> {
+    match errors {
+        "raise" => Ok(false),
+        "mask" => Ok(true),
+        _ => Err(PyValueError::new_err(
+            "errors must be either 'raise' or 'mask'",
+        )),
+    }
+}
+
+/// Applies an interpolation to each query. Failing elements either raise or, if `errors` is
+/// "mask" and the failure is `OutOfBounds`, `NotFound` or `InGap`, are masked. In the latter
+/// case, a (values, mask) tuple is returned instead of the values alone. The GIL is released
+/// while interpolating so that other Python threads can run meanwhile.
+fn batch<T, U>(
+    py: Python<'_>,
+    queries: ArrayView1<'_, T>,
+    errors: &str,
+    func: impl Fn(T) -> Result<U, InterpError> + Sync,
+    to_pyerr: fn(InterpError) -> PyErr,
+) -> PyResult<PyObject>
+where
+    T: Copy + Sync,
+    U: Element + Default + Send,
+{
+    let masked = parse_errors(errors)?;
+    let (out, mask) = py
+        .allow_threads(|| {
+            let mut out = Vec::with_capacity(queries.len());
+            let mut mask = Vec::with_capacity(if masked { queries.len() } else { 0 });
+            for query in queries.iter() {
+                match func(*query) {
+                    Ok(value) => {
+                        out.push(value);
+                        if masked {
+                            mask.push(false);
+                        }
+                    }
+                    Err(InterpError::OutOfBounds | InterpError::NotFound | InterpError::InGap)
+                        if masked =>
+                    {
+                        out.push(U::default());
+                        mask.push(true);
+                    }
+                    Err(err) => return Err(err),
+                }
+            }
+            Ok((out, mask))
+        })
+        .map_err(to_pyerr)?;
+    if masked {
+        Ok((out.into_pyarray(py), mask.into_pyarray(py)).into_py(py))
+    } else {
+        Ok(out.into_pyarray(py).into_py(py))
+    }
+}
+
+/// Applies forward interpolation to each index with the semantics of `batch`, the checks common
+/// to all indices being done once for the whole batch (see `Interp::forward_batch`).
+fn forward_batch<F>(
+    py: Python<'_>,
+    x: ArrayView1<'_, u64>,
+    errors: &str,
+    interp: &Interp<u64, F>,
+    method: Method,
+) -> PyResult<PyObject>
+where
+    u64: Forward<F>,
+    F: Inverse<u64> + Element + Default + Send + Sync,
+{
+    let masked = parse_errors(errors)?;
+    let result = py.allow_threads(|| {
+        let x = x
+            .as_slice()
+            .map_or_else(|| Cow::Owned(x.to_vec()), Cow::Borrowed);
+        if masked {
+            interp.forward_batch_masked(&x, method)
+        } else {
+            interp
+                .forward_batch(&x, method)
+                .map(|out| (out, Vec::new()))
+                .map_err(|err| err.error)
+        }
+    });
+    let (out, mask) = result.map_err(forward_error)?;
+    if masked {
+        Ok((out.into_pyarray(py), mask.into_pyarray(py)).into_py(py))
+    } else {
+        Ok(out.into_pyarray(py).into_py(py))
+    }
+}
+
+/// Applies inverse interpolation to integer values of another type than the values with the
+/// semantics of `batch` (see `Interp::inverse_from`).
+fn inverse_from_batch<Q, F>(
+    py: Python<'_>,
+    f: ArrayView1<'_, Q>,
+    errors: &str,
+    interp: &Interp<u64, F>,
+    method: Method,
+    dtype: Dtype,
+) -> PyResult<PyObject>
+where
+    Q: Into<i128> + Copy + Sync,
+    u64: Forward<F>,
+    F: Inverse<u64> + Copy + Into<i128> + Sync,
+{
+    match dtype {
+        Dtype::Int64 => batch(
+            py,
+            f,
+            errors,
+            |value| interp.inverse_from(value, method).and_then(to_i64),
+            inverse_error,
+        ),
+        _ => batch(
+            py,
+            f,
+            errors,
+            |value| interp.inverse_from(value, method),
+            inverse_error,
+        ),
+    }
+}
+
+/// Extracts the queries and the data points of each pair from three sequences of arrays. The
+/// arguments are named after `name`, the name of the queries ("x" or "f").
+fn extract_pairs<Q, F>(
+    queries: &PyAny,
+    xp: &PyAny,
+    fp: &PyAny,
+    name: &str,
+) -> PyResult<Vec<Pair<Q, F>>>
+where
+    Q: Promote + for<'a> FromPyObject<'a>,
+    F: Promote + for<'a> FromPyObject<'a>,
+{
+    fn sequence<'py>(ob: &'py PyAny, name: &str) -> PyResult<&'py PySequence> {
+        ob.downcast::<PySequence>().map_err(|_| {
+            PyTypeError::new_err(format!(
+                "argument '{name}_list': expected a sequence of arrays"
+            ))
+        })
+    }
+    let queries = sequence(queries, name)?;
+    let xp = sequence(xp, "xp")?;
+    let fp = sequence(fp, "fp")?;
+    let len = queries.len()?;
+    if xp.len()? != len || fp.len()? != len {
+        return Err(PyValueError::new_err(format!(
+            "{name}_list, xp_list and fp_list must have the same length"
+        )));
+    }
+    (0..len)
+        .map(|index| {
+            let q =
+                ArrayArg::<Q>::extract(queries.get_item(index)?, &format!("{name}_list[{index}]"))?;
+            let xp = ArrayArg::<u64>::extract(xp.get_item(index)?, &format!("xp_list[{index}]"))?;
+            let fp = ArrayArg::<F>::extract(fp.get_item(index)?, &format!("fp_list[{index}]"))?;
+            if xp.as_array().len() != fp.as_array().len() {
+                return Err(PyValueError::new_err(format!(
+                    "xp_list[{index}] and fp_list[{index}] must have the same length"
+                )));
+            }
+            Ok((
+                q.as_array().to_vec(),
+                xp.as_array().to_vec(),
+                fp.as_array().to_vec(),
+            ))
+        })
+        .collect()
+}
+
+/// Converts the results of a batch over multiple pairs into a (results, errors) tuple. Failing
+/// pairs are None in the list of results and their exception is stored in a dict by pair index.
+fn multi_to_py<U: Element>(
+    py: Python<'_>,
+    results: Vec<Result<Vec<U>, BatchError>>,
+    to_pyerr: fn(BatchError) -> CrateError,
+) -> PyResult<PyObject> {
+    let errors = PyDict::new(py);
+    let mut out = Vec::with_capacity(results.len());
+    for (index, result) in results.into_iter().enumerate() {
+        match result {
+            Ok(values) => out.push(values.into_pyarray(py).into_py(py)),
+            Err(err) => {
+                errors.set_item(index, PyErr::from(to_pyerr(err)).into_value(py))?;
+                out.push(py.None());
+            }
+        }
+    }
+    Ok((out, errors).into_py(py))
+}
+
+/// Errors of the crate, along with the direction of the interpolation that failed so that
+/// all bindings translate them into the same Python exceptions.
+enum CrateError {
+    /// A forward interpolation failed.
+    Forward(InterpError),
+    /// An inverse interpolation failed.
+    Inverse(InterpError),
+    /// A forward interpolation failed on an element of a batch.
+    ForwardBatch(BatchError),
+    /// An inverse interpolation failed on an element of a batch.
+    InverseBatch(BatchError),
+}
+
+impl From<CrateError> for PyErr {
+    /// Converts crate errors into Python exceptions: invalid data points raise ValueError,
+    /// overflows OverflowError, and failing queries IndexError for indices (forward) or
+    /// KeyError for values (inverse).
+    fn from(err: CrateError) -> PyErr {
+        let (err, forward, index) = match err {
+            CrateError::Forward(err) => (err, true, None),
+            CrateError::Inverse(err) => (err, false, None),
+            CrateError::ForwardBatch(err) => (err.error, true, Some(err.index)),
+            CrateError::InverseBatch(err) => (err.error, false, Some(err.index)),
+        };
+        let (query, output) = if forward { ("x", "f") } else { ("f", "x") };
+        let query = match index {
+            Some(index) => format!("{query}[{index}]"),
+            None => query.to_string(),
+        };
+        match err {
+            InterpError::OutOfBounds | InterpError::NotFound | InterpError::InGap if forward => {
+                PyIndexError::new_err(format!("{query} {err}"))
+            }
+            InterpError::OutOfBounds | InterpError::NotFound | InterpError::InGap => {
+                PyKeyError::new_err(format!("{query} {err}"))
+            }
+            InterpError::Overflow => PyOverflowError::new_err(format!("{output} {err}")),
+            InterpError::NotStrictlyIncreasing
+            | InterpError::NotStrictlyMonotonic
+            | InterpError::LengthMismatch(..)
+            | InterpError::NoSamples
+            | InterpError::Empty
+            | InterpError::InvalidPeriod
+            | InterpError::InvalidArgument(_) => PyValueError::new_err(err.to_string()),
+        }
+    }
+}
+
+/// Converts forward interpolation errors into Python exceptions.
+fn forward_error(err: InterpError) -> PyErr {
+    CrateError::Forward(err).into()
+}
+
+/// Converts inverse interpolation errors into Python exceptions.
+fn inverse_error(err: InterpError) -> PyErr {
+    CrateError::Inverse(err).into()
+}
+
+/// Statistics on the exactness of a batch of inverse interpolations exposed to Python.
+#[pyclass(name = "InverseStats")]
+struct PyInverseStats {
+    #[pyo3(get)]
+    exact_count: usize,
+    #[pyo3(get)]
+    rounded_count: usize,
+    #[pyo3(get)]
+    max_abs_residual: PyObject,
+}
+
+#[pymethods]
+impl PyInverseStats {
+    fn __repr__(&self, py: Python<'_>) -> String {
+        format!(
+            "InverseStats(exact_count={}, rounded_count={}, max_abs_residual={})",
+            self.exact_count,
+            self.rounded_count,
+            self.max_abs_residual.as_ref(py)
+        )
+    }
+}
+
+/// Converts inverse statistics into their Python counterpart. The residual of an empty batch
+/// is reported as zero.
+fn stats_to_py<D: IntoPy<PyObject> + Default>(
+    py: Python<'_>,
+    stats: InverseStats<D>,
+) -> PyResult<Py<PyInverseStats>> {
+    Py::new(
+        py,
+        PyInverseStats {
+            exact_count: stats.exact_count,
+            rounded_count: stats.rounded_count,
+            max_abs_residual: stats.max_abs_residual.unwrap_or_default().into_py(py),
+        },
+    )
+}
+
+/// Report of the comparison of dense values to an interpolation exposed to Python.
+#[pyclass(name = "Verification")]
+struct PyVerification {
+    #[pyo3(get)]
+    violations: usize,
+    #[pyo3(get)]
+    max_deviation: PyObject,
+    #[pyo3(get)]
+    argmax: Option<usize>,
+}
+
+#[pymethods]
+impl PyVerification {
+    fn __repr__(&self, py: Python<'_>) -> String {
+        format!(
+            "Verification(violations={}, max_deviation={}, argmax={})",
+            self.violations,
+            self.max_deviation.as_ref(py),
+            self.argmax.into_py(py).as_ref(py)
+        )
+    }
+}
+
+/// Converts a verification report into its Python counterpart.
+fn verification_to_py<D: IntoPy<PyObject>>(
+    py: Python<'_>,
+    report: Verification<D>,
+) -> PyResult<Py<PyVerification>> {
+    Py::new(
+        py,
+        PyVerification {
+            violations: report.violations,
+            max_deviation: report.max_deviation.into_py(py),
+            argmax: report.argmax,
+        },
+    )
+}
+
+/// Data points of the Python `Interp` class, either integer or float values.
+enum SharedInterp {
+    Int(Arc<Interp<u64, i64>>),
+    Float(Arc<Interp<u64, f64>>),
+}
+
+impl SharedInterp {
+    /// Returns the number of data points.
+    fn len(&self) -> usize {
+        match self {
+            SharedInterp::Int(interp) => interp.len(),
+            SharedInterp::Float(interp) => interp.len(),
+        }
+    }
+    /// Returns the indices.
+    fn xp(&self) -> &[u64] {
+        match self {
+            SharedInterp::Int(interp) => interp.xp(),
+            SharedInterp::Float(interp) => interp.xp(),
+        }
+    }
+    /// Returns a copy of the values as a numpy array.
+    fn fp(&self, py: Python<'_>) -> PyObject {
+        match self {
+            SharedInterp::Int(interp) => PyArray1::from_slice(py, interp.fp()).into_py(py),
+            SharedInterp::Float(interp) => PyArray1::from_slice(py, interp.fp()).into_py(py),
+        }
+    }
+    /// Returns the data point at the given position as an (x, f) tuple, None if out of range.
+    fn pair(&self, py: Python<'_>, index: usize) -> Option<PyObject> {
+        match self {
+            SharedInterp::Int(interp) => interp.get(index).map(|(x, f)| (*x, *f).into_py(py)),
+            SharedInterp::Float(interp) => interp.get(index).map(|(x, f)| (*x, *f).into_py(py)),
+        }
+    }
+    /// Selects the data points in the given range of positions (see `Interp::slice`).
+    fn slice(&self, range: Range<usize>) -> Option<SharedInterp> {
+        match self {
+            SharedInterp::Int(interp) => interp.slice(range).map(Arc::new).map(SharedInterp::Int),
+            SharedInterp::Float(interp) => {
+                interp.slice(range).map(Arc::new).map(SharedInterp::Float)
+            }
+        }
+    }
+    /// Formats the values, eliding the middle ones of long arrays.
+    fn format_fp(&self) -> String {
+        match self {
+            SharedInterp::Int(interp) => format_values(interp.fp()),
+            SharedInterp::Float(interp) => format_values(interp.fp()),
+        }
+    }
+}
+
+/// Formats values as a Python list, eliding the middle ones beyond six values.
+fn format_values<T: std::fmt::Debug>(values: &[T]) -> String {
+    let items: Vec<String> = if values.len() > 6 {
+        let (head, tail) = (&values[..3], &values[values.len() - 3..]);
+        let head = head.iter().map(|value| format!("{value:?}"));
+        let tail = tail.iter().map(|value| format!("{value:?}"));
+        head.chain(std::iter::once("...".to_string()))
+            .chain(tail)
+            .collect()
+    } else {
+        values.iter().map(|value| format!("{value:?}")).collect()
+    };
+    format!("[{}]", items.join(", "))
+}
+
+/// Interpolation exposed to Python as `xinterp.Interp`. Unlike the module functions, which
+/// rebuild the data points at each call, the data points are checked once on creation and held
+/// behind an `Arc`. The class is frozen, so one object can be shared by many threads: `forward`
+/// and `inverse` release the GIL while they run.
+#[pyclass(name = "Interp", frozen)]
+struct PyInterp(SharedInterp);
+
+#[pymethods]
+impl PyInterp {
+    #[new]
+    #[pyo3(signature = (xp, fp, closed = "both", bounds = BoundsArg::Both("raise")))]
+    fn new(xp: &PyAny, fp: &PyAny, closed: &str, bounds: BoundsArg) -> PyResult<Self> {
+        let xp = ArrayArg::<u64>::extract(xp, "xp")?.as_array().to_vec();
+        let closed = parse_closed(closed)?;
+        let bounds = parse_bounds(bounds)?;
+        let shared = match ArrayArg::<i64>::extract(fp, "fp") {
+            Ok(fp) => {
+                let fp = fp.as_array().to_vec();
+                SharedInterp::Int(shared_interp(xp, fp, closed, bounds)?)
+            }
+            Err(err) if err.is_instance_of::<PyTypeError>(fp.py()) => {
+                let fp = ArrayArg::<f64>::extract(fp, "fp")?.as_array().to_vec();
+                SharedInterp::Float(shared_interp(xp, fp, closed, bounds)?)
+            }
+            Err(err) => return Err(err),
+        };
+        Ok(PyInterp(shared))
+    }
+    fn __len__(&self) -> usize {
+        self.0.len()
+    }
+    /// Returns the (x, f) data point at a position, negative positions counting from the end,
+    /// or a new Interp of the data points selected by a slice with a step of one.
+    fn __getitem__(&self, py: Python<'_>, key: &PyAny) -> PyResult<PyObject> {
+        let len = self.0.len();
+        if let Ok(slice) = key.downcast::<PySlice>() {
+            let indices = slice.indices(len as c_long)?;
+            if indices.step != 1 {
+                return Err(PyValueError::new_err("slice step must be 1"));
+            }
+            let start = indices.start as usize;
+            let range = start..start + indices.slicelength as usize;
+            let shared = self.0.slice(range).expect("slice within the data points");
+            return Ok(PyInterp(shared).into_py(py));
+        }
+        let index: isize = key.extract()?;
+        let index = if index < 0 {
+            index + len as isize
+        } else {
+            index
+        };
+        usize::try_from(index)
+            .ok()
+            .and_then(|index| self.0.pair(py, index))
+            .ok_or_else(|| PyIndexError::new_err("Interp index out of range"))
+    }
+    /// Iterates over the data points as (x, f) tuples.
+    fn __iter__<'py>(&self, py: Python<'py>) -> PyResult<&'py PyIterator> {
+        let pairs: Vec<PyObject> = (0..self.0.len())
+            .filter_map(|index| self.0.pair(py, index))
+            .collect();
+        PyIterator::from_object(PyList::new(py, pairs))
+    }
+    fn __repr__(&self) -> String {
+        format!(
+            "Interp(xp={}, fp={})",
+            format_values(self.0.xp()),
+            self.0.format_fp()
+        )
+    }
+    /// Returns a copy of the indices.
+    #[getter]
+    fn xp<'py>(&self, py: Python<'py>) -> &'py PyArray1<u64> {
+        PyArray1::from_slice(py, self.0.xp())
+    }
+    /// Returns a copy of the values.
+    #[getter]
+    fn fp(&self, py: Python<'_>) -> PyObject {
+        self.0.fp(py)
+    }
+    /// Performs forward interpolation at each index, integer values being rounded with
+    /// `method`.
+    #[pyo3(signature = (x, method = Some(MethodArg::Str("nearest")), errors = "raise"))]
+    fn forward(
+        &self,
+        py: Python<'_>,
+        x: &PyAny,
+        method: Option<MethodArg>,
+        errors: &str,
+    ) -> PyResult<PyObject> {
+        let x = ArrayArg::<u64>::extract(x, "x")?;
+        let x = x.as_array();
+        let method = parse_method(method)?;
+        match &self.0 {
+            SharedInterp::Int(interp) => forward_batch(py, x, errors, interp, method),
+            SharedInterp::Float(interp) => forward_batch(py, x, errors, interp, Method::Nearest),
+        }
+    }
+    /// Performs inverse interpolation at each value, inexact matches being rounded with
+    /// `method`.
+    #[pyo3(signature = (f, method = None, errors = "raise"))]
+    fn inverse(
+        &self,
+        py: Python<'_>,
+        f: &PyAny,
+        method: Option<MethodArg>,
+        errors: &str,
+    ) -> PyResult<PyObject> {
+        let method = parse_method(method)?;
+        match &self.0 {
+            SharedInterp::Int(interp) => {
+                let f = ArrayArg::<i64>::extract(f, "f")?;
+                batch(
+                    py,
+                    f.as_array(),
+                    errors,
+                    |value| interp.inverse(value, method),
+                    inverse_error,
+                )
+            }
+            SharedInterp::Float(interp) => {
+                let f = ArrayArg::<f64>::extract(f, "f")?;
+                batch(
+                    py,
+                    f.as_array(),
+                    errors,
+                    |value| interp.inverse(value, method),
+                    inverse_error,
+                )
+            }
+        }
+    }
+}
+
+/// Builds the data points of the Python `Interp` class, checking that their lengths match and
+/// that the indices are strictly increasing.
+fn shared_interp<F>(
+    xp: Vec<u64>,
+    fp: Vec<F>,
+    closed: Closed,
+    bounds: SidedBounds,
+) -> PyResult<Arc<Interp<u64, F>>>
+where
+    u64: Forward<F>,
+    F: Inverse<u64>,
+{
+    let interp = Interp::from_parts(xp, fp).map_err(forward_error)?;
+    if !interp.is_forwardable() {
+        return Err(forward_error(InterpError::NotStrictlyIncreasing));
+    }
+    Ok(Arc::new(interp.with_closed(closed).with_bounds(bounds)))
+}
+
+/// Decodes the rows of raw bytes of a numpy long double array (x87 layout in the first ten bytes
+/// of each row) into F80s.
+fn decode_longdouble(bytes: PyReadonlyArray2<'_, u8>) -> PyResult<Vec<F80>> {
+    let bytes = bytes.as_array();
+    if bytes.ncols() < 10 {
+        return Err(PyValueError::new_err(
+            "long double must have at least 80 bits",
+        ));
+    }
+    bytes
+        .rows()
+        .into_iter()
+        .map(|row| {
+            let bits = row
+                .iter()
+                .take(10)
+                .rev()
+                .fold(0u128, |bits, byte| (bits << 8) | *byte as u128);
+            F80::from_bits(bits).ok_or_else(|| PyValueError::new_err("values must be finite"))
+        })
+        .collect()
+}
+
+/// Encodes F80s into rows of raw bytes in the x87 layout.
+fn encode_longdouble(values: &[F80]) -> Array2<u8> {
+    let mut out = Array2::zeros((values.len(), 10));
+    for (value, mut row) in values.iter().zip(out.rows_mut()) {
+        let bits = value.to_bits();
+        for (index, byte) in row.iter_mut().enumerate() {
+            *byte = (bits >> (8 * index)) as u8;
+        }
+    }
+    out
+}
+
+/// Converts decoding errors into Python exceptions.
+fn decode_error(err: DecodeError) -> PyErr {
+    PyValueError::new_err(err.to_string())
+}
+
+/// Converts .npy reading errors into Python exceptions: unreadable files raise OSError (or
+/// FileNotFoundError), invalid ones ValueError.
+#[cfg(feature = "npy")]
+fn npy_error(err: NpyError) -> PyErr {
+    match err {
+        NpyError::Io(std::io::ErrorKind::NotFound) => PyFileNotFoundError::new_err(err.to_string()),
+        NpyError::Io(_) => PyOSError::new_err(err.to_string()),
+        _ => PyValueError::new_err(err.to_string()),
+    }
+}
+
+/// Extracts a non-negative tolerance.
+#[cfg(feature = "npy")]
+fn extract_epsilon<'a, T: FromPyObject<'a> + PartialOrd + Default>(
+    epsilon: &'a PyAny,
+) -> PyResult<T> {
+    let epsilon: T = epsilon.extract()?;
+    if !matches!(
+        epsilon.partial_cmp(&T::default()),
+        Some(Ordering::Greater | Ordering::Equal)
+    ) {
+        return Err(PyValueError::new_err("epsilon must be non-negative"));
+    }
+    Ok(epsilon)
+}
+
+/// Returns the indices and the values of the data points simplified from the dense values
+/// of a .npy file (see `Interp::from_npy_dense`).
+#[cfg(feature = "npy")]
+#[pyfunction]
+fn load_npy_dense<'py>(
+    py: Python<'py>,
+    path: PathBuf,
+    epsilon: &'py PyAny,
+) -> PyResult<(PyObject, PyObject)> {
+    let descr = NpyArray::open(&path)
+        .map_err(npy_error)?
+        .descr()
+        .to_string();
+    let (xp, fp) = match descr.as_str() {
+        "<i8" => {
+            let epsilon = extract_epsilon::<i64>(epsilon)?;
+            let (xp, fp) = py
+                .allow_threads(|| Interp::<u64, i64>::from_npy_dense(&path, epsilon))
+                .map_err(npy_error)?
+                .into_parts();
+            (xp, fp.into_pyarray(py).into_py(py))
+        }
+        "<u8" => {
+            let epsilon = extract_epsilon::<u64>(epsilon)?;
+            let (xp, fp) = py
+                .allow_threads(|| Interp::<u64, u64>::from_npy_dense(&path, epsilon))
+                .map_err(npy_error)?
+                .into_parts();
+            (xp, fp.into_pyarray(py).into_py(py))
+        }
+        _ => {
+            let epsilon = extract_epsilon::<f64>(epsilon)?;
+            let (xp, fp) = py
+                .allow_threads(|| Interp::<u64, f64>::from_npy_dense(&path, epsilon))
+                .map_err(npy_error)?
+                .into_parts();
+            (xp, fp.into_pyarray(py).into_py(py))
+        }
+    };
+    Ok((xp.into_pyarray(py).into_py(py), fp))
+}
+/// Returns the indices and the values of the data points stored in two .npy files (see
+/// `Interp::from_npy_pair`).
+#[cfg(feature = "npy")]
+#[pyfunction]
+fn load_npy_pair<'py>(
+    py: Python<'py>,
+    xp_path: PathBuf,
+    fp_path: PathBuf,
+) -> PyResult<(PyObject, PyObject)> {
+    let descr = NpyArray::open(&fp_path)
+        .map_err(npy_error)?
+        .descr()
+        .to_string();
+    let (xp, fp) = match descr.as_str() {
+        "<i8" => {
+            let (xp, fp) = Interp::<u64, i64>::from_npy_pair(&xp_path, &fp_path)
+                .map_err(npy_error)?
+                .into_parts();
+            (xp, fp.into_pyarray(py).into_py(py))
+        }
+        "<u8" => {
+            let (xp, fp) = Interp::<u64, u64>::from_npy_pair(&xp_path, &fp_path)
+                .map_err(npy_error)?
+                .into_parts();
+            (xp, fp.into_pyarray(py).into_py(py))
+        }
+        _ => {
+            let (xp, fp) = Interp::<u64, f64>::from_npy_pair(&xp_path, &fp_path)
+                .map_err(npy_error)?
+                .into_parts();
+            (xp, fp.into_pyarray(py).into_py(py))
+        }
+    };
+    Ok((xp.into_pyarray(py).into_py(py), fp))
+}
+
+/// Datetime64 value as an integer, NaT (the minimum of i64) being missing: like NaN, it is
+/// neither equal nor comparable to any value.
+#[derive(Clone, Copy)]
+struct DateTime(i64);
+
+impl DateTime {
+    /// The integer value of numpy NaT (not a time).
+    const NAT: i64 = i64::MIN;
+}
+
+impl PartialEq for DateTime {
+    fn eq(&self, other: &DateTime) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+impl PartialOrd for DateTime {
+    fn partial_cmp(&self, other: &DateTime) -> Option<Ordering> {
+        if self.0 == DateTime::NAT || other.0 == DateTime::NAT {
+            return None;
+        }
+        self.0.partial_cmp(&other.0)
+    }
+}
+
+/// Converts a monotonicity report into a Python dict.
+fn report_to_dict(py: Python<'_>, report: MonotonicityReport) -> PyResult<&PyDict> {
+    let dict = PyDict::new(py);
+    let kind = match report.kind {
+        Monotonicity::Strict => "strict",
+        Monotonicity::NonDecreasing => "non-decreasing",
+        Monotonicity::None => "none",
+    };
+    dict.set_item("kind", kind)?;
+    dict.set_item("violations", report.violations)?;
+    dict.set_item("nans", report.nans)?;
+    Ok(dict)
+}
+
+/// Converts the statistics of the data points into a dict, slopes being converted to floats.
+fn summary_to_dict<F: IntoPy<PyObject>>(py: Python<'_>, summary: Summary<F>) -> PyResult<&PyDict> {
+    let dict = PyDict::new(py);
+    dict.set_item("points", summary.points)?;
+    dict.set_item("domain", summary.domain)?;
+    let range = summary
+        .range
+        .map(|(min, max)| (min.into_py(py), max.into_py(py)));
+    dict.set_item("range", range)?;
+    let slopes = summary
+        .slopes
+        .map(|(min, median, max)| (f64::from(min), f64::from(median), f64::from(max)));
+    dict.set_item("slopes", slopes)?;
+    dict.set_item("lengths", summary.lengths)?;
+    dict.set_item("irregular", summary.irregular)?;
+    Ok(dict)
+}
 
 #[pymodule]
 fn rust<'py>(_py: Python<'py>, m: &'py PyModule) -> PyResult<()> {
+    m.add_class::<PyMethod>()?;
+    m.add_class::<PyInterp>()?;
+    m.add_class::<PyInverseStats>()?;
+    m.add_class::<PyVerification>()?;
     #[pyfn(m)]
+    #[pyo3(signature = (
+        x, xp, fp, method = Some(MethodArg::Str("nearest")), closed = "both", errors = "raise",
+        bounds = BoundsArg::Both("raise")
+    ))]
+    #[allow(clippy::too_many_arguments)]
     fn forward_int<'py>(
         py: Python<'py>,
-        x: PyReadonlyArray1<'py, u64>,
-        xp: PyReadonlyArray1<'py, u64>,
-        fp: PyReadonlyArray1<'py, i64>,
-    ) -> PyResult<&'py PyArray1<i64>> {
+        x: &'py PyAny,
+        xp: &'py PyAny,
+        fp: &'py PyAny,
+        method: Option<MethodArg>,
+        closed: &str,
+        errors: &str,
+        bounds: BoundsArg,
+    ) -> PyResult<PyObject> {
+        let x = ArrayArg::<u64>::extract(x, "x")?;
+        let xp = ArrayArg::<u64>::extract(xp, "xp")?;
+        let fp = ArrayArg::<i64>::extract(fp, "fp")?;
         let x = x.as_array();
         let xp = xp.as_array();
         let fp = fp.as_array();
-        let interp = Interp::new(xp.to_vec(), fp.to_vec());
-        let mut f = Array1::zeros(x.len());
-        for (index, value) in x.iter().zip(f.iter_mut()) {
-            match interp.forward(*index) {
-                Ok(result) => *value = result,
-                Err(InterpError::NotStrictlyIncreasing) => {
-                    return Err(PyValueError::new_err("xp must be strictly increasing"))
-                }
-                Err(InterpError::OutOfBounds) => {
-                    return Err(PyIndexError::new_err("x out of bounds"))
-                }
-                Err(InterpError::NotFound) => return Err(PyIndexError::new_err("x not found")),
-            }
-        }
-        Ok(f.into_pyarray(py))
+        let method = parse_method(method)?;
+        let interp = Interp::new(xp.to_vec(), fp.to_vec())
+            .with_closed(parse_closed(closed)?)
+            .with_bounds(parse_bounds(bounds)?);
+        forward_batch(py, x, errors, &interp, method)
     }
     #[pyfn(m)]
+    #[pyo3(signature = (
+        x, xp, fp, closed = "both", errors = "raise", dtype = "float64",
+        bounds = BoundsArg::Both("raise")
+    ))]
+    #[allow(clippy::too_many_arguments)]
     fn forward_float<'py>(
         py: Python<'py>,
-        x: PyReadonlyArray1<'py, u64>,
-        xp: PyReadonlyArray1<'py, u64>,
-        fp: PyReadonlyArray1<'py, f64>,
+        x: &'py PyAny,
+        xp: &'py PyAny,
+        fp: &'py PyAny,
+        closed: &str,
+        errors: &str,
+        dtype: &str,
+        bounds: BoundsArg,
+    ) -> PyResult<PyObject> {
+        let x = ArrayArg::<u64>::extract(x, "x")?;
+        let xp = ArrayArg::<u64>::extract(xp, "xp")?;
+        let fp = ArrayArg::<f64>::extract(fp, "fp")?;
+        let x = x.as_array();
+        let xp = xp.as_array().to_vec();
+        let fp = fp.as_array().to_vec();
+        let interp = Interp::new(xp, fp)
+            .with_closed(parse_closed(closed)?)
+            .with_bounds(parse_bounds(bounds)?);
+        match parse_dtype(dtype, &[Dtype::Float32, Dtype::Float64])? {
+            Dtype::Float32 => batch(
+                py,
+                x,
+                errors,
+                |index| interp.forward(index).and_then(to_f32),
+                forward_error,
+            ),
+            _ => forward_batch(py, x, errors, &interp, Method::Nearest),
+        }
+    }
+    #[pyfn(m)]
+    #[pyo3(signature = (x, xp, fp, closed = "both"))]
+    fn forward_float_at<'py>(
+        py: Python<'py>,
+        x: &'py PyAny,
+        xp: &'py PyAny,
+        fp: &'py PyAny,
+        closed: &str,
     ) -> PyResult<&'py PyArray1<f64>> {
+        let x = ArrayArg::<f64>::extract(x, "x")?;
+        let xp = ArrayArg::<u64>::extract(xp, "xp")?;
+        let fp = ArrayArg::<f64>::extract(fp, "fp")?;
         let x = x.as_array();
         let xp = xp.as_array().to_vec();
         let fp = fp.as_array().to_vec();
-        let interp = Interp::new(xp, fp);
+        let interp = Interp::new(xp, fp).with_closed(parse_closed(closed)?);
         let mut f = Array1::zeros(x.len());
         for (index, value) in x.iter().zip(f.iter_mut()) {
-            match interp.forward(*index) {
-                Ok(result) => *value = result,
-                Err(InterpError::NotStrictlyIncreasing) => {
-                    return Err(PyValueError::new_err("xp must be strictly increasing"))
-                }
-                Err(InterpError::OutOfBounds) => {
-                    return Err(PyIndexError::new_err("x out of bounds"))
-                }
-                Err(InterpError::NotFound) => return Err(PyIndexError::new_err("x not found")),
-            }
+            *value = interp.forward_at(*index).map_err(forward_error)?;
         }
         Ok(f.into_pyarray(py))
     }
     #[pyfn(m)]
+    fn forward_periodic_int<'py>(
+        py: Python<'py>,
+        x: &'py PyAny,
+        xp: &'py PyAny,
+        fp: &'py PyAny,
+        period: u64,
+    ) -> PyResult<PyObject> {
+        let x = ArrayArg::<i64>::extract(x, "x")?;
+        let xp = ArrayArg::<u64>::extract(xp, "xp")?;
+        let fp = ArrayArg::<i64>::extract(fp, "fp")?;
+        let x = x.as_array();
+        let interp = Interp::new(xp.as_array().to_vec(), fp.as_array().to_vec());
+        batch(
+            py,
+            x,
+            "raise",
+            |index| interp.forward_periodic(index, period),
+            forward_error,
+        )
+    }
+    #[pyfn(m)]
+    fn forward_periodic_float<'py>(
+        py: Python<'py>,
+        x: &'py PyAny,
+        xp: &'py PyAny,
+        fp: &'py PyAny,
+        period: u64,
+    ) -> PyResult<PyObject> {
+        let x = ArrayArg::<i64>::extract(x, "x")?;
+        let xp = ArrayArg::<u64>::extract(xp, "xp")?;
+        let fp = ArrayArg::<f64>::extract(fp, "fp")?;
+        let x = x.as_array();
+        let interp = Interp::new(xp.as_array().to_vec(), fp.as_array().to_vec());
+        batch(
+            py,
+            x,
+            "raise",
+            |index| interp.forward_periodic(index, period),
+            forward_error,
+        )
+    }
+    #[pyfn(m)]
+    fn sort_int<'py>(
+        py: Python<'py>,
+        xp: &'py PyAny,
+        fp: &'py PyAny,
+        duplicates: &str,
+    ) -> PyResult<(&'py PyArray1<u64>, &'py PyArray1<i64>)> {
+        let xp = ArrayArg::<u64>::extract(xp, "xp")?;
+        let fp = ArrayArg::<i64>::extract(fp, "fp")?;
+        let (xp, fp) = Interp::new_sorted(
+            xp.as_array().to_vec(),
+            fp.as_array().to_vec(),
+            parse_duplicates(duplicates)?,
+        )
+        .map_err(sort_error)?
+        .into_parts();
+        Ok((xp.into_pyarray(py), fp.into_pyarray(py)))
+    }
+    #[pyfn(m)]
+    fn sort_float<'py>(
+        py: Python<'py>,
+        xp: &'py PyAny,
+        fp: &'py PyAny,
+        duplicates: &str,
+    ) -> PyResult<(&'py PyArray1<u64>, &'py PyArray1<f64>)> {
+        let xp = ArrayArg::<u64>::extract(xp, "xp")?;
+        let fp = ArrayArg::<f64>::extract(fp, "fp")?;
+        let (xp, fp) = Interp::new_sorted(
+            xp.as_array().to_vec(),
+            fp.as_array().to_vec(),
+            parse_duplicates(duplicates)?,
+        )
+        .map_err(sort_error)?
+        .into_parts();
+        Ok((xp.into_pyarray(py), fp.into_pyarray(py)))
+    }
+    #[pyfn(m)]
+    #[pyo3(signature = (
+        f, xp, fp, method, closed = "both", return_stats = false, errors = "raise",
+        max_gap = None, max_gap_factor = None, gaps = "raise", limit = None, dtype = "uint64",
+        bounds = BoundsArg::Both("raise")
+    ))]
+    #[allow(clippy::too_many_arguments)]
     fn inverse_int<'py>(
         py: Python<'py>,
-        f: PyReadonlyArray1<'py, i64>,
-        xp: PyReadonlyArray1<'py, u64>,
-        fp: PyReadonlyArray1<'py, i64>,
-        method: Option<&str>,
-    ) -> PyResult<&'py PyArray1<u64>> {
+        f: &'py PyAny,
+        xp: &'py PyAny,
+        fp: &'py PyAny,
+        method: Option<MethodArg>,
+        closed: &str,
+        return_stats: bool,
+        errors: &str,
+        max_gap: Option<f64>,
+        max_gap_factor: Option<f64>,
+        gaps: &str,
+        limit: Option<u64>,
+        dtype: &str,
+        bounds: BoundsArg,
+    ) -> PyResult<PyObject> {
+        let f = match parse_datetimes(f, "f")? {
+            Some(values) => ArrayArg::Vec(values),
+            None => ArrayArg::<i64>::extract(f, "f")?,
+        };
+        let xp = ArrayArg::<u64>::extract(xp, "xp")?;
+        let fp = ArrayArg::<i64>::extract(fp, "fp")?;
         let f = f.as_array();
         let xp = xp.as_array();
         let fp = fp.as_array();
-        let method = match method {
-            None => Method::None,
-            Some("nearest") => Method::Nearest,
-            Some("ffill") => Method::ForwardFill,
-            Some("bfill") => Method::BackwardFill,
-            Some(_) => {
+        let method = parse_method(method)?;
+        let interp = Interp::new(xp.to_vec(), fp.to_vec())
+            .with_closed(parse_closed(closed)?)
+            .with_bounds(parse_bounds(bounds)?);
+        let max_gap = parse_max_gap(&interp, max_gap, max_gap_factor)?;
+        let policy = parse_gaps(gaps)?;
+        let dtype = parse_dtype(dtype, &[Dtype::Int64, Dtype::UInt64])?;
+        if max_gap.is_some() && limit.is_some() {
+            return Err(PyValueError::new_err(
+                "max_gap and limit cannot be both provided",
+            ));
+        }
+        if return_stats {
+            if parse_errors(errors)? {
                 return Err(PyValueError::new_err(
-                    "method must be either None, 'nearest', 'ffill' or 'bfill'",
-                ))
+                    "return_stats is not supported with errors='mask'",
+                ));
             }
-        };
-        let interp = Interp::new(xp.to_vec(), fp.to_vec());
-        let mut x = Array1::zeros(f.len());
-        for (value, index) in f.iter().zip(x.iter_mut()) {
-            match interp.inverse(*value, method) {
-                Ok(result) => *index = result,
-                Err(InterpError::NotStrictlyIncreasing) => {
-                    return Err(PyValueError::new_err("fp must be strictly increasing"))
-                }
-                Err(InterpError::OutOfBounds) => {
-                    return Err(PyKeyError::new_err("f out of bounds"))
-                }
-                Err(InterpError::NotFound) => return Err(PyKeyError::new_err("f not found")),
+            if max_gap.is_some() || limit.is_some() {
+                return Err(PyValueError::new_err(
+                    "return_stats is not supported with max_gap or limit",
+                ));
             }
+            let (x, stats) = interp
+                .inverse_with_stats(&f.to_vec(), method)
+                .map_err(inverse_error)?;
+            let stats = stats_to_py(py, stats)?;
+            return Ok((indices_to_py(py, x, dtype)?, stats).into_py(py));
+        }
+        let query = |value| match (max_gap, limit) {
+            (Some(max_gap), _) => interp.inverse_gapped(value, method, max_gap, policy),
+            (None, Some(limit)) => interp.inverse_limited(value, method, limit),
+            (None, None) => interp.inverse(value, method),
+        };
+        match dtype {
+            Dtype::Int64 => batch(
+                py,
+                f,
+                errors,
+                |value| query(value).and_then(to_i64),
+                inverse_error,
+            ),
+            _ => batch(py, f, errors, query, inverse_error),
         }
-        Ok(x.into_pyarray(py))
     }
     #[pyfn(m)]
+    #[pyo3(signature = (
+        f, xp, fp, method, closed = "both", return_stats = false, errors = "raise",
+        max_gap = None, max_gap_factor = None, gaps = "raise", limit = None, dtype = "uint64",
+        bounds = BoundsArg::Both("raise")
+    ))]
+    #[allow(clippy::too_many_arguments)]
     fn inverse_float<'py>(
         py: Python<'py>,
-        f: PyReadonlyArray1<'py, f64>,
-        xp: PyReadonlyArray1<'py, u64>,
-        fp: PyReadonlyArray1<'py, f64>,
-        method: Option<&str>,
-    ) -> PyResult<&'py PyArray1<u64>> {
+        f: &'py PyAny,
+        xp: &'py PyAny,
+        fp: &'py PyAny,
+        method: Option<MethodArg>,
+        closed: &str,
+        return_stats: bool,
+        errors: &str,
+        max_gap: Option<f64>,
+        max_gap_factor: Option<f64>,
+        gaps: &str,
+        limit: Option<u64>,
+        dtype: &str,
+        bounds: BoundsArg,
+    ) -> PyResult<PyObject> {
+        let f = ArrayArg::<f64>::extract(f, "f")?;
+        let xp = ArrayArg::<u64>::extract(xp, "xp")?;
+        let fp = ArrayArg::<f64>::extract(fp, "fp")?;
         let f = f.as_array();
         let xp = xp.as_array().to_vec();
         let fp = fp.as_array().to_vec();
-        let method = match method {
-            None => Method::None,
-            Some("nearest") => Method::Nearest,
-            Some("ffill") => Method::ForwardFill,
-            Some("bfill") => Method::BackwardFill,
-            Some(_) => {
+        let method = parse_method(method)?;
+        let interp = Interp::new(xp, fp)
+            .with_closed(parse_closed(closed)?)
+            .with_bounds(parse_bounds(bounds)?);
+        let max_gap = parse_max_gap(&interp, max_gap, max_gap_factor)?;
+        let policy = parse_gaps(gaps)?;
+        let dtype = parse_dtype(dtype, &[Dtype::Int64, Dtype::UInt64])?;
+        if max_gap.is_some() && limit.is_some() {
+            return Err(PyValueError::new_err(
+                "max_gap and limit cannot be both provided",
+            ));
+        }
+        if return_stats {
+            if parse_errors(errors)? {
                 return Err(PyValueError::new_err(
-                    "method must be either None, 'nearest', 'ffill' or 'bfill'",
-                ))
+                    "return_stats is not supported with errors='mask'",
+                ));
+            }
+            if max_gap.is_some() || limit.is_some() {
+                return Err(PyValueError::new_err(
+                    "return_stats is not supported with max_gap or limit",
+                ));
             }
+            let (x, stats) = interp
+                .inverse_with_stats(&f.to_vec(), method)
+                .map_err(inverse_error)?;
+            let stats = stats_to_py(py, stats)?;
+            return Ok((indices_to_py(py, x, dtype)?, stats).into_py(py));
+        }
+        let query = |value| match (max_gap, limit) {
+            (Some(max_gap), _) => interp.inverse_gapped(value, method, max_gap, policy),
+            (None, Some(limit)) => interp.inverse_limited(value, method, limit),
+            (None, None) => interp.inverse(value, method),
         };
+        match dtype {
+            Dtype::Int64 => batch(
+                py,
+                f,
+                errors,
+                |value| query(value).and_then(to_i64),
+                inverse_error,
+            ),
+            _ => batch(py, f, errors, query, inverse_error),
+        }
+    }
+    /// Performs inverse interpolation on integer values and queries of possibly different
+    /// signedness, each being either int64 or uint64 (see `Interp::inverse_from`).
+    #[pyfn(m)]
+    #[pyo3(signature = (
+        f, xp, fp, method, closed = "both", errors = "raise", dtype = "uint64",
+        bounds = BoundsArg::Both("raise")
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn inverse_cross<'py>(
+        py: Python<'py>,
+        f: &'py PyAny,
+        xp: &'py PyAny,
+        fp: &'py PyAny,
+        method: Option<MethodArg>,
+        closed: &str,
+        errors: &str,
+        dtype: &str,
+        bounds: BoundsArg,
+    ) -> PyResult<PyObject> {
+        let f = IntegerArg::extract(f, "f")?;
+        let xp = ArrayArg::<u64>::extract(xp, "xp")?;
+        let xp = xp.as_array().to_vec();
+        let method = parse_method(method)?;
+        let closed = parse_closed(closed)?;
+        let bounds = parse_bounds(bounds)?;
+        let dtype = parse_dtype(dtype, &[Dtype::Int64, Dtype::UInt64])?;
+        match IntegerArg::extract(fp, "fp")? {
+            IntegerArg::Signed(fp) => {
+                let interp = Interp::new(xp, fp.as_array().to_vec())
+                    .with_closed(closed)
+                    .with_bounds(bounds);
+                match &f {
+                    IntegerArg::Signed(f) => {
+                        inverse_from_batch(py, f.as_array(), errors, &interp, method, dtype)
+                    }
+                    IntegerArg::Unsigned(f) => {
+                        inverse_from_batch(py, f.as_array(), errors, &interp, method, dtype)
+                    }
+                }
+            }
+            IntegerArg::Unsigned(fp) => {
+                let interp = Interp::new(xp, fp.as_array().to_vec())
+                    .with_closed(closed)
+                    .with_bounds(bounds);
+                match &f {
+                    IntegerArg::Signed(f) => {
+                        inverse_from_batch(py, f.as_array(), errors, &interp, method, dtype)
+                    }
+                    IntegerArg::Unsigned(f) => {
+                        inverse_from_batch(py, f.as_array(), errors, &interp, method, dtype)
+                    }
+                }
+            }
+        }
+    }
+    #[pyfn(m)]
+    fn inverse_all_int<'py>(
+        py: Python<'py>,
+        f: &'py PyAny,
+        xp: &'py PyAny,
+        fp: &'py PyAny,
+        method: Option<MethodArg>,
+    ) -> PyResult<Vec<&'py PyArray1<u64>>> {
+        let f = ArrayArg::<i64>::extract(f, "f")?;
+        let xp = ArrayArg::<u64>::extract(xp, "xp")?;
+        let fp = ArrayArg::<i64>::extract(fp, "fp")?;
+        let f = f.as_array();
+        let xp = xp.as_array();
+        let fp = fp.as_array();
+        let method = parse_method(method)?;
+        let interp = Interp::new(xp.to_vec(), fp.to_vec());
+        Ok(f.iter()
+            .map(|value| interp.inverse_all(*value, method).into_pyarray(py))
+            .collect())
+    }
+    #[pyfn(m)]
+    fn inverse_all_float<'py>(
+        py: Python<'py>,
+        f: &'py PyAny,
+        xp: &'py PyAny,
+        fp: &'py PyAny,
+        method: Option<MethodArg>,
+    ) -> PyResult<Vec<&'py PyArray1<u64>>> {
+        let f = ArrayArg::<f64>::extract(f, "f")?;
+        let xp = ArrayArg::<u64>::extract(xp, "xp")?;
+        let fp = ArrayArg::<f64>::extract(fp, "fp")?;
+        let f = f.as_array();
+        let xp = xp.as_array().to_vec();
+        let fp = fp.as_array().to_vec();
+        let method = parse_method(method)?;
         let interp = Interp::new(xp, fp);
+        Ok(f.iter()
+            .map(|value| interp.inverse_all(*value, method).into_pyarray(py))
+            .collect())
+    }
+    #[pyfn(m)]
+    #[pyo3(signature = (f, xp, fp, closed = "both"))]
+    fn inverse_int_fractional<'py>(
+        py: Python<'py>,
+        f: &'py PyAny,
+        xp: &'py PyAny,
+        fp: &'py PyAny,
+        closed: &str,
+    ) -> PyResult<&'py PyArray1<f64>> {
+        let f = ArrayArg::<i64>::extract(f, "f")?;
+        let xp = ArrayArg::<u64>::extract(xp, "xp")?;
+        let fp = ArrayArg::<i64>::extract(fp, "fp")?;
+        let f = f.as_array();
+        let xp = xp.as_array();
+        let fp = fp.as_array();
+        let interp = Interp::new(xp.to_vec(), fp.to_vec()).with_closed(parse_closed(closed)?);
         let mut x = Array1::zeros(f.len());
         for (value, index) in f.iter().zip(x.iter_mut()) {
-            match interp.inverse(*value, method) {
-                Ok(result) => *index = result,
-                Err(InterpError::NotStrictlyIncreasing) => {
-                    return Err(PyValueError::new_err("fp must be strictly increasing"))
-                }
-                Err(InterpError::OutOfBounds) => {
-                    return Err(PyKeyError::new_err("f out of bounds"))
-                }
-                Err(InterpError::NotFound) => return Err(PyKeyError::new_err("f not found")),
+            *index = interp.inverse_fractional(*value).map_err(inverse_error)?;
+        }
+        Ok(x.into_pyarray(py))
+    }
+    #[pyfn(m)]
+    #[pyo3(signature = (f, xp, fp, closed = "both"))]
+    fn inverse_float_fractional<'py>(
+        py: Python<'py>,
+        f: &'py PyAny,
+        xp: &'py PyAny,
+        fp: &'py PyAny,
+        closed: &str,
+    ) -> PyResult<&'py PyArray1<f64>> {
+        let f = ArrayArg::<f64>::extract(f, "f")?;
+        let xp = ArrayArg::<u64>::extract(xp, "xp")?;
+        let fp = ArrayArg::<f64>::extract(fp, "fp")?;
+        let f = f.as_array();
+        let xp = xp.as_array().to_vec();
+        let fp = fp.as_array().to_vec();
+        let interp = Interp::new(xp, fp).with_closed(parse_closed(closed)?);
+        let mut x = Array1::zeros(f.len());
+        for (value, index) in f.iter().zip(x.iter_mut()) {
+            *index = interp.inverse_fractional(*value).map_err(inverse_error)?;
+        }
+        Ok(x.into_pyarray(py))
+    }
+    #[pyfn(m)]
+    fn affine_int<'py>(
+        py: Python<'py>,
+        xp: &'py PyAny,
+        fp: &'py PyAny,
+        scale: i64,
+        offset: i64,
+    ) -> PyResult<&'py PyArray1<i64>> {
+        let xp = ArrayArg::<u64>::extract(xp, "xp")?;
+        let fp = ArrayArg::<i64>::extract(fp, "fp")?;
+        let xp = xp.as_array();
+        let fp = fp.as_array();
+        let interp = Interp::new(xp.to_vec(), fp.to_vec());
+        match interp.affine_fp(scale, offset) {
+            Ok(interp) => Ok(Array1::from_iter(interp.fp().iter().copied()).into_pyarray(py)),
+            Err(InterpError::Overflow) => Err(PyOverflowError::new_err("fp overflow")),
+            Err(err) => Err(forward_error(err)),
+        }
+    }
+    #[pyfn(m)]
+    fn affine_float<'py>(
+        py: Python<'py>,
+        xp: &'py PyAny,
+        fp: &'py PyAny,
+        scale: f64,
+        offset: f64,
+    ) -> PyResult<&'py PyArray1<f64>> {
+        let xp = ArrayArg::<u64>::extract(xp, "xp")?;
+        let fp = ArrayArg::<f64>::extract(fp, "fp")?;
+        let xp = xp.as_array().to_vec();
+        let fp = fp.as_array().to_vec();
+        let interp = Interp::new(xp, fp);
+        match interp.affine_fp(scale, offset) {
+            Ok(interp) => Ok(Array1::from_iter(interp.fp().iter().copied()).into_pyarray(py)),
+            Err(InterpError::Overflow) => Err(PyOverflowError::new_err("fp overflow")),
+            Err(err) => Err(forward_error(err)),
+        }
+    }
+    #[pyfn(m)]
+    fn sample_int<'py>(
+        py: Python<'py>,
+        xp: &'py PyAny,
+        fp: &'py PyAny,
+        n: usize,
+    ) -> PyResult<(&'py PyArray1<u64>, &'py PyArray1<i64>)> {
+        let xp = ArrayArg::<u64>::extract(xp, "xp")?;
+        let fp = ArrayArg::<i64>::extract(fp, "fp")?;
+        let interp = Interp::new(xp.as_array().to_vec(), fp.as_array().to_vec());
+        let (x, f) = interp.sample(n).map_err(forward_error)?;
+        Ok((x.into_pyarray(py), f.into_pyarray(py)))
+    }
+    #[pyfn(m)]
+    fn sample_float<'py>(
+        py: Python<'py>,
+        xp: &'py PyAny,
+        fp: &'py PyAny,
+        n: usize,
+    ) -> PyResult<(&'py PyArray1<u64>, &'py PyArray1<f64>)> {
+        let xp = ArrayArg::<u64>::extract(xp, "xp")?;
+        let fp = ArrayArg::<f64>::extract(fp, "fp")?;
+        let interp = Interp::new(xp.as_array().to_vec(), fp.as_array().to_vec());
+        let (x, f) = interp.sample(n).map_err(forward_error)?;
+        Ok((x.into_pyarray(py), f.into_pyarray(py)))
+    }
+    #[pyfn(m)]
+    fn forward_range_int<'py>(
+        py: Python<'py>,
+        start: u64,
+        stop: u64,
+        step: u64,
+        xp: &'py PyAny,
+        fp: &'py PyAny,
+    ) -> PyResult<&'py PyArray1<i64>> {
+        let xp = ArrayArg::<u64>::extract(xp, "xp")?;
+        let fp = ArrayArg::<i64>::extract(fp, "fp")?;
+        let interp = Interp::new(xp.as_array().to_vec(), fp.as_array().to_vec());
+        let f = interp
+            .forward_range(start, stop, step)
+            .map_err(forward_error)?;
+        Ok(f.into_pyarray(py))
+    }
+    #[pyfn(m)]
+    fn forward_range_float<'py>(
+        py: Python<'py>,
+        start: u64,
+        stop: u64,
+        step: u64,
+        xp: &'py PyAny,
+        fp: &'py PyAny,
+    ) -> PyResult<&'py PyArray1<f64>> {
+        let xp = ArrayArg::<u64>::extract(xp, "xp")?;
+        let fp = ArrayArg::<f64>::extract(fp, "fp")?;
+        let interp = Interp::new(xp.as_array().to_vec(), fp.as_array().to_vec());
+        let f = interp
+            .forward_range(start, stop, step)
+            .map_err(forward_error)?;
+        Ok(f.into_pyarray(py))
+    }
+    #[pyfn(m)]
+    fn derivative_int<'py>(
+        py: Python<'py>,
+        xp: &'py PyAny,
+        fp: &'py PyAny,
+    ) -> PyResult<(&'py PyArray1<u64>, &'py PyArray1<f64>)> {
+        let xp = ArrayArg::<u64>::extract(xp, "xp")?;
+        let fp = ArrayArg::<i64>::extract(fp, "fp")?;
+        let interp = Interp::new(xp.as_array().to_vec(), fp.as_array().to_vec());
+        let derivative = interp.derivative().map_err(forward_error)?;
+        let (starts, slopes): (Vec<u64>, Vec<f64>) = derivative.iter().unzip();
+        Ok((starts.into_pyarray(py), slopes.into_pyarray(py)))
+    }
+    #[pyfn(m)]
+    fn derivative_float<'py>(
+        py: Python<'py>,
+        xp: &'py PyAny,
+        fp: &'py PyAny,
+    ) -> PyResult<(&'py PyArray1<u64>, &'py PyArray1<f64>)> {
+        let xp = ArrayArg::<u64>::extract(xp, "xp")?;
+        let fp = ArrayArg::<f64>::extract(fp, "fp")?;
+        let interp = Interp::new(xp.as_array().to_vec(), fp.as_array().to_vec());
+        let derivative = interp.derivative().map_err(forward_error)?;
+        let (starts, slopes): (Vec<u64>, Vec<f64>) = derivative.iter().unzip();
+        Ok((starts.into_pyarray(py), slopes.into_pyarray(py)))
+    }
+    #[pyfn(m)]
+    #[pyo3(signature = (seconds, method = Some(MethodArg::Str("nearest"))))]
+    fn seconds_to_ns_array<'py>(
+        py: Python<'py>,
+        seconds: &'py PyAny,
+        method: Option<MethodArg>,
+    ) -> PyResult<&'py PyArray1<i64>> {
+        let seconds = ArrayArg::<f64>::extract(seconds, "seconds")?;
+        let method = parse_method(method)?;
+        let ns = seconds
+            .as_array()
+            .iter()
+            .enumerate()
+            .map(|(index, value)| {
+                seconds_to_ns(*value, method).map_err(|err| {
+                    let message = format!("seconds[{index}] = {value}: {err}");
+                    match err {
+                        UnitError::OutOfRange => PyOverflowError::new_err(message),
+                        UnitError::NotFinite | UnitError::Inexact | UnitError::InvalidScale => {
+                            PyValueError::new_err(message)
+                        }
+                    }
+                })
+            })
+            .collect::<PyResult<Vec<i64>>>()?;
+        Ok(ns.into_pyarray(py))
+    }
+    #[pyfn(m)]
+    fn duration_to_ticks(text: &str, unit: &str) -> PyResult<i64> {
+        parse_duration(text, unit).map_err(|err| {
+            let message = format!("duration '{text}' in {unit}: {err}");
+            match err {
+                DurationError::OutOfRange => PyOverflowError::new_err(message),
+                _ => PyValueError::new_err(message),
             }
+        })
+    }
+    #[pyfn(m)]
+    fn ns_to_seconds_array<'py>(py: Python<'py>, ns: &'py PyAny) -> PyResult<&'py PyArray1<f64>> {
+        let ns = ArrayArg::<i64>::extract(ns, "ns")?;
+        let seconds: Vec<f64> = ns.as_array().iter().map(|ns| ns_to_seconds(*ns)).collect();
+        Ok(seconds.into_pyarray(py))
+    }
+    #[pyfn(m)]
+    #[pyo3(signature = (
+        x, xp, fp, scale_factor, add_offset, closed = "both", errors = "raise",
+        bounds = BoundsArg::Both("raise")
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn forward_packed<'py>(
+        py: Python<'py>,
+        x: &'py PyAny,
+        xp: &'py PyAny,
+        fp: &'py PyAny,
+        scale_factor: f64,
+        add_offset: f64,
+        closed: &str,
+        errors: &str,
+        bounds: BoundsArg,
+    ) -> PyResult<PyObject> {
+        let x = ArrayArg::<u64>::extract(x, "x")?;
+        let xp = ArrayArg::<u64>::extract(xp, "xp")?;
+        let fp = ArrayArg::<i64>::extract(fp, "fp")?;
+        let x = x.as_array();
+        let interp = Interp::new(xp.as_array().to_vec(), fp.as_array().to_vec())
+            .with_closed(parse_closed(closed)?)
+            .with_bounds(parse_bounds(bounds)?);
+        let packed = packed(interp, scale_factor, add_offset)?;
+        batch(py, x, errors, |index| packed.forward(index), forward_error)
+    }
+    #[pyfn(m)]
+    #[pyo3(signature = (
+        f, xp, fp, scale_factor, add_offset, method, closed = "both", errors = "raise",
+        bounds = BoundsArg::Both("raise")
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn inverse_packed<'py>(
+        py: Python<'py>,
+        f: &'py PyAny,
+        xp: &'py PyAny,
+        fp: &'py PyAny,
+        scale_factor: f64,
+        add_offset: f64,
+        method: Option<MethodArg>,
+        closed: &str,
+        errors: &str,
+        bounds: BoundsArg,
+    ) -> PyResult<PyObject> {
+        let f = ArrayArg::<f64>::extract(f, "f")?;
+        let xp = ArrayArg::<u64>::extract(xp, "xp")?;
+        let fp = ArrayArg::<i64>::extract(fp, "fp")?;
+        let f = f.as_array();
+        let method = parse_method(method)?;
+        let interp = Interp::new(xp.as_array().to_vec(), fp.as_array().to_vec())
+            .with_closed(parse_closed(closed)?)
+            .with_bounds(parse_bounds(bounds)?);
+        let packed = packed(interp, scale_factor, add_offset)?;
+        if !packed.is_inversable() {
+            return Err(PyValueError::new_err(
+                "unpacked fp values must be strictly monotonic",
+            ));
         }
+        batch(
+            py,
+            f,
+            errors,
+            |value| packed.inverse(value, method),
+            inverse_error,
+        )
+    }
+    #[pyfn(m)]
+    fn summary_int<'py>(
+        py: Python<'py>,
+        xp: &'py PyAny,
+        fp: &'py PyAny,
+        factor: f64,
+    ) -> PyResult<&'py PyDict> {
+        let xp = ArrayArg::<u64>::extract(xp, "xp")?;
+        let fp = ArrayArg::<i64>::extract(fp, "fp")?;
+        let interp = Interp::new(xp.as_array().to_vec(), fp.as_array().to_vec());
+        summary_to_dict(py, interp.summary_with(factor))
+    }
+    #[pyfn(m)]
+    fn summary_float<'py>(
+        py: Python<'py>,
+        xp: &'py PyAny,
+        fp: &'py PyAny,
+        factor: f64,
+    ) -> PyResult<&'py PyDict> {
+        let xp = ArrayArg::<u64>::extract(xp, "xp")?;
+        let fp = ArrayArg::<f64>::extract(fp, "fp")?;
+        let interp = Interp::new(xp.as_array().to_vec(), fp.as_array().to_vec());
+        summary_to_dict(py, interp.summary_with(factor))
+    }
+    #[pyfn(m)]
+    #[pyo3(signature = (i, j, xi, xj, fp, errors = "raise"))]
+    fn forward_2d_int<'py>(
+        py: Python<'py>,
+        i: &'py PyAny,
+        j: &'py PyAny,
+        xi: &'py PyAny,
+        xj: &'py PyAny,
+        fp: PyReadonlyArray2<'py, i64>,
+        errors: &str,
+    ) -> PyResult<PyObject> {
+        let i = ArrayArg::<u64>::extract(i, "i")?;
+        let j = ArrayArg::<u64>::extract(j, "j")?;
+        let xi = ArrayArg::<u64>::extract(xi, "xi")?;
+        let xj = ArrayArg::<u64>::extract(xj, "xj")?;
+        let fp = fp.as_array().iter().copied().collect();
+        let interp = Interp2D::new(xi.as_array().to_vec(), xj.as_array().to_vec(), fp)
+            .map_err(forward_error)?;
+        let queries: Vec<(u64, u64)> = i
+            .as_array()
+            .iter()
+            .copied()
+            .zip(j.as_array().iter().copied())
+            .collect();
+        batch(
+            py,
+            ArrayView1::from(queries.as_slice()),
+            errors,
+            |(i, j)| interp.forward(i, j),
+            forward_error,
+        )
+    }
+    #[pyfn(m)]
+    #[pyo3(signature = (i, j, xi, xj, fp, errors = "raise"))]
+    fn forward_2d_float<'py>(
+        py: Python<'py>,
+        i: &'py PyAny,
+        j: &'py PyAny,
+        xi: &'py PyAny,
+        xj: &'py PyAny,
+        fp: PyReadonlyArray2<'py, f64>,
+        errors: &str,
+    ) -> PyResult<PyObject> {
+        let i = ArrayArg::<u64>::extract(i, "i")?;
+        let j = ArrayArg::<u64>::extract(j, "j")?;
+        let xi = ArrayArg::<u64>::extract(xi, "xi")?;
+        let xj = ArrayArg::<u64>::extract(xj, "xj")?;
+        let fp = fp.as_array().iter().copied().collect();
+        let interp = Interp2D::new(xi.as_array().to_vec(), xj.as_array().to_vec(), fp)
+            .map_err(forward_error)?;
+        let queries: Vec<(u64, u64)> = i
+            .as_array()
+            .iter()
+            .copied()
+            .zip(j.as_array().iter().copied())
+            .collect();
+        batch(
+            py,
+            ArrayView1::from(queries.as_slice()),
+            errors,
+            |(i, j)| interp.forward(i, j),
+            forward_error,
+        )
+    }
+    #[pyfn(m)]
+    fn forward_longdouble<'py>(
+        py: Python<'py>,
+        x: &'py PyAny,
+        xp: &'py PyAny,
+        fp: PyReadonlyArray2<'py, u8>,
+    ) -> PyResult<&'py PyArray2<u8>> {
+        let x = ArrayArg::<u64>::extract(x, "x")?;
+        let xp = ArrayArg::<u64>::extract(xp, "xp")?;
+        let xp = xp.as_array().to_vec();
+        let fp = decode_longdouble(fp)?;
+        let interp = Interp::from_parts(xp, fp).map_err(forward_error)?;
+        let f = x
+            .as_array()
+            .iter()
+            .map(|index| interp.forward(*index).map_err(forward_error))
+            .collect::<PyResult<Vec<F80>>>()?;
+        Ok(encode_longdouble(&f).into_pyarray(py))
+    }
+    #[pyfn(m)]
+    fn inverse_longdouble<'py>(
+        py: Python<'py>,
+        f: PyReadonlyArray2<'py, u8>,
+        xp: &'py PyAny,
+        fp: PyReadonlyArray2<'py, u8>,
+        method: Option<MethodArg>,
+    ) -> PyResult<&'py PyArray1<u64>> {
+        let xp = ArrayArg::<u64>::extract(xp, "xp")?;
+        let f = decode_longdouble(f)?;
+        let xp = xp.as_array().to_vec();
+        let fp = decode_longdouble(fp)?;
+        let method = parse_method(method)?;
+        let interp = Interp::from_parts(xp, fp).map_err(inverse_error)?;
+        let x = f
+            .into_iter()
+            .map(|value| interp.inverse(value, method).map_err(inverse_error))
+            .collect::<PyResult<Vec<u64>>>()?;
         Ok(x.into_pyarray(py))
     }
+    #[pyfn(m)]
+    #[pyo3(signature = (xp, fp, checksum = true))]
+    fn dumps_int<'py>(
+        py: Python<'py>,
+        xp: &'py PyAny,
+        fp: &'py PyAny,
+        checksum: bool,
+    ) -> PyResult<&'py PyBytes> {
+        let xp = ArrayArg::<u64>::extract(xp, "xp")?;
+        let fp = ArrayArg::<i64>::extract(fp, "fp")?;
+        let interp = Interp::from_parts(xp.as_array().to_vec(), fp.as_array().to_vec())
+            .map_err(forward_error)?;
+        Ok(PyBytes::new(py, &interp.to_bytes_with(checksum)))
+    }
+    #[pyfn(m)]
+    #[pyo3(signature = (xp, fp, checksum = true))]
+    fn dumps_float<'py>(
+        py: Python<'py>,
+        xp: &'py PyAny,
+        fp: &'py PyAny,
+        checksum: bool,
+    ) -> PyResult<&'py PyBytes> {
+        let xp = ArrayArg::<u64>::extract(xp, "xp")?;
+        let fp = ArrayArg::<f64>::extract(fp, "fp")?;
+        let interp = Interp::from_parts(xp.as_array().to_vec(), fp.as_array().to_vec())
+            .map_err(forward_error)?;
+        Ok(PyBytes::new(py, &interp.to_bytes_with(checksum)))
+    }
+    #[pyfn(m)]
+    #[pyo3(signature = (xp, fp, checksum = true))]
+    fn dumps_longdouble<'py>(
+        py: Python<'py>,
+        xp: &'py PyAny,
+        fp: PyReadonlyArray2<'py, u8>,
+        checksum: bool,
+    ) -> PyResult<&'py PyBytes> {
+        let xp = ArrayArg::<u64>::extract(xp, "xp")?;
+        let interp = Interp::from_parts(xp.as_array().to_vec(), decode_longdouble(fp)?)
+            .map_err(forward_error)?;
+        Ok(PyBytes::new(py, &interp.to_bytes_with(checksum)))
+    }
+    /// Returns the indices and the values of serialized data points. Long double values are
+    /// returned as rows of raw bytes.
+    #[pyfn(m)]
+    fn loads<'py>(py: Python<'py>, data: &[u8]) -> PyResult<(PyObject, PyObject)> {
+        let (_, tag) = peek_tags(data).map_err(decode_error)?;
+        let (xp, fp) = match tag {
+            i64::TAG => {
+                let (xp, fp) = Interp::<u64, i64>::from_bytes(data)
+                    .map_err(decode_error)?
+                    .into_parts();
+                (xp, fp.into_pyarray(py).into_py(py))
+            }
+            F80::TAG => {
+                let (xp, fp) = Interp::<u64, F80>::from_bytes(data)
+                    .map_err(decode_error)?
+                    .into_parts();
+                (xp, encode_longdouble(&fp).into_pyarray(py).into_py(py))
+            }
+            _ => {
+                let (xp, fp) = Interp::<u64, f64>::from_bytes(data)
+                    .map_err(decode_error)?
+                    .into_parts();
+                (xp, fp.into_pyarray(py).into_py(py))
+            }
+        };
+        Ok((xp.into_pyarray(py).into_py(py), fp))
+    }
+    /// Returns the fingerprint of serialized data points, hashed with the unit of their values
+    /// (see `Interp::fingerprint`).
+    #[pyfn(m)]
+    fn fingerprint(data: &[u8], unit: &str) -> PyResult<u64> {
+        let (_, tag) = peek_tags(data).map_err(decode_error)?;
+        let hash = match tag {
+            i64::TAG => Interp::<u64, i64>::fingerprint(data, unit),
+            F80::TAG => Interp::<u64, F80>::fingerprint(data, unit),
+            _ => Interp::<u64, f64>::fingerprint(data, unit),
+        };
+        hash.map_err(decode_error)
+    }
+    #[pyfn(m)]
+    fn check_monotonic_int<'py>(py: Python<'py>, values: &'py PyAny) -> PyResult<&'py PyDict> {
+        let values = ArrayArg::<i64>::extract(values, "values")?;
+        let values = values.as_array().to_vec();
+        report_to_dict(py, check_strictly_increasing(&values))
+    }
+    #[pyfn(m)]
+    fn check_monotonic_datetime<'py>(py: Python<'py>, values: &'py PyAny) -> PyResult<&'py PyDict> {
+        let values = ArrayArg::<i64>::extract(values, "values")?;
+        let values: Vec<DateTime> = values.as_array().iter().map(|v| DateTime(*v)).collect();
+        report_to_dict(py, check_strictly_increasing(&values))
+    }
+    #[pyfn(m)]
+    fn check_monotonic_uint<'py>(py: Python<'py>, values: &'py PyAny) -> PyResult<&'py PyDict> {
+        let values = ArrayArg::<u64>::extract(values, "values")?;
+        let values = values.as_array().to_vec();
+        report_to_dict(py, check_strictly_increasing(&values))
+    }
+    #[pyfn(m)]
+    fn check_monotonic_float<'py>(py: Python<'py>, values: &'py PyAny) -> PyResult<&'py PyDict> {
+        let values = ArrayArg::<f64>::extract(values, "values")?;
+        let values = values.as_array().to_vec();
+        report_to_dict(py, check_strictly_increasing(&values))
+    }
+    #[pyfn(m)]
+    fn smooth_int<'py>(
+        py: Python<'py>,
+        fp: &'py PyAny,
+        window: usize,
+        kind: &str,
+    ) -> PyResult<&'py PyArray1<i64>> {
+        let fp = ArrayArg::<i64>::extract(fp, "fp")?;
+        let fp = fp.as_array().to_vec();
+        let smoothed = smooth(&fp, window, parse_smoothing(kind)?).map_err(forward_error)?;
+        Ok(smoothed.into_pyarray(py))
+    }
+    #[pyfn(m)]
+    fn smooth_float<'py>(
+        py: Python<'py>,
+        fp: &'py PyAny,
+        window: usize,
+        kind: &str,
+    ) -> PyResult<&'py PyArray1<f64>> {
+        let fp = ArrayArg::<f64>::extract(fp, "fp")?;
+        let fp = fp.as_array().to_vec();
+        let smoothed = smooth(&fp, window, parse_smoothing(kind)?).map_err(forward_error)?;
+        Ok(smoothed.into_pyarray(py))
+    }
+    #[pyfn(m)]
+    fn detect_outliers_int<'py>(
+        py: Python<'py>,
+        xp: &'py PyAny,
+        fp: &'py PyAny,
+        window: usize,
+        threshold: u64,
+    ) -> PyResult<&'py PyArray1<usize>> {
+        let xp = ArrayArg::<u64>::extract(xp, "xp")?;
+        let fp = ArrayArg::<i64>::extract(fp, "fp")?;
+        let xp = xp.as_array().to_vec();
+        let fp = fp.as_array().to_vec();
+        let outliers = detect_outliers(&xp, &fp, window, threshold).map_err(forward_error)?;
+        Ok(outliers.into_pyarray(py))
+    }
+    #[pyfn(m)]
+    fn detect_outliers_float<'py>(
+        py: Python<'py>,
+        xp: &'py PyAny,
+        fp: &'py PyAny,
+        window: usize,
+        threshold: f64,
+    ) -> PyResult<&'py PyArray1<usize>> {
+        let xp = ArrayArg::<u64>::extract(xp, "xp")?;
+        let fp = ArrayArg::<f64>::extract(fp, "fp")?;
+        let xp = xp.as_array().to_vec();
+        let fp = fp.as_array().to_vec();
+        let outliers = detect_outliers(&xp, &fp, window, threshold).map_err(forward_error)?;
+        Ok(outliers.into_pyarray(py))
+    }
+    #[pyfn(m)]
+    fn epsilon_sweep_int<'py>(
+        py: Python<'py>,
+        xp: &'py PyAny,
+        fp: &'py PyAny,
+        epsilons: &'py PyAny,
+    ) -> PyResult<(&'py PyArray1<usize>, &'py PyArray1<u64>)> {
+        let xp = ArrayArg::<u64>::extract(xp, "xp")?;
+        let fp = ArrayArg::<i64>::extract(fp, "fp")?;
+        let epsilons = ArrayArg::<u64>::extract(epsilons, "epsilons")?;
+        let interp = Interp::new(xp.as_array().to_vec(), fp.as_array().to_vec());
+        let sweep = interp
+            .epsilon_sweep(&epsilons.as_array().to_vec())
+            .map_err(forward_error)?;
+        let (counts, errors): (Vec<usize>, Vec<u64>) = sweep
+            .into_iter()
+            .map(|(_, count, error)| (count, error))
+            .unzip();
+        Ok((counts.into_pyarray(py), errors.into_pyarray(py)))
+    }
+    #[pyfn(m)]
+    fn epsilon_sweep_float<'py>(
+        py: Python<'py>,
+        xp: &'py PyAny,
+        fp: &'py PyAny,
+        epsilons: &'py PyAny,
+    ) -> PyResult<(&'py PyArray1<usize>, &'py PyArray1<f64>)> {
+        let xp = ArrayArg::<u64>::extract(xp, "xp")?;
+        let fp = ArrayArg::<f64>::extract(fp, "fp")?;
+        let epsilons = ArrayArg::<f64>::extract(epsilons, "epsilons")?;
+        let interp = Interp::new(xp.as_array().to_vec(), fp.as_array().to_vec());
+        let sweep = interp
+            .epsilon_sweep(&epsilons.as_array().to_vec())
+            .map_err(forward_error)?;
+        let (counts, errors): (Vec<usize>, Vec<f64>) = sweep
+            .into_iter()
+            .map(|(_, count, error)| (count, error))
+            .unzip();
+        Ok((counts.into_pyarray(py), errors.into_pyarray(py)))
+    }
+    #[pyfn(m)]
+    #[pyo3(signature = (xp, fp, k = None, n = None))]
+    fn decimate_int<'py>(
+        py: Python<'py>,
+        xp: &'py PyAny,
+        fp: &'py PyAny,
+        k: Option<usize>,
+        n: Option<usize>,
+    ) -> PyResult<(&'py PyArray1<u64>, &'py PyArray1<i64>, u64)> {
+        let xp = ArrayArg::<u64>::extract(xp, "xp")?;
+        let fp = ArrayArg::<i64>::extract(fp, "fp")?;
+        let interp = Interp::new(xp.as_array().to_vec(), fp.as_array().to_vec());
+        let decimated = parse_decimation(&interp, k, n)?;
+        let (error, _) = interp.max_error(&decimated).map_err(forward_error)?;
+        let (xp, fp) = decimated.into_parts();
+        Ok((xp.into_pyarray(py), fp.into_pyarray(py), error))
+    }
+    #[pyfn(m)]
+    #[pyo3(signature = (xp, fp, k = None, n = None))]
+    fn decimate_float<'py>(
+        py: Python<'py>,
+        xp: &'py PyAny,
+        fp: &'py PyAny,
+        k: Option<usize>,
+        n: Option<usize>,
+    ) -> PyResult<(&'py PyArray1<u64>, &'py PyArray1<f64>, f64)> {
+        let xp = ArrayArg::<u64>::extract(xp, "xp")?;
+        let fp = ArrayArg::<f64>::extract(fp, "fp")?;
+        let interp = Interp::new(xp.as_array().to_vec(), fp.as_array().to_vec());
+        let decimated = parse_decimation(&interp, k, n)?;
+        let (error, _) = interp.max_error(&decimated).map_err(forward_error)?;
+        let (xp, fp) = decimated.into_parts();
+        Ok((xp.into_pyarray(py), fp.into_pyarray(py), error))
+    }
+    #[pyfn(m)]
+    #[pyo3(signature = (xp, fp, k = None, n = None))]
+    fn decimate_uint<'py>(
+        py: Python<'py>,
+        xp: &'py PyAny,
+        fp: &'py PyAny,
+        k: Option<usize>,
+        n: Option<usize>,
+    ) -> PyResult<(&'py PyArray1<u64>, &'py PyArray1<u64>, u64)> {
+        let xp = ArrayArg::<u64>::extract(xp, "xp")?;
+        let fp = ArrayArg::<u64>::extract(fp, "fp")?;
+        let interp = Interp::new(xp.as_array().to_vec(), fp.as_array().to_vec());
+        let decimated = parse_decimation(&interp, k, n)?;
+        let (error, _) = interp.max_error(&decimated).map_err(forward_error)?;
+        let (xp, fp) = decimated.into_parts();
+        Ok((xp.into_pyarray(py), fp.into_pyarray(py), error))
+    }
+    /// Decimates long double values given as rows of raw bytes, returning the values and the
+    /// error as rows of raw bytes.
+    #[pyfn(m)]
+    #[pyo3(signature = (xp, fp, k = None, n = None))]
+    fn decimate_longdouble<'py>(
+        py: Python<'py>,
+        xp: &'py PyAny,
+        fp: PyReadonlyArray2<'py, u8>,
+        k: Option<usize>,
+        n: Option<usize>,
+    ) -> PyResult<(&'py PyArray1<u64>, &'py PyArray2<u8>, &'py PyArray2<u8>)> {
+        let xp = ArrayArg::<u64>::extract(xp, "xp")?;
+        let interp = Interp::new(xp.as_array().to_vec(), decode_longdouble(fp)?);
+        let decimated = parse_decimation(&interp, k, n)?;
+        let (error, _) = interp.max_error(&decimated).map_err(forward_error)?;
+        let (xp, fp) = decimated.into_parts();
+        Ok((
+            xp.into_pyarray(py),
+            encode_longdouble(&fp).into_pyarray(py),
+            encode_longdouble(&[error]).into_pyarray(py),
+        ))
+    }
+    #[pyfn(m)]
+    fn verify_int<'py>(
+        py: Python<'py>,
+        values: &'py PyAny,
+        xp: &'py PyAny,
+        fp: &'py PyAny,
+        atol: u64,
+    ) -> PyResult<Py<PyVerification>> {
+        let values = ArrayArg::<i64>::extract(values, "values")?;
+        let xp = ArrayArg::<u64>::extract(xp, "xp")?;
+        let fp = ArrayArg::<i64>::extract(fp, "fp")?;
+        let values = values.as_array().to_vec();
+        let interp = Interp::new(xp.as_array().to_vec(), fp.as_array().to_vec());
+        let report = interp.verify(&values, atol).map_err(forward_error)?;
+        verification_to_py(py, report)
+    }
+    #[pyfn(m)]
+    fn verify_float<'py>(
+        py: Python<'py>,
+        values: &'py PyAny,
+        xp: &'py PyAny,
+        fp: &'py PyAny,
+        atol: f64,
+    ) -> PyResult<Py<PyVerification>> {
+        let values = ArrayArg::<f64>::extract(values, "values")?;
+        let xp = ArrayArg::<u64>::extract(xp, "xp")?;
+        let fp = ArrayArg::<f64>::extract(fp, "fp")?;
+        let values = values.as_array().to_vec();
+        let interp = Interp::new(xp.as_array().to_vec(), fp.as_array().to_vec());
+        let report = interp.verify(&values, atol).map_err(forward_error)?;
+        verification_to_py(py, report)
+    }
+    #[pyfn(m)]
+    #[pyo3(signature = (x_list, xp_list, fp_list, parallel = false))]
+    fn forward_multi_int(
+        py: Python<'_>,
+        x_list: &PyAny,
+        xp_list: &PyAny,
+        fp_list: &PyAny,
+        parallel: bool,
+    ) -> PyResult<PyObject> {
+        let pairs = extract_pairs::<u64, i64>(x_list, xp_list, fp_list, "x")?;
+        let results = py.allow_threads(|| forward_multi(pairs, parallel));
+        multi_to_py(py, results, CrateError::ForwardBatch)
+    }
+    #[pyfn(m)]
+    #[pyo3(signature = (x_list, xp_list, fp_list, parallel = false))]
+    fn forward_multi_float(
+        py: Python<'_>,
+        x_list: &PyAny,
+        xp_list: &PyAny,
+        fp_list: &PyAny,
+        parallel: bool,
+    ) -> PyResult<PyObject> {
+        let pairs = extract_pairs::<u64, f64>(x_list, xp_list, fp_list, "x")?;
+        let results = py.allow_threads(|| forward_multi(pairs, parallel));
+        multi_to_py(py, results, CrateError::ForwardBatch)
+    }
+    #[pyfn(m)]
+    #[pyo3(signature = (f_list, xp_list, fp_list, method, parallel = false))]
+    fn inverse_multi_int(
+        py: Python<'_>,
+        f_list: &PyAny,
+        xp_list: &PyAny,
+        fp_list: &PyAny,
+        method: Option<MethodArg>,
+        parallel: bool,
+    ) -> PyResult<PyObject> {
+        let method = parse_method(method)?;
+        let pairs = extract_pairs::<i64, i64>(f_list, xp_list, fp_list, "f")?;
+        let results = py.allow_threads(|| inverse_multi(pairs, method, parallel));
+        multi_to_py(py, results, CrateError::InverseBatch)
+    }
+    #[pyfn(m)]
+    #[pyo3(signature = (f_list, xp_list, fp_list, method, parallel = false))]
+    fn inverse_multi_float(
+        py: Python<'_>,
+        f_list: &PyAny,
+        xp_list: &PyAny,
+        fp_list: &PyAny,
+        method: Option<MethodArg>,
+        parallel: bool,
+    ) -> PyResult<PyObject> {
+        let method = parse_method(method)?;
+        let pairs = extract_pairs::<f64, f64>(f_list, xp_list, fp_list, "f")?;
+        let results = py.allow_threads(|| inverse_multi(pairs, method, parallel));
+        multi_to_py(py, results, CrateError::InverseBatch)
+    }
+    #[cfg(feature = "npy")]
+    {
+        m.add_function(wrap_pyfunction!(load_npy_dense, m)?)?;
+        m.add_function(wrap_pyfunction!(load_npy_pair, m)?)?;
+    }
     Ok(())
 }
@@ -0,0 +1,239 @@
+//! Monotonicity diagnostics of arbitrary sequences.
+//!
+//! The checks only rely on `PartialOrd` so that they can be used on both integers and floats.
+//! NaN values are detected as the only values that cannot be compared to themselves.
+
+use std::cmp::Ordering;
+use std::thread;
+
+/// Maximum number of violations and NaN positions reported by default.
+pub const MAX_REPORTED: usize = 100;
+
+/// Minimum length from which `first_violation` spreads the comparisons over several threads.
+pub const PARALLEL_THRESHOLD: usize = 1 << 20;
+
+/// Kind of monotonicity of a sequence.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Monotonicity {
+    Strict,
+    NonDecreasing,
+    None,
+}
+
+/// Diagnostics of the monotonicity of a sequence.
+#[derive(Clone, PartialEq, Debug)]
+pub struct MonotonicityReport {
+    /// The kind of monotonicity of the whole sequence.
+    pub kind: Monotonicity,
+    /// The positions of the elements that are not strictly greater than their predecessor.
+    pub violations: Vec<usize>,
+    /// The positions of the NaN values.
+    pub nans: Vec<usize>,
+}
+
+impl MonotonicityReport {
+    /// Returns true if the sequence is strictly increasing.
+    pub fn is_strict(&self) -> bool {
+        self.kind == Monotonicity::Strict
+    }
+}
+
+/// Checks whether a sequence is strictly increasing, reporting at most `MAX_REPORTED` violations
+/// and NaN positions.
+pub fn check_strictly_increasing<T: PartialOrd>(values: &[T]) -> MonotonicityReport {
+    check_strictly_increasing_with_limit(values, MAX_REPORTED)
+}
+
+/// Checks whether a sequence is strictly increasing, reporting at most `limit` violations and
+/// NaN positions. The kind of monotonicity always accounts for the whole sequence.
+///
+/// # Arguments
+///
+/// * `values` - The sequence to check.
+/// * `limit` - The maximum number of reported violations and NaN positions.
+pub fn check_strictly_increasing_with_limit<T: PartialOrd>(
+    values: &[T],
+    limit: usize,
+) -> MonotonicityReport {
+    let mut kind = Monotonicity::Strict;
+    let mut violations = Vec::new();
+    let mut nans = Vec::new();
+    for (index, value) in values.iter().enumerate() {
+        if value.partial_cmp(value).is_none() {
+            kind = Monotonicity::None;
+            if nans.len() < limit {
+                nans.push(index);
+            }
+        }
+    }
+    for (index, pair) in values.windows(2).enumerate() {
+        match pair[0].partial_cmp(&pair[1]) {
+            Some(Ordering::Less) | None => continue,
+            Some(Ordering::Equal) => {
+                if kind == Monotonicity::Strict {
+                    kind = Monotonicity::NonDecreasing;
+                }
+            }
+            Some(Ordering::Greater) => kind = Monotonicity::None,
+        }
+        if violations.len() < limit {
+            violations.push(index + 1);
+        }
+    }
+    MonotonicityReport {
+        kind,
+        violations,
+        nans,
+    }
+}
+
+/// Returns the position of the first element that is not strictly greater than its
+/// predecessor (including NaN values), None if the sequence is strictly increasing. Sequences
+/// of at least `PARALLEL_THRESHOLD` elements are split into contiguous chunks checked on the
+/// available threads, each chunk also comparing its first element to the last one of the
+/// previous chunk, and the first violation of the earliest failing chunk is returned, so that
+/// the result does not depend on the number of threads.
+pub fn first_violation<T: PartialOrd + Sync>(values: &[T]) -> Option<usize> {
+    let threads = if values.len() < PARALLEL_THRESHOLD {
+        1
+    } else {
+        thread::available_parallelism().map_or(1, |threads| threads.get())
+    };
+    first_violation_chunked(values, threads)
+}
+
+/// Returns the first violation of the sequence checked in the given number of chunks (see
+/// `first_violation`).
+fn first_violation_chunked<T: PartialOrd + Sync>(values: &[T], chunks: usize) -> Option<usize> {
+    let check = |start: usize, end: usize| {
+        (start.max(1)..end)
+            .find(|&index| values[index - 1].partial_cmp(&values[index]) != Some(Ordering::Less))
+    };
+    if chunks < 2 || values.len() < 2 {
+        return check(0, values.len());
+    }
+    let size = values.len().div_ceil(chunks);
+    thread::scope(|scope| {
+        let handles: Vec<_> = (0..values.len())
+            .step_by(size)
+            .map(|start| scope.spawn(move || check(start, (start + size).min(values.len()))))
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("worker thread panicked"))
+            .collect::<Vec<_>>()
+            .into_iter()
+            .flatten()
+            .next()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+
+    #[test]
+    fn test_strictly_increasing() {
+        let report = check_strictly_increasing(&[0u64, 1, 5, 10]);
+        assert_eq!(report.kind, Monotonicity::Strict);
+        assert!(report.is_strict());
+        assert!(report.violations.is_empty());
+        assert!(report.nans.is_empty());
+        let report = check_strictly_increasing::<f64>(&[]);
+        assert!(report.is_strict());
+    }
+
+    #[test]
+    fn test_plateaued() {
+        let report = check_strictly_increasing(&[0i64, 1, 1, 2, 2, 2]);
+        assert_eq!(report.kind, Monotonicity::NonDecreasing);
+        assert_eq!(report.violations, vec![2, 4, 5]);
+        let report = check_strictly_increasing_with_limit(&[0i64, 1, 1, 2, 2, 2], 2);
+        assert_eq!(report.kind, Monotonicity::NonDecreasing);
+        assert_eq!(report.violations, vec![2, 4]);
+    }
+
+    #[test]
+    fn test_decreasing() {
+        let report = check_strictly_increasing(&[3.0, 2.0, 2.0, 1.0]);
+        assert_eq!(report.kind, Monotonicity::None);
+        assert_eq!(report.violations, vec![1, 2, 3]);
+        let report = check_strictly_increasing(&[0, 2, 1, 3]);
+        assert_eq!(report.kind, Monotonicity::None);
+        assert_eq!(report.violations, vec![2]);
+    }
+
+    #[test]
+    fn test_nan() {
+        let report = check_strictly_increasing(&[0.0, f64::NAN, 2.0, 3.0, f64::NAN]);
+        assert_eq!(report.kind, Monotonicity::None);
+        assert!(report.violations.is_empty());
+        assert_eq!(report.nans, vec![1, 4]);
+        let report = check_strictly_increasing(&[0.0, f64::NAN, 2.0, 1.0]);
+        assert_eq!(report.violations, vec![3]);
+        assert_eq!(report.nans, vec![1]);
+    }
+
+    #[test]
+    fn test_first_violation() {
+        let mut rng = Rng(0x9e3779b97f4a7c15);
+        let serial = |values: &[u64]| {
+            values
+                .windows(2)
+                .position(|pair| pair[0] >= pair[1])
+                .map(|index| index + 1)
+        };
+        let increasing: Vec<u64> = (0..1000).map(|x| 3 * x).collect();
+        for chunks in [1, 2, 3, 7, 10, 64] {
+            assert_eq!(first_violation_chunked(&increasing, chunks), None);
+            // Violations exactly at the seams between chunks of 1000 / chunks elements, and
+            // just around them.
+            let size = 1000usize.div_ceil(chunks);
+            for seam in (size..1000).step_by(size) {
+                for index in [seam - 1, seam, seam + 1]
+                    .into_iter()
+                    .filter(|i| (1..1000).contains(i))
+                {
+                    for value in [increasing[index - 1], 0] {
+                        let mut values = increasing.clone();
+                        values[index] = value;
+                        assert_eq!(first_violation_chunked(&values, chunks), serial(&values));
+                    }
+                }
+            }
+            for _ in 0..20 {
+                let mut values = increasing.clone();
+                for _ in 0..rng.below(4) {
+                    values[rng.below(1000) as usize] = rng.below(3000);
+                }
+                assert_eq!(first_violation_chunked(&values, chunks), serial(&values));
+            }
+        }
+        assert_eq!(first_violation_chunked::<u64>(&[], 4), None);
+        assert_eq!(first_violation_chunked(&[1], 4), None);
+        // More chunks than elements.
+        assert_eq!(first_violation_chunked(&[0, 2, 1, 1], 8), Some(2));
+        assert_eq!(first_violation_chunked(&[0.0, f64::NAN, 2.0], 2), Some(1));
+        assert_eq!(first_violation(&[0, 1, 1]), Some(2));
+    }
+
+    #[test]
+    #[ignore = "benchmark, run with --release --ignored"]
+    fn bench_first_violation() {
+        let values: Vec<u64> = (0..100_000_000).collect();
+        let start = std::time::Instant::now();
+        let serial = values.windows(2).all(|pair| pair[0] < pair[1]);
+        let elapsed = start.elapsed();
+        let start = std::time::Instant::now();
+        let violation = first_violation(&values);
+        let parallel_elapsed = start.elapsed();
+        assert!(serial && violation.is_none());
+        if thread::available_parallelism().map_or(1, |threads| threads.get()) > 1 {
+            assert!(
+                parallel_elapsed < elapsed,
+                "{parallel_elapsed:?} vs {elapsed:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,240 @@
+//! Batches of interpolations over many pairs of data points.
+//!
+//! Datasets are often split into many small pieces, each with its own data points. Processing
+//! all the pieces at once avoids paying the cost of crossing the language boundary per piece.
+//! Each pair of data points is processed independently, so that a failing pair does not abort
+//! the batch, and pairs can be spread over several threads.
+
+use std::thread;
+
+use crate::divop::Method;
+use crate::piecewise::{BatchError, Interp};
+use crate::schemes::{Forward, Inverse};
+
+/// Queries with the indices and the values of the data points to interpolate them on.
+pub type Pair<Q, F> = (Vec<Q>, Vec<u64>, Vec<F>);
+
+/// Applies a function to each item, optionally spreading contiguous chunks of items over the
+/// available threads. The results keep the order of the items.
+pub fn map_pairs<T, R>(items: Vec<T>, parallel: bool, func: impl Fn(T) -> R + Sync) -> Vec<R>
+where
+    T: Send,
+    R: Send,
+{
+    let threads = if parallel {
+        thread::available_parallelism().map_or(1, |threads| threads.get())
+    } else {
+        1
+    };
+    if threads < 2 || items.len() < 2 {
+        return items.into_iter().map(func).collect();
+    }
+    let size = items.len().div_ceil(threads);
+    let mut chunks = Vec::with_capacity(threads);
+    let mut rest = items;
+    while rest.len() > size {
+        let tail = rest.split_off(size);
+        chunks.push(rest);
+        rest = tail;
+    }
+    chunks.push(rest);
+    let func = &func;
+    thread::scope(|scope| {
+        let handles: Vec<_> = chunks
+            .into_iter()
+            .map(|chunk| scope.spawn(move || chunk.into_iter().map(func).collect::<Vec<R>>()))
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("worker thread panicked"))
+            .collect()
+    })
+}
+
+/// Performs forward interpolation on each pair of data points. Integer values are rounded to the
+/// nearest integer.
+///
+/// # Arguments
+///
+/// * `pairs` - The indices to interpolate with the data points of each pair.
+/// * `parallel` - Whether to spread the pairs over the available threads.
+///
+/// # Returns
+///
+/// For each pair, either the interpolated values or the error of the first failing index along
+/// with its position.
+///
+/// # Panics
+///
+/// Panics if the lengths of the indices and values of the data points of any pair are not equal.
+pub fn forward_multi<F>(pairs: Vec<Pair<u64, F>>, parallel: bool) -> Vec<Result<Vec<F>, BatchError>>
+where
+    u64: Forward<F>,
+    F: Inverse<u64> + Send,
+{
+    map_pairs(pairs, parallel, |(x, xp, fp)| {
+        let interp = Interp::new(xp, fp);
+        x.into_iter()
+            .enumerate()
+            .map(|(index, x)| {
+                interp
+                    .forward(x)
+                    .map_err(|error| BatchError { index, error })
+            })
+            .collect()
+    })
+}
+
+/// Performs inverse interpolation on each pair of data points.
+///
+/// # Arguments
+///
+/// * `pairs` - The values to interpolate with the data points of each pair.
+/// * `method` - The rounding method to use in case of inexact matching.
+/// * `parallel` - Whether to spread the pairs over the available threads.
+///
+/// # Returns
+///
+/// For each pair, either the interpolated indices or the error of the first failing value along
+/// with its position.
+///
+/// # Panics
+///
+/// Panics if the lengths of the indices and values of the data points of any pair are not equal.
+pub fn inverse_multi<F>(
+    pairs: Vec<Pair<F, F>>,
+    method: Method,
+    parallel: bool,
+) -> Vec<Result<Vec<u64>, BatchError>>
+where
+    u64: Forward<F>,
+    F: Inverse<u64> + Send,
+{
+    map_pairs(pairs, parallel, |(f, xp, fp)| {
+        let interp = Interp::new(xp, fp);
+        f.into_iter()
+            .enumerate()
+            .map(|(index, f)| {
+                interp
+                    .inverse(f, method)
+                    .map_err(|error| BatchError { index, error })
+            })
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::piecewise::InterpError;
+
+    #[test]
+    fn test_map_pairs() {
+        let items: Vec<u64> = (0..1001).collect();
+        let expected: Vec<u64> = items.iter().map(|item| item * item).collect();
+        assert_eq!(
+            map_pairs(items.clone(), false, |item| item * item),
+            expected
+        );
+        assert_eq!(map_pairs(items, true, |item| item * item), expected);
+        assert!(map_pairs(Vec::<u64>::new(), true, |item| item).is_empty());
+    }
+
+    #[test]
+    fn test_forward_multi() {
+        let pairs: Vec<Pair<u64, i64>> = vec![
+            (vec![0, 5, 10], vec![0, 10], vec![100, 200]),
+            (vec![0, 11], vec![0, 10], vec![100, 200]),
+            (vec![1], vec![0, 2], vec![-4, 0]),
+        ];
+        for parallel in [false, true] {
+            assert_eq!(
+                forward_multi(pairs.clone(), parallel),
+                vec![
+                    Ok(vec![100, 150, 200]),
+                    Err(BatchError {
+                        index: 1,
+                        error: InterpError::OutOfBounds
+                    }),
+                    Ok(vec![-2])
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn test_inverse_multi() {
+        let pairs: Vec<Pair<f64, f64>> = vec![
+            (vec![1.0, 1.3], vec![0, 4], vec![1.0, 2.0]),
+            (vec![1.0], vec![0, 2], vec![1.0, 1.0]),
+        ];
+        assert_eq!(
+            inverse_multi(pairs.clone(), Method::Nearest, true),
+            vec![
+                Ok(vec![0, 1]),
+                Err(BatchError {
+                    index: 0,
+                    error: InterpError::NotStrictlyMonotonic
+                })
+            ]
+        );
+        assert_eq!(
+            inverse_multi(pairs, Method::None, false)[0],
+            Err(BatchError {
+                index: 1,
+                error: InterpError::NotFound
+            })
+        );
+    }
+
+    #[test]
+    #[ignore = "benchmark, run with --release --ignored"]
+    fn bench_multi() {
+        let pairs: Vec<Pair<u64, f64>> = (0..10_000u64)
+            .map(|k| {
+                let xp: Vec<u64> = (0..100).map(|i| k + i * 1000).collect();
+                let fp = xp.iter().map(|x| (*x as f64).sqrt()).collect();
+                let x = (0..1000).map(|i| k + i * 99).collect();
+                (x, xp, fp)
+            })
+            .collect();
+        let threads = thread::available_parallelism().map_or(1, |threads| threads.get());
+
+        let input = pairs.clone();
+        let start = std::time::Instant::now();
+        let serial = forward_multi(input, false);
+        let elapsed = start.elapsed();
+        let input = pairs.clone();
+        let start = std::time::Instant::now();
+        let parallel = forward_multi(input, true);
+        let parallel_elapsed = start.elapsed();
+        assert_eq!(parallel, serial);
+        if threads > 1 {
+            assert!(
+                parallel_elapsed < elapsed,
+                "{parallel_elapsed:?} vs {elapsed:?}"
+            );
+        }
+
+        let pairs: Vec<Pair<f64, f64>> = pairs
+            .into_iter()
+            .zip(serial)
+            .map(|((_, xp, fp), f)| (f.unwrap(), xp, fp))
+            .collect();
+        let input = pairs.clone();
+        let start = std::time::Instant::now();
+        let serial = inverse_multi(input, Method::Nearest, false);
+        let elapsed = start.elapsed();
+        let start = std::time::Instant::now();
+        let parallel = inverse_multi(pairs, Method::Nearest, true);
+        let parallel_elapsed = start.elapsed();
+        assert_eq!(parallel, serial);
+        assert!(serial.iter().all(Result::is_ok));
+        if threads > 1 {
+            assert!(
+                parallel_elapsed < elapsed,
+                "{parallel_elapsed:?} vs {elapsed:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,639 @@
+//! Construction of data points from `.npy` files, behind the `npy` feature.
+//!
+//! Dense coordinates are often stored as multi-gigabyte `.npy` files: loading them with numpy
+//! before handing them over doubles the peak memory. The files are instead memory-mapped and
+//! read by streaming over the map, the header being parsed here. Only one-dimensional C-ordered
+//! arrays of little-endian 64-bit integers or floats (`<i8`, `<u8` and `<f8`) are supported,
+//! in the versions 1.0, 2.0 and 3.0 of the format.
+//!
+//! Values are decoded one at a time from their bytes, so that the data need not be aligned and
+//! big-endian hosts read the files correctly.
+
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+use crate::codec::Codec;
+use crate::extended::F80;
+use crate::piecewise::{Interp, InterpError};
+use crate::schemes::{Extended, Forward, Inverse};
+
+/// The first bytes of every `.npy` file.
+pub const MAGIC: &[u8; 6] = b"\x93NUMPY";
+
+/// Errors of the reading of `.npy` files.
+#[derive(PartialEq, Debug)]
+pub enum NpyError {
+    /// The file cannot be opened or mapped.
+    Io(io::ErrorKind),
+    /// The file is not a `.npy` file or its header is malformed.
+    InvalidHeader,
+    /// The version of the format is not supported.
+    UnsupportedVersion(u8),
+    /// The dtype of the array is not supported or not the expected one.
+    UnsupportedDtype(String),
+    /// The array is stored in Fortran order.
+    FortranOrder,
+    /// The array is not one-dimensional.
+    NotOneDimensional,
+    /// The file is shorter than announced by its header (expected and actual bytes of data).
+    Truncated(usize, usize),
+    /// An index is negative (carrying its position).
+    NegativeIndex(usize),
+    /// The data points are invalid.
+    Interp(InterpError),
+}
+
+impl fmt::Display for NpyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NpyError::Io(kind) => write!(f, "cannot read file: {kind}"),
+            NpyError::InvalidHeader => write!(f, "invalid npy header"),
+            NpyError::UnsupportedVersion(version) => {
+                write!(f, "unsupported npy format version {version}")
+            }
+            NpyError::UnsupportedDtype(descr) => write!(f, "unsupported dtype '{descr}'"),
+            NpyError::FortranOrder => write!(f, "fortran order is not supported"),
+            NpyError::NotOneDimensional => write!(f, "array must be 1D"),
+            NpyError::Truncated(expected, actual) => write!(
+                f,
+                "truncated file (expected {expected} bytes of data, got {actual})"
+            ),
+            NpyError::NegativeIndex(position) => write!(f, "xp[{position}] is negative"),
+            NpyError::Interp(err) => write!(f, "{err}"),
+        }
+    }
+}
+impl Error for NpyError {}
+
+impl From<io::Error> for NpyError {
+    fn from(err: io::Error) -> NpyError {
+        NpyError::Io(err.kind())
+    }
+}
+
+/// Implements the `.npy` description of a dtype.
+pub trait Descr: Codec {
+    /// The `descr` field of the header.
+    const DESCR: &'static str;
+}
+impl Descr for u64 {
+    const DESCR: &'static str = "<u8";
+}
+impl Descr for i64 {
+    const DESCR: &'static str = "<i8";
+}
+impl Descr for f64 {
+    const DESCR: &'static str = "<f8";
+}
+
+/// A read-only memory map of a whole file.
+#[cfg(unix)]
+struct Mmap {
+    ptr: *mut libc::c_void,
+    len: usize,
+}
+
+#[cfg(unix)]
+impl Mmap {
+    /// Maps the file, empty files being represented without mapping.
+    fn map(file: &File) -> io::Result<Mmap> {
+        use std::os::unix::io::AsRawFd;
+        let len = usize::try_from(file.metadata()?.len())
+            .map_err(|_| io::Error::from(io::ErrorKind::OutOfMemory))?;
+        if len == 0 {
+            return Ok(Mmap {
+                ptr: std::ptr::null_mut(),
+                len,
+            });
+        }
+        // SAFETY: the mapping is private and read-only, and unmapped only on drop.
+        let ptr = unsafe {
+            libc::mmap(
+                std::ptr::null_mut(),
+                len,
+                libc::PROT_READ,
+                libc::MAP_PRIVATE,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(Mmap { ptr, len })
+    }
+    /// Returns the mapped bytes.
+    fn bytes(&self) -> &[u8] {
+        if self.len == 0 {
+            return &[];
+        }
+        // SAFETY: the mapping covers `len` readable bytes as long as it lives.
+        unsafe { std::slice::from_raw_parts(self.ptr as *const u8, self.len) }
+    }
+}
+
+#[cfg(unix)]
+impl Drop for Mmap {
+    fn drop(&mut self) {
+        if self.len > 0 {
+            // SAFETY: the mapping was created by `map` with this length.
+            unsafe {
+                libc::munmap(self.ptr, self.len);
+            }
+        }
+    }
+}
+
+/// The whole file read in memory, where memory mapping is not available.
+#[cfg(not(unix))]
+struct Mmap(Vec<u8>);
+
+#[cfg(not(unix))]
+impl Mmap {
+    fn map(file: &File) -> io::Result<Mmap> {
+        use std::io::Read;
+        let mut bytes = Vec::new();
+        (&*file).read_to_end(&mut bytes)?;
+        Ok(Mmap(bytes))
+    }
+    fn bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// A one-dimensional array of a memory-mapped `.npy` file.
+pub struct NpyArray {
+    map: Mmap,
+    descr: String,
+    offset: usize,
+    len: usize,
+}
+
+impl NpyArray {
+    /// Opens and memory-maps a `.npy` file, checking its header and its length.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the array.
+    /// Otherwise, returns `Io` if the file cannot be read, `InvalidHeader` or
+    /// `UnsupportedVersion` if its header cannot be parsed, `UnsupportedDtype`,
+    /// `FortranOrder` or `NotOneDimensional` if the array is not supported or `Truncated` if
+    /// the data are shorter than announced.
+    pub fn open(path: impl AsRef<Path>) -> Result<NpyArray, NpyError> {
+        let file = File::open(path)?;
+        let map = Mmap::map(&file)?;
+        let (descr, offset, len) = parse_header(map.bytes())?;
+        let expected = len.checked_mul(8).ok_or(NpyError::InvalidHeader)?;
+        let actual = map.bytes().len() - offset;
+        if actual < expected {
+            return Err(NpyError::Truncated(expected, actual));
+        }
+        Ok(NpyArray {
+            map,
+            descr,
+            offset,
+            len,
+        })
+    }
+    /// Returns the `descr` field of the header, e.g. `<f8`.
+    pub fn descr(&self) -> &str {
+        &self.descr
+    }
+    /// Returns the number of elements.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    /// Whether the array has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    /// Iterates over the elements, decoded from the map one at a time.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the iterator.
+    /// Otherwise, returns `UnsupportedDtype` if the array does not have the dtype of `T`.
+    pub fn values<T: Descr>(&self) -> Result<impl Iterator<Item = T> + '_, NpyError> {
+        if self.descr != T::DESCR {
+            return Err(NpyError::UnsupportedDtype(self.descr.clone()));
+        }
+        let data = &self.map.bytes()[self.offset..self.offset + 8 * self.len];
+        Ok(data
+            .chunks_exact(8)
+            .map(|bytes| T::decode(bytes).expect("eight bytes")))
+    }
+    /// Iterates over the elements as indices, from either unsigned or signed integers.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the iterator, yielding `NegativeIndex` for negative elements.
+    /// Otherwise, returns `UnsupportedDtype` if the array does not have a 64-bit integer dtype.
+    fn indices(&self) -> Result<Box<dyn Iterator<Item = Result<u64, NpyError>> + '_>, NpyError> {
+        if self.descr == i64::DESCR {
+            Ok(Box::new(self.values::<i64>()?.enumerate().map(
+                |(position, x)| u64::try_from(x).map_err(|_| NpyError::NegativeIndex(position)),
+            )))
+        } else {
+            Ok(Box::new(self.values::<u64>()?.map(Ok)))
+        }
+    }
+}
+
+/// Parses the header of a `.npy` file.
+///
+/// # Returns
+///
+/// If successful, returns the `descr` field, the offset of the data and the number of elements.
+/// Otherwise, returns an error indicating the reason for failure.
+fn parse_header(bytes: &[u8]) -> Result<(String, usize, usize), NpyError> {
+    if bytes.len() < 10 || &bytes[..6] != MAGIC {
+        return Err(NpyError::InvalidHeader);
+    }
+    let (start, header_len) = match bytes[6] {
+        1 => (10, u16::from_le_bytes([bytes[8], bytes[9]]) as usize),
+        2 | 3 => {
+            let size = bytes.get(8..12).ok_or(NpyError::InvalidHeader)?;
+            (12, u32::from_le_bytes(size.try_into().unwrap()) as usize)
+        }
+        version => return Err(NpyError::UnsupportedVersion(version)),
+    };
+    let header = bytes
+        .get(start..start + header_len)
+        .ok_or(NpyError::InvalidHeader)?;
+    let header = std::str::from_utf8(header).map_err(|_| NpyError::InvalidHeader)?;
+    let descr = field(header, "descr").ok_or(NpyError::InvalidHeader)?;
+    let quote = descr.chars().next().ok_or(NpyError::InvalidHeader)?;
+    if quote != '\'' && quote != '"' {
+        // structured dtypes are described by a list
+        return Err(NpyError::UnsupportedDtype(descr.to_string()));
+    }
+    let descr = descr[1..]
+        .split(quote)
+        .next()
+        .ok_or(NpyError::InvalidHeader)?;
+    let fortran_order = field(header, "fortran_order").ok_or(NpyError::InvalidHeader)?;
+    if fortran_order.starts_with("True") {
+        return Err(NpyError::FortranOrder);
+    } else if !fortran_order.starts_with("False") {
+        return Err(NpyError::InvalidHeader);
+    }
+    if ![u64::DESCR, i64::DESCR, f64::DESCR].contains(&descr) {
+        return Err(NpyError::UnsupportedDtype(descr.to_string()));
+    }
+    let shape = field(header, "shape")
+        .and_then(|shape| shape.strip_prefix('('))
+        .and_then(|shape| shape.split(')').next())
+        .ok_or(NpyError::InvalidHeader)?;
+    let dims = shape
+        .split(',')
+        .map(str::trim)
+        .filter(|dim| !dim.is_empty())
+        .map(|dim| dim.parse::<usize>().map_err(|_| NpyError::InvalidHeader))
+        .collect::<Result<Vec<usize>, NpyError>>()?;
+    match dims[..] {
+        [len] => Ok((descr.to_string(), start + header_len, len)),
+        _ => Err(NpyError::NotOneDimensional),
+    }
+}
+
+/// Returns the text following the given key of the header dictionary.
+fn field<'a>(header: &'a str, key: &str) -> Option<&'a str> {
+    ["'", "\""].iter().find_map(|quote| {
+        let pattern = format!("{quote}{key}{quote}");
+        let (_, rest) = header.split_once(&pattern)?;
+        Some(rest.trim_start().strip_prefix(':')?.trim_start())
+    })
+}
+
+impl<F> Interp<u64, F>
+where
+    u64: Forward<F>,
+    F: Inverse<u64> + Extended + Descr,
+{
+    /// Builds an Interp from densely sampled values stored in a `.npy` file, the i-th value
+    /// being located at index i, keeping only the samples needed to recover all of them within
+    /// `epsilon`. The samples are simplified greedily in a single pass over the memory-mapped
+    /// file: each segment is grown from its first sample as long as the chord to the next sample
+    /// stays within the tolerance of all the samples in between, which is tracked as an interval
+    /// of admissible slopes. The kept data points are samples, including the extreme ones, but
+    /// may differ from those of `simplify`.
+    ///
+    /// The slopes are computed with extended-precision floats and integer tolerances are
+    /// reduced by one to absorb the rounding of the interpolation. The bound is verified with a
+    /// second pass: in the unlikely case where rounding broke it, every sample is kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the `.npy` file, of the dtype of the values.
+    /// * `epsilon` - The maximum absolute deviation between the interpolation and the samples.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the Interp.
+    /// Otherwise, returns `Interp(Overflow)` if `epsilon` or a sample is not finite,
+    /// `Interp(InvalidArgument)` if `epsilon` is negative or an error indicating the reason for
+    /// failure (see `NpyArray::open`).
+    pub fn from_npy_dense(path: impl AsRef<Path>, epsilon: F) -> Result<Interp<u64, F>, NpyError> {
+        let not_finite = || NpyError::Interp(InterpError::Overflow);
+        let epsilon = epsilon.to_extended().ok_or_else(not_finite)?;
+        if epsilon < *F80::zero() {
+            return Err(NpyError::Interp(InterpError::InvalidArgument(
+                "epsilon must be non-negative",
+            )));
+        }
+        let array = NpyArray::open(path)?;
+        let tolerance = epsilon
+            .sub(&F80::from(F::MARGIN))
+            .max(F80::clone(F80::zero()));
+        let mut xp = Vec::new();
+        let mut fp = Vec::new();
+        // the first sample of the current segment and the admissible slopes of its chord
+        let mut anchor: Option<(u64, F80)> = None;
+        let mut slopes: Option<(F80, F80)> = None;
+        let mut previous: Option<(u64, F, F80)> = None;
+        for (index, value) in (0..).zip(array.values::<F>()?) {
+            let sample = value.to_extended().ok_or_else(not_finite)?;
+            let Some((mut start, mut start_value)) = anchor.clone() else {
+                xp.push(index);
+                fp.push(value);
+                anchor = Some((index, sample.clone()));
+                previous = Some((index, value, sample));
+                continue;
+            };
+            if let Some((lo, hi)) = &slopes {
+                let slope = sample.sub(&start_value).div(&F80::from(index - start));
+                if !(slope >= *lo && slope <= *hi) {
+                    // the chord to this sample leaves a sample behind: close on the previous one
+                    let (last, last_value, last_sample) =
+                        previous.expect("a sample after the anchor");
+                    xp.push(last);
+                    fp.push(last_value);
+                    (start, start_value) = (last, last_sample);
+                    anchor = Some((start, start_value.clone()));
+                    slopes = None;
+                }
+            }
+            let run = F80::from(index - start);
+            let rise = sample.sub(&start_value);
+            let lo = rise.sub(&tolerance).div(&run);
+            let hi = rise.add(&tolerance).div(&run);
+            slopes = Some(match slopes {
+                Some((old_lo, old_hi)) => (old_lo.max(lo), old_hi.min(hi)),
+                None => (lo, hi),
+            });
+            previous = Some((index, value, sample));
+        }
+        if let Some((last, last_value, _)) = previous {
+            if xp.last() != Some(&last) {
+                xp.push(last);
+                fp.push(last_value);
+            }
+        }
+        let interp = Interp::new(xp, fp);
+        let valid = (0..).zip(array.values::<F>()?).all(|(index, value)| {
+            let fitted = interp.forward(index).ok().and_then(F::to_extended);
+            match (fitted, value.to_extended()) {
+                (Some(fitted), Some(value)) => {
+                    fitted.clone().max(value.clone()).sub(&fitted.min(value)) <= epsilon
+                }
+                _ => false,
+            }
+        });
+        if valid {
+            Ok(interp)
+        } else {
+            let fp: Vec<F> = array.values::<F>()?.collect();
+            Ok(Interp::new((0..fp.len() as u64).collect(), fp))
+        }
+    }
+    /// Builds an Interp from data points stored in two `.npy` files, the indices being either
+    /// unsigned or non-negative signed 64-bit integers. The indices are validated while they are
+    /// copied from the memory-mapped file, which is the only copy of the data points.
+    ///
+    /// # Arguments
+    ///
+    /// * `xp_path` - The path of the `.npy` file of the indices.
+    /// * `fp_path` - The path of the `.npy` file of the values, of the dtype of the values.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the Interp.
+    /// Otherwise, returns an error indicating the reason for failure (see `NpyArray::open`),
+    /// `NegativeIndex` if an index is negative or `Interp` with `LengthMismatch` or
+    /// `NotStrictlyIncreasing` if the data points are invalid.
+    pub fn from_npy_pair(
+        xp_path: impl AsRef<Path>,
+        fp_path: impl AsRef<Path>,
+    ) -> Result<Interp<u64, F>, NpyError> {
+        let xp_array = NpyArray::open(xp_path)?;
+        let fp_array = NpyArray::open(fp_path)?;
+        if xp_array.len() != fp_array.len() {
+            return Err(NpyError::Interp(InterpError::LengthMismatch(
+                xp_array.len(),
+                fp_array.len(),
+            )));
+        }
+        let mut xp: Vec<u64> = Vec::with_capacity(xp_array.len());
+        for x in xp_array.indices()? {
+            let x = x?;
+            if xp.last().is_some_and(|last| *last >= x) {
+                return Err(NpyError::Interp(InterpError::NotStrictlyIncreasing));
+            }
+            xp.push(x);
+        }
+        let fp = fp_array.values::<F>()?.collect();
+        Interp::from_parts(xp, fp).map_err(NpyError::Interp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+
+    /// Writes a `.npy` file with the given header dictionary and data in the temporary
+    /// directory, returning its path.
+    fn write_npy(name: &str, dict: &str, data: &[u8]) -> std::path::PathBuf {
+        let mut header = dict.to_string();
+        while !(10 + header.len() + 1).is_multiple_of(64) {
+            header.push(' ');
+        }
+        header.push('\n');
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend_from_slice(&[1, 0]);
+        bytes.extend_from_slice(&(header.len() as u16).to_le_bytes());
+        bytes.extend_from_slice(header.as_bytes());
+        bytes.extend_from_slice(data);
+        let path = std::env::temp_dir().join(format!("xinterp-{}-{name}.npy", std::process::id()));
+        std::fs::write(&path, bytes).unwrap();
+        path
+    }
+
+    fn dict(descr: &str, len: usize) -> String {
+        format!("{{'descr': '{descr}', 'fortran_order': False, 'shape': ({len},), }}")
+    }
+
+    fn data<T: Codec>(values: &[T]) -> Vec<u8> {
+        let mut out = Vec::new();
+        for value in values {
+            value.encode(&mut out);
+        }
+        out
+    }
+
+    #[test]
+    fn test_from_npy_dense() {
+        let mut rng = Rng(0x9e3779b97f4a7c15);
+        let mut values = vec![0i64];
+        for _ in 1..2000 {
+            let step = [0, 10, 10, 11, 200][rng.below(5) as usize];
+            values.push(values.last().unwrap() + step);
+        }
+        let path = write_npy("dense-int", &dict("<i8", values.len()), &data(&values));
+        for epsilon in [0, 1, 5, 100] {
+            let interp = Interp::<u64, i64>::from_npy_dense(&path, epsilon).unwrap();
+            assert_eq!(interp.xp().first(), Some(&0));
+            assert_eq!(interp.xp().last(), Some(&1999));
+            assert!(interp.len() < values.len());
+            for (index, value) in values.iter().enumerate() {
+                let fitted = interp.forward(index as u64).unwrap();
+                assert!((fitted - value).abs() <= epsilon);
+            }
+            for (x, f) in interp.iter() {
+                assert_eq!(values[*x as usize], *f);
+            }
+        }
+        let line: Vec<f64> = (0..1000).map(|x| 0.25 * x as f64 - 3.0).collect();
+        let path = write_npy("dense-float", &dict("<f8", line.len()), &data(&line));
+        let interp = Interp::<u64, f64>::from_npy_dense(&path, 0.0).unwrap();
+        assert_eq!(interp.xp(), &[0, 999]);
+        assert_eq!(
+            Interp::<u64, f64>::from_npy_dense(&path, -1.0).err(),
+            Some(NpyError::Interp(InterpError::InvalidArgument(
+                "epsilon must be non-negative"
+            )))
+        );
+        assert_eq!(
+            Interp::<u64, i64>::from_npy_dense(&path, 0).err(),
+            Some(NpyError::UnsupportedDtype("<f8".to_string()))
+        );
+        let path = write_npy("dense-nan", &dict("<f8", 3), &data(&[0.0, f64::NAN, 1.0]));
+        assert_eq!(
+            Interp::<u64, f64>::from_npy_dense(&path, 0.0).err(),
+            Some(NpyError::Interp(InterpError::Overflow))
+        );
+        let path = write_npy("dense-empty", &dict("<f8", 0), &[]);
+        assert!(Interp::<u64, f64>::from_npy_dense(&path, 0.0)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_from_npy_pair() {
+        let xp = write_npy("pair-xp", &dict("<i8", 3), &data(&[0i64, 10, 30]));
+        let fp = write_npy("pair-fp", &dict("<f8", 3), &data(&[1.0, 2.0, 4.0]));
+        let interp = Interp::<u64, f64>::from_npy_pair(&xp, &fp).unwrap();
+        assert_eq!(interp, Interp::new(vec![0, 10, 30], vec![1.0, 2.0, 4.0]));
+        let unsigned = write_npy("pair-xp-unsigned", &dict("<u8", 3), &data(&[0u64, 10, 30]));
+        let interp = Interp::<u64, f64>::from_npy_pair(&unsigned, &fp).unwrap();
+        assert_eq!(interp.forward(20), Ok(3.0));
+
+        let unsorted = write_npy("pair-xp-unsorted", &dict("<i8", 3), &data(&[0i64, 30, 10]));
+        assert_eq!(
+            Interp::<u64, f64>::from_npy_pair(&unsorted, &fp).err(),
+            Some(NpyError::Interp(InterpError::NotStrictlyIncreasing))
+        );
+        let negative = write_npy("pair-xp-negative", &dict("<i8", 3), &data(&[-1i64, 0, 1]));
+        assert_eq!(
+            Interp::<u64, f64>::from_npy_pair(&negative, &fp).err(),
+            Some(NpyError::NegativeIndex(0))
+        );
+        let short = write_npy("pair-xp-short", &dict("<i8", 2), &data(&[0i64, 1]));
+        assert_eq!(
+            Interp::<u64, f64>::from_npy_pair(&short, &fp).err(),
+            Some(NpyError::Interp(InterpError::LengthMismatch(2, 3)))
+        );
+        assert_eq!(
+            Interp::<u64, f64>::from_npy_pair(&xp, &xp).err(),
+            Some(NpyError::UnsupportedDtype("<i8".to_string()))
+        );
+        let empty_xp = write_npy("pair-xp-empty", &dict("<i8", 0), &[]);
+        let empty_fp = write_npy("pair-fp-empty", &dict("<f8", 0), &[]);
+        assert!(Interp::<u64, f64>::from_npy_pair(&empty_xp, &empty_fp)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_errors() {
+        let open =
+            |name: &str, dict: &str, data: &[u8]| NpyArray::open(write_npy(name, dict, data)).err();
+        let values = data(&[1.0, 2.0]);
+        assert_eq!(
+            open("truncated", &dict("<f8", 3), &values),
+            Some(NpyError::Truncated(24, 16))
+        );
+        assert_eq!(
+            open("int32", &dict("<i4", 4), &values),
+            Some(NpyError::UnsupportedDtype("<i4".to_string()))
+        );
+        assert_eq!(
+            open("big-endian", &dict(">f8", 2), &values),
+            Some(NpyError::UnsupportedDtype(">f8".to_string()))
+        );
+        let fortran = "{'descr': '<f8', 'fortran_order': True, 'shape': (2,), }";
+        assert_eq!(
+            open("fortran", fortran, &values),
+            Some(NpyError::FortranOrder)
+        );
+        let matrix = "{'descr': '<f8', 'fortran_order': False, 'shape': (1, 2), }";
+        assert_eq!(
+            open("matrix", matrix, &values),
+            Some(NpyError::NotOneDimensional)
+        );
+        let scalar = "{'descr': '<f8', 'fortran_order': False, 'shape': (), }";
+        assert_eq!(
+            open("scalar", scalar, &values),
+            Some(NpyError::NotOneDimensional)
+        );
+        let structured = "{'descr': [('a', '<f8')], 'fortran_order': False, 'shape': (2,), }";
+        assert!(matches!(
+            open("structured", structured, &values),
+            Some(NpyError::UnsupportedDtype(_))
+        ));
+        assert_eq!(
+            open("garbled", "{'descr'", &values),
+            Some(NpyError::InvalidHeader)
+        );
+
+        let path = write_npy("corrupted", &dict("<f8", 2), &values);
+        let mut bytes = std::fs::read(&path).unwrap();
+        bytes[0] = b'x';
+        std::fs::write(&path, &bytes).unwrap();
+        assert_eq!(NpyArray::open(&path).err(), Some(NpyError::InvalidHeader));
+        bytes[0] = MAGIC[0];
+        bytes[6] = 4;
+        std::fs::write(&path, &bytes).unwrap();
+        assert_eq!(
+            NpyArray::open(&path).err(),
+            Some(NpyError::UnsupportedVersion(4))
+        );
+        std::fs::write(&path, b"").unwrap();
+        assert_eq!(NpyArray::open(&path).err(), Some(NpyError::InvalidHeader));
+        assert_eq!(
+            NpyArray::open(std::env::temp_dir().join("xinterp-missing.npy")).err(),
+            Some(NpyError::Io(io::ErrorKind::NotFound))
+        );
+        assert_eq!(
+            NpyError::Truncated(24, 16).to_string(),
+            "truncated file (expected 24 bytes of data, got 16)"
+        );
+    }
+}
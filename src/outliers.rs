@@ -0,0 +1,142 @@
+//! Detection of values deviating from the local linear trend.
+//!
+//! Each value is compared to the linear interpolation between the endpoints of a centered window
+//! of data points. Near the edges, the window shrinks symmetrically. The first and last values,
+//! which have no neighbor on one side, are compared to the linear extrapolation of the two
+//! nearest window endpoints on the other side, computed with extended-precision floats.
+
+use crate::divop::Method;
+use crate::extended::F80;
+use crate::piecewise::InterpError;
+use crate::schemes::{Distance, Extended, Forward};
+
+/// Finds the data points whose value deviates from the local linear trend by more than a
+/// threshold.
+///
+/// # Arguments
+///
+/// * `xp` - The indices of the data points, must be strictly increasing.
+/// * `fp` - The values of the data points.
+/// * `window` - The width of the window, even widths are rounded down to the previous odd width.
+///   A width of at least three is required to detect anything.
+/// * `threshold` - The maximum deviation from the trend.
+///
+/// # Returns
+///
+/// If successful, returns the sorted positions of the outliers.
+/// Otherwise, returns `LengthMismatch` if `xp` and `fp` do not have the same length or
+/// `NotStrictlyIncreasing` if the indices are not strictly increasing.
+pub fn detect_outliers<F>(
+    xp: &[u64],
+    fp: &[F],
+    window: usize,
+    threshold: F::Output,
+) -> Result<Vec<usize>, InterpError>
+where
+    u64: Forward<F>,
+    F: Distance + Extended,
+{
+    if xp.len() != fp.len() {
+        return Err(InterpError::LengthMismatch(xp.len(), fp.len()));
+    }
+    if !xp.windows(2).all(|pair| pair[0] < pair[1]) {
+        return Err(InterpError::NotStrictlyIncreasing);
+    }
+    let half = window.saturating_sub(1) / 2;
+    let last = xp.len().saturating_sub(1);
+    let mut out = Vec::new();
+    for index in 0..xp.len() {
+        let reach = half.min(index).min(last - index);
+        let trend = if reach > 0 {
+            let (start, end) = (index - reach, index + reach);
+            xp[index].forward(xp[start], xp[end], fp[start], fp[end], Method::Nearest)
+        } else {
+            let reach = half.min(last / 2);
+            if reach == 0 {
+                continue;
+            }
+            let (near, far) = if index == 0 {
+                (reach, 2 * reach)
+            } else {
+                (last - reach, last - 2 * reach)
+            };
+            extrapolate(xp, fp, near, far, index)
+        };
+        match trend {
+            Some(trend) if trend.distance(fp[index]) <= threshold => (),
+            _ => out.push(index),
+        }
+    }
+    Ok(out)
+}
+
+/// Extrapolates the line joining two data points at the index of a third one, None if one of
+/// their values is not finite.
+fn extrapolate<F: Extended>(
+    xp: &[u64],
+    fp: &[F],
+    near: usize,
+    far: usize,
+    index: usize,
+) -> Option<F> {
+    let x = F80::from(xp[index]);
+    let (x0, x1) = (F80::from(xp[near]), F80::from(xp[far]));
+    let (f0, f1) = (fp[near].to_extended()?, fp[far].to_extended()?);
+    let f = f0.add(&f1.sub(&f0).mul(&x.sub(&x0)).div(&x1.sub(&x0)));
+    Some(F::from_extended(f))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_isolated() {
+        let xp: Vec<u64> = (0..20).collect();
+        let mut fp: Vec<i64> = (0..20)
+            .map(|x| 1_700_000_000_000_000_000 + 10 * x)
+            .collect();
+        assert_eq!(detect_outliers(&xp, &fp, 5, 0), Ok(vec![]));
+        fp[7] += 100;
+        fp[13] -= 100;
+        assert_eq!(detect_outliers(&xp, &fp, 5, 60), Ok(vec![7, 13]));
+        assert_eq!(detect_outliers(&xp, &fp, 3, 60), Ok(vec![7, 13]));
+        assert_eq!(
+            detect_outliers(&xp, &fp, 5, 40),
+            Ok(vec![5, 7, 9, 11, 13, 15])
+        );
+        assert_eq!(detect_outliers(&xp, &fp, 1, 0), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_consecutive() {
+        let xp: Vec<u64> = (0..20).map(|x| 2 * x).collect();
+        let mut fp: Vec<f64> = (0..20).map(|x| 0.5 * x as f64).collect();
+        fp[8] += 10.0;
+        fp[9] += 10.0;
+        assert_eq!(detect_outliers(&xp, &fp, 5, 6.0), Ok(vec![8, 9]));
+        assert_eq!(detect_outliers(&xp, &fp, 3, 6.0), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_ends() {
+        let xp: Vec<u64> = (0..10).collect();
+        let mut fp: Vec<i64> = (0..10).map(|x| 3 * x).collect();
+        fp[0] = 50;
+        fp[9] -= 50;
+        assert_eq!(detect_outliers(&xp, &fp, 5, 30), Ok(vec![0, 9]));
+        assert_eq!(detect_outliers(&xp[..2], &fp[..2], 5, 0), Ok(vec![]));
+        assert_eq!(detect_outliers::<i64>(&[], &[], 5, 0), Ok(vec![]));
+        let values = [f64::INFINITY, 1.0, 2.0, 3.0, 4.0];
+        assert_eq!(detect_outliers(&xp[..5], &values, 3, 0.5), Ok(vec![0, 1]));
+        assert_eq!(
+            detect_outliers(&xp, &fp[..3], 3, 0),
+            Err(InterpError::LengthMismatch(xp.len(), 3))
+        );
+        let xp: Vec<u64> = vec![0, 2, 1];
+        assert_eq!(
+            detect_outliers(&xp, &fp[..3], 3, 0),
+            Err(InterpError::NotStrictlyIncreasing)
+        );
+    }
+}
@@ -0,0 +1,298 @@
+//! Interpolation of values packed as integers with the CF conventions.
+//!
+//! NetCDF variables following the CF conventions often store floating values as small integers
+//! along with `scale_factor` and `add_offset` attributes, the physical value being
+//! `packed * scale_factor + add_offset`. Unpacking whole arrays to f64 defeats the memory
+//! savings of packing: `Packed` keeps the integer values and only converts the values crossing
+//! its boundary, in extended precision. Interpolated values are computed from the exact integers
+//! and unpacked at the end, and queried values are packed into a fractional F80 that is located
+//! among the integers without being rounded.
+
+use std::ops::Range;
+
+use crate::divop::Method;
+use crate::extended::F80;
+use crate::piecewise::{Interp, InterpError, Order};
+
+/// Structure for performing interpolation on packed integer values (e.g. int32 counts) with
+/// values exchanged in physical units.
+///
+/// Unpacking computes `packed * scale_factor + add_offset` with a single rounding to an F80,
+/// then rounds to the nearest f64. A negative scale factor reverses the order of the values,
+/// hence inverse interpolation requires the unpacked values to be strictly monotonic, which is
+/// checked on creation: distinct integers unpacked to the same f64 (e.g. a scale factor too small
+/// for the offset) break the monotonicity.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Packed {
+    interp: Interp<u64, i64>,
+    scale_factor: F80,
+    add_offset: F80,
+    order: Order,
+}
+
+impl Packed {
+    /// Creates a new Packed instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `interp` - The interpolation of the packed values.
+    /// * `scale_factor` - The factor applied to the packed values.
+    /// * `add_offset` - The offset added to the scaled values.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the Packed.
+    /// Otherwise, returns `InvalidArgument` if the scale factor is zero or not finite or if the
+    /// offset is not finite.
+    pub fn new(
+        interp: Interp<u64, i64>,
+        scale_factor: f64,
+        add_offset: f64,
+    ) -> Result<Packed, InterpError> {
+        let scale_factor = F80::from_finite(scale_factor)
+            .filter(|scale_factor| scale_factor != F80::zero())
+            .ok_or(InterpError::InvalidArgument(
+                "scale_factor must be finite and nonzero",
+            ))?;
+        let add_offset = F80::from_finite(add_offset)
+            .ok_or(InterpError::InvalidArgument("add_offset must be finite"))?;
+        Ok(Packed::assemble(interp, scale_factor, add_offset))
+    }
+    /// Returns the interpolation of the packed values.
+    pub fn interp(&self) -> &Interp<u64, i64> {
+        &self.interp
+    }
+    /// Returns the factor applied to the packed values.
+    pub fn scale_factor(&self) -> f64 {
+        self.scale_factor.clone().into()
+    }
+    /// Returns the offset added to the scaled values.
+    pub fn add_offset(&self) -> f64 {
+        self.add_offset.clone().into()
+    }
+    /// Returns the monotonicity of the unpacked values.
+    pub fn order(&self) -> Order {
+        self.order
+    }
+    /// Whether the unpacked values are strictly monotonic, as required by inverse
+    /// interpolation.
+    pub fn is_inversable(&self) -> bool {
+        self.order != Order::Unordered
+    }
+    /// Unpacks a value into physical units.
+    pub fn unpack(&self, packed: i64) -> f64 {
+        self.unpack_extended(&F80::from(packed))
+    }
+    /// Packs a physical value into a fractional packed value, computed in extended precision.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the packed value.
+    /// Otherwise, returns `NotFound` if the value is not finite.
+    pub fn pack(&self, value: f64) -> Result<F80, InterpError> {
+        let value = F80::from_finite(value).ok_or(InterpError::NotFound)?;
+        Ok(value.sub(&self.add_offset).div(&self.scale_factor))
+    }
+    /// Performs forward interpolation at the given index. The packed value is interpolated in
+    /// extended precision without being rounded to an integer, then unpacked.
+    ///
+    /// # Arguments
+    ///
+    /// * `rhs` - The index for forward interpolation.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the interpolated value in physical units.
+    /// Otherwise, returns an error indicating the reason for failure.
+    pub fn forward(&self, rhs: u64) -> Result<f64, InterpError> {
+        self.interp
+            .forward_as(rhs, Method::None, |packed| F80::from(*packed))
+            .map(|packed| self.unpack_extended(&packed))
+    }
+    /// Performs inverse interpolation at the given value, with the semantics of
+    /// `Interp::inverse`: the rounding methods refer to the direction of the indices, whatever
+    /// the sign of the scale factor. Values unpacked from an integer are inverted as that
+    /// integer, others as their fractional packed value.
+    ///
+    /// # Arguments
+    ///
+    /// * `rhs` - The value for inverse interpolation, in physical units.
+    /// * `method` - The rounding method to use in case of inexact matching, None for the
+    ///   default method of the interpolation.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the interpolated index.
+    /// Otherwise, returns `NotStrictlyMonotonic` if the unpacked values are not strictly
+    /// monotonic or an error from `pack` or from `Interp::inverse`.
+    pub fn inverse(&self, rhs: f64, method: impl Into<Option<Method>>) -> Result<u64, InterpError> {
+        if !self.is_inversable() {
+            return Err(InterpError::NotStrictlyMonotonic);
+        }
+        let packed = self.pack(rhs)?;
+        match packed.round().try_to_i64() {
+            Ok(integer) if self.unpack(integer) == rhs => self.interp.inverse(integer, method),
+            _ => self
+                .interp
+                .inverse_as(packed, method, |packed| F80::from(*packed)),
+        }
+    }
+    /// Selects the data points in the given range of positions (see `Interp::slice`).
+    ///
+    /// # Returns
+    ///
+    /// The Packed of the selected data points, None if the range is out of bounds or reversed.
+    pub fn slice(&self, range: Range<usize>) -> Option<Packed> {
+        let interp = self.interp.slice(range)?;
+        Some(Packed::assemble(
+            interp,
+            self.scale_factor.clone(),
+            self.add_offset.clone(),
+        ))
+    }
+    /// Restricts the function to the given closed interval of indices (see `Interp::crop`).
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the cropped Packed.
+    /// Otherwise, returns an error indicating the reason for failure.
+    pub fn crop(&self, start: u64, end: u64) -> Result<Packed, InterpError> {
+        let interp = self.interp.crop(start, end)?;
+        Ok(Packed::assemble(
+            interp,
+            self.scale_factor.clone(),
+            self.add_offset.clone(),
+        ))
+    }
+    /// Creates a Packed from checked CF attributes.
+    fn assemble(interp: Interp<u64, i64>, scale_factor: F80, add_offset: F80) -> Packed {
+        let mut packed = Packed {
+            interp,
+            scale_factor,
+            add_offset,
+            order: Order::Unordered,
+        };
+        packed.order = packed.unpacked_order();
+        packed
+    }
+    /// Unpacks a fractional packed value into physical units.
+    fn unpack_extended(&self, packed: &F80) -> f64 {
+        packed.mul_add(&self.scale_factor, &self.add_offset).into()
+    }
+    /// Checks the monotonicity of the unpacked values, one at a time.
+    fn unpacked_order(&self) -> Order {
+        let (mut increasing, mut decreasing) = (true, true);
+        let mut previous = None;
+        for value in self.interp.fp().iter().map(|packed| self.unpack(*packed)) {
+            if let Some(previous) = previous {
+                increasing &= previous < value;
+                decreasing &= previous > value;
+            }
+            previous = Some(value);
+        }
+        if increasing {
+            Order::Increasing
+        } else if decreasing {
+            Order::Decreasing
+        } else {
+            Order::Unordered
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_negative_scale_factor() {
+        let interp = Interp::new(vec![0, 10, 20], vec![100i64, 200, 400]);
+        assert_eq!(
+            Packed::new(interp.clone(), 0.0, 10.0),
+            Err(InterpError::InvalidArgument(
+                "scale_factor must be finite and nonzero"
+            ))
+        );
+        assert_eq!(
+            Packed::new(interp.clone(), f64::NAN, 10.0),
+            Err(InterpError::InvalidArgument(
+                "scale_factor must be finite and nonzero"
+            ))
+        );
+        assert_eq!(
+            Packed::new(interp.clone(), -0.5, f64::INFINITY),
+            Err(InterpError::InvalidArgument("add_offset must be finite"))
+        );
+        let packed = Packed::new(interp, -0.5, 10.0).unwrap();
+        assert_eq!(packed.order(), Order::Decreasing);
+        assert_eq!(packed.unpack(100), -40.0);
+        assert_eq!(packed.forward(0), Ok(-40.0));
+        assert_eq!(packed.forward(5), Ok(-65.0));
+        assert_eq!(packed.forward(15), Ok(-140.0));
+        assert_eq!(packed.forward(21), Err(InterpError::OutOfBounds));
+        assert_eq!(packed.inverse(-65.0, None), Ok(5));
+        assert_eq!(packed.inverse(-190.0, Method::None), Ok(20));
+        // -66 packs to 152, i.e. index 5.2: previous and next refer to the indices.
+        assert_eq!(
+            packed.inverse(-66.0, Method::None),
+            Err(InterpError::NotFound)
+        );
+        assert_eq!(packed.inverse(-66.0, Method::Nearest), Ok(5));
+        assert_eq!(packed.inverse(-66.0, Method::ForwardFill), Ok(5));
+        assert_eq!(packed.inverse(-66.0, Method::BackwardFill), Ok(6));
+        // Fractional packed values are not rounded to an integer first.
+        assert_eq!(packed.inverse(-65.8, Method::BackwardFill), Ok(6));
+        assert_eq!(packed.inverse(-30.0, Method::Nearest), Ok(0));
+        assert_eq!(
+            packed.inverse(-30.0, Method::None),
+            Err(InterpError::OutOfBounds)
+        );
+        assert_eq!(packed.inverse(-200.0, Method::Nearest), Ok(20));
+        assert_eq!(packed.inverse(f64::NAN, None), Err(InterpError::NotFound));
+        let cropped = packed.crop(5, 15).unwrap();
+        assert_eq!(cropped.interp().fp(), &[150, 200, 300]);
+        assert_eq!(cropped.order(), Order::Decreasing);
+        assert_eq!(packed.slice(1..3).unwrap().forward(10), Ok(-90.0));
+    }
+
+    #[test]
+    fn test_offset_precision() {
+        // Epoch seconds packed as int32 milliseconds: f32 values are 128 s apart at this
+        // magnitude, so naive f32 unpacking merges thousands of consecutive samples.
+        let (scale_factor, add_offset) = (1e-3, 1.7e9);
+        let interp = Interp::new(vec![0, 1000], vec![0i64, i32::MAX as i64]);
+        let packed = Packed::new(interp, scale_factor, add_offset).unwrap();
+        let naive = |packed: i64| packed as f32 * scale_factor as f32 + add_offset as f32;
+        assert_eq!(naive(0), naive(50_000));
+        for x in [0, 1, 2, 499, 500, 999, 1000] {
+            let value = packed.forward(x).unwrap();
+            assert_eq!(packed.inverse(value, Method::Nearest), Ok(x));
+        }
+        assert_eq!(packed.forward(1000), Ok(1_702_147_483.647));
+        assert_eq!(packed.inverse(1_702_147_483.647, Method::None), Ok(1000));
+        assert_eq!(packed.inverse(1_700_000_000.0, Method::None), Ok(0));
+
+        // Offsets beyond the range of f32 overflow to infinity.
+        let interp = Interp::new(vec![0, 10], vec![i32::MIN as i64, i32::MAX as i64]);
+        let packed = Packed::new(interp, 1e28, 4e38).unwrap();
+        assert!((packed.add_offset() as f32).is_infinite());
+        assert_eq!(packed.unpack(0), 4e38);
+        assert_eq!(packed.forward(0), Ok(4e38 - 2f64.powi(31) * 1e28));
+        assert_eq!(packed.inverse(packed.unpack(-1), Method::Nearest), Ok(5));
+        assert_eq!(
+            packed.inverse(packed.unpack(-1), Method::None),
+            Err(InterpError::NotFound)
+        );
+
+        // A scale factor too small for the offset unpacks distinct integers to the same f64.
+        let interp = Interp::new(vec![0, 10], vec![0i64, 1]);
+        let packed = Packed::new(interp, 1e-9, 1.7e9).unwrap();
+        assert!(packed.interp().is_inversable());
+        assert_eq!(packed.order(), Order::Unordered);
+        assert_eq!(
+            packed.inverse(1.7e9, None),
+            Err(InterpError::NotStrictlyMonotonic)
+        );
+        assert_eq!(packed.forward(10), Ok(1.7e9));
+    }
+}
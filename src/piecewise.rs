@@ -26,14 +26,42 @@
 //! # Errors
 //!
 //! - `InterpError::OutOfBounds`: Indicates that the input value is outside the range of known
-//! data points.
+//!   data points.
 //! - `InterpError::NotFound`: Indicates that the output value does not exist within the range of
-//! known data points.
+//!   known data points.
 //! - `InterpError::NotStrictlyIncreasing`: Indicates that the input or output values are not
-//! strictly increasing, which is required for interpolation.
+//!   strictly increasing, which is required for interpolation.
+//! - `InterpError::NotStrictlyMonotonic`: Indicates that the output values are neither strictly
+//!   increasing nor strictly decreasing, which is required for inverse interpolation.
+//! - `InterpError::Overflow`: Indicates that a transformation of the data points overflows.
+//! - `InterpError::LengthMismatch`: Indicates that the indices and values of the data points do
+//!   not have the same length (carrying both lengths).
+//! - `InterpError::NoSamples`: Indicates that zero samples were requested.
+//! - `InterpError::Empty`: Indicates that there are no data points to construct an Interp from.
+//! - `InterpError::InGap`: Indicates that the input value falls between two pieces of an
+//!   `InterpUnion` or inside an acquisition gap of the data points.
+//! - `InterpError::InvalidPeriod`: Indicates that the period of a periodic interpolation does not
+//!   exceed the span of the indices.
+//! - `InterpError::InvalidArgument`: Indicates that an argument is outside of its domain
+//!   (carrying the requirement it fails).
 
-use crate::divop::Method;
-use crate::schemes::{Forward, Inverse};
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+use std::ops::Range;
+
+use crate::affine::Affine;
+use crate::divop::{DivOp, Method};
+use crate::extended::F80;
+use crate::fit::fit;
+use crate::monotonicity::{check_strictly_increasing, first_violation, MonotonicityReport};
+use crate::schemes::{
+    forward_checked, round_with, Combine, Distance, Extended, Forward, ForwardAt, Inverse,
+    Progression,
+};
+use crate::step::StepFn;
+#[cfg(feature = "tracing")]
+use crate::trace;
 
 // Interpolation Errors
 #[derive(PartialEq, Debug)]
@@ -41,19 +69,383 @@ pub enum InterpError {
     OutOfBounds,
     NotFound,
     NotStrictlyIncreasing,
+    NotStrictlyMonotonic,
+    Overflow,
+    LengthMismatch(usize, usize),
+    NoSamples,
+    Empty,
+    InGap,
+    InvalidPeriod,
+    InvalidArgument(&'static str),
+}
+
+impl fmt::Display for InterpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InterpError::OutOfBounds => write!(f, "out of bounds"),
+            InterpError::NotFound => write!(f, "not found"),
+            InterpError::NotStrictlyIncreasing => write!(f, "xp must be strictly increasing"),
+            InterpError::NotStrictlyMonotonic => write!(f, "fp must be strictly monotonic"),
+            InterpError::Overflow => write!(f, "overflow"),
+            InterpError::LengthMismatch(xp, fp) => {
+                write!(f, "xp and fp must have the same length (got {xp} and {fp})")
+            }
+            InterpError::NoSamples => write!(f, "n must be positive"),
+            InterpError::Empty => write!(f, "xp and fp must not be empty"),
+            InterpError::InGap => write!(f, "in a gap"),
+            InterpError::InvalidPeriod => write!(f, "period must exceed the span of xp"),
+            InterpError::InvalidArgument(requirement) => write!(f, "{requirement}"),
+        }
+    }
+}
+impl Error for InterpError {}
+
+/// Error of the interpolation of a batch of queries, locating the first failing query.
+#[derive(PartialEq, Debug)]
+pub struct BatchError {
+    /// The position of the failing query in the batch.
+    pub index: usize,
+    /// The error of the failing query.
+    pub error: InterpError,
+}
+impl fmt::Display for BatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "element {}: {}", self.index, self.error)
+    }
+}
+impl Error for BatchError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+// Quantization Errors
+#[derive(PartialEq, Debug)]
+pub enum QuantizeError {
+    InvalidScale,
+    Inexact(usize),
+    Overflow(usize),
+    NotStrictlyMonotonic(usize),
+}
+impl fmt::Display for QuantizeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QuantizeError::InvalidScale => write!(f, "scale must be positive and finite"),
+            QuantizeError::Inexact(index) => write!(f, "fp[{index}] is not on the grid"),
+            QuantizeError::Overflow(index) => write!(f, "fp[{index}] overflows once quantized"),
+            QuantizeError::NotStrictlyMonotonic(index) => {
+                write!(f, "fp[{index}] merges with its predecessor once quantized")
+            }
+        }
+    }
+}
+impl Error for QuantizeError {}
+
+/// Result of `Interp::epsilon_sweep` for one tolerance: the tolerance, the number of kept data
+/// points and the largest deviation of the dropped ones.
+pub type Sweep<D> = (D, usize, D);
+
+/// Chunk of `Interp::chunk`: the offset of its indices and the rebased function.
+pub type Chunk<F> = (u64, Interp<u64, F>);
+
+/// Split of the Douglas-Peucker recursion: the deviation of the kept data point and the
+/// tolerance below which its chord is reached (see `Interp::splits`).
+type Split<D> = (Option<D>, Option<D>);
+
+/// Monotonicity of a sequence of data points.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Order {
+    Increasing,
+    Decreasing,
+    Unordered,
+}
+
+/// Direction of a strictly monotonic piece of a function (see `Interp::monotone_pieces`).
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Direction {
+    Increasing,
+    Decreasing,
+}
+
+/// Location of a query relative to the extreme breakpoints, once the inclusivity of the
+/// breakpoints has been applied.
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Location {
+    Before,
+    Within,
+    After,
+}
+
+/// Inclusivity of the two extreme breakpoints when checking bounds. Left and right refer to the
+/// first and last breakpoints along the indices, whatever the monotonicity of the values.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Closed {
+    Both,
+    Left,
+    Right,
+}
+
+/// Behavior of queries beyond the extreme breakpoints.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Bounds {
+    /// Out of bounds queries fail with `OutOfBounds`.
+    Error,
+    /// Out of bounds queries return the nearest extreme data point.
+    Clamp,
+}
+
+/// Behaviors of out of bounds queries before the first breakpoint and after the last one, e.g.
+/// failing at the start of a recording but clamping at the end while acquisition is still
+/// running. Left and right refer to the indices, as with `Closed`. A single `Bounds` applies to
+/// both sides.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct SidedBounds {
+    /// Behavior of queries before the first breakpoint.
+    pub left: Bounds,
+    /// Behavior of queries after the last breakpoint.
+    pub right: Bounds,
+}
+
+impl From<Bounds> for SidedBounds {
+    fn from(bounds: Bounds) -> SidedBounds {
+        SidedBounds {
+            left: bounds,
+            right: bounds,
+        }
+    }
+}
+
+/// Threshold on the steepness of the segments beyond which they are acquisition gaps, the
+/// steepness being the absolute difference of values per index step.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum MaxGap {
+    /// Maximum steepness, in units of the values per index step.
+    Absolute(f64),
+    /// Maximum steepness, as a multiple of the median steepness of the segments.
+    Median(f64),
+}
+
+/// Behavior of inexact queries falling in a gap.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum GapPolicy {
+    /// Queries in a gap fail with `InGap`.
+    Error,
+    /// Queries in a gap snap to a breakpoint of the gap: the nearest one with `Nearest`, the
+    /// previous one with `ForwardFill` and the next one with `BackwardFill`. Exact matching
+    /// still fails with `InGap`.
+    Snap,
+}
+
+/// Interpolation kind between two consecutive data points.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Kind {
+    Linear,
+}
+
+/// Errors of the construction of an Interp with `InterpBuilder`.
+#[derive(PartialEq, Debug)]
+pub enum BuildError {
+    /// The indices and values of the data points do not have the same length (carrying both
+    /// lengths).
+    LengthMismatch(usize, usize),
+    /// The indices are not strictly increasing.
+    NotStrictlyIncreasing,
+}
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildError::LengthMismatch(xp, fp) => {
+                write!(f, "xp and fp must have the same length (got {xp} and {fp})")
+            }
+            BuildError::NotStrictlyIncreasing => write!(f, "xp must be strictly increasing"),
+        }
+    }
+}
+impl Error for BuildError {}
+
+/// Policy when a transformation of the indices makes several data points collide.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Collision {
+    KeepFirst,
+    Error,
+}
+
+/// Pointwise operation combining the values of two Interps.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Op {
+    Add,
+    Sub,
+}
+
+/// Statistics on the exactness of a batch of inverse interpolations.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct InverseStats<D> {
+    /// The number of values exactly attained at the returned index.
+    pub exact_count: usize,
+    /// The number of values whose index had to be rounded.
+    pub rounded_count: usize,
+    /// The maximum distance between the values and the function at the returned indices (with
+    /// values rounded to the nearest integer), None if the batch is empty.
+    pub max_abs_residual: Option<D>,
+}
+
+/// Point where two Interps take the same value, see `Interp::intersections`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Crossing<X, F> {
+    /// The index of the crossing, rounded to the nearest integer.
+    pub x: X,
+    /// The value of the first Interp at the index.
+    pub f: F,
+    /// Whether both Interps take exactly the same value at the index.
+    pub exact: bool,
+}
+
+/// Report of the comparison of densely sampled values to an interpolation.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Verification<D> {
+    /// The number of values deviating from the interpolation by more than the tolerance.
+    pub violations: usize,
+    /// The maximum deviation, None if there are no values.
+    pub max_deviation: Option<D>,
+    /// The index where the maximum deviation occurs (the first one in case of ties), None if
+    /// there are no values.
+    pub argmax: Option<usize>,
+}
+
+/// Descriptive statistics of the data points.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Summary<F> {
+    /// The number of data points.
+    pub points: usize,
+    /// The first and last indices, None if there are no data points.
+    pub domain: Option<(u64, u64)>,
+    /// The minimum and maximum values, None if there are no data points.
+    pub range: Option<(F, F)>,
+    /// The minimum, median and maximum slopes of the segments in units of value per index, None
+    /// if there are no segments.
+    pub slopes: Option<(F80, F80, F80)>,
+    /// The minimum and maximum lengths of the segments in indices, None if there are no
+    /// segments.
+    pub lengths: Option<(u64, u64)>,
+    /// The number of segments whose slope deviates from the median by more than the factor.
+    pub irregular: usize,
+}
+
+impl<F: fmt::Display> fmt::Display for Summary<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "points: {}", self.points)?;
+        if let Some((first, last)) = &self.domain {
+            writeln!(f, "domain: [{first}, {last}]")?;
+        }
+        if let Some((min, max)) = &self.range {
+            writeln!(f, "range: [{min}, {max}]")?;
+        }
+        if let Some((min, median, max)) = &self.slopes {
+            let (min, median, max) = (
+                f64::from(min.clone()),
+                f64::from(median.clone()),
+                f64::from(max.clone()),
+            );
+            writeln!(f, "slopes: min {min}, median {median}, max {max}")?;
+        }
+        if let Some((min, max)) = &self.lengths {
+            writeln!(f, "lengths: min {min}, max {max}")?;
+        }
+        write!(f, "irregular: {}", self.irregular)
+    }
 }
 
 /// Structure for performing forward and inverse interpolation on piecewise linear functions.
+/// Two Interps are equal if they have the same data points and the same inclusivity of the
+/// extreme breakpoints.
+///
+/// Interp is immutable once built (short of `push` and `simplify_inplace`, which require
+/// exclusive access) and has no interior mutability, hence it is Send and Sync whenever its
+/// indices and values are: a single Interp can be shared by reference (or through an Arc) among
+/// many threads. Any state mutated while querying, such as caches of the last visited segment,
+/// must live in separate objects.
+#[derive(Clone, PartialEq, Debug)]
 pub struct Interp<X, F> {
     xp: Vec<X>,
     fp: Vec<F>,
     forwardable: bool,
-    inversable: bool,
+    inversable: Order,
+    closed: Closed,
+    bounds: SidedBounds,
+    method: Method,
+}
+
+/// Builder of Interp with non-default options, see `Interp::builder`.
+pub struct InterpBuilder<X, F> {
+    xp: Vec<X>,
+    fp: Vec<F>,
+    closed: Closed,
+    bounds: SidedBounds,
+    method: Method,
+    kind: Kind,
+    assume_sorted: bool,
+}
+
+impl<X, F> InterpBuilder<X, F>
+where
+    X: Forward<F> + Sync,
+    F: Inverse<X>,
+{
+    /// Sets the inclusivity of the extreme breakpoints (closed on both sides by default).
+    pub fn closed(mut self, closed: Closed) -> InterpBuilder<X, F> {
+        self.closed = closed;
+        self
+    }
+    /// Sets the behavior of out of bounds queries, either on both sides or on each side (error
+    /// by default).
+    pub fn bounds(mut self, bounds: impl Into<SidedBounds>) -> InterpBuilder<X, F> {
+        self.bounds = bounds.into();
+        self
+    }
+    /// Sets the rounding method used by calls that do not specify one (nearest by default).
+    pub fn default_method(mut self, method: Method) -> InterpBuilder<X, F> {
+        self.method = method;
+        self
+    }
+    /// Sets the interpolation kind (linear by default).
+    pub fn kind(mut self, kind: Kind) -> InterpBuilder<X, F> {
+        self.kind = kind;
+        self
+    }
+    /// Whether to trust the indices to be strictly increasing, skipping their check (false by
+    /// default). Unsorted indices then lead to wrong results instead of errors.
+    pub fn assume_sorted(mut self, assume_sorted: bool) -> InterpBuilder<X, F> {
+        self.assume_sorted = assume_sorted;
+        self
+    }
+    /// Builds the Interp.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the Interp.
+    /// Otherwise, returns an error indicating the reason for failure (`NotStrictlyIncreasing` if
+    /// the indices are checked and are not strictly increasing).
+    pub fn build(self) -> Result<Interp<X, F>, BuildError> {
+        if self.xp.len() != self.fp.len() {
+            return Err(BuildError::LengthMismatch(self.xp.len(), self.fp.len()));
+        }
+        match self.kind {
+            Kind::Linear => (),
+        }
+        if !self.assume_sorted && first_violation(&self.xp).is_some() {
+            return Err(BuildError::NotStrictlyIncreasing);
+        }
+        let mut interp = Interp::assemble(self.xp, self.fp, true);
+        interp.closed = self.closed;
+        interp.bounds = self.bounds;
+        interp.method = self.method;
+        Ok(interp)
+    }
 }
 
 impl<X, F> Interp<X, F>
 where
-    X: Forward<F>,
+    X: Forward<F> + Sync,
     F: Inverse<X>,
 {
     /// Constructs a new Interp instance with the given data points.
@@ -68,16 +460,277 @@ where
     /// Panics if the lengths of `xp` and `fp` are not equal.
     pub fn new(xp: Vec<X>, fp: Vec<F>) -> Interp<X, F> {
         assert!(xp.len() == fp.len(), "xp and fp must have same length");
-        let forwardable = xp.windows(2).all(|pair| pair[0] < pair[1]);
-        let inversable = fp.windows(2).all(|pair| pair[0] < pair[1]);
+        let forwardable = first_violation(&xp).is_none();
+        Interp::assemble(xp, fp, forwardable)
+    }
+    /// Constructs a new Interp instance from the given data points, as `new` does but without
+    /// panicking and without accepting empty data points, on which every query would fail.
+    ///
+    /// # Arguments
+    ///
+    /// * `xp` - Vector of indices.
+    /// * `fp` - Vector of corresponding values.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the Interp.
+    /// Otherwise, returns `LengthMismatch` if the lengths of `xp` and `fp` are not equal or
+    /// `Empty` if there are no data points.
+    pub fn try_new(xp: Vec<X>, fp: Vec<F>) -> Result<Interp<X, F>, InterpError> {
+        if xp.is_empty() && fp.is_empty() {
+            return Err(InterpError::Empty);
+        }
+        Interp::from_parts(xp, fp)
+    }
+    /// Constructs a new Interp from data points in any order, e.g. assembled from unordered
+    /// metadata records. The data points are sorted by index with a stable sort, so that
+    /// duplicate indices keep their input order and are resolved according to the collision
+    /// policy. Already sorted indices are detected with a single scan and kept as is.
+    ///
+    /// # Arguments
+    ///
+    /// * `xp` - Vector of indices.
+    /// * `fp` - Vector of corresponding values.
+    /// * `collision` - Whether to keep the first of the data points with the same index (in the
+    ///   input order) or to error.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the Interp.
+    /// Otherwise, returns `LengthMismatch` if the lengths of `xp` and `fp` differ or
+    /// `NotStrictlyIncreasing` if indices are duplicated with the `Error` policy.
+    pub fn new_sorted(
+        xp: Vec<X>,
+        fp: Vec<F>,
+        collision: Collision,
+    ) -> Result<Interp<X, F>, BuildError> {
+        if xp.len() != fp.len() {
+            return Err(BuildError::LengthMismatch(xp.len(), fp.len()));
+        }
+        if first_violation(&xp).is_none() {
+            return Ok(Interp::assemble(xp, fp, true));
+        }
+        let mut order: Vec<usize> = (0..xp.len()).collect();
+        order.sort_by_key(|position| xp[*position]);
+        let mut kept_xp: Vec<X> = Vec::with_capacity(xp.len());
+        let mut kept_fp = Vec::with_capacity(xp.len());
+        for position in order {
+            if kept_xp.last() == Some(&xp[position]) {
+                match collision {
+                    Collision::KeepFirst => continue,
+                    Collision::Error => return Err(BuildError::NotStrictlyIncreasing),
+                }
+            }
+            kept_xp.push(xp[position]);
+            kept_fp.push(fp[position].clone());
+        }
+        Ok(Interp::assemble(kept_xp, kept_fp, true))
+    }
+    /// Starts building an Interp with non-default options: the inclusivity of the extreme
+    /// breakpoints, the behavior of out of bounds queries, the default rounding method and the
+    /// interpolation kind.
+    ///
+    /// # Arguments
+    ///
+    /// * `xp` - Vector of indices.
+    /// * `fp` - Vector of corresponding values.
+    pub fn builder(xp: Vec<X>, fp: Vec<F>) -> InterpBuilder<X, F> {
+        InterpBuilder {
+            xp,
+            fp,
+            closed: Closed::Both,
+            bounds: Bounds::Error.into(),
+            method: Method::Nearest,
+            kind: Kind::Linear,
+            assume_sorted: false,
+        }
+    }
+    /// Assembles an Interp with default options, only the monotonicity of the values being
+    /// checked.
+    fn assemble(xp: Vec<X>, fp: Vec<F>, forwardable: bool) -> Interp<X, F> {
+        let inversable = Interp::<X, F>::order(&fp);
         Interp {
             xp,
             fp,
             forwardable,
             inversable,
+            closed: Closed::Both,
+            bounds: Bounds::Error.into(),
+            method: Method::Nearest,
+        }
+    }
+    /// Checks the monotonicity of the values.
+    fn order(fp: &[F]) -> Order {
+        if fp.iter().any(|f| f.partial_cmp(f).is_none()) {
+            Order::Unordered
+        } else if fp.windows(2).all(|pair| pair[0] < pair[1]) {
+            Order::Increasing
+        } else if fp.windows(2).all(|pair| pair[0] > pair[1]) {
+            Order::Decreasing
+        } else {
+            Order::Unordered
+        }
+    }
+    /// Constructs a new Interp instance from the given data points, as `new` does but without
+    /// panicking. Unlike `try_new`, empty data points are accepted.
+    ///
+    /// # Arguments
+    ///
+    /// * `xp` - Vector of indices.
+    /// * `fp` - Vector of corresponding values.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the Interp.
+    /// Otherwise, returns `LengthMismatch` if the lengths of `xp` and `fp` are not equal.
+    pub fn from_parts(xp: Vec<X>, fp: Vec<F>) -> Result<Interp<X, F>, InterpError> {
+        if xp.len() != fp.len() {
+            return Err(InterpError::LengthMismatch(xp.len(), fp.len()));
+        }
+        let forwardable = first_violation(&xp).is_none();
+        Ok(Interp::assemble(xp, fp, forwardable))
+    }
+    /// Consumes the Interp and returns its indices and values, without copying them.
+    pub fn into_parts(self) -> (Vec<X>, Vec<F>) {
+        (self.xp, self.fp)
+    }
+    /// Checks that the indices are strictly increasing. Large arrays are first scanned in
+    /// parallel (see `first_violation`), the diagnostics only being computed on failure.
+    ///
+    /// # Returns
+    ///
+    /// If the indices are valid, returns Ok. Otherwise, returns the diagnostics of the indices.
+    pub fn validate(&self) -> Result<(), MonotonicityReport> {
+        if first_violation(&self.xp).is_none() {
+            return Ok(());
+        }
+        let report = check_strictly_increasing(&self.xp);
+        if report.is_strict() {
+            Ok(())
+        } else {
+            Err(report)
+        }
+    }
+    /// Returns the indices of the data points.
+    pub fn xp(&self) -> &[X] {
+        &self.xp
+    }
+    /// Returns the values of the data points.
+    pub fn fp(&self) -> &[F] {
+        &self.fp
+    }
+    /// Returns the number of data points.
+    pub fn len(&self) -> usize {
+        self.xp.len()
+    }
+    /// Whether there are no data points.
+    pub fn is_empty(&self) -> bool {
+        self.xp.is_empty()
+    }
+    /// Returns the data point at the given position, None if out of range.
+    pub fn get(&self, index: usize) -> Option<(&X, &F)> {
+        self.xp.get(index).zip(self.fp.get(index))
+    }
+    /// Iterates over the data points as (index, value) pairs.
+    pub fn iter(&self) -> impl Iterator<Item = (&X, &F)> {
+        self.xp.iter().zip(&self.fp)
+    }
+    /// Selects the data points in the given range of positions, keeping the settings of this
+    /// Interp.
+    ///
+    /// # Returns
+    ///
+    /// The Interp of the selected data points, None if the range is out of bounds or reversed.
+    pub fn slice(&self, range: Range<usize>) -> Option<Interp<X, F>> {
+        let xp = self.xp.get(range.clone())?.to_vec();
+        let fp = self.fp.get(range)?.to_vec();
+        Some(self.configure(Interp::new(xp, fp)))
+    }
+    /// Whether the indices are strictly increasing, as required by forward interpolation.
+    pub fn is_forwardable(&self) -> bool {
+        self.forwardable
+    }
+    /// Whether the values are strictly monotonic, as required by inverse interpolation.
+    pub fn is_inversable(&self) -> bool {
+        self.inversable != Order::Unordered
+    }
+    /// Returns the indices of the first and last data points, None if there are none.
+    pub fn domain(&self) -> Option<(X, X)> {
+        Some((*self.xp.first()?, *self.xp.last()?))
+    }
+    /// Returns the values of the first and last data points, None if there are none. The first
+    /// value is the greatest one if the values are decreasing.
+    pub fn range(&self) -> Option<(F, F)> {
+        Some((self.fp.first()?.clone(), self.fp.last()?.clone()))
+    }
+    /// Whether the data points lie exactly on a straight line: the indices are strictly
+    /// increasing and there are either two data points or more that are collinear. Each value
+    /// must be exactly equal to the interpolation between the extreme data points, which is an
+    /// exact check for integers (see `Forward`).
+    pub fn is_affine(&self) -> bool {
+        let (Some((x0, f0)), Some((x1, f1))) = (self.iter().next(), self.iter().last()) else {
+            return false;
+        };
+        self.forwardable
+            && self.len() > 1
+            && self.iter().all(|(x, f)| {
+                x.forward(*x0, *x1, f0.clone(), f1.clone(), Method::None)
+                    .is_some_and(|value| value == *f)
+            })
+    }
+    /// Returns the straight line through the data points if they are collinear (see
+    /// `is_affine`), None otherwise.
+    pub fn affine(&self) -> Option<Affine<X, F>> {
+        if !self.is_affine() {
+            return None;
+        }
+        let (x0, f0) = self.iter().next()?;
+        let (x1, f1) = self.iter().last()?;
+        Affine::new((*x0, f0.clone()), (*x1, f1.clone())).ok()
+    }
+    /// Appends a data point, updating the monotonicity of the indices and of the values without
+    /// scanning the previous data points.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The index of the data point.
+    /// * `f` - The value of the data point.
+    pub fn push(&mut self, x: X, f: F) {
+        if let Some(last) = self.xp.last() {
+            self.forwardable &= *last < x;
         }
+        self.inversable = match (self.inversable, self.fp.last()) {
+            _ if f.partial_cmp(&f).is_none() => Order::Unordered,
+            (order, None) => order,
+            (Order::Increasing, Some(last)) if *last < f => Order::Increasing,
+            (Order::Increasing, Some(last)) if self.fp.len() == 1 && *last > f => Order::Decreasing,
+            (Order::Decreasing, Some(last)) if *last > f => Order::Decreasing,
+            _ => Order::Unordered,
+        };
+        self.xp.push(x);
+        self.fp.push(f);
+    }
+    /// Sets the inclusivity of the extreme breakpoints (closed on both sides by default). Queries
+    /// exactly equal to an open extreme breakpoint are considered out of bounds.
+    ///
+    /// # Arguments
+    ///
+    /// * `closed` - The side(s) on which the extreme breakpoints are included.
+    pub fn with_closed(mut self, closed: Closed) -> Interp<X, F> {
+        self.closed = closed;
+        self
+    }
+    /// Sets the behavior of out of bounds queries (error by default).
+    ///
+    /// # Arguments
+    ///
+    /// * `bounds` - The behavior on both sides, or on each side with `SidedBounds`.
+    pub fn with_bounds(mut self, bounds: impl Into<SidedBounds>) -> Interp<X, F> {
+        self.bounds = bounds.into();
+        self
     }
-    /// Performs forward interpolation at the given index.
+    /// Performs forward interpolation at the given index. Integer values are rounded with the
+    /// default method (to the nearest integer unless configured otherwise with the builder).
     ///
     /// # Arguments
     ///
@@ -88,54 +741,76 @@ where
     /// If successful, returns the interpolated value.
     /// Otherwise, returns an error indicating the reason for failure.
     pub fn forward(&self, rhs: X) -> Result<F, InterpError> {
-        if self.forwardable {
-            match self.xp.binary_search(&rhs) {
-                Ok(index) => Ok(self.fp[index]),
-                Err(0) => Err(InterpError::OutOfBounds),
-                Err(len) if len == self.xp.len() => Err(InterpError::OutOfBounds),
-                Err(index) => Ok(rhs.forward(
-                    self.xp[index - 1],
-                    self.xp[index],
-                    self.fp[index - 1],
-                    self.fp[index],
-                )),
-            }
-        } else {
-            Err(InterpError::NotStrictlyIncreasing)
-        }
+        self.forward_with(rhs, self.method)
     }
-    /// Performs inverse interpolation at the given value.
+    /// Performs exact forward interpolation at the given index. Integer values must be exactly
+    /// represented by the linear interpolation, breakpoints always are.
     ///
     /// # Arguments
     ///
-    /// * `rhs` - The value for inverse interpolation.
-    /// * `method` - The rounding method to use in case of inexact matching.
+    /// * `rhs` - The index for forward interpolation.
     ///
     /// # Returns
     ///
-    /// If successful, returns the interpolated input value.
+    /// If successful, returns the interpolated value.
+    /// Otherwise, returns an error indicating the reason for failure (`NotFound` if inexact).
+    pub fn forward_exact(&self, rhs: X) -> Result<F, InterpError> {
+        self.forward_with(rhs, Method::None)
+    }
+    /// Performs forward interpolation at the given index, as a shorthand for scripts where
+    /// failures are bugs.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the forward interpolation fails (see `forward`), e.g. if the index is out of
+    /// bounds with the error policy, like slice indexing.
+    pub fn at(&self, rhs: X) -> F {
+        self.forward(rhs)
+            .unwrap_or_else(|err| panic!("forward interpolation failed: {err}"))
+    }
+    /// Performs forward interpolation at the given index with the given rounding method. Out of
+    /// bounds indices are either an error or clamped to the extreme values depending on the
+    /// bounds policy of their side.
+    ///
+    /// # Arguments
+    ///
+    /// * `rhs` - The index for forward interpolation.
+    /// * `method` - The rounding method to use in case of inexact integer values.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the interpolated value.
     /// Otherwise, returns an error indicating the reason for failure.
-    pub fn inverse(&self, rhs: F, method: Method) -> Result<X, InterpError> {
-        if self.inversable {
-            match self
-                .fp
-                .binary_search_by(|f| f.partial_cmp(&rhs).expect("nan or inf encountered"))
-            {
-                Ok(index) => Ok(self.xp[index]),
-                Err(0) => match method {
-                    Method::None | Method::ForwardFill => Err(InterpError::OutOfBounds),
-                    Method::Nearest | Method::BackwardFill => Ok(self.xp[0]),
-                },
-                Err(len) if len == self.xp.len() => match method {
-                    Method::None | Method::BackwardFill => Err(InterpError::OutOfBounds),
-                    Method::Nearest | Method::ForwardFill => Ok(self.xp[len - 1]),
-                },
+    pub fn forward_with(&self, rhs: X, method: Method) -> Result<F, InterpError> {
+        self.forward_as(rhs, method, F::clone)
+    }
+    /// Performs forward interpolation at the given index on the values converted into another
+    /// type, e.g. integer values interpolated in extended precision, with the semantics of
+    /// `forward_with`. Only the two values around the index are converted.
+    pub(crate) fn forward_as<G>(
+        &self,
+        rhs: X,
+        method: Method,
+        convert: impl Fn(&F) -> G,
+    ) -> Result<G, InterpError>
+    where
+        X: Forward<G>,
+    {
+        if self.forwardable {
+            match self.bounded(self.xp.binary_search(&rhs)) {
+                Ok(index) => Ok(convert(&self.fp[index])),
+                Err(0) => self
+                    .clamp(self.bounds.left, self.fp.first())
+                    .map(|f| convert(&f)),
+                Err(len) if len == self.xp.len() => self
+                    .clamp(self.bounds.right, self.fp.last())
+                    .map(|f| convert(&f)),
                 Err(index) => rhs
-                    .inverse(
+                    .forward(
                         self.xp[index - 1],
                         self.xp[index],
-                        self.fp[index - 1],
-                        self.fp[index],
+                        convert(&self.fp[index - 1]),
+                        convert(&self.fp[index]),
                         method,
                     )
                     .ok_or(InterpError::NotFound),
@@ -144,45 +819,3499 @@ where
             Err(InterpError::NotStrictlyIncreasing)
         }
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_initialization() {
-        let xp: Vec<u64> = vec![0, 10];
-        let fp: Vec<i64> = vec![20, 25];
-        let interp = Interp::new(xp, fp);
-        assert!(interp.forwardable);
-        assert!(interp.inversable);
-
-        let xp: Vec<u64> = vec![0, 10];
-        let fp: Vec<i64> = vec![-20, -25];
-        let interp = Interp::new(xp, fp);
-        assert!(interp.forwardable);
-        assert!(!interp.inversable);
+    /// Performs forward interpolation at each of the given indices, with the semantics of
+    /// `forward_with`. The monotonicity of the indices and the bounds are checked once for the
+    /// whole batch: a first pass locates the queries relative to the extreme breakpoints, then
+    /// the queries within bounds are interpolated without any further check.
+    ///
+    /// # Arguments
+    ///
+    /// * `rhs` - The indices for forward interpolation.
+    /// * `method` - The rounding method to use in case of inexact integer values.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the interpolated values.
+    /// Otherwise, returns the error of the first failing index along with its position
+    /// (`NotStrictlyIncreasing` is reported at the first index).
+    pub fn forward_batch(&self, rhs: &[X], method: Method) -> Result<Vec<F>, BatchError> {
+        #[cfg(feature = "tracing")]
+        let _span = trace::batch("forward_batch", rhs.len());
+        let (before, after) = self
+            .batch_extremes(rhs, "forward_batch")
+            .map_err(|error| BatchError { index: 0, error })?;
+        let locations = self.locate_batch(rhs, "forward_batch");
+        // Nothing past the first index out of bounds with the error policy is interpolated, but
+        // inexact indices before it must still be reported first.
+        let end = locations
+            .iter()
+            .position(|location| match location {
+                Location::Before => before.is_none(),
+                Location::Within => false,
+                Location::After => after.is_none(),
+            })
+            .unwrap_or(rhs.len());
+        let mut out = Vec::with_capacity(rhs.len());
+        for (index, (x, location)) in rhs[..end].iter().zip(&locations).enumerate() {
+            let value = match location {
+                Location::Before => before.clone(),
+                Location::Within => self.forward_within(*x, method),
+                Location::After => after.clone(),
+            };
+            out.push(value.ok_or(BatchError {
+                index,
+                error: InterpError::NotFound,
+            })?);
+        }
+        if end < rhs.len() {
+            return Err(BatchError {
+                index: end,
+                error: InterpError::OutOfBounds,
+            });
+        }
+        Ok(out)
     }
-
-    #[test]
-    fn test_forward_unsigned() {
-        let xp: Vec<u64> = vec![0, 10];
-        let fp: Vec<u64> = vec![20, 25];
-        let interp = Interp::new(xp, fp);
-        assert_eq!(interp.forward(0), Ok(20));
-        assert_eq!(interp.forward(1), Ok(20));
-        assert_eq!(interp.forward(2), Ok(21));
-        assert_eq!(interp.forward(3), Ok(22));
-        assert_eq!(interp.forward(11), Err(InterpError::OutOfBounds));
+    /// Performs forward interpolation at each of the given indices like `forward_batch`, masking
+    /// the indices that fail with `OutOfBounds` or `NotFound` instead of stopping at them.
+    ///
+    /// # Arguments
+    ///
+    /// * `rhs` - The indices for forward interpolation.
+    /// * `method` - The rounding method to use in case of inexact integer values.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the interpolated values, the default value for masked indices,
+    /// along with the mask.
+    /// Otherwise, returns `NotStrictlyIncreasing` if the batch is not empty and the indices of
+    /// the data points are not strictly increasing.
+    pub fn forward_batch_masked(
+        &self,
+        rhs: &[X],
+        method: Method,
+    ) -> Result<(Vec<F>, Vec<bool>), InterpError>
+    where
+        F: Default,
+    {
+        #[cfg(feature = "tracing")]
+        let _span = trace::batch("forward_batch_masked", rhs.len());
+        let (before, after) = self.batch_extremes(rhs, "forward_batch_masked")?;
+        let locations = self.locate_batch(rhs, "forward_batch_masked");
+        let (out, mask) = rhs
+            .iter()
+            .zip(&locations)
+            .map(|(x, location)| {
+                let value = match location {
+                    Location::Before => before.clone(),
+                    Location::Within => self.forward_within(*x, method),
+                    Location::After => after.clone(),
+                };
+                match value {
+                    Some(value) => (value, false),
+                    None => (F::default(), true),
+                }
+            })
+            .unzip();
+        Ok((out, mask))
     }
-
-    #[test]
-    fn test_forward_signed() {
-        let xp: Vec<u64> = vec![0, 10];
-        let fp: Vec<i64> = vec![-20, -25];
-        let interp = Interp::new(xp, fp);
-        assert_eq!(interp.forward(0), Ok(-20));
+    /// Checks once for a batch of forward interpolations that the indices are strictly
+    /// increasing, unless the batch is empty, and returns the values of the queries before and
+    /// after the extreme breakpoints (None if they fail). `name` identifies the batch operation
+    /// in the trace events.
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    fn batch_extremes(
+        &self,
+        rhs: &[X],
+        name: &'static str,
+    ) -> Result<(Option<F>, Option<F>), InterpError> {
+        if !self.forwardable && !rhs.is_empty() {
+            #[cfg(feature = "tracing")]
+            trace::invalid(name, "xp must be strictly increasing");
+            return Err(InterpError::NotStrictlyIncreasing);
+        }
+        let before = self.clamp(self.bounds.left, self.fp.first()).ok();
+        let after = self.clamp(self.bounds.right, self.fp.last()).ok();
+        Ok((before, after))
+    }
+    /// Locates each query relative to the extreme breakpoints, with the semantics of `bounded`.
+    /// This pass only compares each query with two constants. `name` identifies the batch
+    /// operation in the trace events.
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    fn locate_batch(&self, rhs: &[X], name: &'static str) -> Vec<Location> {
+        let locations = match (self.xp.first(), self.xp.last()) {
+            (Some(first), Some(last)) => {
+                let (open_first, open_last) =
+                    (self.closed == Closed::Right, self.closed == Closed::Left);
+                rhs.iter()
+                    .map(|x| {
+                        if x < first || (open_first && x == first) {
+                            Location::Before
+                        } else if x > last || (open_last && x == last) {
+                            Location::After
+                        } else {
+                            Location::Within
+                        }
+                    })
+                    .collect()
+            }
+            _ => vec![Location::Before; rhs.len()],
+        };
+        #[cfg(feature = "tracing")]
+        {
+            let count = locations
+                .iter()
+                .filter(|location| !matches!(location, Location::Within))
+                .count();
+            if count > 0 {
+                trace::out_of_bounds(name, count);
+            }
+        }
+        locations
+    }
+    /// Performs forward interpolation at an index within bounds, None if the integer value is
+    /// inexact with the given method.
+    fn forward_within(&self, rhs: X, method: Method) -> Option<F> {
+        match self.xp.binary_search(&rhs) {
+            Ok(index) => Some(self.fp[index].clone()),
+            Err(index) => rhs.forward(
+                self.xp[index - 1],
+                self.xp[index],
+                self.fp[index - 1].clone(),
+                self.fp[index].clone(),
+                method,
+            ),
+        }
+    }
+    /// Performs inverse interpolation at the given value. Values can be either strictly
+    /// increasing or strictly decreasing. In both cases, the rounding methods refer to the
+    /// direction of the indices: forward fill always picks the previous index and backward fill
+    /// the next one. Out of bounds values are either an error or clamped to the extreme indices
+    /// depending on the bounds policy of their side (along the indices), the rounding methods
+    /// only applying with the error policy.
+    ///
+    /// # Arguments
+    ///
+    /// * `rhs` - The value for inverse interpolation.
+    /// * `method` - The rounding method to use in case of inexact matching, None for the
+    ///   default method.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the interpolated input value.
+    /// Otherwise, returns an error indicating the reason for failure.
+    pub fn inverse(&self, rhs: F, method: impl Into<Option<Method>>) -> Result<X, InterpError> {
+        let method = method.into().unwrap_or(self.method);
+        let found = self.search(&rhs)?;
+        self.inverse_found(rhs, found, method)
+    }
+    /// Performs inverse interpolation at a value of another type into which the values convert
+    /// exactly and monotonically, e.g. a fractional value among integer values, with the
+    /// semantics of `inverse`. Only the values visited by the search are converted.
+    pub(crate) fn inverse_as<G>(
+        &self,
+        rhs: G,
+        method: impl Into<Option<Method>>,
+        convert: impl Fn(&F) -> G,
+    ) -> Result<X, InterpError>
+    where
+        G: Inverse<X>,
+    {
+        let method = method.into().unwrap_or(self.method);
+        let found = self.search_as(&rhs, &convert)?;
+        self.inverse_found_as(rhs, found, method, convert)
+    }
+    /// Performs inverse interpolation at an integer value of another type than the values, e.g.
+    /// i64 timestamps against u64 counters, with the semantics of `inverse`. The value is
+    /// compared exactly with the values in i128, hence values beyond the range of the type of
+    /// the values (e.g. negative ones against u64 values) are out of bounds, or clamped
+    /// according to the bounds policy, and others are inverted as if they were of that type.
+    ///
+    /// # Arguments
+    ///
+    /// * `rhs` - The value for inverse interpolation.
+    /// * `method` - The rounding method to use in case of inexact matching, None for the
+    ///   default method.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the interpolated input value.
+    /// Otherwise, returns an error indicating the reason for failure.
+    pub fn inverse_from<Q>(
+        &self,
+        rhs: Q,
+        method: impl Into<Option<Method>>,
+    ) -> Result<X, InterpError>
+    where
+        Q: Into<i128>,
+        F: Copy + Into<i128>,
+        i128: Inverse<X>,
+    {
+        self.inverse_as(rhs.into(), method, |f| (*f).into())
+    }
+    /// Performs inverse interpolation at a value already located among the values (see
+    /// `search`).
+    fn inverse_found(
+        &self,
+        rhs: F,
+        found: Result<usize, usize>,
+        method: Method,
+    ) -> Result<X, InterpError> {
+        self.inverse_found_as(rhs, found, method, F::clone)
+    }
+    /// Performs inverse interpolation at a value of another type already located among the
+    /// converted values (see `search_as`).
+    fn inverse_found_as<G>(
+        &self,
+        rhs: G,
+        found: Result<usize, usize>,
+        method: Method,
+        convert: impl Fn(&F) -> G,
+    ) -> Result<X, InterpError>
+    where
+        G: Inverse<X>,
+    {
+        match found {
+            Ok(index) => Ok(self.xp[index]),
+            Err(0) if self.bounds.left == Bounds::Clamp => {
+                self.clamp(self.bounds.left, self.xp.first())
+            }
+            Err(len) if len == self.xp.len() && self.bounds.right == Bounds::Clamp => {
+                self.clamp(self.bounds.right, self.xp.last())
+            }
+            Err(0) => match method {
+                Method::None | Method::ForwardFill => Err(InterpError::OutOfBounds),
+                Method::Nearest | Method::BackwardFill => Ok(self.xp[0]),
+            },
+            Err(len) if len == self.xp.len() => match method {
+                Method::None | Method::BackwardFill => Err(InterpError::OutOfBounds),
+                Method::Nearest | Method::ForwardFill => Ok(self.xp[len - 1]),
+            },
+            Err(index) => {
+                let (x0, x1) = (self.xp[index - 1], self.xp[index]);
+                let (f0, f1) = (convert(&self.fp[index - 1]), convert(&self.fp[index]));
+                match self.inversable {
+                    Order::Decreasing => rhs.inverse(x1, x0, f1, f0, method),
+                    _ => rhs.inverse(x0, x1, f0, f1, method),
+                }
+                .ok_or(InterpError::NotFound)
+            }
+        }
+    }
+    /// Performs inverse interpolation on a batch of values while collecting statistics on the
+    /// exactness of the results.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - The values for inverse interpolation.
+    /// * `method` - The rounding method to use in case of inexact matching.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the interpolated indices and the statistics.
+    /// Otherwise, returns the error of the first failing value.
+    pub fn inverse_with_stats(
+        &self,
+        values: &[F],
+        method: Method,
+    ) -> Result<(Vec<X>, InverseStats<F::Output>), InterpError>
+    where
+        F: Distance,
+    {
+        #[cfg(feature = "tracing")]
+        let _span = trace::batch("inverse_with_stats", values.len());
+        let mut stats = InverseStats {
+            exact_count: 0,
+            rounded_count: 0,
+            max_abs_residual: None,
+        };
+        let mut out = Vec::with_capacity(values.len());
+        for value in values {
+            // The value is located once for both the rounded and the exact inversions.
+            let found = self.search(value)?;
+            let index = self.inverse_found(value.clone(), found, method)?;
+            if self
+                .inverse_found(value.clone(), found, Method::None)
+                .is_ok()
+            {
+                stats.exact_count += 1;
+            } else {
+                stats.rounded_count += 1;
+            }
+            let residual = self.forward(index)?.distance(value.clone());
+            if stats
+                .max_abs_residual
+                .as_ref()
+                .is_none_or(|max| residual > *max)
+            {
+                stats.max_abs_residual = Some(residual);
+            }
+            out.push(index);
+        }
+        Ok((out, stats))
+    }
+    /// Performs inverse interpolation at the given value without rounding the resulting index.
+    ///
+    /// The position is computed in extended precision and converted to f64 at the end, so it is
+    /// subject to f64 precision (indices above 2^53 cannot all be represented). Exact hits on
+    /// breakpoints return integral values.
+    ///
+    /// # Arguments
+    ///
+    /// * `rhs` - The value for inverse interpolation.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the real-valued interpolated index.
+    /// Otherwise, returns an error indicating the reason for failure.
+    pub fn inverse_fractional(&self, rhs: F) -> Result<f64, InterpError> {
+        match self.search(&rhs)? {
+            Ok(index) => rhs
+                .inverse_fractional(
+                    self.xp[index],
+                    self.xp[index],
+                    self.fp[index].clone(),
+                    self.fp[index].clone(),
+                )
+                .ok_or(InterpError::NotFound),
+            Err(0) => Err(InterpError::OutOfBounds),
+            Err(len) if len == self.xp.len() => Err(InterpError::OutOfBounds),
+            Err(index) => {
+                let (x0, x1) = (self.xp[index - 1], self.xp[index]);
+                let (f0, f1) = (self.fp[index - 1].clone(), self.fp[index].clone());
+                match self.inversable {
+                    Order::Decreasing => rhs.inverse_fractional(x1, x0, f1, f0),
+                    _ => rhs.inverse_fractional(x0, x1, f0, f1),
+                }
+                .ok_or(InterpError::NotFound)
+            }
+        }
+    }
+    /// Applies a transformation to the values. The transformation is expected to be monotonic:
+    /// if the values were strictly monotonic they must remain so.
+    ///
+    /// # Arguments
+    ///
+    /// * `func` - The function applied to each value.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the transformed Interp.
+    /// Otherwise, returns `NotStrictlyMonotonic` if the transformation broke the monotonicity.
+    pub fn map_fp<G>(&self, func: impl FnMut(&F) -> G) -> Result<Interp<X, G>, InterpError>
+    where
+        X: Forward<G>,
+        G: Inverse<X>,
+    {
+        let fp = self.fp.iter().map(func).collect();
+        let interp = self.configure(Interp::new(self.xp.clone(), fp));
+        if self.inversable != Order::Unordered && interp.inversable == Order::Unordered {
+            Err(InterpError::NotStrictlyMonotonic)
+        } else {
+            Ok(interp)
+        }
+    }
+    /// Applies a transformation to the indices. The transformation must be strictly increasing.
+    ///
+    /// # Arguments
+    ///
+    /// * `func` - The function applied to each index.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the transformed Interp.
+    /// Otherwise, returns `NotStrictlyIncreasing` if the transformed indices are not strictly
+    /// increasing.
+    pub fn map_xp(&self, func: impl FnMut(&X) -> X) -> Result<Interp<X, F>, InterpError> {
+        self.map_xp_with(func, Collision::Error)
+    }
+    /// Applies a transformation to the indices. The transformation must be increasing, data
+    /// points landing on the same index are resolved according to the collision policy.
+    ///
+    /// # Arguments
+    ///
+    /// * `func` - The function applied to each index.
+    /// * `collision` - Whether to keep the first of the colliding data points or to error.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the transformed Interp.
+    /// Otherwise, returns `NotStrictlyIncreasing` if the transformed indices are decreasing
+    /// somewhere or collide with the `Error` policy.
+    pub fn map_xp_with(
+        &self,
+        func: impl FnMut(&X) -> X,
+        collision: Collision,
+    ) -> Result<Interp<X, F>, InterpError> {
+        self.with_xp(self.xp.iter().map(func).collect(), collision)
+    }
+    /// Rebuilds the Interp with new indices, resolving collisions.
+    fn with_xp(&self, xp: Vec<X>, collision: Collision) -> Result<Interp<X, F>, InterpError> {
+        let mut kept_xp: Vec<X> = Vec::with_capacity(xp.len());
+        let mut kept_fp = Vec::with_capacity(xp.len());
+        for (x, f) in xp.into_iter().zip(self.fp.iter()) {
+            match kept_xp.last() {
+                Some(last) if x < *last => return Err(InterpError::NotStrictlyIncreasing),
+                Some(last) if x == *last => match collision {
+                    Collision::KeepFirst => continue,
+                    Collision::Error => return Err(InterpError::NotStrictlyIncreasing),
+                },
+                _ => {
+                    kept_xp.push(x);
+                    kept_fp.push(f.clone());
+                }
+            }
+        }
+        Ok(self.configure(Interp::new(kept_xp, kept_fp)))
+    }
+    /// Combines the values of this Interp with those of another one pointwise. Both are
+    /// evaluated at the union of their breakpoints over the overlapping domain, integer values
+    /// being rounded with the default method of each Interp. The sum or difference of two
+    /// piecewise linear functions being piecewise linear with breakpoints among theirs, the
+    /// result is exact at these breakpoints and in between (up to the rounding of integers).
+    /// The monotonicity of the result is derived from its values and the options of this Interp
+    /// are kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The Interp to combine with.
+    /// * `op` - Whether to add the values of `other` or to subtract them.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the combined Interp.
+    /// Otherwise, returns `NotStrictlyIncreasing` if the indices of either Interp are not
+    /// strictly increasing, `OutOfBounds` if their domains do not overlap, `NotFound` if an
+    /// interpolated value is inexact with `Method::None` and `Overflow` if a combined value
+    /// overflows.
+    pub fn zip_with(&self, other: &Interp<X, F>, op: Op) -> Result<Interp<X, F>, InterpError>
+    where
+        F: Combine,
+    {
+        let xp = self.shared_xp(other)?.ok_or(InterpError::OutOfBounds)?;
+        let mut fp = Vec::with_capacity(xp.len());
+        for x in &xp {
+            let lhs = self
+                .value_at(*x, self.method)
+                .ok_or(InterpError::NotFound)?;
+            let rhs = other
+                .value_at(*x, other.method)
+                .ok_or(InterpError::NotFound)?;
+            let value = match op {
+                Op::Add => lhs.checked_add(rhs),
+                Op::Sub => lhs.checked_sub(rhs),
+            };
+            fp.push(value.ok_or(InterpError::Overflow)?);
+        }
+        Ok(self.configure(Interp::new(xp, fp)))
+    }
+    /// Collects the union of the breakpoints of two Interps over their overlapping domain, None
+    /// if their domains do not overlap.
+    fn shared_xp(&self, other: &Interp<X, F>) -> Result<Option<Vec<X>>, InterpError> {
+        if !self.forwardable || !other.forwardable {
+            return Err(InterpError::NotStrictlyIncreasing);
+        }
+        let (Some(first), Some(last)) = (self.xp.first(), self.xp.last()) else {
+            return Ok(None);
+        };
+        let (Some(other_first), Some(other_last)) = (other.xp.first(), other.xp.last()) else {
+            return Ok(None);
+        };
+        let (start, end) = (*first.max(other_first), *last.min(other_last));
+        if start > end {
+            return Ok(None);
+        }
+        let mut xp: Vec<X> = self
+            .xp
+            .iter()
+            .chain(&other.xp)
+            .filter(|x| start <= **x && **x <= end)
+            .copied()
+            .collect();
+        xp.sort();
+        xp.dedup();
+        Ok(Some(xp))
+    }
+    /// Interpolates the value at the given index within the data points with the given method,
+    /// regardless of the inclusivity of the extreme breakpoints and of the bounds policy. None
+    /// if out of bounds or inexact with `Method::None`.
+    fn value_at(&self, rhs: X, method: Method) -> Option<F> {
+        match self.xp.binary_search(&rhs) {
+            Ok(index) => Some(self.fp[index].clone()),
+            Err(0) => None,
+            Err(len) if len == self.xp.len() => None,
+            Err(index) => rhs.forward(
+                self.xp[index - 1],
+                self.xp[index],
+                self.fp[index - 1].clone(),
+                self.fp[index].clone(),
+                method,
+            ),
+        }
+    }
+    /// Locates the given value in the values, with the semantics of `binary_search` along the
+    /// direction of the indices whatever the monotonicity of the values.
+    fn search(&self, rhs: &F) -> Result<Result<usize, usize>, InterpError> {
+        self.search_as(rhs, F::clone)
+    }
+    /// Locates a value of another type among the values converted into that type (see
+    /// `search`).
+    fn search_as<G: PartialOrd>(
+        &self,
+        rhs: &G,
+        convert: impl Fn(&F) -> G,
+    ) -> Result<Result<usize, usize>, InterpError> {
+        if rhs.partial_cmp(rhs).is_none() {
+            return Err(InterpError::NotFound);
+        }
+        match self.inversable {
+            Order::Increasing => Ok(self.bounded(self.fp.binary_search_by(|f| {
+                convert(f)
+                    .partial_cmp(rhs)
+                    .expect("monotonic values are comparable")
+            }))),
+            Order::Decreasing => Ok(self.bounded(self.fp.binary_search_by(|f| {
+                rhs.partial_cmp(&convert(f))
+                    .expect("monotonic values are comparable")
+            }))),
+            Order::Unordered => Err(InterpError::NotStrictlyMonotonic),
+        }
+    }
+    /// Returns the given extreme data point if out of bounds queries are clamped on its side.
+    fn clamp<T: Clone>(&self, bounds: Bounds, extreme: Option<&T>) -> Result<T, InterpError> {
+        match (bounds, extreme) {
+            (Bounds::Clamp, Some(extreme)) => Ok(extreme.clone()),
+            _ => Err(InterpError::OutOfBounds),
+        }
+    }
+    /// Copies the options of this Interp to another one built from its data points.
+    fn configure<G>(&self, interp: Interp<X, G>) -> Interp<X, G> {
+        Interp {
+            closed: self.closed,
+            bounds: self.bounds,
+            method: self.method,
+            ..interp
+        }
+    }
+    /// Moves exact hits on open extreme breakpoints out of bounds.
+    fn bounded(&self, found: Result<usize, usize>) -> Result<usize, usize> {
+        match (found, self.closed) {
+            (Ok(0), Closed::Right) => Err(0),
+            (Ok(index), Closed::Left) if index + 1 == self.xp.len() => Err(self.xp.len()),
+            _ => found,
+        }
+    }
+    /// Clips the values into the given closed range. Out of range values are replaced by the
+    /// nearest bound and breakpoints are inserted where segments cross a bound, at the index
+    /// given by inverse interpolation rounded to the nearest integer (crossings landing on an
+    /// existing breakpoint are not duplicated). The result generally has plateaus, hence it is
+    /// not inversable, and it keeps the options of this Interp.
+    ///
+    /// # Arguments
+    ///
+    /// * `lo` - The lower bound of the range.
+    /// * `hi` - The upper bound of the range.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the clipped Interp.
+    /// Otherwise, returns `InvalidArgument` if `lo` is greater than `hi` or if they are not
+    /// comparable, `NotStrictlyIncreasing` if the indices are not strictly increasing or
+    /// `NotFound` if a crossing cannot be located (e.g. NaN values).
+    pub fn clip(&self, lo: F, hi: F) -> Result<Interp<X, F>, InterpError> {
+        if matches!(lo.partial_cmp(&hi), None | Some(Ordering::Greater)) {
+            return Err(InterpError::InvalidArgument("lo must not exceed hi"));
+        }
+        if !self.forwardable {
+            return Err(InterpError::NotStrictlyIncreasing);
+        }
+        let clamp = |f: &F| {
+            if *f < lo {
+                lo.clone()
+            } else if *f > hi {
+                hi.clone()
+            } else {
+                f.clone()
+            }
+        };
+        let mut xp: Vec<X> = Vec::with_capacity(self.xp.len());
+        let mut fp = Vec::with_capacity(self.xp.len());
+        for index in 0..self.xp.len() {
+            if index > 0 {
+                let (x0, x1) = (self.xp[index - 1], self.xp[index]);
+                let (f0, f1) = (&self.fp[index - 1], &self.fp[index]);
+                let levels = if f0 < f1 { [&lo, &hi] } else { [&hi, &lo] };
+                for level in levels {
+                    let crossed = (f0 < level && level < f1) || (f1 < level && level < f0);
+                    if !crossed {
+                        continue;
+                    }
+                    let x = if f0 < f1 {
+                        level
+                            .clone()
+                            .inverse(x0, x1, f0.clone(), f1.clone(), Method::Nearest)
+                    } else {
+                        level
+                            .clone()
+                            .inverse(x1, x0, f1.clone(), f0.clone(), Method::Nearest)
+                    }
+                    .ok_or(InterpError::NotFound)?;
+                    if xp.last().is_some_and(|last| *last < x) && x < x1 {
+                        xp.push(x);
+                        fp.push(level.clone());
+                    }
+                }
+            }
+            xp.push(self.xp[index]);
+            fp.push(clamp(&self.fp[index]));
+        }
+        Ok(self.configure(Interp::new(xp, fp)))
+    }
+    /// Restricts the function to the given closed interval of indices. The data points lying
+    /// inside are kept and new ones are interpolated at both ends of the interval (integer
+    /// values are rounded to the nearest integer).
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The first index of the interval.
+    /// * `end` - The last index of the interval.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the cropped Interp.
+    /// Otherwise, returns an error indicating the reason for failure (`OutOfBounds` if any end
+    /// of the interval is out of bounds or if the interval is reversed).
+    pub fn crop(&self, start: X, end: X) -> Result<Interp<X, F>, InterpError> {
+        if start > end {
+            return Err(InterpError::OutOfBounds);
+        }
+        let first = self.forward(start)?;
+        if start == end {
+            return Ok(self.configure(Interp::new(vec![start], vec![first])));
+        }
+        let last = self.forward(end)?;
+        let lower = self.xp.partition_point(|x| *x <= start);
+        let upper = self.xp.partition_point(|x| *x < end);
+        let mut xp = vec![start];
+        let mut fp = vec![first];
+        if lower < upper {
+            xp.extend_from_slice(&self.xp[lower..upper]);
+            fp.extend_from_slice(&self.fp[lower..upper]);
+        }
+        xp.push(end);
+        fp.push(last);
+        Ok(self.configure(Interp::new(xp, fp)))
+    }
+    /// Computes the minimum and maximum values attained by the function over the given closed
+    /// interval of indices. They are found among the interpolated values at both ends of the
+    /// interval (integer values are rounded to the nearest integer) and the breakpoints lying
+    /// inside, hence the values do not need to be monotonic.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The first index of the interval.
+    /// * `end` - The last index of the interval.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the (min, max) tuple.
+    /// Otherwise, returns an error indicating the reason for failure (`OutOfBounds` if any end
+    /// of the interval is out of bounds or if the interval is reversed).
+    pub fn extrema(&self, start: X, end: X) -> Result<(F, F), InterpError> {
+        if start > end {
+            return Err(InterpError::OutOfBounds);
+        }
+        let first = self.forward(start)?;
+        let last = self.forward(end)?;
+        let (mut min, mut max) = if first < last {
+            (first, last)
+        } else {
+            (last, first)
+        };
+        let lower = self.xp.partition_point(|x| *x <= start);
+        let upper = self.xp.partition_point(|x| *x < end);
+        if lower < upper {
+            for f in &self.fp[lower..upper] {
+                if *f < min {
+                    min = f.clone();
+                }
+                if *f > max {
+                    max = f.clone();
+                }
+            }
+        }
+        Ok((min, max))
+    }
+    /// Finds every index at which the function attains the given value. Segments are scanned
+    /// one by one so that `fp` does not need to be monotonic. Breakpoints exactly matching the
+    /// value are always included, while each bracketing segment contributes the interpolated
+    /// index rounded with the given method (if any). Constant segments only contribute their
+    /// breakpoints.
+    ///
+    /// # Arguments
+    ///
+    /// * `rhs` - The value for inverse interpolation.
+    /// * `method` - The rounding method to use in case of inexact matching.
+    ///
+    /// # Returns
+    ///
+    /// The sorted and deduplicated indices, empty if the value is never attained.
+    pub fn inverse_all(&self, rhs: F, method: Method) -> Vec<X> {
+        let mut out = Vec::new();
+        for (index, f) in self.fp.iter().enumerate() {
+            if *f == rhs {
+                out.push(self.xp[index]);
+            }
+        }
+        for index in 1..self.fp.len() {
+            let (f0, f1) = (self.fp[index - 1].clone(), self.fp[index].clone());
+            let (x0, x1) = (self.xp[index - 1], self.xp[index]);
+            let solution = if f0 < rhs && rhs < f1 {
+                rhs.clone().inverse(x0, x1, f0, f1, method)
+            } else if f1 < rhs && rhs < f0 {
+                rhs.clone().inverse(x1, x0, f1, f0, method)
+            } else {
+                None
+            };
+            out.extend(solution);
+        }
+        out.sort();
+        out.dedup();
+        out
+    }
+    /// Splits the function into the maximal pieces on which the values are strictly monotonic,
+    /// e.g. the forth and back sweeps of a scanning coordinate, so that each piece can be
+    /// inverted on its own. Neighboring pieces share their turning breakpoint. Constant segments
+    /// (plateaus) terminate pieces and belong to none of them. The pieces keep the options of
+    /// this Interp.
+    ///
+    /// # Returns
+    ///
+    /// The pieces in the order of the indices along with their direction, empty if there are
+    /// less than two data points.
+    pub fn monotone_pieces(&self) -> Vec<(Interp<X, F>, Direction)> {
+        let mut pieces = Vec::new();
+        let mut start = 0;
+        let mut current: Option<Direction> = None;
+        for index in 1..=self.fp.len() {
+            let direction =
+                self.fp
+                    .get(index)
+                    .and_then(|f| match self.fp[index - 1].partial_cmp(f) {
+                        Some(Ordering::Less) => Some(Direction::Increasing),
+                        Some(Ordering::Greater) => Some(Direction::Decreasing),
+                        _ => None,
+                    });
+            if direction == current {
+                continue;
+            }
+            if let Some(current) = current {
+                pieces.extend(self.slice(start..index).map(|piece| (piece, current)));
+            }
+            start = index - 1;
+            current = direction;
+        }
+        pieces
+    }
+    /// Computes the maximum deviation between two functions over their common domain. The
+    /// difference of two piecewise linear functions is itself piecewise linear, so its maximum
+    /// is attained at a breakpoint of either function: both are evaluated at the union of their
+    /// breakpoints inside the common domain (integer values are rounded to the nearest integer).
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The function to compare to, e.g. a simplified version of this one.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the maximum deviation and the index where it occurs (the first one
+    /// in case of ties).
+    /// Otherwise, returns `OutOfBounds` if the domains do not overlap or an error indicating the
+    /// reason for failure of the forward interpolation of either function.
+    pub fn max_error(&self, other: &Interp<X, F>) -> Result<(F::Output, X), InterpError>
+    where
+        F: Distance,
+    {
+        let (Some(first), Some(last)) = (self.xp.first(), self.xp.last()) else {
+            return Err(InterpError::OutOfBounds);
+        };
+        let (Some(other_first), Some(other_last)) = (other.xp.first(), other.xp.last()) else {
+            return Err(InterpError::OutOfBounds);
+        };
+        let (start, end) = (*first.max(other_first), *last.min(other_last));
+        if start > end {
+            return Err(InterpError::OutOfBounds);
+        }
+        let mut breakpoints: Vec<X> = self
+            .xp
+            .iter()
+            .chain(other.xp.iter())
+            .filter(|x| start <= **x && **x <= end)
+            .copied()
+            .collect();
+        breakpoints.sort();
+        breakpoints.dedup();
+        let mut out: Option<(F::Output, X)> = None;
+        for x in breakpoints {
+            let deviation = self.forward(x)?.distance(other.forward(x)?);
+            if out.as_ref().is_none_or(|(max, _)| deviation > *max) {
+                out = Some((deviation, x));
+            }
+        }
+        out.ok_or(InterpError::OutOfBounds)
+    }
+    /// Removes the data points that can be recovered by interpolation within a tolerance using
+    /// the Douglas-Peucker algorithm. The extreme data points are always kept. A data point is
+    /// dropped if its value deviates from the chord joining the kept neighbors by at most
+    /// `epsilon`, the chord being evaluated with the forward scheme (integers are rounded to the
+    /// nearest). A deviation equal to `epsilon` is within the tolerance. When several data
+    /// points share the largest deviation from a chord, the one with the smallest index is kept,
+    /// whatever the order in which they are evaluated.
+    ///
+    /// The recursion is limited to O(n log n) deviation evaluations. On adversarial inputs that
+    /// would exceed this limit, the remaining chords are simplified greedily instead: the result
+    /// still keeps the extreme data points and satisfies the tolerance, but the kept data points
+    /// may differ from those of the plain Douglas-Peucker algorithm. The same applies to the
+    /// other simplification methods.
+    ///
+    /// # Arguments
+    ///
+    /// * `epsilon` - The maximum deviation of the values.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the simplified Interp.
+    /// Otherwise, returns `NotStrictlyIncreasing` if the indices are not strictly increasing.
+    pub fn simplify(&self, epsilon: F::Output) -> Result<Interp<X, F>, InterpError>
+    where
+        F: Distance,
+    {
+        self.simplify_by(self.within(&epsilon))
+    }
+    /// Removes the data points that can be recovered by interpolation within a tolerance, as
+    /// `simplify` does but in place. The kept data points are moved within the existing buffers,
+    /// the only allocation being a mask of the kept data points, and nothing is moved when all
+    /// data points are kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `epsilon` - The maximum deviation of the values.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns nothing and this Interp is simplified.
+    /// Otherwise, returns `NotStrictlyIncreasing` if the indices are not strictly increasing and
+    /// this Interp is left unchanged.
+    pub fn simplify_inplace(&mut self, epsilon: F::Output) -> Result<(), InterpError>
+    where
+        F: Distance,
+    {
+        if self.forwardable && self.xp.len() <= 2 {
+            return Ok(());
+        }
+        let keep = self.mask_by(self.within(&epsilon))?;
+        if keep.iter().all(|keep| *keep) {
+            return Ok(());
+        }
+        let mut mask = keep.iter();
+        self.xp
+            .retain(|_| *mask.next().expect("one flag per data point"));
+        let mut mask = keep.iter();
+        self.fp
+            .retain(|_| *mask.next().expect("one flag per data point"));
+        self.inversable = Interp::<X, F>::order(&self.fp);
+        Ok(())
+    }
+    /// Simplifies the data points as `simplify_inplace` does, consuming this Interp and reusing
+    /// its buffers.
+    ///
+    /// # Arguments
+    ///
+    /// * `epsilon` - The maximum deviation of the values.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the simplified Interp.
+    /// Otherwise, returns `NotStrictlyIncreasing` if the indices are not strictly increasing.
+    pub fn into_simplified(mut self, epsilon: F::Output) -> Result<Interp<X, F>, InterpError>
+    where
+        F: Distance,
+    {
+        self.simplify_inplace(epsilon)?;
+        Ok(self)
+    }
+    /// Summarizes the results of `simplify` for several tolerances, e.g. to choose a compression
+    /// level. A single pass of the Douglas-Peucker recursion splits every chord, whatever its
+    /// deviation, recording the tolerance below which each data point is kept: its deviation,
+    /// lowered to the one of its parent split since a data point is only reached if the chord
+    /// containing it was split. Each tolerance is then answered from the recorded splits
+    /// without evaluating any deviation, the results being identical to independent calls to
+    /// `simplify`. If the splits exceed the work limit of `simplify`, which then falls back to a
+    /// greedy simplification, the tolerances are instead answered by independent runs.
+    ///
+    /// # Arguments
+    ///
+    /// * `epsilons` - The maximum deviations of the values, in any order.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns for each tolerance the tolerance itself, the number of kept data
+    /// points and the largest deviation of the dropped data points from the simplified
+    /// function (zero if none is dropped).
+    /// Otherwise, returns `NotStrictlyIncreasing` if the indices are not strictly increasing or
+    /// `OutOfBounds` if there are no data points.
+    pub fn epsilon_sweep(
+        &self,
+        epsilons: &[F::Output],
+    ) -> Result<Vec<Sweep<F::Output>>, InterpError>
+    where
+        F: Distance,
+    {
+        if !self.forwardable {
+            return Err(InterpError::NotStrictlyIncreasing);
+        }
+        let first = self.fp.first().ok_or(InterpError::OutOfBounds)?;
+        let zero = first.clone().distance(first.clone());
+        let Some(splits) = self.splits() else {
+            #[cfg(feature = "tracing")]
+            trace::fallback("epsilon_sweep", "work limit exceeded");
+            return epsilons
+                .iter()
+                .map(|epsilon| {
+                    let keep = self.mask_by(self.within(epsilon))?;
+                    let count = keep.iter().filter(|keep| **keep).count();
+                    Ok((
+                        epsilon.clone(),
+                        count,
+                        self.max_deviation(&keep, zero.clone()),
+                    ))
+                })
+                .collect();
+        };
+        let exceeds = |deviation: &Option<F::Output>, epsilon: &F::Output| {
+            deviation
+                .as_ref()
+                .is_none_or(|deviation| deviation > epsilon)
+        };
+        Ok(epsilons
+            .iter()
+            .map(|epsilon| {
+                let mut count = self.xp.len().min(2);
+                let mut error = zero.clone();
+                for (deviation, parent) in &splits {
+                    if !exceeds(parent, epsilon) {
+                        continue;
+                    }
+                    if exceeds(deviation, epsilon) {
+                        count += 1;
+                    } else if let Some(deviation) = deviation {
+                        if *deviation > error {
+                            error = deviation.clone();
+                        }
+                    }
+                }
+                (epsilon.clone(), count, error)
+            })
+            .collect())
+    }
+    /// Removes the data points that can be recovered by interpolation within a tolerance on both
+    /// the values and the indices. A data point is kept if either its value deviates from the
+    /// chord by more than `eps_f` or its index deviates from the inverse of the chord by more
+    /// than `eps_x`. The inverse is rounded to the nearest index. Data points whose value lies
+    /// outside the range of the chord, or on a constant chord, have no inverse and are always
+    /// kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `eps_x` - The maximum deviation of the indices.
+    /// * `eps_f` - The maximum deviation of the values.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the simplified Interp.
+    /// Otherwise, returns `NotStrictlyIncreasing` if the indices are not strictly increasing.
+    pub fn simplify2(&self, eps_x: X::Output, eps_f: F::Output) -> Result<Interp<X, F>, InterpError>
+    where
+        X: Distance,
+        F: Distance,
+    {
+        self.simplify_by(|start, end| {
+            farthest(
+                start + 1..end,
+                |index| self.vertical(start, end, index),
+                &eps_f,
+            )
+            .or_else(|| {
+                farthest(
+                    start + 1..end,
+                    |index| self.horizontal(start, end, index),
+                    &eps_x,
+                )
+            })
+        })
+    }
+    /// Removes the data points that can be recovered by interpolation within a tolerance while
+    /// ignoring up to `max_outliers` data points. Ignored data points are neither kept nor
+    /// required to be within `epsilon` of the result. The extreme data points are never ignored.
+    ///
+    /// Outliers are selected greedily before simplification: an interior data point is a
+    /// candidate if it deviates from the chord joining its neighbors by more than `epsilon` while
+    /// ignoring it brings both neighbors within `epsilon` of their own chords. The candidate with
+    /// the largest deviation is ignored first, true corners of the function being protected by the
+    /// condition on the neighbors.
+    ///
+    /// # Arguments
+    ///
+    /// * `epsilon` - The maximum deviation of the values.
+    /// * `max_outliers` - The maximum number of ignored data points.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the simplified Interp and the sorted positions of the ignored data
+    /// points. Otherwise, returns `NotStrictlyIncreasing` if the indices are not strictly
+    /// increasing.
+    pub fn simplify_robust(
+        &self,
+        epsilon: F::Output,
+        max_outliers: usize,
+    ) -> Result<(Interp<X, F>, Vec<usize>), InterpError>
+    where
+        F: Distance,
+    {
+        if !self.forwardable {
+            return Err(InterpError::NotStrictlyIncreasing);
+        }
+        let within = |deviation: Option<F::Output>| matches!(deviation, Some(d) if d <= epsilon);
+        let mut active: Vec<usize> = (0..self.xp.len()).collect();
+        let mut outliers = Vec::new();
+        while outliers.len() < max_outliers {
+            let candidates = (1..active.len().saturating_sub(1)).filter(|&position| {
+                let (prev, next) = (active[position - 1], active[position + 1]);
+                let before = position.checked_sub(2).map(|before| active[before]);
+                let after = active.get(position + 2).copied();
+                before.is_none_or(|before| within(self.vertical(before, next, prev)))
+                    && after.is_none_or(|after| within(self.vertical(prev, after, next)))
+            });
+            let found = farthest(
+                candidates,
+                |position| {
+                    self.vertical(active[position - 1], active[position + 1], active[position])
+                },
+                &epsilon,
+            );
+            match found {
+                Some(position) => outliers.push(active.remove(position)),
+                None => break,
+            }
+        }
+        outliers.sort();
+        let xp = active.iter().map(|index| self.xp[*index]).collect();
+        let fp = active.iter().map(|index| self.fp[*index].clone()).collect();
+        let interp = self.configure(Interp::new(xp, fp));
+        Ok((interp.simplify(epsilon)?, outliers))
+    }
+    /// Keeps every `k`-th data point, i.e. the positions 0, k, 2k, ..., and always the last one,
+    /// whatever the values. This is cheaper and more predictable than `simplify`, e.g. before
+    /// plotting: the deviation given up can be measured with `max_error`. The kept data points
+    /// are a subset in the same order, hence they stay valid if this Interp is.
+    ///
+    /// # Arguments
+    ///
+    /// * `k` - The step between the positions of the kept data points, 1 to keep all of them.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the decimated Interp.
+    /// Otherwise, returns `InvalidArgument` if `k` is zero.
+    pub fn decimate(&self, k: usize) -> Result<Interp<X, F>, InterpError> {
+        if k == 0 {
+            return Err(InterpError::InvalidArgument("k must be positive"));
+        }
+        let last = self.xp.len().saturating_sub(1);
+        let (xp, fp) = self
+            .iter()
+            .enumerate()
+            .filter(|(position, _)| position % k == 0 || *position == last)
+            .map(|(_, (x, f))| (*x, f.clone()))
+            .unzip();
+        Ok(self.configure(Interp::new(xp, fp)))
+    }
+    /// Keeps every k-th data point as `decimate` does, with the smallest step `k` that leaves at
+    /// most `n` data points.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - The maximum number of kept data points.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the decimated Interp.
+    /// Otherwise, returns `InvalidArgument` if `n` is lower than two, the first and last data
+    /// points being always kept.
+    pub fn decimate_to(&self, n: usize) -> Result<Interp<X, F>, InterpError> {
+        if n < 2 {
+            return Err(InterpError::InvalidArgument("n must be at least 2"));
+        }
+        let len = self.xp.len();
+        if len <= n {
+            return Ok(self.clone());
+        }
+        // With a step k, the kept data points are the first one and ceil((len - 1) / k) others.
+        self.decimate((len - 1).div_ceil(n - 1))
+    }
+    /// Runs the Douglas-Peucker recursion. For a chord between two kept data points, `select`
+    /// returns the data point in between to keep, if any.
+    ///
+    /// Each call to `select` costs one deviation per data point in between, so the recursion
+    /// degrades to a quadratic number of evaluations when every split only peels off a few data
+    /// points. The recursion is therefore given a budget of O(n log n) evaluations, after which
+    /// the remaining chords are simplified with `gallop`, whose cost is O(n log n) as well.
+    fn simplify_by(
+        &self,
+        select: impl FnMut(usize, usize) -> Option<usize>,
+    ) -> Result<Interp<X, F>, InterpError> {
+        let keep = self.mask_by(select)?;
+        if keep.iter().all(|keep| *keep) {
+            return Ok(self.clone());
+        }
+        let (xp, fp) = self
+            .xp
+            .iter()
+            .zip(self.fp.iter())
+            .zip(keep)
+            .filter(|(_, keep)| *keep)
+            .map(|((x, f), _)| (*x, f.clone()))
+            .unzip();
+        Ok(self.configure(Interp::new(xp, fp)))
+    }
+    /// Selects the data points kept by `simplify_by`, as a mask with one flag per data point.
+    fn mask_by(
+        &self,
+        mut select: impl FnMut(usize, usize) -> Option<usize>,
+    ) -> Result<Vec<bool>, InterpError> {
+        if !self.forwardable {
+            return Err(InterpError::NotStrictlyIncreasing);
+        }
+        let len = self.xp.len();
+        let mut keep = vec![true; len];
+        if len > 2 {
+            keep[1..len - 1].fill(false);
+            let mut budget = len.saturating_mul(len.ilog2() as usize + 1);
+            let mut stack = vec![(0, len - 1)];
+            while let Some((start, end)) = stack.pop() {
+                let work = end - start - 1;
+                if work > budget {
+                    #[cfg(feature = "tracing")]
+                    trace::fallback("simplify", "work limit exceeded");
+                    for (start, end) in std::iter::once((start, end)).chain(stack.drain(..)) {
+                        gallop(start, end, &mut keep, &mut select);
+                    }
+                    break;
+                }
+                budget -= work;
+                if let Some(index) = select(start, end) {
+                    keep[index] = true;
+                    stack.push((start, index));
+                    stack.push((index, end));
+                }
+            }
+        }
+        Ok(keep)
+    }
+    /// Splits every chord of the Douglas-Peucker recursion at the data point deviating the most,
+    /// as `simplify` would for a zero tolerance. Each split is recorded as the deviation of the
+    /// data point and the tolerance below which its chord is reached (the lowest deviation of
+    /// the enclosing splits), None standing for an infinite deviation or tolerance.
+    ///
+    /// # Returns
+    ///
+    /// The splits, None if they exceed the work limit of `mask_by`.
+    fn splits(&self) -> Option<Vec<Split<F::Output>>>
+    where
+        F: Distance,
+    {
+        let len = self.xp.len();
+        let mut budget = len.saturating_mul(len.max(1).ilog2() as usize + 1);
+        let mut splits = Vec::with_capacity(len.saturating_sub(2));
+        let mut stack = vec![(0, len.saturating_sub(1), None)];
+        while let Some((start, end, parent)) = stack.pop() {
+            if end - start < 2 {
+                continue;
+            }
+            let work = end - start - 1;
+            if work > budget {
+                return None;
+            }
+            budget -= work;
+            let (index, deviation) =
+                argmax(start + 1..end, |index| self.vertical(start, end, index))
+                    .expect("at least one data point in between");
+            let reached = match (&deviation, &parent) {
+                (Some(deviation), Some(parent)) if deviation < parent => Some(deviation.clone()),
+                (Some(deviation), None) => Some(deviation.clone()),
+                _ => parent.clone(),
+            };
+            splits.push((deviation, parent));
+            stack.push((start, index, reached.clone()));
+            stack.push((index, end, reached));
+        }
+        Some(splits)
+    }
+    /// Computes the largest deviation of the dropped data points from the chords joining the
+    /// kept ones, `zero` if none is dropped.
+    fn max_deviation(&self, keep: &[bool], zero: F::Output) -> F::Output
+    where
+        F: Distance,
+    {
+        let kept: Vec<usize> = (0..keep.len()).filter(|index| keep[*index]).collect();
+        let mut error = zero;
+        for pair in kept.windows(2) {
+            for index in pair[0] + 1..pair[1] {
+                if let Some(deviation) = self.vertical(pair[0], pair[1], index) {
+                    if deviation > error {
+                        error = deviation;
+                    }
+                }
+            }
+        }
+        error
+    }
+    /// Returns the selection of `simplify`: the data point deviating the most from the chord,
+    /// if by more than `epsilon`.
+    fn within<'a>(&'a self, epsilon: &'a F::Output) -> impl Fn(usize, usize) -> Option<usize> + 'a
+    where
+        F: Distance,
+    {
+        move |start, end| {
+            farthest(
+                start + 1..end,
+                |index| self.vertical(start, end, index),
+                epsilon,
+            )
+        }
+    }
+    /// Computes the deviation of a value from the chord joining two data points, None if the
+    /// chord cannot be evaluated.
+    fn vertical(&self, start: usize, end: usize, index: usize) -> Option<F::Output>
+    where
+        F: Distance,
+    {
+        self.xp[index]
+            .forward(
+                self.xp[start],
+                self.xp[end],
+                self.fp[start].clone(),
+                self.fp[end].clone(),
+                Method::Nearest,
+            )
+            .map(|f| f.distance(self.fp[index].clone()))
+    }
+    /// Computes the deviation of an index from the inverse of the chord joining two data points,
+    /// None if the value has no inverse on the chord.
+    fn horizontal(&self, start: usize, end: usize, index: usize) -> Option<X::Output>
+    where
+        X: Distance,
+    {
+        let (x0, x1) = (self.xp[start], self.xp[end]);
+        let (f0, f1) = (self.fp[start].clone(), self.fp[end].clone());
+        let f = self.fp[index].clone();
+        let x = if f0 < f1 && f0 <= f && f <= f1 {
+            f.inverse(x0, x1, f0, f1, Method::Nearest)
+        } else if f1 < f0 && f1 <= f && f <= f0 {
+            f.inverse(x1, x0, f1, f0, Method::Nearest)
+        } else {
+            None
+        };
+        x.map(|x| x.distance(self.xp[index]))
+    }
+}
+
+impl<F> Interp<u64, F>
+where
+    u64: Forward<F>,
+    f64: ForwardAt<F>,
+    F: Inverse<u64>,
+{
+    /// Performs forward interpolation at the given real-valued index. Integer values are rounded
+    /// to the nearest integer.
+    ///
+    /// # Arguments
+    ///
+    /// * `rhs` - The real-valued index for forward interpolation.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the interpolated value.
+    /// Otherwise, returns an error indicating the reason for failure.
+    pub fn forward_at(&self, rhs: f64) -> Result<F, InterpError> {
+        self.forward_at_with(rhs, Method::Nearest)
+    }
+    /// Performs forward interpolation at the given real-valued index with the given rounding
+    /// method. The segment is located using the floor of the index. Negative, NaN or infinite
+    /// indices are out of bounds.
+    ///
+    /// # Arguments
+    ///
+    /// * `rhs` - The real-valued index for forward interpolation.
+    /// * `method` - The rounding method to use in case of inexact integer values.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the interpolated value.
+    /// Otherwise, returns an error indicating the reason for failure.
+    pub fn forward_at_with(&self, rhs: f64, method: Method) -> Result<F, InterpError> {
+        if !self.forwardable {
+            return Err(InterpError::NotStrictlyIncreasing);
+        }
+        if !(rhs >= 0.0 && rhs < u64::MAX as f64) {
+            return Err(InterpError::OutOfBounds);
+        }
+        let floor = rhs.floor();
+        if floor == rhs {
+            return self.forward_with(rhs as u64, method);
+        }
+        match self.xp.partition_point(|&x| x <= floor as u64) {
+            0 => Err(InterpError::OutOfBounds),
+            len if len == self.xp.len() => Err(InterpError::OutOfBounds),
+            index => rhs
+                .forward_at(
+                    self.xp[index - 1],
+                    self.xp[index],
+                    self.fp[index - 1].clone(),
+                    self.fp[index].clone(),
+                    method,
+                )
+                .ok_or(InterpError::NotFound),
+        }
+    }
+    /// Performs forward interpolation of a periodic function at the given real-valued index (see
+    /// `forward_periodic`). The index is reduced with an F80 remainder, then rounded to the
+    /// nearest f64.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the interpolated value.
+    /// Otherwise, returns `InvalidPeriod` if the period does not exceed the span of the indices,
+    /// `NoSamples` if there are no data points, `NotFound` if the index is not finite or an error
+    /// indicating the reason for failure.
+    pub fn forward_periodic_at(&self, rhs: f64, period: u64) -> Result<F, InterpError> {
+        let first = self.periodic_start(period)?;
+        let offset = F80::from_finite(rhs)
+            .ok_or(InterpError::NotFound)?
+            .sub(&F80::from(first))
+            .rem_euclid(&F80::from(period))
+            .expect("positive period");
+        let offset = f64::from(offset).min(period as f64).max(0.0);
+        let x = first as f64 + offset;
+        self.forward_wrapped(x.floor() as u64, period, |x0, x1, f0, f1| {
+            x.forward_at(x0, x1, f0, f1, self.method)
+        })
+    }
+}
+
+impl<F> Interp<u64, F>
+where
+    u64: Forward<F>,
+    F: Inverse<u64>,
+{
+    /// Performs forward interpolation of a periodic function at the given index, as the
+    /// `period` of `np.interp` does (e.g. azimuths or circular buffers). The index is reduced
+    /// modulo the period into `[xp[0], xp[0] + period)` with an exact integer remainder, and
+    /// indices beyond the last breakpoint are interpolated across the wrap, between the last data
+    /// point and the first one shifted by a period. Queries are never out of bounds, hence the
+    /// inclusivity and bounds options do not apply. Integer values are rounded with the default
+    /// method.
+    ///
+    /// # Arguments
+    ///
+    /// * `rhs` - The index for forward interpolation, negative indices wrapping backward.
+    /// * `period` - The period of the indices.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the interpolated value.
+    /// Otherwise, returns `InvalidPeriod` if the period does not exceed the span of the indices,
+    /// `NoSamples` if there are no data points or an error indicating the reason for failure.
+    pub fn forward_periodic(&self, rhs: i64, period: u64) -> Result<F, InterpError> {
+        let first = self.periodic_start(period)?;
+        let offset = (rhs as i128 - first as i128).rem_euclid(period as i128) as u64;
+        self.forward_wrapped(first + offset, period, |x0, x1, f0, f1| {
+            (first + offset).forward(x0, x1, f0, f1, self.method)
+        })
+    }
+    /// Checks that the data points fit in one period and returns the first breakpoint.
+    fn periodic_start(&self, period: u64) -> Result<u64, InterpError> {
+        if !self.forwardable {
+            return Err(InterpError::NotStrictlyIncreasing);
+        }
+        let (Some(first), Some(last)) = (self.xp.first(), self.xp.last()) else {
+            return Err(InterpError::NoSamples);
+        };
+        if period <= last - first || first.checked_add(period).is_none() {
+            return Err(InterpError::InvalidPeriod);
+        }
+        Ok(*first)
+    }
+    /// Interpolates at an index reduced into the first period, located by its floor, the segment
+    /// after the last breakpoint wrapping to the first data point shifted by a period.
+    fn forward_wrapped(
+        &self,
+        floor: u64,
+        period: u64,
+        scheme: impl Fn(u64, u64, F, F) -> Option<F>,
+    ) -> Result<F, InterpError> {
+        let first = self.xp[0];
+        let (x0, x1, f0, f1) = match self.xp.binary_search(&floor) {
+            Ok(index) if index + 1 < self.xp.len() => (
+                self.xp[index],
+                self.xp[index + 1],
+                self.fp[index].clone(),
+                self.fp[index + 1].clone(),
+            ),
+            Err(index) if index < self.xp.len() => (
+                self.xp[index - 1],
+                self.xp[index],
+                self.fp[index - 1].clone(),
+                self.fp[index].clone(),
+            ),
+            _ => {
+                let last = self.xp.len() - 1;
+                (
+                    self.xp[last],
+                    first + period,
+                    self.fp[last].clone(),
+                    self.fp[0].clone(),
+                )
+            }
+        };
+        scheme(x0, x1, f0, f1).ok_or(InterpError::NotFound)
+    }
+    /// Performs inverse interpolation at the given value, giving up when forward or backward
+    /// filling reaches farther than the limit, as the `limit` of pandas does. The distance is
+    /// measured along the indices between the real-valued position of the value and the filled
+    /// index. Beyond the extreme breakpoints, the position is extrapolated along the extreme
+    /// segment, so that values slightly out of bounds can still be filled. Other methods
+    /// ignore the limit and exact hits on breakpoints always succeed.
+    ///
+    /// # Arguments
+    ///
+    /// * `rhs` - The value for inverse interpolation.
+    /// * `method` - The rounding method to use in case of inexact matching, None for the
+    ///   default method.
+    /// * `limit` - The maximum distance, in indices, of forward and backward filling.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the interpolated index.
+    /// Otherwise, returns `NotFound` if the filling exceeds the limit or an error indicating
+    /// the reason for failure (see `inverse`).
+    pub fn inverse_limited(
+        &self,
+        rhs: F,
+        method: impl Into<Option<Method>>,
+        limit: u64,
+    ) -> Result<u64, InterpError>
+    where
+        F: Extended,
+    {
+        let method = method.into().unwrap_or(self.method);
+        let x = self.inverse(rhs, method)?;
+        if !matches!(method, Method::ForwardFill | Method::BackwardFill) {
+            return Ok(x);
+        }
+        let index = match self.search(&rhs)? {
+            Ok(_) => return Ok(x),
+            Err(index) => index,
+        };
+        if self.xp.len() < 2 {
+            return Err(InterpError::NotFound);
+        }
+        let index = index.clamp(1, self.xp.len() - 1);
+        let (x0, x1) = (self.xp[index - 1], self.xp[index]);
+        let f = rhs.to_extended();
+        let f0 = self.fp[index - 1].to_extended();
+        let f1 = self.fp[index].to_extended();
+        let (Some(f), Some(f0), Some(f1)) = (f, f0, f1) else {
+            return Err(InterpError::NotFound);
+        };
+        // The offset of the position from the filled index is built from exact index
+        // differences, so that it keeps its fractional part even for large indices.
+        let ratio = f.sub(&f0).checked_div(&f1.sub(&f0));
+        let offset = ratio.map(|ratio| {
+            F80::from(x0)
+                .sub(&F80::from(x))
+                .add(&F80::from(x1 - x0).mul(&ratio))
+        });
+        // The limit is an integer, hence it is exceeded exactly when the rounded up distance is.
+        match offset.and_then(|offset| offset.abs().to_u64(Method::BackwardFill)) {
+            Some(distance) if distance <= limit => Ok(x),
+            _ => Err(InterpError::NotFound),
+        }
+    }
+    /// Divides the indices by the given factor, e.g. to follow a decimation of the data.
+    ///
+    /// # Arguments
+    ///
+    /// * `factor` - The decimation factor.
+    /// * `method` - The rounding method to use in case of inexact division.
+    /// * `collision` - The policy for data points landing on the same index.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the decimated Interp.
+    /// Otherwise, returns `InvalidArgument` if the factor is zero, `NotFound` if a division is
+    /// inexact with `Method::None` or `NotStrictlyIncreasing` if data points collide with the
+    /// `Error` policy.
+    pub fn decimate_xp(
+        &self,
+        factor: u64,
+        method: Method,
+        collision: Collision,
+    ) -> Result<Interp<u64, F>, InterpError> {
+        if factor == 0 {
+            return Err(InterpError::InvalidArgument("factor must be positive"));
+        }
+        let xp = self
+            .xp
+            .iter()
+            .map(|x| (*x as u128).div(factor as u128, method).map(|x| x as u64))
+            .collect::<Option<Vec<u64>>>()
+            .ok_or(InterpError::NotFound)?;
+        self.with_xp(xp, collision)
+    }
+    /// Shifts the indices by the given signed offset, e.g. to concatenate data.
+    ///
+    /// # Arguments
+    ///
+    /// * `delta` - The offset added to each index.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the shifted Interp.
+    /// Otherwise, returns `Overflow` if any index falls outside the u64 range.
+    pub fn offset_xp(&self, delta: i64) -> Result<Interp<u64, F>, InterpError> {
+        let xp = self
+            .xp
+            .iter()
+            .map(|x| x.checked_add_signed(delta))
+            .collect::<Option<Vec<u64>>>()
+            .ok_or(InterpError::Overflow)?;
+        self.with_xp(xp, Collision::Error)
+    }
+    /// Splits the function into chunks of `chunk_size` consecutive indices, e.g. to process
+    /// chunked data. The k-th chunk covers the indices from k·chunk_size included to
+    /// (k + 1)·chunk_size excluded, restricted to the data points. Chunks are only split at
+    /// existing data points: each chunk holds the data points of the segments overlapping its
+    /// window, so that consecutive chunks share a data point when a segment straddles the
+    /// boundary between their windows. The indices of each chunk are rebased so that its first
+    /// data point is at 0. Chunks are closed on both sides and keep the other options of this
+    /// Interp.
+    ///
+    /// Since no breakpoint is inserted, restoring the offsets of the chunks reproduces the
+    /// function exactly at every index of their windows.
+    ///
+    /// # Arguments
+    ///
+    /// * `chunk_size` - The number of indices of each chunk.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the chunks in order along with their offsets (the indices of
+    /// their first data points), from the one containing the first data point to the one
+    /// containing the last data point (none if there are no data points).
+    /// Otherwise, returns `InvalidArgument` if `chunk_size` is zero or `NotStrictlyIncreasing`
+    /// if the indices are not strictly increasing.
+    pub fn chunk(&self, chunk_size: u64) -> Result<Vec<Chunk<F>>, InterpError> {
+        if chunk_size == 0 {
+            return Err(InterpError::InvalidArgument("chunk size must be positive"));
+        }
+        if !self.forwardable {
+            return Err(InterpError::NotStrictlyIncreasing);
+        }
+        let (Some(&first), Some(&last)) = (self.xp.first(), self.xp.last()) else {
+            return Ok(vec![]);
+        };
+        Ok((first / chunk_size..=last / chunk_size)
+            .map(|k| {
+                let start = (k * chunk_size).max(first);
+                let end = (k * chunk_size).saturating_add(chunk_size - 1).min(last);
+                // The last data point at or before the start and the first one at or after the
+                // end, both within the data points since first <= start <= end <= last.
+                let lower = self.xp.partition_point(|x| *x <= start) - 1;
+                let upper = self.xp.partition_point(|x| *x < end);
+                let offset = self.xp[lower];
+                let xp = self.xp[lower..=upper].iter().map(|x| x - offset).collect();
+                let fp = self.fp[lower..=upper].to_vec();
+                let chunk = self
+                    .configure(Interp::new(xp, fp))
+                    .with_closed(Closed::Both);
+                (offset, chunk)
+            })
+            .collect())
+    }
+    /// Builds the data points reproducing a reference function within a tolerance, the inverse
+    /// of `simplify`. Starting from the ends of the domain, each segment is split at its midpoint
+    /// as long as the function deviates there from the chord by more than `epsilon` (the chord
+    /// being evaluated with the forward scheme, integers are rounded to the nearest). Only
+    /// midpoints are checked, which bounds the deviation everywhere for functions that are
+    /// smooth at the scale of the final segments. At most `MAX_REFINED_POINTS` data points are
+    /// produced.
+    ///
+    /// # Arguments
+    ///
+    /// * `domain` - The first and last indices of the domain.
+    /// * `func` - The reference function.
+    /// * `epsilon` - The maximum deviation of the values.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the built Interp.
+    /// Otherwise, returns `InvalidArgument` if the domain is reversed.
+    pub fn approx_from_fn(
+        domain: (u64, u64),
+        func: impl Fn(u64) -> F,
+        epsilon: F::Output,
+    ) -> Result<Interp<u64, F>, InterpError>
+    where
+        F: Distance,
+    {
+        Interp::approx_from_fn_with(domain, func, epsilon, MAX_REFINED_POINTS)
+    }
+    /// Builds the data points reproducing a reference function within a tolerance with the given
+    /// maximum number of data points. Segments are refined depth first, from left to right: if
+    /// the limit is reached, the rightmost segments are left unrefined.
+    ///
+    /// # Arguments
+    ///
+    /// * `domain` - The first and last indices of the domain.
+    /// * `func` - The reference function.
+    /// * `epsilon` - The maximum deviation of the values.
+    /// * `max_points` - The maximum number of data points, at least the ends of the domain are
+    ///   kept.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the built Interp.
+    /// Otherwise, returns `InvalidArgument` if the domain is reversed.
+    pub fn approx_from_fn_with(
+        domain: (u64, u64),
+        func: impl Fn(u64) -> F,
+        epsilon: F::Output,
+        max_points: usize,
+    ) -> Result<Interp<u64, F>, InterpError>
+    where
+        F: Distance,
+    {
+        let (start, end) = domain;
+        if start > end {
+            return Err(InterpError::InvalidArgument("domain must not be reversed"));
+        }
+        if start == end {
+            return Ok(Interp::new(vec![start], vec![func(start)]));
+        }
+        let (first, last) = (func(start), func(end));
+        let mut xp = vec![start];
+        let mut fp = vec![first.clone()];
+        let mut budget = max_points.saturating_sub(2);
+        refine(
+            (start, first),
+            (end, last.clone()),
+            &func,
+            &epsilon,
+            &mut budget,
+            &mut xp,
+            &mut fp,
+        );
+        xp.push(end);
+        fp.push(last);
+        Ok(Interp::new(xp, fp))
+    }
+    /// Samples the interpolation at evenly spaced indices spanning the data points, e.g. for
+    /// plotting. The first and last indices of the data points are always included, the ones in
+    /// between are rounded to the nearest integer with exact integer arithmetic.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - The number of samples.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the sampled indices and their interpolated values. A single sample
+    /// is the first data point.
+    /// Otherwise, returns `NoSamples` if `n` is zero or an error indicating the reason for
+    /// failure of the forward interpolation (`OutOfBounds` if there are no data points).
+    pub fn sample(&self, n: usize) -> Result<(Vec<u64>, Vec<F>), InterpError> {
+        if n == 0 {
+            return Err(InterpError::NoSamples);
+        }
+        let (Some(&first), Some(&last)) = (self.xp.first(), self.xp.last()) else {
+            return Err(InterpError::OutOfBounds);
+        };
+        let span = last.saturating_sub(first) as u128;
+        let steps = n.saturating_sub(1).max(1) as u128;
+        let x: Vec<u64> = (0..n as u128)
+            .map(|step| {
+                let delta = (step * span).div(steps, Method::Nearest);
+                first + delta.expect("nearest division is never inexact") as u64
+            })
+            .collect();
+        let f = x
+            .iter()
+            .map(|index| self.forward(*index))
+            .collect::<Result<Vec<F>, InterpError>>()?;
+        Ok((x, f))
+    }
+    /// Performs forward interpolation over the evenly spaced indices `start`, `start + step`...
+    /// below `stop`, without materializing them. The indices are computed exactly from `start`
+    /// and walked segment by segment alongside the data points, so no search is involved. Out of
+    /// bounds indices follow the bounds policy and integer values are rounded with the default
+    /// method.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The first index (included).
+    /// * `stop` - The end of the range (excluded).
+    /// * `step` - The spacing between consecutive indices.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the interpolated values, none if `start` is not below `stop`.
+    /// Otherwise, returns `InvalidArgument` if `step` is zero or the error of the first failing
+    /// index.
+    pub fn forward_range(&self, start: u64, stop: u64, step: u64) -> Result<Vec<F>, InterpError> {
+        if step == 0 {
+            return Err(InterpError::InvalidArgument("step must be positive"));
+        }
+        if !self.forwardable {
+            return Err(InterpError::NotStrictlyIncreasing);
+        }
+        let count = if start < stop {
+            (stop - start - 1) / step + 1
+        } else {
+            0
+        };
+        let mut segment = 0;
+        (0..count)
+            .map(|k| {
+                let x = start + k * step;
+                while segment < self.xp.len() && self.xp[segment] < x {
+                    segment += 1;
+                }
+                let found = match self.xp.get(segment) {
+                    Some(breakpoint) if *breakpoint == x => Ok(segment),
+                    _ => Err(segment),
+                };
+                match self.bounded(found) {
+                    Ok(position) => Ok(self.fp[position].clone()),
+                    Err(0) => self.clamp(self.bounds.left, self.fp.first()),
+                    Err(len) if len == self.xp.len() => {
+                        self.clamp(self.bounds.right, self.fp.last())
+                    }
+                    Err(position) => x
+                        .forward(
+                            self.xp[position - 1],
+                            self.xp[position],
+                            self.fp[position - 1].clone(),
+                            self.fp[position].clone(),
+                            self.method,
+                        )
+                        .ok_or(InterpError::NotFound),
+                }
+            })
+            .collect()
+    }
+    /// Performs forward interpolation at each index of the range, walking the data points
+    /// segment by segment (see `forward_range`), as a shorthand for scripts where failures are
+    /// bugs.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the forward interpolation fails at any index of the range, e.g. if the range
+    /// is not within bounds with the error policy, like slice indexing.
+    pub fn values(&self, range: Range<u64>) -> Vec<F> {
+        self.forward_range(range.start, range.end, 1)
+            .unwrap_or_else(|err| panic!("forward interpolation failed: {err}"))
+    }
+    /// Compares densely sampled values, the i-th value being located at index i, to the forward
+    /// interpolation at their indices (integer values being rounded to the nearest integer). The
+    /// data points are walked segment by segment alongside the values, so no search is involved.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - The densely sampled values.
+    /// * `atol` - The maximum absolute deviation.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the number of violations and the maximum deviation with its index.
+    /// NaN deviations count as violations but are not reported as the maximum.
+    /// Otherwise, returns an error indicating the reason for failure (`OutOfBounds` if the
+    /// values extend beyond the data points).
+    pub fn verify(
+        &self,
+        values: &[F],
+        atol: F::Output,
+    ) -> Result<Verification<F::Output>, InterpError>
+    where
+        F: Distance,
+    {
+        if !self.forwardable {
+            return Err(InterpError::NotStrictlyIncreasing);
+        }
+        let mut report = Verification {
+            violations: 0,
+            max_deviation: None,
+            argmax: None,
+        };
+        let mut segment = 0;
+        for (index, value) in values.iter().enumerate() {
+            let x = index as u64;
+            while segment < self.xp.len() && self.xp[segment] < x {
+                segment += 1;
+            }
+            let found = match self.xp.get(segment) {
+                Some(breakpoint) if *breakpoint == x => Ok(segment),
+                _ => Err(segment),
+            };
+            let f = match self.bounded(found) {
+                Ok(position) => self.fp[position].clone(),
+                Err(0) => return Err(InterpError::OutOfBounds),
+                Err(len) if len == self.xp.len() => return Err(InterpError::OutOfBounds),
+                Err(position) => x
+                    .forward(
+                        self.xp[position - 1],
+                        self.xp[position],
+                        self.fp[position - 1].clone(),
+                        self.fp[position].clone(),
+                        Method::Nearest,
+                    )
+                    .ok_or(InterpError::NotFound)?,
+            };
+            let deviation = f.distance(value.clone());
+            let comparable = match deviation.partial_cmp(&atol) {
+                Some(Ordering::Less | Ordering::Equal) => true,
+                Some(Ordering::Greater) => {
+                    report.violations += 1;
+                    true
+                }
+                None => {
+                    report.violations += 1;
+                    false
+                }
+            };
+            if comparable
+                && report
+                    .max_deviation
+                    .as_ref()
+                    .is_none_or(|max| deviation > *max)
+            {
+                report.max_deviation = Some(deviation);
+                report.argmax = Some(index);
+            }
+        }
+        Ok(report)
+    }
+}
+
+/// Default maximum number of data points built by `Interp::approx_from_fn`.
+pub const MAX_REFINED_POINTS: usize = 1 << 20;
+
+/// Recursively splits the segment joining two data points at its midpoint while the function
+/// deviates there from the chord by more than `epsilon`, pushing the inner data points in order.
+/// Each added data point consumes one unit of the budget.
+fn refine<F>(
+    (x0, f0): (u64, F),
+    (x1, f1): (u64, F),
+    func: &impl Fn(u64) -> F,
+    epsilon: &F::Output,
+    budget: &mut usize,
+    xp: &mut Vec<u64>,
+    fp: &mut Vec<F>,
+) where
+    u64: Forward<F>,
+    F: Distance,
+{
+    if x1 - x0 < 2 || *budget == 0 {
+        return;
+    }
+    let x = x0 + (x1 - x0) / 2;
+    let f = func(x);
+    let within = x
+        .forward(x0, x1, f0.clone(), f1.clone(), Method::Nearest)
+        .is_some_and(|chord| chord.distance(f.clone()) <= *epsilon);
+    if within {
+        return;
+    }
+    *budget -= 1;
+    refine((x0, f0), (x, f.clone()), func, epsilon, budget, xp, fp);
+    xp.push(x);
+    fp.push(f.clone());
+    refine((x, f), (x1, f1), func, epsilon, budget, xp, fp);
+}
+
+impl<F> Interp<u64, F>
+where
+    u64: Forward<F>,
+    F: Inverse<u64> + Extended,
+{
+    /// Fits a continuous piecewise linear function to densely sampled values, the i-th value
+    /// being located at index i. Segments are greedily grown as long as some line can stay
+    /// within `max_err` of every sample of the segment while joining the previous one. The
+    /// breakpoint values hence do not need to be equal to any sample.
+    ///
+    /// Integer values are fitted with a tolerance reduced by one to absorb the rounding of the
+    /// breakpoints and of the interpolation to the nearest integer. The bound is verified at
+    /// the end: in the unlikely case where rounding broke it, every sample is kept as a
+    /// breakpoint.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - The densely sampled values.
+    /// * `max_err` - The maximum absolute deviation between the fit and the samples.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the fitted function.
+    /// Otherwise, returns `Overflow` if `max_err` or a sample is not finite or
+    /// `InvalidArgument` if `max_err` is negative.
+    pub fn fit_dense(values: &[F], max_err: F) -> Result<Interp<u64, F>, InterpError> {
+        let max_err = max_err.to_extended().ok_or(InterpError::Overflow)?;
+        if max_err < *F80::zero() {
+            return Err(InterpError::InvalidArgument("max_err must be non-negative"));
+        }
+        let samples = values
+            .iter()
+            .map(|value| value.to_extended())
+            .collect::<Option<Vec<F80>>>()
+            .ok_or(InterpError::Overflow)?;
+        if values.len() < 2 {
+            let xp = (0..values.len() as u64).collect();
+            return Ok(Interp::new(xp, values.to_vec()));
+        }
+        let tolerance = max_err
+            .sub(&F80::from(F::MARGIN))
+            .max(F80::clone(F80::zero()));
+        let (xp, fp) = fit(&samples, &tolerance);
+        let fp = fp.into_iter().map(F::from_extended).collect();
+        let interp = Interp::new(xp, fp);
+        let valid = samples.iter().enumerate().all(|(index, sample)| {
+            match interp.forward(index as u64).ok().and_then(F::to_extended) {
+                Some(value) => {
+                    value
+                        .clone()
+                        .max(sample.clone())
+                        .sub(&value.min(sample.clone()))
+                        <= max_err
+                }
+                None => false,
+            }
+        });
+        if valid {
+            Ok(interp)
+        } else {
+            Ok(Interp::new(
+                (0..values.len() as u64).collect(),
+                values.to_vec(),
+            ))
+        }
+    }
+    /// Computes descriptive statistics of the data points, e.g. for logging. Segments are
+    /// irregular if their slope deviates from the median by more than a factor of two.
+    pub fn summary(&self) -> Summary<F> {
+        self.summary_with(2.0)
+    }
+    /// Computes descriptive statistics of the data points with the given factor of deviation
+    /// from the median slope beyond which segments are irregular. Slopes are computed with
+    /// extended-precision floats, the median of an even number of slopes being the mean of the
+    /// two middle ones. Segments of opposite sign to a nonzero median slope are irregular, as
+    /// are sloped segments if the median slope is zero. The statistics of the segments assume
+    /// strictly increasing indices: segments of zero length or with values that are not finite
+    /// have no slope.
+    ///
+    /// # Arguments
+    ///
+    /// * `factor` - The factor of deviation from the median slope, at least one. An infinite or
+    ///   NaN factor makes no segment irregular.
+    pub fn summary_with(&self, factor: f64) -> Summary<F> {
+        let mut range: Option<(F, F)> = None;
+        for f in &self.fp {
+            range = Some(match range {
+                None => (*f, *f),
+                Some((min, max)) => (
+                    if *f < min { *f } else { min },
+                    if *f > max { *f } else { max },
+                ),
+            });
+        }
+        let lengths: Vec<u64> = self
+            .xp
+            .windows(2)
+            .map(|pair| pair[1].saturating_sub(pair[0]))
+            .collect();
+        let slopes: Vec<F80> = self
+            .fp
+            .windows(2)
+            .zip(&lengths)
+            .filter_map(|(pair, length)| {
+                pair[1]
+                    .to_extended()?
+                    .sub(&pair[0].to_extended()?)
+                    .checked_div(&F80::from(*length))
+            })
+            .collect();
+        let mut sorted = slopes.clone();
+        sorted.sort();
+        let median = match sorted.len() {
+            0 => None,
+            len if len % 2 == 1 => Some(sorted[len / 2].clone()),
+            len => Some(sorted[len / 2 - 1].add(&sorted[len / 2]).div(F80::two())),
+        };
+        let irregular = match (&median, F80::from_finite(factor)) {
+            (Some(median), Some(factor)) => {
+                let (low, high) = if *median < *F80::zero() {
+                    (median.mul(&factor), median.div(&factor))
+                } else {
+                    (median.div(&factor), median.mul(&factor))
+                };
+                slopes
+                    .iter()
+                    .filter(|slope| **slope < low || **slope > high)
+                    .count()
+            }
+            _ => 0,
+        };
+        Summary {
+            points: self.xp.len(),
+            domain: self.xp.first().zip(self.xp.last()).map(|(a, b)| (*a, *b)),
+            range,
+            slopes: median.map(|median| {
+                let (first, last) = (sorted[0].clone(), sorted[sorted.len() - 1].clone());
+                (first, median, last)
+            }),
+            lengths: lengths
+                .iter()
+                .min()
+                .zip(lengths.iter().max())
+                .map(|(a, b)| (*a, *b)),
+            irregular,
+        }
+    }
+    /// Computes the derivative of the function: the slope of each segment, in units of the
+    /// values per index step, as a step function keeping the options of this Interp. Slopes are
+    /// computed with extended-precision floats (exactly for the differences of integers) and
+    /// rounded to f64 at the end, hence they are accurate to about one f64 ulp.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the step function of the slopes.
+    /// Otherwise, returns `NotStrictlyIncreasing` if the indices are not strictly increasing or
+    /// `Overflow` if any value is not finite.
+    pub fn derivative(&self) -> Result<StepFn<u64, f64>, InterpError> {
+        if !self.forwardable {
+            return Err(InterpError::NotStrictlyIncreasing);
+        }
+        let slopes = self
+            .xp
+            .windows(2)
+            .zip(self.fp.windows(2))
+            .map(|(x, f)| {
+                let slope = f[1]
+                    .to_extended()?
+                    .sub(&f[0].to_extended()?)
+                    .div(&F80::from(x[1] - x[0]));
+                Some(slope.into())
+            })
+            .collect::<Option<_>>()
+            .ok_or(InterpError::Overflow)?;
+        Ok(StepFn::new(self.xp.clone(), slopes)?
+            .with_closed(self.closed)
+            .with_bounds(self.bounds))
+    }
+    /// Finds the indices where this Interp and another one take the same value. Both are
+    /// evaluated in extended precision at the union of their breakpoints over the overlapping
+    /// domain, their difference being linear in between. Each sign change of the difference
+    /// within a segment yields a crossing, whose real-valued index is rounded to the nearest
+    /// integer. Breakpoints where the difference is zero yield a crossing too, reported once
+    /// whether the difference changes sign there or only touches zero.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The Interp to intersect with.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the crossings sorted by index, empty if the domains do not
+    /// overlap.
+    /// Otherwise, returns `NotStrictlyIncreasing` if the indices of either Interp are not
+    /// strictly increasing, `Overflow` if a value over the overlapping domain is not finite or
+    /// `NotFound` if the value at a crossing is inexact with `Method::None` as default method.
+    pub fn intersections(
+        &self,
+        other: &Interp<u64, F>,
+    ) -> Result<Vec<Crossing<u64, F>>, InterpError> {
+        let Some(xp) = self.shared_xp(other)? else {
+            return Ok(Vec::new());
+        };
+        let differences: Vec<F80> = xp
+            .iter()
+            .map(|x| Some(self.extended_at(*x)?.sub(&other.extended_at(*x)?)))
+            .collect::<Option<_>>()
+            .ok_or(InterpError::Overflow)?;
+        let zero = F80::clone(F80::zero());
+        let mut crossings: Vec<Crossing<u64, F>> = Vec::new();
+        for (index, difference) in differences.iter().enumerate() {
+            let x = if *difference == zero {
+                xp[index]
+            } else {
+                match differences.get(index + 1) {
+                    Some(next) if *next != zero && (*difference < zero) != (*next < zero) => {
+                        let (x0, x1) = (F80::from(xp[index]), F80::from(xp[index + 1]));
+                        let ratio = difference.div(&difference.sub(next));
+                        x0.add(&x1.sub(&x0).mul(&ratio)).round().into()
+                    }
+                    _ => continue,
+                }
+            };
+            if crossings.last().is_some_and(|crossing| crossing.x == x) {
+                continue;
+            }
+            let f = self.value_at(x, self.method).ok_or(InterpError::NotFound)?;
+            let exact = match (
+                self.value_at(x, Method::None),
+                other.value_at(x, Method::None),
+            ) {
+                (Some(lhs), Some(rhs)) => lhs == rhs,
+                _ => false,
+            };
+            crossings.push(Crossing { x, f, exact });
+        }
+        Ok(crossings)
+    }
+    /// Interpolates the value at the given index within the data points in extended precision,
+    /// None if a value of the segment is not finite.
+    fn extended_at(&self, rhs: u64) -> Option<F80> {
+        let index = self.xp.partition_point(|x| *x < rhs);
+        if self.xp.get(index) == Some(&rhs) {
+            return self.fp[index].to_extended();
+        }
+        let (x0, x1) = (F80::from(self.xp[index - 1]), F80::from(self.xp[index]));
+        let (f0, f1) = (
+            self.fp[index - 1].to_extended()?,
+            self.fp[index].to_extended()?,
+        );
+        Some(f0.add(&f1.sub(&f0).mul(&F80::from(rhs).sub(&x0)).div(&x1.sub(&x0))))
+    }
+    /// Returns the median steepness of the segments (absolute difference of values per index
+    /// step), None if there are less than two data points. The median of an even number of
+    /// segments is the mean of the two middle ones. Segments of zero length or with values that
+    /// are not finite are infinitely steep and left out, None if no segment remains.
+    pub fn median_steepness(&self) -> Option<F80> {
+        let mut sorted: Vec<F80> = (1..self.xp.len())
+            .filter_map(|index| self.steepness(index))
+            .collect();
+        sorted.sort();
+        match sorted.len() {
+            0 => None,
+            len if len % 2 == 1 => Some(sorted[len / 2].clone()),
+            len => Some(sorted[len / 2 - 1].add(&sorted[len / 2]).div(F80::two())),
+        }
+    }
+    /// Performs inverse interpolation at the given value, refusing to interpolate inside the
+    /// segments steeper than the threshold. Such segments are acquisition gaps: the indices
+    /// strictly between their breakpoints correspond to no real sample. Exact hits on
+    /// breakpoints always succeed and queries elsewhere behave as with `inverse`. Infinitely
+    /// steep segments (see `median_steepness`) are always gaps, whereas an infinite threshold
+    /// makes no other segment a gap and a NaN one makes every segment a gap.
+    ///
+    /// A median threshold is recomputed at each call, batches should rather resolve it once
+    /// with `median_steepness` and pass an absolute threshold.
+    ///
+    /// # Arguments
+    ///
+    /// * `rhs` - The value for inverse interpolation.
+    /// * `method` - The rounding method to use in case of inexact matching, None for the
+    ///   default method.
+    /// * `max_gap` - The steepness beyond which a segment is a gap.
+    /// * `policy` - Whether queries in a gap fail or snap to a breakpoint of the gap.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the interpolated index.
+    /// Otherwise, returns `InGap` if the value falls inside a gap (and is not snapped) or an
+    /// error indicating the reason for failure (see `inverse`).
+    pub fn inverse_gapped(
+        &self,
+        rhs: F,
+        method: impl Into<Option<Method>>,
+        max_gap: MaxGap,
+        policy: GapPolicy,
+    ) -> Result<u64, InterpError> {
+        let method = method.into().unwrap_or(self.method);
+        let index = match self.search(&rhs)? {
+            Err(index) if index > 0 && index < self.xp.len() => index,
+            _ => return self.inverse(rhs, method),
+        };
+        let (threshold, factor) = match max_gap {
+            MaxGap::Absolute(threshold) => (Some(F80::clone(F80::one())), threshold),
+            MaxGap::Median(factor) => (self.median_steepness(), factor),
+        };
+        let within = match (self.steepness(index), threshold, F80::from_finite(factor)) {
+            (Some(steepness), Some(threshold), Some(factor)) => steepness <= threshold.mul(&factor),
+            (Some(_), _, None) => factor == f64::INFINITY,
+            _ => false,
+        };
+        if within {
+            return self.inverse(rhs, method);
+        }
+        match (policy, method) {
+            (GapPolicy::Error, _) | (GapPolicy::Snap, Method::None) => Err(InterpError::InGap),
+            (GapPolicy::Snap, Method::ForwardFill) => Ok(self.xp[index - 1]),
+            (GapPolicy::Snap, Method::BackwardFill) => Ok(self.xp[index]),
+            (GapPolicy::Snap, Method::Nearest) => {
+                let value = rhs.to_extended().ok_or(InterpError::NotFound)?;
+                let distance = |f: F| Some(value.clone().distance(f.to_extended()?));
+                let nearer = match (distance(self.fp[index - 1]), distance(self.fp[index])) {
+                    (Some(before), Some(after)) => after < before,
+                    (before, _) => before.is_none(),
+                };
+                Ok(if nearer {
+                    self.xp[index]
+                } else {
+                    self.xp[index - 1]
+                })
+            }
+        }
+    }
+    /// Computes the steepness of the segment ending at the given position, None if the segment
+    /// is infinitely steep (zero length or values that are not finite).
+    fn steepness(&self, index: usize) -> Option<F80> {
+        let length = self.xp[index].saturating_sub(self.xp[index - 1]);
+        self.fp[index]
+            .to_extended()?
+            .distance(self.fp[index - 1].to_extended()?)
+            .checked_div(&F80::from(length))
+    }
+}
+
+impl<X> Interp<X, i64>
+where
+    X: Forward<i64> + Sync,
+    i64: Inverse<X>,
+{
+    /// Applies the affine transformation `scale * f + offset` to the values using exact integer
+    /// arithmetic.
+    ///
+    /// # Arguments
+    ///
+    /// * `scale` - The multiplicative factor (negative factors reverse the monotonicity).
+    /// * `offset` - The additive term.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the transformed Interp.
+    /// Otherwise, returns `Overflow` if any value does not fit i64 or `NotStrictlyMonotonic` if
+    /// the scale is zero.
+    pub fn affine_fp(&self, scale: i64, offset: i64) -> Result<Interp<X, i64>, InterpError> {
+        if self.fp.iter().any(|f| {
+            f.checked_mul(scale)
+                .and_then(|f| f.checked_add(offset))
+                .is_none()
+        }) {
+            return Err(InterpError::Overflow);
+        }
+        self.map_fp(|f| f * scale + offset)
+    }
+}
+
+impl<X> Interp<X, f64>
+where
+    X: Forward<f64> + Sync,
+    f64: Inverse<X>,
+{
+    /// Applies the affine transformation `scale * f + offset` to the values. The computation is
+    /// done in extended precision so that each value is only rounded once.
+    ///
+    /// # Arguments
+    ///
+    /// * `scale` - The multiplicative factor (negative factors reverse the monotonicity).
+    /// * `offset` - The additive term.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the transformed Interp.
+    /// Otherwise, returns `Overflow` if any value is not finite or `NotStrictlyMonotonic` if the
+    /// transformation broke the monotonicity.
+    pub fn affine_fp(&self, scale: f64, offset: f64) -> Result<Interp<X, f64>, InterpError> {
+        let (Some(scale), Some(offset)) = (F80::from_finite(scale), F80::from_finite(offset))
+        else {
+            return Err(InterpError::Overflow);
+        };
+        let mut overflow = false;
+        let interp = self.map_fp(|f| {
+            let value = F80::from_finite(*f).map(|f| f.mul(&scale).add(&offset).into());
+            match value {
+                Some(value) if f64::is_finite(value) => value,
+                _ => {
+                    overflow = true;
+                    f64::NAN
+                }
+            }
+        });
+        if overflow {
+            return Err(InterpError::Overflow);
+        }
+        interp
+    }
+}
+
+impl Interp<u64, f64> {
+    /// Performs forward interpolation at the given index, also reporting whether the value is
+    /// exact, i.e. whether the interpolation did not involve any rounding. Breakpoints are always
+    /// exact.
+    ///
+    /// # Arguments
+    ///
+    /// * `rhs` - The index for forward interpolation.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the interpolated value and its exactness.
+    /// Otherwise, returns an error indicating the reason for failure.
+    pub fn forward_detailed(&self, rhs: u64) -> Result<(f64, bool), InterpError> {
+        if !self.forwardable {
+            return Err(InterpError::NotStrictlyIncreasing);
+        }
+        match self.bounded(self.xp.binary_search(&rhs)) {
+            Ok(index) => Ok((self.fp[index], true)),
+            Err(0) => Err(InterpError::OutOfBounds),
+            Err(len) if len == self.xp.len() => Err(InterpError::OutOfBounds),
+            Err(index) => forward_checked(
+                rhs,
+                self.xp[index - 1],
+                self.xp[index],
+                self.fp[index - 1],
+                self.fp[index],
+            )
+            .ok_or(InterpError::NotFound),
+        }
+    }
+    /// Quantizes the values onto an integer grid. Each value is multiplied by the scale in
+    /// extended precision and rounded with the given method.
+    ///
+    /// # Arguments
+    ///
+    /// * `scale` - The number of grid steps per unit of value (e.g. 1e9 for seconds to
+    ///   nanoseconds).
+    /// * `method` - The rounding method to use in case of inexact scaled values.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the quantized Interp.
+    /// Otherwise, returns an error with the index of the faulty value: `Inexact` if rounding is
+    /// not allowed, `Overflow` if the value is not finite or if the scaled value does not fit
+    /// i64 and `NotStrictlyMonotonic` if quantization merges consecutive values of a monotonic
+    /// Interp.
+    pub fn quantize(&self, scale: f64, method: Method) -> Result<Interp<u64, i64>, QuantizeError> {
+        let scale = F80::from_finite(scale)
+            .filter(|scale| scale != F80::zero())
+            .ok_or(QuantizeError::InvalidScale)?;
+        let min = F80::from(i64::MIN);
+        let max = F80::from(i64::MAX);
+        let mut fp = Vec::with_capacity(self.fp.len());
+        for (index, value) in self.fp.iter().enumerate() {
+            let value = F80::from_finite(*value).ok_or(QuantizeError::Overflow(index))?;
+            let value =
+                round_with(&value.mul(&scale), method).ok_or(QuantizeError::Inexact(index))?;
+            if value < min || value > max {
+                return Err(QuantizeError::Overflow(index));
+            }
+            fp.push(value.into());
+        }
+        if self.inversable != Order::Unordered {
+            if let Some(index) = fp.windows(2).position(|pair| pair[0] == pair[1]) {
+                return Err(QuantizeError::NotStrictlyMonotonic(index + 1));
+            }
+        }
+        Ok(self.configure(Interp::new(self.xp.clone(), fp)))
+    }
+}
+
+impl<F> Interp<u64, F>
+where
+    u64: Forward<F>,
+    F: Progression,
+{
+    /// Performs inverse interpolation at the regularly spaced values `start`, `start + step`...
+    /// (`n` values), e.g. a regular time grid. The values are computed exactly (floats are
+    /// rounded once) and located by walking the data points from one value to the next instead
+    /// of searching each of them. Consecutive values within a segment step the division of the
+    /// previous one for integer values. The results are identical to independent calls to
+    /// `inverse`.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The first value.
+    /// * `step` - The difference between consecutive values, either sign.
+    /// * `n` - The number of values.
+    /// * `method` - The rounding method to use in case of inexact matching.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the interpolated indices.
+    /// Otherwise, returns the error of the first failing value or `Overflow` if a value is not
+    /// representable.
+    pub fn inverse_range(
+        &self,
+        start: F,
+        step: F::Step,
+        n: usize,
+        method: Method,
+    ) -> Result<Vec<u64>, InterpError> {
+        let increasing = match self.inversable {
+            Order::Increasing => true,
+            Order::Decreasing => false,
+            Order::Unordered if n == 0 => return Ok(vec![]),
+            Order::Unordered => return Err(InterpError::NotStrictlyMonotonic),
+        };
+        let before = |f: &F, rhs: &F| if increasing { f < rhs } else { f > rhs };
+        let mut out = Vec::with_capacity(n);
+        let mut position = 0;
+        let mut k = 0;
+        while k < n {
+            let rhs = F::nth(&start, step, k).ok_or(InterpError::Overflow)?;
+            if rhs.partial_cmp(&rhs).is_none() {
+                return Err(InterpError::NotFound);
+            }
+            while position < self.fp.len() && before(&self.fp[position], &rhs) {
+                position += 1;
+            }
+            while position > 0 && !before(&self.fp[position - 1], &rhs) {
+                position -= 1;
+            }
+            if 0 < position && position < self.fp.len() && self.fp[position] != rhs {
+                let lower = (self.xp[position - 1], &self.fp[position - 1]);
+                let upper = (self.xp[position], &self.fp[position]);
+                let segment = if increasing {
+                    [lower, upper]
+                } else {
+                    [upper, lower]
+                };
+                k = F::inverse_run(&start, step, k..n, segment, method, &mut out)
+                    .map_err(|_| InterpError::NotFound)?;
+                continue;
+            }
+            let found = match self.fp.get(position) {
+                Some(f) if *f == rhs => Ok(position),
+                _ => Err(position),
+            };
+            out.push(self.inverse_found(rhs, self.bounded(found), method)?);
+            k += 1;
+        }
+        Ok(out)
+    }
+}
+
+impl Interp<u64, i64> {
+    /// Dequantizes the values from an integer grid. Each value is divided by the scale in
+    /// extended precision and rounded to the nearest f64.
+    ///
+    /// # Arguments
+    ///
+    /// * `scale` - The number of grid steps per unit of value.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the dequantized Interp.
+    /// Otherwise, returns `InvalidScale` if the scale is zero or not finite.
+    pub fn dequantize(&self, scale: f64) -> Result<Interp<u64, f64>, QuantizeError> {
+        let scale = F80::from_finite(scale)
+            .filter(|scale| scale != F80::zero())
+            .ok_or(QuantizeError::InvalidScale)?;
+        let fp = self
+            .fp
+            .iter()
+            .map(|value| F80::from(*value).div(&scale).into())
+            .collect();
+        Ok(self.configure(Interp::new(self.xp.clone(), fp)))
+    }
+}
+
+/// Greedily simplifies the data points between two kept ones. From each kept data point, the
+/// next one to keep is the furthest for which `select` finds nothing to keep in between, found
+/// by doubling the length of the chord and then bisecting. Validity is not monotonic with the
+/// length of the chord, but the bisection always ends on a valid one. Reaching k data points
+/// further costs O(k log k) evaluations, hence O(n log n) in total.
+fn gallop(
+    start: usize,
+    end: usize,
+    keep: &mut [bool],
+    select: &mut impl FnMut(usize, usize) -> Option<usize>,
+) {
+    let mut valid =
+        |anchor: usize, next: usize| next == anchor + 1 || select(anchor, next).is_none();
+    let mut anchor = start;
+    while anchor < end {
+        let (mut good, mut bad) = (anchor + 1, end + 1);
+        let mut span = 2;
+        while good < end {
+            let next = anchor.saturating_add(span).min(end);
+            if !valid(anchor, next) {
+                bad = next;
+                break;
+            }
+            good = next;
+            span = span.saturating_mul(2);
+        }
+        while bad - good > 1 {
+            let middle = good + (bad - good) / 2;
+            if valid(anchor, middle) {
+                good = middle;
+            } else {
+                bad = middle;
+            }
+        }
+        keep[good] = true;
+        anchor = good;
+    }
+}
+
+/// Returns the position with the largest deviation if it exceeds `epsilon` (strictly), a
+/// deviation that cannot be evaluated counting as the largest. Ties are broken in favor of the
+/// smallest position.
+fn farthest<D: PartialOrd>(
+    positions: impl Iterator<Item = usize>,
+    deviation: impl Fn(usize) -> Option<D>,
+    epsilon: &D,
+) -> Option<usize> {
+    match argmax(positions, deviation) {
+        Some((position, None)) => Some(position),
+        Some((position, Some(deviation))) if deviation > *epsilon => Some(position),
+        _ => None,
+    }
+}
+
+/// Returns the position with the largest deviation along with its deviation, with the rules of
+/// `farthest`. None if there are no positions.
+fn argmax<D: PartialOrd>(
+    positions: impl Iterator<Item = usize>,
+    deviation: impl Fn(usize) -> Option<D>,
+) -> Option<(usize, Option<D>)> {
+    let mut found: Option<(usize, Option<D>)> = None;
+    for position in positions {
+        let current = deviation(position);
+        let further = match (&found, &current) {
+            (None, _) => true,
+            (Some((best, None)), None) => position < *best,
+            (Some((_, None)), Some(_)) => false,
+            (Some((_, Some(_))), None) => true,
+            (Some((index, Some(best))), Some(current)) => {
+                current > best || (current == best && position < *index)
+            }
+        };
+        if further {
+            found = Some((position, current));
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+    use std::panic;
+
+    #[test]
+    fn test_initialization() {
+        let xp: Vec<u64> = vec![0, 10];
+        let fp: Vec<i64> = vec![20, 25];
+        let interp = Interp::new(xp, fp);
+        assert!(interp.forwardable);
+        assert_eq!(interp.inversable, Order::Increasing);
+
+        let xp: Vec<u64> = vec![0, 10];
+        let fp: Vec<i64> = vec![-20, -25];
+        let interp = Interp::new(xp, fp);
+        assert!(interp.forwardable);
+        assert_eq!(interp.inversable, Order::Decreasing);
+
+        let xp: Vec<u64> = vec![0, 10, 20];
+        let fp: Vec<i64> = vec![-20, -25, -20];
+        let interp = Interp::new(xp, fp);
+        assert!(interp.forwardable);
+        assert_eq!(interp.inversable, Order::Unordered);
+        assert_eq!(
+            interp.inverse(-22, Method::Nearest),
+            Err(InterpError::NotStrictlyMonotonic)
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(InterpError::OutOfBounds.to_string(), "out of bounds");
+        assert_eq!(
+            InterpError::LengthMismatch(3, 2).to_string(),
+            "xp and fp must have the same length (got 3 and 2)"
+        );
+        let err = BatchError {
+            index: 4,
+            error: InterpError::NotFound,
+        };
+        assert_eq!(err.to_string(), "element 4: not found");
+        assert_eq!(
+            err.source().map(|source| source.to_string()),
+            Some("not found".to_string())
+        );
+        assert_eq!(
+            QuantizeError::Inexact(2).to_string(),
+            "fp[2] is not on the grid"
+        );
+        assert_eq!(
+            BuildError::NotStrictlyIncreasing.to_string(),
+            "xp must be strictly increasing"
+        );
+    }
+
+    #[test]
+    fn test_inverse_nan() {
+        let interp = Interp::new(vec![0, 10], vec![0.0, 1.0]);
+        assert_eq!(
+            interp.inverse(f64::NAN, Method::Nearest),
+            Err(InterpError::NotFound)
+        );
+        assert_eq!(
+            interp.inverse_fractional(f64::NAN),
+            Err(InterpError::NotFound)
+        );
+        let mut interp = Interp::new(vec![0], vec![f64::NAN]);
+        assert!(!interp.is_inversable());
+        assert_eq!(
+            interp.inverse(1.0, Method::Nearest),
+            Err(InterpError::NotStrictlyMonotonic)
+        );
+        interp = Interp::new(vec![], vec![]);
+        interp.push(0, f64::NAN);
+        assert!(!interp.is_inversable());
+    }
+
+    #[test]
+    fn test_non_finite() {
+        let interp = Interp::new(vec![0, 10, 20], vec![0.0, 10.0, f64::INFINITY]);
+        assert!(interp.is_inversable());
+        assert_eq!(interp.forward(5), Ok(5.0));
+        assert_eq!(interp.forward(15), Err(InterpError::NotFound));
+        assert_eq!(interp.forward(20), Ok(f64::INFINITY));
+        assert_eq!(interp.forward_detailed(15), Err(InterpError::NotFound));
+        assert_eq!(interp.forward_at(15.0), Err(InterpError::NotFound));
+        assert!(interp.forward_at(f64::NAN).is_err());
+        assert!(interp.forward_batch(&[5, 15], Method::None).is_err());
+        assert_eq!(
+            interp.inverse(20.0, Method::Nearest),
+            Err(InterpError::NotFound)
+        );
+        assert_eq!(
+            interp.inverse(20.0, Method::None),
+            Err(InterpError::NotFound)
+        );
+        assert_eq!(interp.inverse_fractional(20.0), Err(InterpError::NotFound));
+        assert_eq!(interp.inverse(f64::INFINITY, Method::None), Ok(20));
+
+        // Infinitely steep segments are gaps, whatever the threshold.
+        assert_eq!(interp.median_steepness(), Some(F80::clone(F80::one())));
+        for max_gap in [
+            MaxGap::Absolute(1.0),
+            MaxGap::Absolute(f64::INFINITY),
+            MaxGap::Median(2.0),
+        ] {
+            let query = |policy| interp.inverse_gapped(20.0, Method::Nearest, max_gap, policy);
+            assert_eq!(query(GapPolicy::Error), Err(InterpError::InGap));
+            assert_eq!(query(GapPolicy::Snap), Ok(10));
+        }
+        let query = |max_gap| interp.inverse_gapped(5.0, Method::None, max_gap, GapPolicy::Error);
+        assert_eq!(query(MaxGap::Absolute(f64::INFINITY)), Ok(5));
+        assert_eq!(query(MaxGap::Median(f64::INFINITY)), Ok(5));
+        assert_eq!(query(MaxGap::Absolute(f64::NAN)), Err(InterpError::InGap));
+        assert_eq!(query(MaxGap::Median(f64::NAN)), Err(InterpError::InGap));
+
+        let summary = interp.summary_with(f64::NAN);
+        assert_eq!(summary.irregular, 0);
+        let slope = F80::clone(F80::one());
+        assert_eq!(summary.slopes, Some((slope.clone(), slope.clone(), slope)));
+        assert_eq!(interp.derivative(), Err(InterpError::Overflow));
+        let other = Interp::new(vec![0, 20], vec![1.0, 0.0]);
+        assert_eq!(interp.intersections(&other), Err(InterpError::Overflow));
+        assert_eq!(interp.affine_fp(2.0, 0.0), Err(InterpError::Overflow));
+        assert_eq!(
+            interp.quantize(1.0, Method::Nearest),
+            Err(QuantizeError::Overflow(2))
+        );
+
+        let interp = Interp::new(vec![0, 10], vec![0.0, f64::NAN]);
+        assert_eq!(interp.forward(5), Err(InterpError::NotFound));
+        assert_eq!(interp.median_steepness(), None);
+        assert_eq!(interp.summary().slopes, None);
+    }
+
+    #[test]
+    fn test_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Interp<u64, u64>>();
+        assert_send_sync::<Interp<u64, i64>>();
+        assert_send_sync::<Interp<u64, f64>>();
+        assert_send_sync::<Interp<u64, F80>>();
+    }
+
+    #[test]
+    fn test_shared_across_threads() {
+        let xp: Vec<u64> = (0..1000).map(|x| 10 * x).collect();
+        let fp: Vec<i64> = (0..1000)
+            .map(|x| 1_700_000_000_000_000_000 + 7 * x)
+            .collect();
+        let interp = std::sync::Arc::new(Interp::new(xp, fp));
+        std::thread::scope(|scope| {
+            for thread in 0..8u64 {
+                let interp = std::sync::Arc::clone(&interp);
+                scope.spawn(move || {
+                    for x in (thread..9990).step_by(8) {
+                        let f = interp.forward(x).unwrap();
+                        let expected = 1_700_000_000_000_000_000 + ((7 * x as i64 + 5) / 10);
+                        assert!((f - expected).abs() <= 1);
+                        assert_eq!(interp.inverse(f, Method::ForwardFill).unwrap() / 10, x / 10);
+                    }
+                });
+            }
+        });
+    }
+
+    #[test]
+    fn test_builder() {
+        let xp: Vec<u64> = vec![0, 4, 8];
+        let fp: Vec<i64> = vec![0, 10, 30];
+        let interp = Interp::builder(xp.clone(), fp.clone()).build().unwrap();
+        assert_eq!(interp, Interp::new(xp.clone(), fp.clone()));
+        let interp = Interp::builder(xp.clone(), fp.clone())
+            .bounds(Bounds::Clamp)
+            .default_method(Method::ForwardFill)
+            .kind(Kind::Linear)
+            .build()
+            .unwrap();
+        assert_eq!(interp.forward(1), Ok(2));
+        assert_eq!(interp.forward_with(1, Method::BackwardFill), Ok(3));
+        assert_eq!(interp.forward(9), Ok(30));
+        assert_eq!(interp.inverse(5, None), Ok(2));
+        assert_eq!(interp.inverse(5, Method::BackwardFill), Ok(2));
+        assert_eq!(interp.inverse(6, None), Ok(2));
+        assert_eq!(interp.inverse(6, Method::Nearest), Ok(2));
+        assert_eq!(interp.inverse(6, Method::BackwardFill), Ok(3));
+        assert_eq!(interp.inverse(-1, None), Ok(0));
+        assert_eq!(interp.inverse(31, Method::None), Ok(8));
+        let mapped = interp.map_fp(|f| 3 * f).unwrap();
+        assert_eq!(mapped.forward(9), Ok(90));
+        assert_eq!(mapped.forward(1), Ok(7));
+        let interp = Interp::builder(xp.clone(), fp.clone())
+            .closed(Closed::Left)
+            .build()
+            .unwrap();
+        assert_eq!(interp.forward(8), Err(InterpError::OutOfBounds));
+        assert_eq!(
+            interp.inverse(30, Method::None),
+            Err(InterpError::OutOfBounds)
+        );
+    }
+
+    #[test]
+    fn test_new_sorted() {
+        let sorted = Interp::new(vec![0u64, 10, 20, 30], vec![0i64, 5, 15, 30]);
+        let reversed =
+            Interp::new_sorted(vec![30, 20, 10, 0], vec![30, 15, 5, 0], Collision::Error);
+        assert_eq!(reversed, Ok(sorted.clone()));
+        let shuffled =
+            Interp::new_sorted(vec![20, 0, 30, 10], vec![15, 0, 30, 5], Collision::Error);
+        assert_eq!(shuffled, Ok(sorted.clone()));
+        let unchanged =
+            Interp::new_sorted(vec![0, 10, 20, 30], vec![0, 5, 15, 30], Collision::Error);
+        assert_eq!(unchanged, Ok(sorted.clone()));
+        assert!(unchanged.unwrap().is_forwardable());
+
+        // Duplicates keep their input order whatever their position in the sort.
+        let xp = vec![20, 10, 0, 10, 30, 20, 10];
+        let fp = vec![15, 5, 0, 6, 30, 16, 7];
+        assert_eq!(
+            Interp::new_sorted(xp.clone(), fp.clone(), Collision::KeepFirst),
+            Ok(sorted)
+        );
+        assert_eq!(
+            Interp::new_sorted(xp, fp, Collision::Error),
+            Err(BuildError::NotStrictlyIncreasing)
+        );
+        assert_eq!(
+            Interp::<u64, i64>::new_sorted(vec![1, 0], vec![0], Collision::Error),
+            Err(BuildError::LengthMismatch(2, 1))
+        );
+
+        // The stable sort is checked against a random permutation of random data points.
+        let mut rng = Rng(0x9e3779b97f4a7c15);
+        let points: Vec<(u64, i64)> = (0..200).map(|k| (rng.below(50), k)).collect();
+        let mut expected: Vec<(u64, i64)> = Vec::new();
+        for (x, f) in points.iter().copied() {
+            if !expected.iter().any(|(kept, _)| *kept == x) {
+                expected.push((x, f));
+            }
+        }
+        expected.sort();
+        let (xp, fp): (Vec<u64>, Vec<i64>) = points.into_iter().unzip();
+        let interp = Interp::new_sorted(xp, fp, Collision::KeepFirst).unwrap();
+        let (xp, fp): (Vec<u64>, Vec<i64>) = expected.into_iter().unzip();
+        assert_eq!(interp, Interp::new(xp, fp));
+    }
+
+    #[test]
+    fn test_builder_errors() {
+        assert_eq!(
+            Interp::<u64, i64>::builder(vec![0, 1], vec![0]).build(),
+            Err(BuildError::LengthMismatch(2, 1))
+        );
+        assert_eq!(
+            Interp::<u64, i64>::builder(vec![1, 0], vec![0, 1]).build(),
+            Err(BuildError::NotStrictlyIncreasing)
+        );
+        let interp = Interp::<u64, i64>::builder(vec![0, 2], vec![0, 1])
+            .assume_sorted(true)
+            .build()
+            .unwrap();
+        assert!(interp.is_forwardable());
+        let empty = Interp::<u64, i64>::builder(vec![], vec![])
+            .bounds(Bounds::Clamp)
+            .build()
+            .unwrap();
+        assert_eq!(empty.forward(0), Err(InterpError::OutOfBounds));
+        assert_eq!(empty.inverse(0, None), Err(InterpError::OutOfBounds));
+    }
+
+    #[test]
+    fn test_sided_bounds() {
+        fn expect<T>(policy: Bounds, extreme: T) -> Result<T, InterpError> {
+            match policy {
+                Bounds::Clamp => Ok(extreme),
+                Bounds::Error => Err(InterpError::OutOfBounds),
+            }
+        }
+        let policies = [Bounds::Error, Bounds::Clamp];
+        for left in policies {
+            for right in policies {
+                let bounds = SidedBounds { left, right };
+                let interp = Interp::new(vec![10u64, 20], vec![100i64, 200]).with_bounds(bounds);
+                assert_eq!(interp.forward(5), expect(left, 100));
+                assert_eq!(interp.forward(25), expect(right, 200));
+                assert_eq!(interp.forward(15), Ok(150));
+                assert_eq!(interp.inverse(50, Method::None), expect(left, 10));
+                assert_eq!(interp.inverse(250, Method::None), expect(right, 20));
+                // The sides follow the indices whatever the order of the values.
+                let interp = Interp::builder(vec![10u64, 20], vec![2.0, 1.0])
+                    .bounds(bounds)
+                    .closed(Closed::Left)
+                    .build()
+                    .unwrap();
+                assert_eq!(interp.inverse(3.0, Method::None), expect(left, 10));
+                assert_eq!(interp.inverse(0.0, Method::None), expect(right, 20));
+                assert_eq!(interp.forward(20), expect(right, 1.0));
+                assert_eq!(interp.forward(10), Ok(2.0));
+                // Error sides still snap with the methods pointing inwards.
+                assert_eq!(interp.inverse(3.0, Method::BackwardFill), Ok(10));
+                assert_eq!(interp.inverse(0.0, Method::ForwardFill), Ok(20));
+                let step = StepFn::new(vec![10u64, 20], vec![1])
+                    .unwrap()
+                    .with_bounds(bounds);
+                assert_eq!(step.forward(5), expect(left, 1));
+                assert_eq!(step.forward(25), expect(right, 1));
+                let derivative = interp.derivative().unwrap();
+                assert_eq!(derivative.forward(5), expect(left, -0.1));
+                assert_eq!(derivative.forward(25), expect(right, -0.1));
+            }
+        }
+        assert_eq!(
+            SidedBounds::from(Bounds::Clamp),
+            SidedBounds {
+                left: Bounds::Clamp,
+                right: Bounds::Clamp
+            }
+        );
+    }
+
+    #[test]
+    fn test_at() {
+        let interp = Interp::new(vec![0, 10, 20], vec![0.0, 5.0, 25.0]);
+        assert_eq!(interp.at(4), 2.0);
+        assert_eq!(interp.at(15), 15.0);
+        assert_eq!(interp.values(8..13), vec![4.0, 4.5, 5.0, 7.0, 9.0]);
+        assert_eq!(interp.values(20..21), vec![25.0]);
+        assert_eq!(interp.values(5..5), vec![]);
+        assert!(panic::catch_unwind(|| interp.at(21)).is_err());
+        assert!(panic::catch_unwind(|| interp.values(18..22)).is_err());
+        let clamped = interp.clone().with_bounds(Bounds::Clamp);
+        assert_eq!(clamped.at(21), 25.0);
+        assert_eq!(clamped.values(19..22), vec![23.0, 25.0, 25.0]);
+        let interp = Interp::new(vec![0, 2], vec![0i64, 1]);
+        assert_eq!(interp.values(0..3), vec![0, 0, 1]);
+        let exact = Interp::builder(vec![0, 2], vec![0i64, 1])
+            .default_method(Method::None)
+            .build()
+            .unwrap();
+        assert!(panic::catch_unwind(|| exact.at(1)).is_err());
+    }
+
+    #[test]
+    fn test_inverse_from() {
+        let max = i64::MAX as u64;
+        let interp = Interp::new(vec![0, 10, 20], vec![0u64, max, u64::MAX]);
+        assert_eq!(interp.inverse_from(0i64, Method::None), Ok(0));
+        assert_eq!(interp.inverse_from(i64::MAX, Method::None), Ok(10));
+        assert_eq!(interp.inverse_from(u64::MAX, Method::None), Ok(20));
+        assert_eq!(interp.inverse_from(max / 10 * 3, Method::Nearest), Ok(3));
+        assert_eq!(
+            interp.inverse_from(-1i64, Method::None),
+            Err(InterpError::OutOfBounds)
+        );
+        assert_eq!(
+            interp.inverse_from(i64::MIN, Method::ForwardFill),
+            Err(InterpError::OutOfBounds)
+        );
+        assert_eq!(interp.inverse_from(-1i64, Method::Nearest), Ok(0));
+        let clipped = interp.clone().with_bounds(Bounds::Clamp);
+        assert_eq!(clipped.inverse_from(i64::MIN, Method::None), Ok(0));
+        // Decreasing values: negative queries are beyond the last index.
+        let interp = Interp::new(vec![0, 10], vec![max + 10, 0u64]);
+        assert_eq!(
+            interp.inverse_from(-5i64, Method::None),
+            Err(InterpError::OutOfBounds)
+        );
+        assert_eq!(interp.inverse_from(-5i64, Method::ForwardFill), Ok(10));
+        assert_eq!(interp.inverse_from(i64::MAX, Method::Nearest), Ok(0));
+        assert_eq!(interp.inverse_from(i64::MAX, Method::BackwardFill), Ok(1));
+
+        let interp = Interp::new(vec![0, 10, 20], vec![i64::MIN, 0, i64::MAX]);
+        assert_eq!(interp.inverse_from(0u64, Method::None), Ok(10));
+        assert_eq!(interp.inverse_from(max, Method::None), Ok(20));
+        assert_eq!(
+            interp.inverse_from(max + 1, Method::None),
+            Err(InterpError::OutOfBounds)
+        );
+        assert_eq!(interp.inverse_from(u64::MAX, Method::Nearest), Ok(20));
+        let interp = interp.with_bounds(SidedBounds {
+            left: Bounds::Error,
+            right: Bounds::Clamp,
+        });
+        assert_eq!(interp.inverse_from(u64::MAX, Method::None), Ok(20));
+        assert_eq!(interp.inverse_from(i64::MIN, Method::None), Ok(0));
+        // In range values are inverted as if they were of the type of the values.
+        for value in [i64::MIN + 1, -12345, 1, 98765, i64::MAX - 1] {
+            for method in [Method::Nearest, Method::ForwardFill, Method::BackwardFill] {
+                assert_eq!(
+                    interp.inverse_from(value, method),
+                    interp.inverse(value, method)
+                );
+            }
+        }
+        let empty = Interp::<u64, u64>::new(vec![], vec![]);
+        assert_eq!(
+            empty.inverse_from(-1i64, Method::None),
+            Err(InterpError::OutOfBounds)
+        );
+    }
+
+    #[test]
+    fn test_forward_batch() {
+        let mut rng = Rng(0x9e3779b97f4a7c15);
+        let policies = [Bounds::Error, Bounds::Clamp];
+        for closed in [Closed::Both, Closed::Left, Closed::Right] {
+            for (left, right) in policies.iter().flat_map(|l| policies.map(|r| (*l, r))) {
+                for xp in [vec![], vec![20], vec![10, 20, 40]] {
+                    let fp: Vec<i64> = xp.iter().map(|x| 3 * *x as i64 / 2).collect();
+                    let interp = Interp::new(xp, fp)
+                        .with_closed(closed)
+                        .with_bounds(SidedBounds { left, right });
+                    let x: Vec<u64> = (0..50).map(|_| rng.below(50)).collect();
+                    for method in [Method::None, Method::Nearest] {
+                        let expected: Vec<_> =
+                            x.iter().map(|x| interp.forward_with(*x, method)).collect();
+                        let (out, mask) = interp.forward_batch_masked(&x, method).unwrap();
+                        let values: Vec<i64> =
+                            expected.iter().map(|f| *f.as_ref().unwrap_or(&0)).collect();
+                        let errors: Vec<bool> = expected.iter().map(Result::is_err).collect();
+                        assert_eq!((out, mask), (values.clone(), errors));
+                        let first = expected.iter().position(Result::is_err);
+                        let batch = interp.forward_batch(&x, method);
+                        match first {
+                            None => assert_eq!(batch, Ok(values)),
+                            Some(index) => assert_eq!(
+                                batch,
+                                Err(BatchError {
+                                    index,
+                                    error: interp.forward_with(x[index], method).unwrap_err(),
+                                })
+                            ),
+                        }
+                    }
+                }
+            }
+        }
+        let interp = Interp::new(vec![0u64, 10], vec![0i64, 5]);
+        assert_eq!(
+            interp.forward_batch(&[10, 0, 2], Method::None),
+            Ok(vec![5, 0, 1])
+        );
+        // An inexact index is reported before a later out of bounds one.
+        assert_eq!(
+            interp.forward_batch(&[3, 11], Method::None),
+            Err(BatchError {
+                index: 0,
+                error: InterpError::NotFound
+            })
+        );
+        assert_eq!(
+            interp.forward_batch(&[2, 11, 3], Method::None),
+            Err(BatchError {
+                index: 1,
+                error: InterpError::OutOfBounds
+            })
+        );
+        let interp = Interp::new(vec![10u64, 0], vec![0.0, 1.0]);
+        assert_eq!(interp.forward_batch(&[], Method::None), Ok(vec![]));
+        assert_eq!(
+            interp.forward_batch_masked(&[5], Method::None),
+            Err(InterpError::NotStrictlyIncreasing)
+        );
+        assert_eq!(
+            interp.forward_batch(&[5], Method::None),
+            Err(BatchError {
+                index: 0,
+                error: InterpError::NotStrictlyIncreasing
+            })
+        );
+    }
+
+    #[test]
+    #[ignore = "benchmark, run with --release --ignored"]
+    fn bench_forward_batch() {
+        let xp: Vec<u64> = (0..1000).map(|k| k * 10_000).collect();
+        let fp: Vec<f64> = xp.iter().map(|x| (*x as f64).sqrt()).collect();
+        let interp = Interp::new(xp, fp);
+        let x: Vec<u64> = (0..10_000_000u64).map(|k| k * 7919 % 9_990_000).collect();
+        let start = std::time::Instant::now();
+        let reference: Result<Vec<f64>, InterpError> =
+            x.iter().map(|x| interp.forward(*x)).collect();
+        let elapsed = start.elapsed();
+        let start = std::time::Instant::now();
+        let out = interp.forward_batch(&x, Method::Nearest);
+        let batch_elapsed = start.elapsed();
+        assert_eq!(out.unwrap(), reference.unwrap());
+        assert!(batch_elapsed < elapsed, "{batch_elapsed:?} vs {elapsed:?}");
+    }
+
+    #[test]
+    fn test_sequence() {
+        let interp =
+            Interp::new(vec![0, 10, 20, 30], vec![1.0, 2.0, 4.0, 8.0]).with_closed(Closed::Left);
+        assert_eq!(interp.len(), 4);
+        assert!(!interp.is_empty());
+        assert_eq!(interp.get(2), Some((&20, &4.0)));
+        assert_eq!(interp.get(4), None);
+        let pairs: Vec<(u64, f64)> = interp.iter().map(|(x, f)| (*x, *f)).collect();
+        assert_eq!(pairs, vec![(0, 1.0), (10, 2.0), (20, 4.0), (30, 8.0)]);
+        let sliced = interp.slice(1..3).unwrap();
+        assert_eq!(
+            sliced,
+            Interp::new(vec![10, 20], vec![2.0, 4.0]).with_closed(Closed::Left)
+        );
+        assert_eq!(sliced.forward(15), Ok(3.0));
+        assert!(interp.slice(2..2).unwrap().is_empty());
+        assert_eq!(interp.slice(3..5), None);
+        let empty: Interp<u64, i64> = Interp::new(vec![], vec![]);
+        assert!(empty.is_empty());
+        assert_eq!(empty.iter().count(), 0);
+    }
+
+    #[test]
+    fn test_push() {
+        let mut interp: Interp<u64, i64> = Interp::new(vec![], vec![]);
+        interp.push(0, 10);
+        interp.push(10, 0);
+        assert_eq!(interp, Interp::new(vec![0, 10], vec![10, 0]));
+        assert_eq!(interp.inverse(5, Method::None), Ok(5));
+        interp.push(20, 5);
+        assert!(interp.is_forwardable());
+        assert!(!interp.is_inversable());
+        assert_eq!(interp.forward(15), Ok(2));
+        assert_eq!(
+            interp.inverse(5, Method::None),
+            Err(InterpError::NotStrictlyMonotonic)
+        );
+        interp.push(20, 6);
+        assert!(!interp.is_forwardable());
+        assert_eq!(interp.forward(15), Err(InterpError::NotStrictlyIncreasing));
+        assert_eq!(interp.xp(), &[0, 10, 20, 20]);
+        assert_eq!(interp.fp(), &[10, 0, 5, 6]);
+        let mut interp = Interp::new(vec![0], vec![0.0]);
+        interp.push(1, 1.0);
+        assert!(interp.is_inversable());
+        assert_eq!(interp, Interp::new(vec![0, 1], vec![0.0, 1.0]));
+    }
+
+    #[test]
+    fn test_sample() {
+        let interp = Interp::new(vec![10, 20, 24], vec![0i64, 100, 400]);
+        assert_eq!(interp.sample(3), Ok((vec![10, 17, 24], vec![0, 70, 400])));
+        let (x, f) = interp.sample(15).unwrap();
+        assert_eq!(x, (10..=24).collect::<Vec<u64>>());
+        assert_eq!((f[0], f[10], f[14]), (0, 100, 400));
+        assert_eq!(interp.sample(1), Ok((vec![10], vec![0])));
+        assert_eq!(interp.sample(0), Err(InterpError::NoSamples));
+        let interp = Interp::new(vec![0, u64::MAX], vec![0.0, 1.0]);
+        let (x, _) = interp.sample(4).unwrap();
+        assert_eq!(x, vec![0, u64::MAX / 3, u64::MAX / 3 * 2, u64::MAX]);
+        let interp: Interp<u64, i64> = Interp::new(vec![], vec![]);
+        assert_eq!(interp.sample(2), Err(InterpError::OutOfBounds));
+    }
+
+    #[test]
+    fn test_forward_range() {
+        let interp = Interp::new(vec![10, 20, 24, 1000], vec![0i64, 100, 400, -3]);
+        for (start, stop, step) in [(10, 1001, 1), (10, 1001, 7), (13, 900, 100), (24, 25, 3)] {
+            let naive: Result<Vec<i64>, InterpError> = (start..stop)
+                .step_by(step as usize)
+                .map(|x| interp.forward(x))
+                .collect();
+            assert_eq!(interp.forward_range(start, stop, step), naive);
+        }
+        assert_eq!(interp.forward_range(20, 10, 1), Ok(vec![]));
+        assert_eq!(
+            interp.forward_range(0, 20, 5),
+            Err(InterpError::OutOfBounds)
+        );
+        assert_eq!(
+            interp.forward_range(990, 1010, 5),
+            Err(InterpError::OutOfBounds)
+        );
+        let clamped = Interp::builder(vec![10, 20], vec![0.0, 1.0])
+            .bounds(Bounds::Clamp)
+            .closed(Closed::Left)
+            .build()
+            .unwrap();
+        assert_eq!(
+            clamped.forward_range(0, 30, 5),
+            Ok(vec![0.0, 0.0, 0.0, 0.5, 1.0, 1.0])
+        );
+        let interp = Interp::new(vec![0, u64::MAX], vec![0i64, 1 << 62]);
+        let values = interp.forward_range(u64::MAX - 10, u64::MAX, 4).unwrap();
+        assert_eq!(values.len(), 3);
+        assert_eq!(values[2], interp.forward(u64::MAX - 2).unwrap());
+        assert_eq!(
+            interp.forward_range(0, 10, 0),
+            Err(InterpError::InvalidArgument("step must be positive"))
+        );
+        let unsorted = Interp::new(vec![10, 0], vec![0i64, 1]);
+        assert_eq!(
+            unsorted.forward_range(0, 10, 1),
+            Err(InterpError::NotStrictlyIncreasing)
+        );
+    }
+
+    #[test]
+    fn test_inverse_range() {
+        let mut rng = Rng(0x9e3779b97f4a7c15);
+        let methods = [
+            Method::None,
+            Method::Nearest,
+            Method::ForwardFill,
+            Method::BackwardFill,
+        ];
+        for _ in 0..200 {
+            let len = 2 + rng.below(6) as usize;
+            let sign = if rng.below(2) == 0 { 1 } else { -1 };
+            let (mut x, mut f) = (rng.below(100), rng.below(1000) as i64 - 500);
+            let (mut xp, mut fp) = (vec![], vec![]);
+            for _ in 0..len {
+                xp.push(x);
+                fp.push(f);
+                x += 1 + rng.below(50);
+                f += sign * (1 + rng.below(200) as i64);
+            }
+            let closed = [Closed::Both, Closed::Left, Closed::Right][rng.below(3) as usize];
+            let bounds = [Bounds::Error, Bounds::Clamp][rng.below(2) as usize];
+            let interp = Interp::builder(xp, fp)
+                .closed(closed)
+                .bounds(bounds)
+                .build()
+                .unwrap();
+            let start = rng.below(3000) as i64 - 1500;
+            let step = rng.below(80) as i64 - 40;
+            let n = rng.below(60) as usize;
+            let shifted = interp.map_fp(|f| (f + 5000) as u64).unwrap();
+            let float = interp.map_fp(|f| *f as f64 / 4.0).unwrap();
+            for method in methods {
+                let naive: Result<Vec<u64>, InterpError> = (0..n as i64)
+                    .map(|k| interp.inverse(start + k * step, method))
+                    .collect();
+                assert_eq!(interp.inverse_range(start, step, n, method), naive);
+                let naive: Result<Vec<u64>, InterpError> = (0..n as i64)
+                    .map(|k| shifted.inverse((start + 5000 + k * step) as u64, method))
+                    .collect();
+                assert_eq!(
+                    shifted.inverse_range((start + 5000) as u64, step, n, method),
+                    naive
+                );
+                let naive: Result<Vec<u64>, InterpError> = (0..n as i64)
+                    .map(|k| float.inverse((start + k * step) as f64 / 4.0, method))
+                    .collect();
+                assert_eq!(
+                    float.inverse_range(start as f64 / 4.0, step as f64 / 4.0, n, method),
+                    naive
+                );
+            }
+        }
+        let interp = Interp::new(vec![0, 10], vec![0.0, 1.0]);
+        assert_eq!(
+            interp.inverse_range(0.0, 0.25, 5, Method::Nearest),
+            Ok(vec![0, 2, 5, 8, 10])
+        );
+        assert_eq!(
+            interp.inverse_range(f64::NAN, 0.25, 1, Method::Nearest),
+            Err(InterpError::Overflow)
+        );
+        let interp = Interp::new(vec![0, u64::MAX], vec![0u64, 3]);
+        assert_eq!(
+            interp.inverse_range(1, 1, 2, Method::Nearest),
+            Ok(vec![u64::MAX / 3, u64::MAX / 3 * 2])
+        );
+        let interp = Interp::new(vec![0, 10], vec![0i64, 100]);
+        assert_eq!(
+            interp.inverse_range(0, 25, 5, Method::Nearest),
+            Ok(vec![0, 2, 5, 8, 10])
+        );
+        assert_eq!(interp.inverse_range(0, 5, 0, Method::None), Ok(vec![]));
+        assert_eq!(
+            interp.inverse_range(i64::MAX - 1, 1, 3, Method::Nearest),
+            Err(InterpError::Overflow)
+        );
+        let unordered = Interp::new(vec![0, 10, 20], vec![0i64, 100, 0]);
+        assert_eq!(
+            unordered.inverse_range(0, 5, 2, Method::None),
+            Err(InterpError::NotStrictlyMonotonic)
+        );
+    }
+
+    #[test]
+    fn test_chunk() {
+        /// Checks that the chunks reproduce the function at every index of their windows.
+        fn check(interp: &Interp<u64, i64>, chunk_size: u64) {
+            let chunks = interp.chunk(chunk_size).unwrap();
+            let first = interp.xp()[0];
+            for x in first..=*interp.xp().last().unwrap() {
+                let (offset, chunk) = &chunks[(x / chunk_size - first / chunk_size) as usize];
+                assert_eq!(chunk.forward(x - offset), interp.forward(x), "{x}");
+            }
+        }
+        // Segments of slopes 3, -2 and 5 per index.
+        let interp = Interp::new(vec![5, 17, 40, 61], vec![100i64, 136, 90, 195]);
+        let chunks = interp.chunk(10).unwrap();
+        assert_eq!(chunks.len(), 7);
+        assert_eq!(chunks[0].0, 5);
+        assert_eq!(chunks[0].1.xp(), &[0, 12]);
+        assert_eq!(chunks[1].0, 5);
+        assert_eq!(chunks[1].1.xp(), &[0, 12, 35]);
+        assert_eq!(chunks[6].0, 40);
+        assert_eq!(chunks[6].1.xp(), &[0, 21]);
+        check(&interp, 10);
+        let chunks = interp.chunk(100).unwrap();
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].1.xp(), &[0, 12, 35, 56]);
+        assert_eq!(chunks[0].1.fp(), interp.fp());
+        let chunks = interp.chunk(1).unwrap();
+        assert_eq!(chunks.len(), 57);
+        assert!(chunks.iter().all(|(_, chunk)| chunk.len() <= 2));
+        check(&interp, 1);
+
+        // Non-integer slopes: the values at the window ends are inexact.
+        let interp = Interp::new(vec![0, 4], vec![0i64, 1]);
+        let chunks = interp.chunk(4).unwrap();
+        assert_eq!(chunks[0].1.forward(2), interp.forward(2));
+        check(&interp, 4);
+        check(&interp, 3);
+        let interp = Interp::new(vec![3, 10, 17, 29], vec![0i64, 2, 3, 10]);
+        for chunk_size in 1..=30 {
+            check(&interp, chunk_size);
+        }
+        let interp = Interp::new(vec![0, 7, 20], vec![0.0, 1.75, 5.0]);
+        let chunks = interp.chunk(8).unwrap();
+        for x in 0..=20 {
+            let (offset, chunk) = &chunks[(x / 8) as usize];
+            assert_eq!(chunk.forward(x - offset), interp.forward(x), "{x}");
+        }
+
+        assert_eq!(
+            interp.chunk(0),
+            Err(InterpError::InvalidArgument("chunk size must be positive"))
+        );
+        let unsorted = Interp::new(vec![10, 0], vec![0.0, 1.0]);
+        assert_eq!(unsorted.chunk(2), Err(InterpError::NotStrictlyIncreasing));
+        let empty: Interp<u64, i64> = Interp::new(vec![], vec![]);
+        assert_eq!(empty.chunk(2), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_approx_from_fn() {
+        let func = |x: u64| 1_700_000_000_000_000_000 + (x as i64 - 300).pow(2);
+        let interp = Interp::approx_from_fn((0, 1000), func, 100).unwrap();
+        assert_eq!((interp.xp()[0], *interp.xp().last().unwrap()), (0, 1000));
+        assert!(interp.xp().len() < 100);
+        for x in 0..=1000 {
+            assert!((interp.forward(x).unwrap() - func(x)).abs() <= 100, "{x}");
+        }
+
+        let func = |x: u64| (x as f64 / 100.0).sin();
+        let interp = Interp::approx_from_fn((0, 2000), func, 1e-4).unwrap();
+        for x in 0..=2000 {
+            assert!((interp.forward(x).unwrap() - func(x)).abs() <= 1e-4, "{x}");
+        }
+        let interp = Interp::approx_from_fn((10, 20), |x| x as f64, 0.0).unwrap();
+        assert_eq!(interp.xp(), &[10, 20]);
+
+        let interp = Interp::approx_from_fn_with((0, 1000), func, 0.0, 5).unwrap();
+        assert_eq!(interp.xp().len(), 5);
+        assert_eq!(interp.xp().last(), Some(&1000));
+        let interp = Interp::approx_from_fn((7, 7), func, 0.0).unwrap();
+        assert_eq!(interp.xp(), &[7]);
+        assert_eq!(
+            Interp::approx_from_fn((8, 7), func, 0.0).err(),
+            Some(InterpError::InvalidArgument("domain must not be reversed"))
+        );
+    }
+
+    #[test]
+    fn test_parts() {
+        let xp: Vec<u64> = vec![0, 10, 20];
+        let fp: Vec<f64> = vec![0.0, 5.0, 3.0];
+        let interp = Interp::from_parts(xp.clone(), fp.clone()).unwrap();
+        assert_eq!(interp, Interp::new(xp.clone(), fp.clone()));
+        assert_eq!(interp.clone().into_parts(), (xp.clone(), fp.clone()));
+        let (xp, fp) = interp.clone().into_parts();
+        assert_eq!(Interp::from_parts(xp, fp), Ok(interp));
+        assert_eq!(
+            Interp::<u64, f64>::from_parts(vec![0, 1], vec![0.0]),
+            Err(InterpError::LengthMismatch(2, 1))
+        );
+    }
+
+    #[test]
+    fn test_introspection() {
+        let increasing = Interp::new(vec![2u64, 5, 9], vec![1.0, 4.0, 8.0]);
+        assert!(increasing.is_forwardable() && increasing.is_inversable());
+        assert_eq!((increasing.len(), increasing.is_empty()), (3, false));
+        assert_eq!(increasing.domain(), Some((2, 9)));
+        assert_eq!(increasing.range(), Some((1.0, 8.0)));
+
+        let decreasing = Interp::new(vec![0u64, 10], vec![7i64, -3]);
+        assert!(decreasing.is_forwardable() && decreasing.is_inversable());
+        assert_eq!(decreasing.range(), Some((7, -3)));
+
+        let unordered = Interp::new(vec![0u64, 10, 5], vec![0.0, 1.0, 2.0]);
+        assert!(!unordered.is_forwardable() && unordered.is_inversable());
+        assert_eq!(unordered.domain(), Some((0, 5)));
+        assert_eq!(
+            unordered.forward(5),
+            Err(InterpError::NotStrictlyIncreasing)
+        );
+        let flat = Interp::new(vec![0u64, 10], vec![3i64, 3]);
+        assert!(flat.is_forwardable() && !flat.is_inversable());
+
+        let empty: Interp<u64, f64> = Interp::new(vec![], vec![]);
+        assert!(empty.is_forwardable() && empty.is_inversable());
+        assert_eq!((empty.len(), empty.is_empty()), (0, true));
+        assert_eq!(empty.domain(), None);
+        assert_eq!(empty.range(), None);
+    }
+
+    #[test]
+    fn test_try_new() {
+        let interp = Interp::try_new(vec![0u64, 10], vec![0.0, 5.0]).unwrap();
+        assert_eq!(interp, Interp::new(vec![0, 10], vec![0.0, 5.0]));
+        assert_eq!(
+            Interp::<u64, f64>::try_new(vec![0, 1, 2], vec![0.0]),
+            Err(InterpError::LengthMismatch(3, 1))
+        );
+        assert_eq!(
+            Interp::<u64, f64>::try_new(vec![], vec![0.0]),
+            Err(InterpError::LengthMismatch(0, 1))
+        );
+        assert_eq!(
+            Interp::<u64, f64>::try_new(vec![], vec![]),
+            Err(InterpError::Empty)
+        );
+        assert_eq!(
+            InterpError::Empty.to_string(),
+            "xp and fp must not be empty"
+        );
+        // A single data point is valid, only its own index being in bounds.
+        let single = Interp::try_new(vec![5u64], vec![2.5]).unwrap();
+        assert_eq!(single.forward(5), Ok(2.5));
+        assert_eq!(single.forward(6), Err(InterpError::OutOfBounds));
+        assert_eq!(single.inverse(2.5, None), Ok(5));
+        // Unlike `try_new`, `new` accepts empty inputs and panics on mismatched lengths.
+        assert!(Interp::<u64, f64>::new(vec![], vec![]).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "xp and fp must have same length")]
+    fn test_new_length_mismatch() {
+        Interp::<u64, f64>::new(vec![0], vec![]);
+    }
+
+    #[test]
+    fn test_equality() {
+        let interp: Interp<u64, i64> = Interp::new(vec![0, 10], vec![0, 100]);
+        assert_eq!(interp.clone(), interp);
+        assert_ne!(interp, Interp::new(vec![0, 10], vec![0, 101]));
+        assert_ne!(interp, Interp::new(vec![0, 11], vec![0, 100]));
+        assert_ne!(interp.clone().with_closed(Closed::Left), interp);
+        assert_eq!(
+            interp.clone().with_closed(Closed::Left),
+            Interp::new(vec![0, 10], vec![0, 100]).with_closed(Closed::Left)
+        );
+        let interp = Interp::new(vec![0, 10], vec![0.0, f64::NAN]);
+        assert_ne!(interp.clone(), interp);
+    }
+
+    #[test]
+    fn test_validate() {
+        let xp: Vec<u64> = vec![0, 10, 20];
+        let fp: Vec<i64> = vec![0, 1, 0];
+        let interp = Interp::new(xp, fp);
+        assert_eq!(interp.validate(), Ok(()));
+
+        let xp: Vec<u64> = vec![0, 10, 10, 5];
+        let fp: Vec<i64> = vec![0, 1, 2, 3];
+        let interp = Interp::new(xp, fp);
+        let report = interp.validate().unwrap_err();
+        assert_eq!(report.violations, vec![2, 3]);
+    }
+
+    #[test]
+    fn test_forward_unsigned() {
+        let xp: Vec<u64> = vec![0, 10];
+        let fp: Vec<u64> = vec![20, 25];
+        let interp = Interp::new(xp, fp);
+        assert_eq!(interp.forward(0), Ok(20));
+        assert_eq!(interp.forward(1), Ok(20));
+        assert_eq!(interp.forward(2), Ok(21));
+        assert_eq!(interp.forward(3), Ok(22));
+        assert_eq!(interp.forward(11), Err(InterpError::OutOfBounds));
+    }
+
+    #[test]
+    fn test_forward_signed() {
+        let xp: Vec<u64> = vec![0, 10];
+        let fp: Vec<i64> = vec![-20, -25];
+        let interp = Interp::new(xp, fp);
+        assert_eq!(interp.forward(0), Ok(-20));
         assert_eq!(interp.forward(1), Ok(-20));
         assert_eq!(interp.forward(2), Ok(-21));
         assert_eq!(interp.forward(3), Ok(-22));
@@ -190,253 +4319,1998 @@ mod tests {
     }
 
     #[test]
-    fn test_forward_float() {
+    fn test_forward_float() {
+        let xp: Vec<u64> = vec![0, 10];
+        let fp: Vec<f64> = vec![20.0, 25.0];
+        let interp = Interp::new(xp, fp);
+        assert_eq!(interp.forward(0), Ok(20.0));
+        assert_eq!(interp.forward(1), Ok(20.5));
+        assert_eq!(interp.forward(2), Ok(21.0));
+        assert_eq!(interp.forward(3), Ok(21.5));
+        assert_eq!(interp.forward(11), Err(InterpError::OutOfBounds));
+    }
+
+    #[test]
+    fn test_forward_exact_unsigned() {
+        let xp: Vec<u64> = vec![0, 4];
+        let fp: Vec<u64> = vec![20, 30];
+        let interp = Interp::new(xp, fp);
+        assert_eq!(interp.forward_with(0, Method::None), Ok(20));
+        assert_eq!(
+            interp.forward_with(1, Method::None),
+            Err(InterpError::NotFound)
+        );
+        assert_eq!(interp.forward_with(2, Method::None), Ok(25));
+        assert_eq!(
+            interp.forward_with(3, Method::None),
+            Err(InterpError::NotFound)
+        );
+        assert_eq!(interp.forward_with(4, Method::None), Ok(30));
+        assert_eq!(
+            interp.forward_with(5, Method::None),
+            Err(InterpError::OutOfBounds)
+        );
+    }
+
+    #[test]
+    fn test_forward_exact() {
+        let xp: Vec<u64> = vec![0, 3, 7];
+        let fp: Vec<i64> = vec![i64::MIN, 0, 1];
+        let interp = Interp::new(xp, fp);
+        assert_eq!(interp.forward_exact(0), Ok(i64::MIN));
+        assert_eq!(interp.forward_exact(3), Ok(0));
+        assert_eq!(interp.forward_exact(7), Ok(1));
+        assert_eq!(interp.forward_exact(4), Err(InterpError::NotFound));
+        assert_eq!(interp.forward_exact(8), Err(InterpError::OutOfBounds));
+        let xp: Vec<u64> = vec![0, 3];
+        let fp: Vec<f64> = vec![0.0, 1.0];
+        let interp = Interp::new(xp, fp);
+        assert_eq!(interp.forward_exact(1), interp.forward(1));
+    }
+
+    #[test]
+    fn test_forward_round_unsigned() {
+        let xp: Vec<u64> = vec![0, 4];
+        let fp: Vec<u64> = vec![20, 30];
+        let interp = Interp::new(xp, fp);
+        assert_eq!(interp.forward_with(0, Method::Nearest), Ok(20));
+        assert_eq!(interp.forward_with(1, Method::Nearest), Ok(22));
+        assert_eq!(interp.forward_with(2, Method::Nearest), Ok(25));
+        assert_eq!(interp.forward_with(3, Method::Nearest), Ok(28));
+        assert_eq!(interp.forward_with(4, Method::Nearest), Ok(30));
+        assert_eq!(
+            interp.forward_with(5, Method::Nearest),
+            Err(InterpError::OutOfBounds)
+        );
+    }
+
+    #[test]
+    fn test_forward_ffill_unsigned() {
+        let xp: Vec<u64> = vec![0, 4];
+        let fp: Vec<u64> = vec![20, 30];
+        let interp = Interp::new(xp, fp);
+        assert_eq!(interp.forward_with(0, Method::ForwardFill), Ok(20));
+        assert_eq!(interp.forward_with(1, Method::ForwardFill), Ok(22));
+        assert_eq!(interp.forward_with(2, Method::ForwardFill), Ok(25));
+        assert_eq!(interp.forward_with(3, Method::ForwardFill), Ok(27));
+        assert_eq!(interp.forward_with(4, Method::ForwardFill), Ok(30));
+        assert_eq!(
+            interp.forward_with(5, Method::ForwardFill),
+            Err(InterpError::OutOfBounds)
+        );
+    }
+
+    #[test]
+    fn test_forward_bfill_unsigned() {
+        let xp: Vec<u64> = vec![0, 4];
+        let fp: Vec<u64> = vec![20, 30];
+        let interp = Interp::new(xp, fp);
+        assert_eq!(interp.forward_with(0, Method::BackwardFill), Ok(20));
+        assert_eq!(interp.forward_with(1, Method::BackwardFill), Ok(23));
+        assert_eq!(interp.forward_with(2, Method::BackwardFill), Ok(25));
+        assert_eq!(interp.forward_with(3, Method::BackwardFill), Ok(28));
+        assert_eq!(interp.forward_with(4, Method::BackwardFill), Ok(30));
+        assert_eq!(
+            interp.forward_with(5, Method::BackwardFill),
+            Err(InterpError::OutOfBounds)
+        );
+    }
+
+    #[test]
+    fn test_forward_exact_signed() {
+        let xp: Vec<u64> = vec![0, 4];
+        let fp: Vec<i64> = vec![-30, -20];
+        let interp = Interp::new(xp, fp);
+        assert_eq!(interp.forward_with(0, Method::None), Ok(-30));
+        assert_eq!(
+            interp.forward_with(1, Method::None),
+            Err(InterpError::NotFound)
+        );
+        assert_eq!(interp.forward_with(2, Method::None), Ok(-25));
+        assert_eq!(
+            interp.forward_with(3, Method::None),
+            Err(InterpError::NotFound)
+        );
+        assert_eq!(interp.forward_with(4, Method::None), Ok(-20));
+        assert_eq!(
+            interp.forward_with(5, Method::None),
+            Err(InterpError::OutOfBounds)
+        );
+    }
+
+    #[test]
+    fn test_forward_round_signed() {
+        let xp: Vec<u64> = vec![0, 4];
+        let fp: Vec<i64> = vec![-30, -20];
+        let interp = Interp::new(xp, fp);
+        assert_eq!(interp.forward_with(0, Method::Nearest), Ok(-30));
+        assert_eq!(interp.forward_with(1, Method::Nearest), Ok(-28));
+        assert_eq!(interp.forward_with(2, Method::Nearest), Ok(-25));
+        assert_eq!(interp.forward_with(3, Method::Nearest), Ok(-22));
+        assert_eq!(interp.forward_with(4, Method::Nearest), Ok(-20));
+        assert_eq!(
+            interp.forward_with(5, Method::Nearest),
+            Err(InterpError::OutOfBounds)
+        );
+    }
+
+    #[test]
+    fn test_forward_ffill_signed() {
+        let xp: Vec<u64> = vec![0, 4];
+        let fp: Vec<i64> = vec![-30, -20];
+        let interp = Interp::new(xp, fp);
+        assert_eq!(interp.forward_with(0, Method::ForwardFill), Ok(-30));
+        assert_eq!(interp.forward_with(1, Method::ForwardFill), Ok(-28));
+        assert_eq!(interp.forward_with(2, Method::ForwardFill), Ok(-25));
+        assert_eq!(interp.forward_with(3, Method::ForwardFill), Ok(-23));
+        assert_eq!(interp.forward_with(4, Method::ForwardFill), Ok(-20));
+        assert_eq!(
+            interp.forward_with(5, Method::ForwardFill),
+            Err(InterpError::OutOfBounds)
+        );
+    }
+
+    #[test]
+    fn test_forward_bfill_signed() {
+        let xp: Vec<u64> = vec![0, 4];
+        let fp: Vec<i64> = vec![-30, -20];
+        let interp = Interp::new(xp, fp);
+        assert_eq!(interp.forward_with(0, Method::BackwardFill), Ok(-30));
+        assert_eq!(interp.forward_with(1, Method::BackwardFill), Ok(-27));
+        assert_eq!(interp.forward_with(2, Method::BackwardFill), Ok(-25));
+        assert_eq!(interp.forward_with(3, Method::BackwardFill), Ok(-22));
+        assert_eq!(interp.forward_with(4, Method::BackwardFill), Ok(-20));
+        assert_eq!(
+            interp.forward_with(5, Method::BackwardFill),
+            Err(InterpError::OutOfBounds)
+        );
+    }
+
+    #[test]
+    fn test_forward_at() {
+        let xp: Vec<u64> = vec![2, 4, 8];
+        let fp: Vec<f64> = vec![20.0, 30.0, 50.0];
+        let interp = Interp::new(xp, fp);
+        assert_eq!(interp.forward_at(1.5), Err(InterpError::OutOfBounds));
+        assert_eq!(interp.forward_at(2.0), Ok(20.0));
+        assert_eq!(interp.forward_at(3.25), Ok(26.25));
+        assert_eq!(interp.forward_at(4.0), Ok(30.0));
+        assert_eq!(interp.forward_at(5.0), Ok(35.0));
+        assert_eq!(interp.forward_at(8.0 + 0.0), Ok(50.0));
+        assert_eq!(interp.forward_at(8.5), Err(InterpError::OutOfBounds));
+        assert_eq!(interp.forward_at(-1.0), Err(InterpError::OutOfBounds));
+        assert_eq!(interp.forward_at(f64::NAN), Err(InterpError::OutOfBounds));
+        assert_eq!(
+            interp.forward_at(f64::INFINITY),
+            Err(InterpError::OutOfBounds)
+        );
+
+        let xp: Vec<u64> = vec![0, 4];
+        let fp: Vec<i64> = vec![-30, -20];
+        let interp = Interp::new(xp, fp);
+        assert_eq!(interp.forward_at(0.0), Ok(-30));
+        assert_eq!(interp.forward_at(0.2), Ok(-30));
+        assert_eq!(interp.forward_at(0.6), Ok(-28));
+        assert_eq!(interp.forward_at_with(0.6, Method::ForwardFill), Ok(-29));
+        assert_eq!(interp.forward_at_with(0.6, Method::BackwardFill), Ok(-28));
+        assert_eq!(
+            interp.forward_at_with(0.6, Method::None),
+            Err(InterpError::NotFound)
+        );
+        assert_eq!(interp.forward_at_with(0.8, Method::None), Ok(-28));
+        assert_eq!(interp.forward_at(4.0), Ok(-20));
+    }
+
+    #[test]
+    fn test_forward_periodic() {
+        let xp: Vec<u64> = vec![0, 90, 180, 270];
+        let fp: Vec<f64> = vec![0.0, 10.0, 20.0, 30.0];
+        let interp = Interp::new(xp, fp);
+        assert_eq!(interp.forward_periodic(45, 360), Ok(5.0));
+        assert_eq!(interp.forward_periodic(270, 360), Ok(30.0));
+        assert_eq!(interp.forward_periodic(315, 360), Ok(15.0));
+        assert_eq!(interp.forward_periodic(360, 360), Ok(0.0));
+        assert_eq!(interp.forward_periodic(405, 360), Ok(5.0));
+        assert_eq!(interp.forward_periodic(-45, 360), Ok(15.0));
+        assert_eq!(interp.forward_periodic(-360, 360), Ok(0.0));
+        assert_eq!(
+            interp.forward_periodic(-1, 360),
+            interp.forward_periodic(359, 360)
+        );
+        assert_eq!(
+            interp.forward_periodic(i64::MIN, 360),
+            interp.forward_periodic(i64::MIN.rem_euclid(360), 360)
+        );
+        assert_eq!(interp.forward_periodic_at(-45.0, 360), Ok(15.0));
+        assert_eq!(
+            interp.forward_periodic_at(-0.5, 360),
+            interp.forward_periodic_at(359.5, 360)
+        );
+        assert_eq!(interp.forward_periodic_at(360.0, 360), Ok(0.0));
+        assert_eq!(interp.forward_periodic_at(-1e-300, 360), Ok(0.0));
+        // 10^20 is exact in f64 and congruent to 280 modulo 360.
+        assert_eq!(
+            interp.forward_periodic_at(1e20, 360),
+            interp.forward_periodic_at(280.0, 360)
+        );
+        assert_eq!(
+            interp.forward_periodic_at(f64::NAN, 360),
+            Err(InterpError::NotFound)
+        );
+        assert_eq!(
+            interp.forward_periodic(0, 270),
+            Err(InterpError::InvalidPeriod)
+        );
+        assert_eq!(interp.forward_periodic(0, 271), Ok(0.0));
+        assert_eq!(interp.forward_periodic(-1, 271), Ok(30.0));
+
+        // The wrap is shifted along with the first breakpoint.
+        let xp: Vec<u64> = vec![10, 12];
+        let fp: Vec<i64> = vec![0, 10];
+        let interp = Interp::new(xp, fp);
+        assert_eq!(interp.forward_periodic(13, 4), Ok(5));
+        assert_eq!(interp.forward_periodic(9, 4), Ok(5));
+        assert_eq!(interp.forward_periodic(0, 4), Ok(10));
+        assert_eq!(interp.forward_periodic(-3, 4), Ok(5));
+        assert_eq!(interp.forward_periodic(-2, 4), Ok(0));
+        assert_eq!(interp.forward_periodic(2, 4), Ok(0));
+        let interp = Interp::new(vec![u64::MAX - 1, u64::MAX], vec![0i64, 1]);
+        assert_eq!(
+            interp.forward_periodic(0, 2),
+            Err(InterpError::InvalidPeriod)
+        );
+        let empty = Interp::<u64, i64>::new(vec![], vec![]);
+        assert_eq!(empty.forward_periodic(0, 10), Err(InterpError::NoSamples));
+    }
+
+    #[test]
+    fn test_inverse_exact_unsigned() {
+        let xp: Vec<u64> = vec![0, 5];
+        let fp: Vec<u64> = vec![20, 30];
+        let interp = Interp::new(xp, fp);
+        assert_eq!(
+            interp.inverse(19, Method::None),
+            Err(InterpError::OutOfBounds)
+        );
+        assert_eq!(interp.inverse(20, Method::None), Ok(0));
+        assert_eq!(interp.inverse(21, Method::None), Err(InterpError::NotFound));
+        assert_eq!(interp.inverse(22, Method::None), Ok(1));
+        assert_eq!(interp.inverse(23, Method::None), Err(InterpError::NotFound));
+        assert_eq!(interp.inverse(24, Method::None), Ok(2));
+        assert_eq!(interp.inverse(25, Method::None), Err(InterpError::NotFound));
+        assert_eq!(interp.inverse(26, Method::None), Ok(3));
+        assert_eq!(interp.inverse(30, Method::None), Ok(5));
+        assert_eq!(
+            interp.inverse(31, Method::None),
+            Err(InterpError::OutOfBounds)
+        );
+    }
+
+    #[test]
+    fn test_inverse_round_unsigned() {
+        let xp: Vec<u64> = vec![0, 5];
+        let fp: Vec<u64> = vec![20, 30];
+        let interp = Interp::new(xp, fp);
+        assert_eq!(interp.inverse(19, Method::Nearest), Ok(0));
+        assert_eq!(interp.inverse(20, Method::Nearest), Ok(0));
+        assert_eq!(interp.inverse(21, Method::Nearest), Ok(0));
+        assert_eq!(interp.inverse(22, Method::Nearest), Ok(1));
+        assert_eq!(interp.inverse(23, Method::Nearest), Ok(2));
+        assert_eq!(interp.inverse(24, Method::Nearest), Ok(2));
+        assert_eq!(interp.inverse(25, Method::Nearest), Ok(2));
+        assert_eq!(interp.inverse(26, Method::Nearest), Ok(3));
+        assert_eq!(interp.inverse(30, Method::Nearest), Ok(5));
+        assert_eq!(interp.inverse(31, Method::Nearest), Ok(5));
+    }
+
+    #[test]
+    fn test_inverse_ffill_unsigned() {
+        let xp: Vec<u64> = vec![0, 5];
+        let fp: Vec<u64> = vec![20, 30];
+        let interp = Interp::new(xp, fp);
+        assert_eq!(
+            interp.inverse(19, Method::ForwardFill),
+            Err(InterpError::OutOfBounds)
+        );
+        assert_eq!(interp.inverse(20, Method::ForwardFill), Ok(0));
+        assert_eq!(interp.inverse(21, Method::ForwardFill), Ok(0));
+        assert_eq!(interp.inverse(22, Method::ForwardFill), Ok(1));
+        assert_eq!(interp.inverse(23, Method::ForwardFill), Ok(1));
+        assert_eq!(interp.inverse(24, Method::ForwardFill), Ok(2));
+        assert_eq!(interp.inverse(25, Method::ForwardFill), Ok(2));
+        assert_eq!(interp.inverse(26, Method::ForwardFill), Ok(3));
+        assert_eq!(interp.inverse(30, Method::ForwardFill), Ok(5));
+        assert_eq!(interp.inverse(31, Method::ForwardFill), Ok(5));
+    }
+
+    #[test]
+    fn test_inverse_bfill_unsigned() {
+        let xp: Vec<u64> = vec![0, 5];
+        let fp: Vec<u64> = vec![20, 30];
+        let interp = Interp::new(xp, fp);
+        assert_eq!(interp.inverse(19, Method::BackwardFill), Ok(0));
+        assert_eq!(interp.inverse(20, Method::BackwardFill), Ok(0));
+        assert_eq!(interp.inverse(21, Method::BackwardFill), Ok(1));
+        assert_eq!(interp.inverse(22, Method::BackwardFill), Ok(1));
+        assert_eq!(interp.inverse(23, Method::BackwardFill), Ok(2));
+        assert_eq!(interp.inverse(24, Method::BackwardFill), Ok(2));
+        assert_eq!(interp.inverse(25, Method::BackwardFill), Ok(3));
+        assert_eq!(interp.inverse(26, Method::BackwardFill), Ok(3));
+        assert_eq!(interp.inverse(30, Method::BackwardFill), Ok(5));
+        assert_eq!(
+            interp.inverse(31, Method::BackwardFill),
+            Err(InterpError::OutOfBounds)
+        );
+    }
+
+    #[test]
+    fn test_inverse_exact_signed() {
+        let xp: Vec<u64> = vec![0, 5];
+        let fp: Vec<i64> = vec![-30, -20];
+        let interp = Interp::new(xp, fp);
+        assert_eq!(
+            interp.inverse(-31, Method::None),
+            Err(InterpError::OutOfBounds)
+        );
+        assert_eq!(interp.inverse(-30, Method::None), Ok(0));
+        assert_eq!(
+            interp.inverse(-29, Method::None),
+            Err(InterpError::NotFound)
+        );
+        assert_eq!(interp.inverse(-28, Method::None), Ok(1));
+        assert_eq!(
+            interp.inverse(-27, Method::None),
+            Err(InterpError::NotFound)
+        );
+        assert_eq!(interp.inverse(-26, Method::None), Ok(2));
+        assert_eq!(
+            interp.inverse(-25, Method::None),
+            Err(InterpError::NotFound)
+        );
+        assert_eq!(interp.inverse(-24, Method::None), Ok(3));
+        assert_eq!(interp.inverse(-20, Method::None), Ok(5));
+        assert_eq!(
+            interp.inverse(-19, Method::None),
+            Err(InterpError::OutOfBounds)
+        );
+    }
+
+    #[test]
+    fn test_inverse_round_signed() {
+        let xp: Vec<u64> = vec![0, 5];
+        let fp: Vec<i64> = vec![-30, -20];
+        let interp = Interp::new(xp, fp);
+        assert_eq!(interp.inverse(-31, Method::Nearest), Ok(0));
+        assert_eq!(interp.inverse(-30, Method::Nearest), Ok(0));
+        assert_eq!(interp.inverse(-29, Method::Nearest), Ok(0));
+        assert_eq!(interp.inverse(-28, Method::Nearest), Ok(1));
+        assert_eq!(interp.inverse(-27, Method::Nearest), Ok(2));
+        assert_eq!(interp.inverse(-26, Method::Nearest), Ok(2));
+        assert_eq!(interp.inverse(-25, Method::Nearest), Ok(2));
+        assert_eq!(interp.inverse(-24, Method::Nearest), Ok(3));
+        assert_eq!(interp.inverse(-20, Method::Nearest), Ok(5));
+        assert_eq!(interp.inverse(-19, Method::Nearest), Ok(5));
+    }
+
+    #[test]
+    fn test_inverse_ffill_signed() {
+        let xp: Vec<u64> = vec![0, 5];
+        let fp: Vec<i64> = vec![-30, -20];
+        let interp = Interp::new(xp, fp);
+        assert_eq!(
+            interp.inverse(-31, Method::ForwardFill),
+            Err(InterpError::OutOfBounds)
+        );
+        assert_eq!(interp.inverse(-30, Method::ForwardFill), Ok(0));
+        assert_eq!(interp.inverse(-29, Method::ForwardFill), Ok(0));
+        assert_eq!(interp.inverse(-28, Method::ForwardFill), Ok(1));
+        assert_eq!(interp.inverse(-27, Method::ForwardFill), Ok(1));
+        assert_eq!(interp.inverse(-26, Method::ForwardFill), Ok(2));
+        assert_eq!(interp.inverse(-25, Method::ForwardFill), Ok(2));
+        assert_eq!(interp.inverse(-24, Method::ForwardFill), Ok(3));
+        assert_eq!(interp.inverse(-20, Method::ForwardFill), Ok(5));
+        assert_eq!(interp.inverse(-19, Method::ForwardFill), Ok(5));
+    }
+
+    #[test]
+    fn test_inverse_bfill_signed() {
+        let xp: Vec<u64> = vec![0, 5];
+        let fp: Vec<i64> = vec![-30, -20];
+        let interp = Interp::new(xp, fp);
+        assert_eq!(interp.inverse(-31, Method::BackwardFill), Ok(0));
+        assert_eq!(interp.inverse(-30, Method::BackwardFill), Ok(0));
+        assert_eq!(interp.inverse(-29, Method::BackwardFill), Ok(1));
+        assert_eq!(interp.inverse(-28, Method::BackwardFill), Ok(1));
+        assert_eq!(interp.inverse(-27, Method::BackwardFill), Ok(2));
+        assert_eq!(interp.inverse(-26, Method::BackwardFill), Ok(2));
+        assert_eq!(interp.inverse(-25, Method::BackwardFill), Ok(3));
+        assert_eq!(interp.inverse(-24, Method::BackwardFill), Ok(3));
+        assert_eq!(interp.inverse(-20, Method::BackwardFill), Ok(5));
+        assert_eq!(
+            interp.inverse(-19, Method::BackwardFill),
+            Err(InterpError::OutOfBounds)
+        );
+    }
+
+    #[test]
+    fn test_inverse_round_float() {
+        let xp: Vec<u64> = vec![0, 5];
+        let fp: Vec<f64> = vec![20.0, 30.0];
+        let interp = Interp::new(xp, fp);
+        assert_eq!(interp.inverse(19.9, Method::Nearest), Ok(0));
+        assert_eq!(interp.inverse(20.0, Method::Nearest), Ok(0));
+        assert_eq!(interp.inverse(20.1, Method::Nearest), Ok(0));
+        assert_eq!(interp.inverse(20.9, Method::Nearest), Ok(0));
+        assert_eq!(interp.inverse(21.1, Method::Nearest), Ok(1));
+        assert_eq!(interp.inverse(22.0, Method::Nearest), Ok(1));
+        assert_eq!(interp.inverse(29.9, Method::Nearest), Ok(5));
+        assert_eq!(interp.inverse(30.0, Method::Nearest), Ok(5));
+        assert_eq!(interp.inverse(30.1, Method::Nearest), Ok(5));
+        assert_eq!(interp.inverse(21.0, Method::Nearest), Ok(0));
+        assert_eq!(interp.inverse(23.0, Method::Nearest), Ok(2));
+        assert_eq!(interp.inverse(25.0, Method::Nearest), Ok(2));
+        assert_eq!(interp.inverse(27.0, Method::Nearest), Ok(4));
+        assert_eq!(interp.inverse(29.0, Method::Nearest), Ok(4));
+    }
+
+    #[test]
+    fn test_inverse_ffill_float() {
+        let xp: Vec<u64> = vec![0, 5];
+        let fp: Vec<f64> = vec![20.0, 30.0];
+        let interp = Interp::new(xp, fp);
+        assert_eq!(
+            interp.inverse(19.9, Method::ForwardFill),
+            Err(InterpError::OutOfBounds)
+        );
+        assert_eq!(interp.inverse(20.0, Method::ForwardFill), Ok(0));
+        assert_eq!(interp.inverse(20.1, Method::ForwardFill), Ok(0));
+        assert_eq!(interp.inverse(20.9, Method::ForwardFill), Ok(0));
+        assert_eq!(interp.inverse(21.1, Method::ForwardFill), Ok(0));
+        assert_eq!(interp.inverse(22.0, Method::ForwardFill), Ok(1));
+        assert_eq!(interp.inverse(29.9, Method::ForwardFill), Ok(4));
+        assert_eq!(interp.inverse(30.0, Method::ForwardFill), Ok(5));
+        assert_eq!(interp.inverse(30.1, Method::ForwardFill), Ok(5));
+        assert_eq!(interp.inverse(21.0, Method::ForwardFill), Ok(0));
+        assert_eq!(interp.inverse(23.0, Method::ForwardFill), Ok(1));
+        assert_eq!(interp.inverse(25.0, Method::ForwardFill), Ok(2));
+        assert_eq!(interp.inverse(27.0, Method::ForwardFill), Ok(3));
+        assert_eq!(interp.inverse(29.0, Method::ForwardFill), Ok(4));
+    }
+
+    #[test]
+    fn test_inverse_bfill_float() {
+        let xp: Vec<u64> = vec![0, 5];
+        let fp: Vec<f64> = vec![20.0, 30.0];
+        let interp = Interp::new(xp, fp);
+        assert_eq!(interp.inverse(19.9, Method::BackwardFill), Ok(0));
+        assert_eq!(interp.inverse(20.0, Method::BackwardFill), Ok(0));
+        assert_eq!(interp.inverse(20.1, Method::BackwardFill), Ok(1));
+        assert_eq!(interp.inverse(20.9, Method::BackwardFill), Ok(1));
+        assert_eq!(interp.inverse(21.1, Method::BackwardFill), Ok(1));
+        assert_eq!(interp.inverse(22.0, Method::BackwardFill), Ok(1));
+        assert_eq!(interp.inverse(29.9, Method::BackwardFill), Ok(5));
+        assert_eq!(interp.inverse(30.0, Method::BackwardFill), Ok(5));
+        assert_eq!(
+            interp.inverse(30.1, Method::BackwardFill),
+            Err(InterpError::OutOfBounds)
+        );
+        assert_eq!(interp.inverse(21.0, Method::BackwardFill), Ok(1));
+        assert_eq!(interp.inverse(23.0, Method::BackwardFill), Ok(2));
+        assert_eq!(interp.inverse(25.0, Method::BackwardFill), Ok(3));
+        assert_eq!(interp.inverse(27.0, Method::BackwardFill), Ok(4));
+        assert_eq!(interp.inverse(29.0, Method::BackwardFill), Ok(5));
+    }
+
+    #[test]
+    fn test_inverse_exact_unsigned_decreasing() {
+        let xp: Vec<u64> = vec![0, 5];
+        let fp: Vec<u64> = vec![30, 20];
+        let interp = Interp::new(xp, fp);
+        assert_eq!(
+            interp.inverse(31, Method::None),
+            Err(InterpError::OutOfBounds)
+        );
+        assert_eq!(interp.inverse(30, Method::None), Ok(0));
+        assert_eq!(interp.inverse(29, Method::None), Err(InterpError::NotFound));
+        assert_eq!(interp.inverse(28, Method::None), Ok(1));
+        assert_eq!(interp.inverse(27, Method::None), Err(InterpError::NotFound));
+        assert_eq!(interp.inverse(26, Method::None), Ok(2));
+        assert_eq!(interp.inverse(25, Method::None), Err(InterpError::NotFound));
+        assert_eq!(interp.inverse(24, Method::None), Ok(3));
+        assert_eq!(interp.inverse(20, Method::None), Ok(5));
+        assert_eq!(
+            interp.inverse(19, Method::None),
+            Err(InterpError::OutOfBounds)
+        );
+    }
+
+    #[test]
+    fn test_inverse_exact_signed_decreasing() {
+        let xp: Vec<u64> = vec![0, 5];
+        let fp: Vec<i64> = vec![-20, -30];
+        let interp = Interp::new(xp, fp);
+        assert_eq!(
+            interp.inverse(-19, Method::None),
+            Err(InterpError::OutOfBounds)
+        );
+        assert_eq!(interp.inverse(-20, Method::None), Ok(0));
+        assert_eq!(
+            interp.inverse(-21, Method::None),
+            Err(InterpError::NotFound)
+        );
+        assert_eq!(interp.inverse(-22, Method::None), Ok(1));
+        assert_eq!(
+            interp.inverse(-23, Method::None),
+            Err(InterpError::NotFound)
+        );
+        assert_eq!(interp.inverse(-24, Method::None), Ok(2));
+        assert_eq!(
+            interp.inverse(-25, Method::None),
+            Err(InterpError::NotFound)
+        );
+        assert_eq!(interp.inverse(-26, Method::None), Ok(3));
+        assert_eq!(interp.inverse(-30, Method::None), Ok(5));
+        assert_eq!(
+            interp.inverse(-31, Method::None),
+            Err(InterpError::OutOfBounds)
+        );
+    }
+
+    #[test]
+    fn test_inverse_round_unsigned_decreasing() {
+        let xp: Vec<u64> = vec![0, 5];
+        let fp: Vec<u64> = vec![30, 20];
+        let interp = Interp::new(xp, fp);
+        assert_eq!(interp.inverse(31, Method::Nearest), Ok(0));
+        assert_eq!(interp.inverse(30, Method::Nearest), Ok(0));
+        assert_eq!(interp.inverse(29, Method::Nearest), Ok(0));
+        assert_eq!(interp.inverse(28, Method::Nearest), Ok(1));
+        assert_eq!(interp.inverse(27, Method::Nearest), Ok(2));
+        assert_eq!(interp.inverse(26, Method::Nearest), Ok(2));
+        assert_eq!(interp.inverse(25, Method::Nearest), Ok(2));
+        assert_eq!(interp.inverse(24, Method::Nearest), Ok(3));
+        assert_eq!(interp.inverse(20, Method::Nearest), Ok(5));
+        assert_eq!(interp.inverse(19, Method::Nearest), Ok(5));
+    }
+
+    #[test]
+    fn test_inverse_round_signed_decreasing() {
+        let xp: Vec<u64> = vec![0, 5];
+        let fp: Vec<i64> = vec![-20, -30];
+        let interp = Interp::new(xp, fp);
+        assert_eq!(interp.inverse(-19, Method::Nearest), Ok(0));
+        assert_eq!(interp.inverse(-20, Method::Nearest), Ok(0));
+        assert_eq!(interp.inverse(-21, Method::Nearest), Ok(0));
+        assert_eq!(interp.inverse(-22, Method::Nearest), Ok(1));
+        assert_eq!(interp.inverse(-23, Method::Nearest), Ok(2));
+        assert_eq!(interp.inverse(-24, Method::Nearest), Ok(2));
+        assert_eq!(interp.inverse(-25, Method::Nearest), Ok(2));
+        assert_eq!(interp.inverse(-26, Method::Nearest), Ok(3));
+        assert_eq!(interp.inverse(-30, Method::Nearest), Ok(5));
+        assert_eq!(interp.inverse(-31, Method::Nearest), Ok(5));
+    }
+
+    #[test]
+    fn test_inverse_round_float_decreasing() {
+        let xp: Vec<u64> = vec![0, 5];
+        let fp: Vec<f64> = vec![30.0, 20.0];
+        let interp = Interp::new(xp, fp);
+        assert_eq!(interp.inverse(30.1, Method::Nearest), Ok(0));
+        assert_eq!(interp.inverse(30.0, Method::Nearest), Ok(0));
+        assert_eq!(interp.inverse(29.9, Method::Nearest), Ok(0));
+        assert_eq!(interp.inverse(29.1, Method::Nearest), Ok(0));
+        assert_eq!(interp.inverse(28.9, Method::Nearest), Ok(1));
+        assert_eq!(interp.inverse(28.0, Method::Nearest), Ok(1));
+        assert_eq!(interp.inverse(20.1, Method::Nearest), Ok(5));
+        assert_eq!(interp.inverse(20.0, Method::Nearest), Ok(5));
+        assert_eq!(interp.inverse(19.9, Method::Nearest), Ok(5));
+        assert_eq!(interp.inverse(29.0, Method::Nearest), Ok(0));
+        assert_eq!(interp.inverse(27.0, Method::Nearest), Ok(2));
+        assert_eq!(interp.inverse(25.0, Method::Nearest), Ok(2));
+        assert_eq!(interp.inverse(23.0, Method::Nearest), Ok(4));
+        assert_eq!(interp.inverse(21.0, Method::Nearest), Ok(4));
+    }
+
+    #[test]
+    fn test_inverse_ffill_unsigned_decreasing() {
+        let xp: Vec<u64> = vec![0, 5];
+        let fp: Vec<u64> = vec![30, 20];
+        let interp = Interp::new(xp, fp);
+        assert_eq!(
+            interp.inverse(31, Method::ForwardFill),
+            Err(InterpError::OutOfBounds)
+        );
+        assert_eq!(interp.inverse(30, Method::ForwardFill), Ok(0));
+        assert_eq!(interp.inverse(29, Method::ForwardFill), Ok(0));
+        assert_eq!(interp.inverse(28, Method::ForwardFill), Ok(1));
+        assert_eq!(interp.inverse(27, Method::ForwardFill), Ok(1));
+        assert_eq!(interp.inverse(26, Method::ForwardFill), Ok(2));
+        assert_eq!(interp.inverse(25, Method::ForwardFill), Ok(2));
+        assert_eq!(interp.inverse(24, Method::ForwardFill), Ok(3));
+        assert_eq!(interp.inverse(20, Method::ForwardFill), Ok(5));
+        assert_eq!(interp.inverse(19, Method::ForwardFill), Ok(5));
+    }
+
+    #[test]
+    fn test_inverse_ffill_signed_decreasing() {
+        let xp: Vec<u64> = vec![0, 5];
+        let fp: Vec<i64> = vec![-20, -30];
+        let interp = Interp::new(xp, fp);
+        assert_eq!(
+            interp.inverse(-19, Method::ForwardFill),
+            Err(InterpError::OutOfBounds)
+        );
+        assert_eq!(interp.inverse(-20, Method::ForwardFill), Ok(0));
+        assert_eq!(interp.inverse(-21, Method::ForwardFill), Ok(0));
+        assert_eq!(interp.inverse(-22, Method::ForwardFill), Ok(1));
+        assert_eq!(interp.inverse(-23, Method::ForwardFill), Ok(1));
+        assert_eq!(interp.inverse(-24, Method::ForwardFill), Ok(2));
+        assert_eq!(interp.inverse(-25, Method::ForwardFill), Ok(2));
+        assert_eq!(interp.inverse(-26, Method::ForwardFill), Ok(3));
+        assert_eq!(interp.inverse(-30, Method::ForwardFill), Ok(5));
+        assert_eq!(interp.inverse(-31, Method::ForwardFill), Ok(5));
+    }
+
+    #[test]
+    fn test_inverse_ffill_float_decreasing() {
+        let xp: Vec<u64> = vec![0, 5];
+        let fp: Vec<f64> = vec![30.0, 20.0];
+        let interp = Interp::new(xp, fp);
+        assert_eq!(
+            interp.inverse(30.1, Method::ForwardFill),
+            Err(InterpError::OutOfBounds)
+        );
+        assert_eq!(interp.inverse(30.0, Method::ForwardFill), Ok(0));
+        assert_eq!(interp.inverse(29.9, Method::ForwardFill), Ok(0));
+        assert_eq!(interp.inverse(29.1, Method::ForwardFill), Ok(0));
+        assert_eq!(interp.inverse(28.9, Method::ForwardFill), Ok(0));
+        assert_eq!(interp.inverse(28.0, Method::ForwardFill), Ok(1));
+        assert_eq!(interp.inverse(20.1, Method::ForwardFill), Ok(4));
+        assert_eq!(interp.inverse(20.0, Method::ForwardFill), Ok(5));
+        assert_eq!(interp.inverse(19.9, Method::ForwardFill), Ok(5));
+        assert_eq!(interp.inverse(29.0, Method::ForwardFill), Ok(0));
+        assert_eq!(interp.inverse(27.0, Method::ForwardFill), Ok(1));
+        assert_eq!(interp.inverse(25.0, Method::ForwardFill), Ok(2));
+        assert_eq!(interp.inverse(23.0, Method::ForwardFill), Ok(3));
+        assert_eq!(interp.inverse(21.0, Method::ForwardFill), Ok(4));
+    }
+
+    #[test]
+    fn test_inverse_bfill_unsigned_decreasing() {
+        let xp: Vec<u64> = vec![0, 5];
+        let fp: Vec<u64> = vec![30, 20];
+        let interp = Interp::new(xp, fp);
+        assert_eq!(interp.inverse(31, Method::BackwardFill), Ok(0));
+        assert_eq!(interp.inverse(30, Method::BackwardFill), Ok(0));
+        assert_eq!(interp.inverse(29, Method::BackwardFill), Ok(1));
+        assert_eq!(interp.inverse(28, Method::BackwardFill), Ok(1));
+        assert_eq!(interp.inverse(27, Method::BackwardFill), Ok(2));
+        assert_eq!(interp.inverse(26, Method::BackwardFill), Ok(2));
+        assert_eq!(interp.inverse(25, Method::BackwardFill), Ok(3));
+        assert_eq!(interp.inverse(24, Method::BackwardFill), Ok(3));
+        assert_eq!(interp.inverse(20, Method::BackwardFill), Ok(5));
+        assert_eq!(
+            interp.inverse(19, Method::BackwardFill),
+            Err(InterpError::OutOfBounds)
+        );
+    }
+
+    #[test]
+    fn test_inverse_bfill_signed_decreasing() {
+        let xp: Vec<u64> = vec![0, 5];
+        let fp: Vec<i64> = vec![-20, -30];
+        let interp = Interp::new(xp, fp);
+        assert_eq!(interp.inverse(-19, Method::BackwardFill), Ok(0));
+        assert_eq!(interp.inverse(-20, Method::BackwardFill), Ok(0));
+        assert_eq!(interp.inverse(-21, Method::BackwardFill), Ok(1));
+        assert_eq!(interp.inverse(-22, Method::BackwardFill), Ok(1));
+        assert_eq!(interp.inverse(-23, Method::BackwardFill), Ok(2));
+        assert_eq!(interp.inverse(-24, Method::BackwardFill), Ok(2));
+        assert_eq!(interp.inverse(-25, Method::BackwardFill), Ok(3));
+        assert_eq!(interp.inverse(-26, Method::BackwardFill), Ok(3));
+        assert_eq!(interp.inverse(-30, Method::BackwardFill), Ok(5));
+        assert_eq!(
+            interp.inverse(-31, Method::BackwardFill),
+            Err(InterpError::OutOfBounds)
+        );
+    }
+
+    #[test]
+    fn test_inverse_bfill_float_decreasing() {
+        let xp: Vec<u64> = vec![0, 5];
+        let fp: Vec<f64> = vec![30.0, 20.0];
+        let interp = Interp::new(xp, fp);
+        assert_eq!(interp.inverse(30.1, Method::BackwardFill), Ok(0));
+        assert_eq!(interp.inverse(30.0, Method::BackwardFill), Ok(0));
+        assert_eq!(interp.inverse(29.9, Method::BackwardFill), Ok(1));
+        assert_eq!(interp.inverse(29.1, Method::BackwardFill), Ok(1));
+        assert_eq!(interp.inverse(28.9, Method::BackwardFill), Ok(1));
+        assert_eq!(interp.inverse(28.0, Method::BackwardFill), Ok(1));
+        assert_eq!(interp.inverse(20.1, Method::BackwardFill), Ok(5));
+        assert_eq!(interp.inverse(20.0, Method::BackwardFill), Ok(5));
+        assert_eq!(
+            interp.inverse(19.9, Method::BackwardFill),
+            Err(InterpError::OutOfBounds)
+        );
+        assert_eq!(interp.inverse(29.0, Method::BackwardFill), Ok(1));
+        assert_eq!(interp.inverse(27.0, Method::BackwardFill), Ok(2));
+        assert_eq!(interp.inverse(25.0, Method::BackwardFill), Ok(3));
+        assert_eq!(interp.inverse(23.0, Method::BackwardFill), Ok(4));
+        assert_eq!(interp.inverse(21.0, Method::BackwardFill), Ok(5));
+    }
+
+    #[test]
+    fn test_inverse_fractional() {
+        let xp: Vec<u64> = vec![0, 5];
+        let fp: Vec<i64> = vec![-30, -20];
+        let interp = Interp::new(xp, fp);
+        assert_eq!(
+            interp.inverse_fractional(-31),
+            Err(InterpError::OutOfBounds)
+        );
+        assert_eq!(interp.inverse_fractional(-30), Ok(0.0));
+        assert_eq!(interp.inverse_fractional(-29), Ok(0.5));
+        assert_eq!(interp.inverse_fractional(-25), Ok(2.5));
+        assert_eq!(interp.inverse_fractional(-20), Ok(5.0));
+        assert_eq!(
+            interp.inverse_fractional(-19),
+            Err(InterpError::OutOfBounds)
+        );
+
+        let xp: Vec<u64> = vec![0, 3, 7];
+        let fp: Vec<f64> = vec![0.0, 1.0, 2.0];
+        let interp = Interp::new(xp, fp);
+        assert_eq!(interp.inverse_fractional(1.0), Ok(3.0));
+        assert_eq!(interp.inverse_fractional(1.25), Ok(4.0));
+        assert_eq!(interp.inverse_fractional(2.0), Ok(7.0));
+
+        let xp: Vec<u64> = vec![0, 3, 7];
+        let fp: Vec<f64> = vec![2.0, 1.0, 0.0];
+        let interp = Interp::new(xp, fp);
+        assert_eq!(
+            interp.inverse_fractional(2.5),
+            Err(InterpError::OutOfBounds)
+        );
+        assert_eq!(interp.inverse_fractional(1.0), Ok(3.0));
+        assert_eq!(interp.inverse_fractional(0.75), Ok(4.0));
+        assert_eq!(interp.inverse_fractional(0.0), Ok(7.0));
+
+        let interp = Interp::new(vec![0, u64::MAX], vec![i64::MIN, i64::MAX]);
+        assert_eq!(interp.inverse_fractional(i64::MAX), Ok(u64::MAX as f64));
+        assert_eq!(interp.inverse_fractional(0), Ok(2f64.powi(63)));
+    }
+
+    #[test]
+    fn test_inverse_all() {
+        let xp: Vec<u64> = vec![0, 4, 8, 10];
+        let fp: Vec<i64> = vec![0, 8, 0, 8];
+        let interp = Interp::new(xp, fp);
+        assert_eq!(interp.inversable, Order::Unordered);
+        assert_eq!(interp.inverse_all(-1, Method::Nearest), vec![]);
+        assert_eq!(interp.inverse_all(0, Method::None), vec![0, 8]);
+        assert_eq!(interp.inverse_all(2, Method::None), vec![1, 7]);
+        assert_eq!(interp.inverse_all(3, Method::None), vec![]);
+        assert_eq!(interp.inverse_all(3, Method::ForwardFill), vec![1, 6, 8]);
+        assert_eq!(interp.inverse_all(3, Method::BackwardFill), vec![2, 7, 9]);
+        assert_eq!(interp.inverse_all(8, Method::None), vec![4, 10]);
+        assert_eq!(interp.inverse_all(9, Method::Nearest), vec![]);
+
+        let xp: Vec<u64> = vec![0, 2, 4];
+        let fp: Vec<f64> = vec![0.0, 1.0, 0.0];
+        let interp = Interp::new(xp, fp);
+        assert_eq!(interp.inverse_all(0.9, Method::Nearest), vec![2]);
+        assert_eq!(interp.inverse_all(0.5, Method::None), vec![1, 3]);
+    }
+
+    #[test]
+    fn test_crop() {
+        let interp = Interp::new(vec![0, 4, 8, 12], vec![0i64, 8, -8, 0]);
+        let cropped = interp.crop(2, 9).unwrap();
+        assert_eq!(cropped.xp(), &[2, 4, 8, 9]);
+        assert_eq!(cropped.fp(), &[4, 8, -8, -6]);
+        let cropped = interp.crop(4, 8).unwrap();
+        assert_eq!(cropped.xp(), &[4, 8]);
+        assert_eq!(cropped.fp(), &[8, -8]);
+        assert_eq!(interp.crop(6, 6).unwrap().fp(), &[0]);
+        assert_eq!(interp.crop(7, 6), Err(InterpError::OutOfBounds));
+        assert_eq!(interp.crop(6, 13), Err(InterpError::OutOfBounds));
+    }
+
+    #[test]
+    fn test_extrema() {
+        let xp: Vec<u64> = vec![0, 4, 8, 12];
+        let fp: Vec<i64> = vec![0, 8, -8, 0];
+        let interp = Interp::new(xp, fp);
+        assert_eq!(interp.extrema(0, 12), Ok((-8, 8)));
+        assert_eq!(interp.extrema(1, 3), Ok((2, 6)));
+        assert_eq!(interp.extrema(3, 5), Ok((4, 8)));
+        assert_eq!(interp.extrema(5, 7), Ok((-4, 4)));
+        assert_eq!(interp.extrema(4, 8), Ok((-8, 8)));
+        assert_eq!(interp.extrema(6, 6), Ok((0, 0)));
+        assert_eq!(interp.extrema(7, 6), Err(InterpError::OutOfBounds));
+        assert_eq!(interp.extrema(6, 13), Err(InterpError::OutOfBounds));
+
+        let xp: Vec<u64> = vec![0, 4];
+        let fp: Vec<f64> = vec![1.0, 0.0];
+        let interp = Interp::new(xp, fp);
+        assert_eq!(interp.extrema(1, 2), Ok((0.5, 0.75)));
+    }
+
+    #[test]
+    fn test_max_error() {
+        fn brute_force(a: &Interp<u64, i64>, b: &Interp<u64, i64>) -> (u64, u64) {
+            let start = a.xp()[0].max(b.xp()[0]);
+            let end = a.xp().last().unwrap().min(b.xp().last().unwrap());
+            let mut out = (0, start);
+            for x in start..=*end {
+                let deviation = a.forward(x).unwrap().distance(b.forward(x).unwrap());
+                if deviation > out.0 {
+                    out = (deviation, x);
+                }
+            }
+            out
+        }
+        let a = Interp::new(vec![0, 4, 8, 12, 16], vec![0i64, 8, 8, 20, 20]);
+        let b = Interp::new(vec![2, 8, 14], vec![4i64, 10, 22]);
+        assert_eq!(a.max_error(&b), Ok((2, 4)));
+        assert_eq!(a.max_error(&b), Ok(brute_force(&a, &b)));
+        assert_eq!(b.max_error(&a), Ok(brute_force(&b, &a)));
+        let simplified = a.simplify(4).unwrap();
+        assert_eq!(a.max_error(&simplified), Ok(brute_force(&a, &simplified)));
+        assert_eq!(a.max_error(&a), Ok((0, 0)));
+
+        let a = Interp::new(vec![0, 10], vec![0.0, 1.0]);
+        let b = Interp::new(vec![5, 10, 20], vec![0.5, 0.75, 2.0]);
+        assert_eq!(a.max_error(&b), Ok((0.25, 10)));
+        let c = Interp::new(vec![11, 20], vec![0.0, 1.0]);
+        assert_eq!(a.max_error(&c), Err(InterpError::OutOfBounds));
+    }
+
+    #[test]
+    fn test_quantize() {
+        let xp: Vec<u64> = vec![0, 10, 20];
+        let fp: Vec<f64> = vec![-0.5, 1700000000.456, 3000000000.1];
+        let interp = Interp::new(xp.clone(), fp);
+        let quantized = interp.quantize(1e9, Method::Nearest).unwrap();
+        assert_eq!(
+            quantized.fp,
+            vec![-500000000, 1700000000456000090, 3000000000099999905]
+        );
+        assert_eq!(quantized.xp, xp);
+        assert_eq!(quantized.inversable, Order::Increasing);
+        assert_eq!(
+            interp.quantize(1e9, Method::None).err(),
+            Some(QuantizeError::Inexact(1))
+        );
+        assert_eq!(
+            interp.quantize(1e10, Method::Nearest).err(),
+            Some(QuantizeError::Overflow(1))
+        );
+        assert_eq!(
+            interp.quantize(f64::NAN, Method::Nearest).err(),
+            Some(QuantizeError::InvalidScale)
+        );
+        assert_eq!(
+            interp.quantize(1e-10, Method::Nearest).err(),
+            Some(QuantizeError::NotStrictlyMonotonic(1))
+        );
+        let quantized = interp.quantize(1.0, Method::ForwardFill).unwrap();
+        assert_eq!(quantized.fp, vec![-1, 1700000000, 3000000000]);
+        let quantized = interp.quantize(1.0, Method::BackwardFill).unwrap();
+        assert_eq!(quantized.fp, vec![0, 1700000001, 3000000001]);
+
         let xp: Vec<u64> = vec![0, 10];
-        let fp: Vec<f64> = vec![20.0, 25.0];
+        let fp: Vec<i64> = vec![-500000000, 1700000000456000090];
         let interp = Interp::new(xp, fp);
-        assert_eq!(interp.forward(0), Ok(20.0));
-        assert_eq!(interp.forward(1), Ok(20.5));
-        assert_eq!(interp.forward(2), Ok(21.0));
-        assert_eq!(interp.forward(3), Ok(21.5));
-        assert_eq!(interp.forward(11), Err(InterpError::OutOfBounds));
+        let dequantized = interp.dequantize(1e9).unwrap();
+        assert_eq!(dequantized.fp, vec![-0.5, 1700000000.456]);
+        assert_eq!(
+            interp.dequantize(0.0).err(),
+            Some(QuantizeError::InvalidScale)
+        );
     }
 
     #[test]
-    fn test_inverse_exact_unsigned() {
-        let xp: Vec<u64> = vec![0, 5];
-        let fp: Vec<u64> = vec![20, 30];
+    fn test_map_fp() {
+        let xp: Vec<u64> = vec![0, 5, 10];
+        let fp: Vec<i64> = vec![-2, 0, 3];
         let interp = Interp::new(xp, fp);
+        let mapped = interp.map_fp(|f| *f as f64 / 2.0).unwrap();
+        assert_eq!(mapped.fp, vec![-1.0, 0.0, 1.5]);
+        assert_eq!(mapped.inversable, Order::Increasing);
+        let mapped = interp.map_fp(|f| -f).unwrap();
+        assert_eq!(mapped.inversable, Order::Decreasing);
+        assert_eq!(mapped.inverse(0, Method::None), Ok(5));
         assert_eq!(
-            interp.inverse(19, Method::None),
+            interp.map_fp(|f| f * f).err(),
+            Some(InterpError::NotStrictlyMonotonic)
+        );
+
+        let xp: Vec<u64> = vec![0, 5, 10];
+        let fp: Vec<i64> = vec![0, 3, 0];
+        let interp = Interp::new(xp, fp);
+        let mapped = interp.map_fp(|f| f * f).unwrap();
+        assert_eq!(mapped.inversable, Order::Unordered);
+    }
+
+    #[test]
+    fn test_affine_fp() {
+        let xp: Vec<u64> = vec![0, 5, 10];
+        let fp: Vec<i64> = vec![-2, 0, 3];
+        let interp = Interp::new(xp.clone(), fp);
+        let mapped = interp.affine_fp(1_000_000_000, i64::MAX / 2).unwrap();
+        assert_eq!(
+            mapped.fp,
+            vec![
+                i64::MAX / 2 - 2_000_000_000,
+                i64::MAX / 2,
+                i64::MAX / 2 + 3_000_000_000
+            ]
+        );
+        let mapped = interp.affine_fp(-1, 0).unwrap();
+        assert_eq!(mapped.fp, vec![2, 0, -3]);
+        assert_eq!(
+            interp.affine_fp(i64::MAX, 0).err(),
+            Some(InterpError::Overflow)
+        );
+        assert_eq!(
+            interp.affine_fp(1, i64::MAX).err(),
+            Some(InterpError::Overflow)
+        );
+        assert_eq!(
+            interp.affine_fp(0, 1).err(),
+            Some(InterpError::NotStrictlyMonotonic)
+        );
+
+        let fp: Vec<f64> = vec![0.5, 1.0, 1.5];
+        let interp = Interp::new(xp, fp);
+        let mapped = interp.affine_fp(10.0, 1.0).unwrap();
+        assert_eq!(mapped.fp, vec![6.0, 11.0, 16.0]);
+        assert_eq!(
+            interp.affine_fp(f64::MAX, f64::MAX).err(),
+            Some(InterpError::Overflow)
+        );
+    }
+
+    #[test]
+    fn test_map_xp() {
+        let xp: Vec<u64> = vec![0, 5, 10];
+        let fp: Vec<i64> = vec![-2, 0, 3];
+        let interp = Interp::new(xp, fp);
+        let mapped = interp.map_xp(|x| 2 * x + 1).unwrap();
+        assert_eq!(mapped.xp, vec![1, 11, 21]);
+        assert_eq!(mapped.fp, vec![-2, 0, 3]);
+        assert_eq!(
+            interp.map_xp(|x| 10 - x).err(),
+            Some(InterpError::NotStrictlyIncreasing)
+        );
+        assert_eq!(
+            interp.map_xp(|x| x / 6).err(),
+            Some(InterpError::NotStrictlyIncreasing)
+        );
+        let mapped = interp.map_xp_with(|x| x / 6, Collision::KeepFirst).unwrap();
+        assert_eq!(mapped.xp, vec![0, 1]);
+        assert_eq!(mapped.fp, vec![-2, 3]);
+    }
+
+    #[test]
+    fn test_decimate_xp() {
+        let xp: Vec<u64> = vec![0, 4, 6, 12];
+        let fp: Vec<f64> = vec![0.0, 1.0, 2.0, 3.0];
+        let interp = Interp::new(xp, fp);
+        let decimated = interp
+            .decimate_xp(2, Method::None, Collision::Error)
+            .unwrap();
+        assert_eq!(decimated.xp, vec![0, 2, 3, 6]);
+        assert_eq!(
+            interp.decimate_xp(4, Method::None, Collision::Error).err(),
+            Some(InterpError::NotFound)
+        );
+        let decimated = interp
+            .decimate_xp(4, Method::Nearest, Collision::Error)
+            .unwrap();
+        assert_eq!(decimated.xp, vec![0, 1, 2, 3]);
+        let decimated = interp
+            .decimate_xp(4, Method::BackwardFill, Collision::Error)
+            .unwrap();
+        assert_eq!(decimated.xp, vec![0, 1, 2, 3]);
+        assert_eq!(
+            interp
+                .decimate_xp(4, Method::ForwardFill, Collision::Error)
+                .err(),
+            Some(InterpError::NotStrictlyIncreasing)
+        );
+        let decimated = interp
+            .decimate_xp(4, Method::ForwardFill, Collision::KeepFirst)
+            .unwrap();
+        assert_eq!(decimated.xp, vec![0, 1, 3]);
+        assert_eq!(decimated.fp, vec![0.0, 1.0, 3.0]);
+        assert_eq!(
+            interp.decimate_xp(0, Method::None, Collision::Error).err(),
+            Some(InterpError::InvalidArgument("factor must be positive"))
+        );
+    }
+
+    #[test]
+    fn test_offset_xp() {
+        let xp: Vec<u64> = vec![10, 20];
+        let fp: Vec<i64> = vec![0, 1];
+        let interp = Interp::new(xp, fp);
+        assert_eq!(interp.offset_xp(5).unwrap().xp, vec![15, 25]);
+        assert_eq!(interp.offset_xp(-10).unwrap().xp, vec![0, 10]);
+        assert_eq!(interp.offset_xp(-11).err(), Some(InterpError::Overflow));
+        let interp = interp.offset_xp(i64::MAX).unwrap();
+        assert_eq!(interp.xp, vec![i64::MAX as u64 + 10, i64::MAX as u64 + 20]);
+        assert_eq!(
+            interp.offset_xp(i64::MAX - 19).unwrap().xp,
+            vec![u64::MAX - 10, u64::MAX]
+        );
+        assert_eq!(
+            interp.offset_xp(i64::MAX - 18).err(),
+            Some(InterpError::Overflow)
+        );
+    }
+
+    #[test]
+    fn test_summary() {
+        let xp: Vec<u64> = vec![0, 10, 20, 25, 35];
+        let fp: Vec<i64> = vec![100, 200, 300, 450, 440];
+        let summary = Interp::new(xp, fp).summary();
+        assert_eq!(summary.points, 5);
+        assert_eq!(summary.domain, Some((0, 35)));
+        assert_eq!(summary.range, Some((100, 450)));
+        assert_eq!(
+            summary.slopes,
+            Some((
+                F80::try_from(-1.0).unwrap(),
+                F80::try_from(10.0).unwrap(),
+                F80::try_from(30.0).unwrap()
+            ))
+        );
+        assert_eq!(summary.lengths, Some((5, 10)));
+        assert_eq!(summary.irregular, 2);
+        assert_eq!(
+            summary.to_string(),
+            "points: 5\ndomain: [0, 35]\nrange: [100, 450]\n\
+             slopes: min -1, median 10, max 30\nlengths: min 5, max 10\nirregular: 2"
+        );
+
+        let summary = Interp::new(vec![5, 9], vec![1.0, 0.0]).summary_with(1.0);
+        assert_eq!(summary.range, Some((0.0, 1.0)));
+        assert_eq!(
+            summary.slopes,
+            Some((
+                F80::try_from(-0.25).unwrap(),
+                F80::try_from(-0.25).unwrap(),
+                F80::try_from(-0.25).unwrap()
+            ))
+        );
+        assert_eq!(summary.lengths, Some((4, 4)));
+        assert_eq!(summary.irregular, 0);
+
+        let summary = Interp::new(vec![5], vec![1.0]).summary();
+        assert_eq!((summary.points, summary.domain), (1, Some((5, 5))));
+        assert_eq!((&summary.slopes, summary.lengths), (&None, None));
+        assert_eq!(
+            summary.to_string(),
+            "points: 1\ndomain: [5, 5]\nrange: [1, 1]\nirregular: 0"
+        );
+    }
+
+    #[test]
+    fn test_intersections() {
+        let crossing = |x, f, exact| Crossing { x, f, exact };
+        let base = Interp::new(vec![0, 10], vec![0i64, 100]);
+        let parallel = Interp::new(vec![0, 10], vec![50i64, 150]);
+        assert_eq!(base.intersections(&parallel), Ok(vec![]));
+        let opposite = Interp::new(vec![0, 10], vec![100i64, 0]);
+        assert_eq!(
+            base.intersections(&opposite),
+            Ok(vec![crossing(5, 50, true)])
+        );
+        let inexact = Interp::new(vec![0, 10], vec![99i64, 0]);
+        assert_eq!(
+            base.intersections(&inexact),
+            Ok(vec![crossing(5, 50, false)])
+        );
+
+        let rising = Interp::new(vec![0, 10, 20], vec![0i64, 100, 200]);
+        let falling = Interp::new(vec![0, 10, 20], vec![200i64, 100, 0]);
+        assert_eq!(
+            rising.intersections(&falling),
+            Ok(vec![crossing(10, 100, true)])
+        );
+        let peak = Interp::new(vec![0, 10, 20], vec![0i64, 100, 0]);
+        let flat = Interp::new(vec![0, 20], vec![100i64, 100]);
+        assert_eq!(peak.intersections(&flat), Ok(vec![crossing(10, 100, true)]));
+
+        let zigzag = Interp::new(vec![0, 10, 20, 30, 40], vec![0.0, 100.0, 0.0, 100.0, 0.0]);
+        let level = Interp::new(vec![12, 40], vec![50.0, 50.0]);
+        let crossings = zigzag.intersections(&level).unwrap();
+        let xs: Vec<u64> = crossings.iter().map(|crossing| crossing.x).collect();
+        assert_eq!(xs, vec![15, 25, 35]);
+        assert!(crossings.iter().all(|crossing| crossing.exact));
+        let disjoint = Interp::new(vec![50, 60], vec![0.0, 1.0]);
+        assert_eq!(zigzag.intersections(&disjoint), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_monotone_pieces() {
+        let xp: Vec<u64> = (0..9).map(|i| 10 * i).collect();
+        let fp: Vec<i64> = vec![0, 50, 100, 50, 0, 50, 100, 50, 0];
+        let interp = Interp::new(xp, fp);
+        let pieces = interp.monotone_pieces();
+        let bounds: Vec<(&[u64], Direction)> = pieces
+            .iter()
+            .map(|(piece, direction)| (piece.xp(), *direction))
+            .collect();
+        assert_eq!(
+            bounds,
+            vec![
+                (&[0, 10, 20][..], Direction::Increasing),
+                (&[20, 30, 40], Direction::Decreasing),
+                (&[40, 50, 60], Direction::Increasing),
+                (&[60, 70, 80], Direction::Decreasing),
+            ]
+        );
+        assert!(pieces.iter().all(|(piece, _)| piece.is_inversable()));
+        let mut all: Vec<u64> = pieces
+            .iter()
+            .filter_map(|(piece, _)| piece.inverse(75, Method::None).ok())
+            .collect();
+        all.dedup();
+        assert_eq!(all, interp.inverse_all(75, Method::None));
+
+        let plateau = Interp::new(vec![0, 1, 2, 3, 4], vec![0.0, 1.0, 1.0, 1.0, 0.0]);
+        let pieces = plateau.monotone_pieces();
+        assert_eq!(pieces.len(), 2);
+        assert_eq!(pieces[0].0.xp(), &[0, 1]);
+        assert_eq!(pieces[1].0.xp(), &[3, 4]);
+        assert_eq!(pieces[1].1, Direction::Decreasing);
+        let flat = Interp::new(vec![0, 1, 2], vec![1i64, 1, 1]);
+        assert!(flat.monotone_pieces().is_empty());
+        assert!(Interp::new(vec![0], vec![1i64])
+            .monotone_pieces()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_clip() {
+        let interp = Interp::new(vec![0, 10, 20], vec![-40i64, 160, 60]);
+        let clipped = interp.clip(0, 100).unwrap();
+        assert_eq!(clipped.xp(), &[0, 2, 7, 10, 16, 20]);
+        assert_eq!(clipped.fp(), &[0, 0, 100, 100, 100, 60]);
+        assert!(clipped.is_forwardable());
+        assert!(!clipped.is_inversable());
+        for x in 0..=20 {
+            let expected = interp.forward(x).unwrap().clamp(0, 100);
+            assert_eq!(clipped.forward(x), Ok(expected));
+        }
+
+        let interp = Interp::new(vec![0, 5], vec![200i64, 300]);
+        assert_eq!(interp.clip(0, 100).unwrap().fp(), &[100, 100]);
+        let interp = Interp::new(vec![0, 5], vec![-5i64, -1]);
+        assert_eq!(interp.clip(0, 100).unwrap().fp(), &[0, 0]);
+        let interp = Interp::new(vec![0, 5], vec![0i64, 100]);
+        assert_eq!(interp.clip(0, 100).unwrap(), interp);
+
+        let interp = Interp::new(vec![0, 4], vec![0.0, 1.0]);
+        let clipped = interp.clip(0.25, 0.5).unwrap();
+        assert_eq!(clipped.xp(), &[0, 1, 2, 4]);
+        assert_eq!(clipped.fp(), &[0.25, 0.25, 0.5, 0.5]);
+        let invalid = Err(InterpError::InvalidArgument("lo must not exceed hi"));
+        assert_eq!(interp.clip(0.5, 0.25), invalid);
+        assert_eq!(interp.clip(f64::NAN, 0.5), invalid);
+        assert_eq!(interp.clip(0.5, 0.5).unwrap().fp(), &[0.5, 0.5, 0.5]);
+        let interp = Interp::new(vec![4, 0], vec![0.0, 1.0]);
+        assert_eq!(
+            interp.clip(0.25, 0.5),
+            Err(InterpError::NotStrictlyIncreasing)
+        );
+    }
+
+    #[test]
+    fn test_derivative() {
+        let interp = Interp::new(vec![0, 10, 14, 22], vec![100i64, 200, 180, 182]);
+        let derivative = interp.derivative().unwrap();
+        assert_eq!(derivative.xp(), &[0, 10, 14, 22]);
+        assert_eq!(derivative.values(), &[10.0, -5.0, 0.25]);
+        assert_eq!(derivative.forward(0), Ok(10.0));
+        assert_eq!(derivative.forward(13), Ok(-5.0));
+        assert_eq!(derivative.forward(14), Ok(0.25));
+        assert_eq!(derivative.forward(22), Ok(0.25));
+        assert_eq!(derivative.forward(23), Err(InterpError::OutOfBounds));
+
+        let interp = Interp::builder(vec![0, 4], vec![1.0, 0.0])
+            .bounds(Bounds::Clamp)
+            .build()
+            .unwrap();
+        let derivative = interp.derivative().unwrap();
+        assert_eq!(derivative.forward(100), Ok(-0.25));
+        let interp = Interp::new(vec![10, 0], vec![0i64, 0]);
+        assert_eq!(interp.derivative(), Err(InterpError::NotStrictlyIncreasing));
+    }
+
+    #[test]
+    fn test_zip_with() {
+        let base = Interp::new(vec![0, 10, 20], vec![0i64, 100, 200]);
+        let drift = Interp::new(vec![0, 5, 20], vec![1000i64, 950, 800]);
+        let sum = base.zip_with(&drift, Op::Add).unwrap();
+        assert_eq!(sum.xp(), &[0, 5, 10, 20]);
+        assert_eq!(sum.fp(), &[1000, 1000, 1000, 1000]);
+        assert!(sum.is_forwardable());
+        assert!(!sum.is_inversable());
+
+        let other = Interp::new(vec![0, 10, 20], vec![0i64, 200, 200]);
+        let difference = base.zip_with(&other, Op::Sub).unwrap();
+        assert_eq!(difference.fp(), &[0, -100, 0]);
+        assert!(difference.is_forwardable());
+        assert!(!difference.is_inversable());
+        assert_eq!(difference.forward(5), Ok(-50));
+
+        let shifted = Interp::new(vec![10, 15, 30], vec![0i64, 10, 20]);
+        let sum = base.zip_with(&shifted, Op::Add).unwrap();
+        assert_eq!(sum.xp(), &[10, 15, 20]);
+        assert_eq!(sum.fp(), &[100, 160, 213]);
+        let disjoint = Interp::new(vec![30, 40], vec![0i64, 1]);
+        assert_eq!(
+            base.zip_with(&disjoint, Op::Add),
+            Err(InterpError::OutOfBounds)
+        );
+        let huge = Interp::new(vec![0, 20], vec![i64::MAX, i64::MAX]);
+        assert_eq!(base.zip_with(&huge, Op::Add), Err(InterpError::Overflow));
+
+        let base = Interp::new(vec![0, 8], vec![0.5, 1.5]);
+        let drift = Interp::new(vec![0, 4, 8], vec![0.25, 0.75, 1.0]);
+        let difference = base.zip_with(&drift, Op::Sub).unwrap();
+        assert_eq!(difference.xp(), &[0, 4, 8]);
+        assert_eq!(difference.fp(), &[0.25, 0.25, 0.5]);
+        assert!(!difference.is_inversable());
+    }
+
+    #[test]
+    fn test_inverse_limited() {
+        let interp = Interp::new(vec![10, 20, 30], vec![100i64, 200, 300]);
+        assert_eq!(interp.inverse_limited(350, Method::ForwardFill, 5), Ok(30));
+        assert_eq!(
+            interp.inverse_limited(350, Method::ForwardFill, 4),
+            Err(InterpError::NotFound)
+        );
+        assert_eq!(interp.inverse_limited(50, Method::BackwardFill, 5), Ok(10));
+        assert_eq!(
+            interp.inverse_limited(50, Method::BackwardFill, 4),
+            Err(InterpError::NotFound)
+        );
+        assert_eq!(
+            interp.inverse_limited(50, Method::ForwardFill, 100),
+            Err(InterpError::OutOfBounds)
+        );
+        assert_eq!(interp.inverse_limited(255, Method::ForwardFill, 1), Ok(25));
+        assert_eq!(
+            interp.inverse_limited(255, Method::ForwardFill, 0),
+            Err(InterpError::NotFound)
+        );
+        assert_eq!(interp.inverse_limited(200, Method::BackwardFill, 0), Ok(20));
+        assert_eq!(interp.inverse_limited(350, Method::Nearest, 0), Ok(30));
+
+        let interp = Interp::new(vec![10, 20, 30], vec![300i64, 200, 100]);
+        assert_eq!(interp.inverse_limited(50, Method::ForwardFill, 5), Ok(30));
+        assert_eq!(
+            interp.inverse_limited(50, Method::ForwardFill, 4),
+            Err(InterpError::NotFound)
+        );
+
+        let origin = 1 << 62;
+        let interp = Interp::new(vec![origin, origin + 10], vec![0i64, 20]);
+        assert_eq!(
+            interp.inverse_limited(25, Method::ForwardFill, 3),
+            Ok(origin + 10)
+        );
+        assert_eq!(
+            interp.inverse_limited(25, Method::ForwardFill, 2),
+            Err(InterpError::NotFound)
+        );
+        assert_eq!(
+            interp.inverse_limited(-3, Method::BackwardFill, 2),
+            Ok(origin)
+        );
+        assert_eq!(
+            interp.inverse_limited(-3, Method::BackwardFill, 1),
+            Err(InterpError::NotFound)
+        );
+
+        let interp = Interp::new(vec![5], vec![1.0]);
+        assert_eq!(interp.inverse_limited(1.0, Method::ForwardFill, 0), Ok(5));
+        assert_eq!(
+            interp.inverse_limited(2.0, Method::ForwardFill, 100),
+            Err(InterpError::NotFound)
+        );
+    }
+
+    #[test]
+    fn test_inverse_gapped() {
+        let interp = Interp::new(vec![0, 10, 11, 21], vec![0i64, 100, 1000, 1100]);
+        assert_eq!(
+            interp.median_steepness(),
+            Some(F80::try_from(10.0).unwrap())
+        );
+        let gap = MaxGap::Median(5.0);
+        let inverse = |rhs, method, policy| interp.inverse_gapped(rhs, method, gap, policy);
+        assert_eq!(inverse(50, Method::None, GapPolicy::Error), Ok(5));
+        assert_eq!(inverse(1050, Method::None, GapPolicy::Error), Ok(16));
+        assert_eq!(inverse(100, Method::None, GapPolicy::Error), Ok(10));
+        assert_eq!(inverse(1000, Method::None, GapPolicy::Error), Ok(11));
+        assert_eq!(
+            inverse(600, Method::Nearest, GapPolicy::Error),
+            Err(InterpError::InGap)
+        );
+        assert_eq!(
+            inverse(2000, Method::None, GapPolicy::Error),
+            Err(InterpError::OutOfBounds)
+        );
+        assert_eq!(inverse(600, Method::Nearest, GapPolicy::Snap), Ok(11));
+        assert_eq!(inverse(400, Method::Nearest, GapPolicy::Snap), Ok(10));
+        assert_eq!(inverse(600, Method::ForwardFill, GapPolicy::Snap), Ok(10));
+        assert_eq!(inverse(400, Method::BackwardFill, GapPolicy::Snap), Ok(11));
+        assert_eq!(
+            inverse(600, Method::None, GapPolicy::Snap),
+            Err(InterpError::InGap)
+        );
+        assert_eq!(
+            interp.inverse_gapped(600, None, MaxGap::Absolute(1000.0), GapPolicy::Error),
+            Ok(11)
+        );
+
+        let interp = Interp::new(vec![0, 10, 11, 21], vec![0.0, -100.0, -1000.0, -1100.0]);
+        let gap = MaxGap::Absolute(50.0);
+        assert_eq!(
+            interp.inverse_gapped(-600.0, Method::ForwardFill, gap, GapPolicy::Snap),
+            Ok(10)
+        );
+        assert_eq!(
+            interp.inverse_gapped(-600.0, Method::None, gap, GapPolicy::Error),
+            Err(InterpError::InGap)
+        );
+        assert_eq!(
+            interp.inverse_gapped(-50.0, Method::None, gap, GapPolicy::Error),
+            Ok(5)
+        );
+        assert_eq!(Interp::new(vec![0], vec![0.0]).median_steepness(), None);
+    }
+
+    #[test]
+    fn test_fit_dense() {
+        let noise = |index: usize| ((index * 7919) % 7) as f64 - 3.0;
+        let values: Vec<f64> = (0..1000).map(|i| 10.0 * i as f64 + noise(i)).collect();
+        let interp = Interp::fit_dense(&values, 5.0).unwrap();
+        assert!(interp.xp.len() <= 3);
+        // Douglas-Peucker keeps samples, hence needs more data points to follow the noise.
+        let dense = Interp::new((0..1000).collect(), values.clone());
+        assert!(interp.xp().len() < dense.simplify(5.0).unwrap().xp().len());
+        assert_eq!(interp.xp[0], 0);
+        assert_eq!(*interp.xp.last().unwrap(), 999);
+        for (index, value) in values.iter().enumerate() {
+            assert!((interp.forward(index as u64).unwrap() - value).abs() <= 5.0);
+        }
+
+        let values: Vec<i64> = (0..1000)
+            .map(|i| 1_700_000_000_000_000_000 + 50 * (i - 500i64).abs() + noise(i as usize) as i64)
+            .collect();
+        let interp = Interp::fit_dense(&values, 5).unwrap();
+        assert!(interp.xp.len() <= 5);
+        let dense = Interp::new((0..1000).collect(), values.clone());
+        assert!(interp.xp().len() < dense.simplify(5).unwrap().xp().len());
+        for (index, value) in values.iter().enumerate() {
+            assert!((interp.forward(index as u64).unwrap() - value).abs() <= 5);
+        }
+
+        let interp = Interp::fit_dense(&values, 0).unwrap();
+        for (index, value) in values.iter().enumerate() {
+            assert_eq!(interp.forward(index as u64), Ok(*value));
+        }
+        let values: Vec<u64> = vec![0, 2, 4, 6, 5, 4];
+        let interp = Interp::fit_dense(&values, 0).unwrap();
+        assert_eq!(interp.xp, vec![0, 3, 5]);
+        assert_eq!(interp.fp, vec![0, 6, 4]);
+        let interp = Interp::fit_dense(&values[..1], 0).unwrap();
+        assert_eq!(interp.xp, vec![0]);
+
+        assert_eq!(
+            Interp::fit_dense(&values, u64::MAX).map(|interp| interp.xp),
+            Ok(vec![0, 5])
+        );
+        assert_eq!(
+            Interp::fit_dense(&[0.0, 1.0], -1.0),
+            Err(InterpError::InvalidArgument("max_err must be non-negative"))
+        );
+        assert_eq!(
+            Interp::fit_dense(&[0.0, 1.0], f64::NAN),
+            Err(InterpError::Overflow)
+        );
+        assert_eq!(
+            Interp::fit_dense(&[0.0, f64::INFINITY, 1.0], 1.0),
+            Err(InterpError::Overflow)
+        );
+        assert_eq!(
+            Interp::fit_dense(&[f64::NAN], 1.0),
+            Err(InterpError::Overflow)
+        );
+    }
+
+    #[test]
+    fn test_simplify() {
+        let xp: Vec<u64> = vec![0, 1, 2, 3, 4, 5];
+        let fp: Vec<i64> = vec![0, 2, 4, 7, 8, 10];
+        let interp = Interp::new(xp.clone(), fp.clone());
+        let simplified = interp.simplify(1).unwrap();
+        assert_eq!(simplified.xp, vec![0, 5]);
+        assert_eq!(simplified.fp, vec![0, 10]);
+        let simplified = interp.simplify(0).unwrap();
+        assert_eq!(simplified.xp, vec![0, 2, 3, 5]);
+        assert_eq!(simplified.fp, vec![0, 4, 7, 10]);
+        for x in 0..=5 {
+            assert_eq!(simplified.forward(x), interp.forward(x));
+        }
+        let fp: Vec<u64> = vec![u64::MAX - 10, u64::MAX - 8, u64::MAX - 6, u64::MAX - 3, 0];
+        let interp = Interp::new(xp[..5].to_vec(), fp);
+        let simplified = interp.simplify(u64::MAX).unwrap();
+        assert_eq!(simplified.xp, vec![0, 4]);
+        let simplified = interp.simplify(1).unwrap();
+        assert_eq!(simplified.xp, vec![0, 3, 4]);
+        let simplified = interp.simplify(0).unwrap();
+        assert_eq!(simplified.xp, vec![0, 2, 3, 4]);
+        let xp: Vec<u64> = vec![0, 10];
+        let fp: Vec<f64> = vec![0.0, 1.0];
+        let simplified = Interp::new(xp.clone(), fp.clone()).simplify(0.0).unwrap();
+        assert_eq!(simplified.xp, xp);
+        assert_eq!(simplified.fp, fp);
+        let xp: Vec<u64> = vec![0, 2, 1];
+        let fp: Vec<f64> = vec![0.0, 1.0, 2.0];
+        let interp = Interp::new(xp, fp);
+        assert_eq!(
+            interp.simplify(1.0).err(),
+            Some(InterpError::NotStrictlyIncreasing)
+        );
+    }
+
+    #[test]
+    fn test_forward_extended() {
+        let start = 1_000_000_000_000_000_001u64;
+        let xp: Vec<u64> = vec![0, 2];
+        let fp: Vec<F80> = vec![F80::from(start), F80::from(start + 2)];
+        let interp = Interp::new(xp, fp.clone());
+        assert_eq!(interp.forward(1), Ok(F80::from(start + 1)));
+        assert_eq!(interp.forward(2), Ok(F80::from(start + 2)));
+        assert_eq!(interp.forward(3), Err(InterpError::OutOfBounds));
+        let interp = Interp::new(vec![2, 0], fp);
+        assert_eq!(interp.forward(1), Err(InterpError::NotStrictlyIncreasing));
+    }
+
+    #[test]
+    fn test_inverse_extended() {
+        let start = 1_000_000_000_000_000_001u64;
+        let xp: Vec<u64> = vec![0, 4];
+        let fp: Vec<F80> = vec![F80::from(start), F80::from(start + 8)];
+        let interp = Interp::new(xp.clone(), fp);
+        assert_eq!(interp.inverse(F80::from(start + 2), Method::None), Ok(1));
+        let f = F80::from(start + 3);
+        assert_eq!(
+            interp.inverse(f.clone(), Method::None),
+            Err(InterpError::NotFound)
+        );
+        assert_eq!(interp.inverse(f.clone(), Method::ForwardFill), Ok(1));
+        assert_eq!(interp.inverse(f.clone(), Method::BackwardFill), Ok(2));
+        let f = F80::from(start + 9);
+        assert_eq!(
+            interp.inverse(f.clone(), Method::None),
             Err(InterpError::OutOfBounds)
         );
-        assert_eq!(interp.inverse(20, Method::None), Ok(0));
-        assert_eq!(interp.inverse(21, Method::None), Err(InterpError::NotFound));
-        assert_eq!(interp.inverse(22, Method::None), Ok(1));
-        assert_eq!(interp.inverse(23, Method::None), Err(InterpError::NotFound));
-        assert_eq!(interp.inverse(24, Method::None), Ok(2));
-        assert_eq!(interp.inverse(25, Method::None), Err(InterpError::NotFound));
-        assert_eq!(interp.inverse(26, Method::None), Ok(3));
-        assert_eq!(interp.inverse(30, Method::None), Ok(5));
+        assert_eq!(interp.inverse(f, Method::Nearest), Ok(4));
+        let fp: Vec<F80> = vec![F80::from(start + 8), F80::from(start)];
+        let interp = Interp::new(xp.clone(), fp);
+        let f = F80::from(start + 3);
+        assert_eq!(interp.inverse(f.clone(), Method::ForwardFill), Ok(2));
+        assert_eq!(interp.inverse(f.clone(), Method::BackwardFill), Ok(3));
+        let fp: Vec<F80> = vec![F80::from(start), F80::from(start)];
+        let interp = Interp::new(xp, fp);
+        assert_eq!(
+            interp.inverse(f, Method::None),
+            Err(InterpError::NotStrictlyMonotonic)
+        );
+    }
+
+    #[test]
+    fn test_simplify_extended() {
+        let start = 1_000_000_000_000_000_000u64;
+        let xp: Vec<u64> = vec![0, 1, 2, 3];
+        let fp: Vec<F80> = [0, 2, 5, 6]
+            .iter()
+            .map(|offset| F80::from(start + offset))
+            .collect();
+        let interp = Interp::new(xp, fp);
+        let simplified = interp.simplify(F80::from(1u64)).unwrap();
+        assert_eq!(simplified.xp, vec![0, 3]);
+        let simplified = interp.simplify(F80::try_from(0.5).unwrap()).unwrap();
+        assert_eq!(simplified.xp, vec![0, 2, 3]);
+        let simplified = interp.simplify(F80::try_from(0.25).unwrap()).unwrap();
+        assert_eq!(simplified.xp, vec![0, 1, 2, 3]);
+        for x in 0..=3 {
+            assert_eq!(simplified.forward(x), interp.forward(x));
+        }
+    }
+
+    #[test]
+    fn test_simplify_inplace() {
+        let interp = Interp::builder(vec![0, 1, 2, 3, 4, 5], vec![0i64, 2, 4, 7, 8, 10])
+            .bounds(Bounds::Clamp)
+            .build()
+            .unwrap();
+        for epsilon in [0, 1, 2, 10] {
+            let mut inplace = interp.clone();
+            let (xp, fp) = (inplace.xp.as_ptr(), inplace.fp.as_ptr());
+            let capacity = (inplace.xp.capacity(), inplace.fp.capacity());
+            inplace.simplify_inplace(epsilon).unwrap();
+            assert_eq!(inplace, interp.simplify(epsilon).unwrap());
+            assert_eq!((inplace.xp.as_ptr(), inplace.fp.as_ptr()), (xp, fp));
+            assert_eq!((inplace.xp.capacity(), inplace.fp.capacity()), capacity);
+            let consumed = interp.clone().into_simplified(epsilon).unwrap();
+            assert_eq!(consumed, inplace);
+        }
+
+        // Dropping the only data point out of order makes the values monotonic.
+        let mut interp = Interp::new(vec![0, 1, 2, 3], vec![0i64, 11, 10, 30]);
+        assert!(!interp.is_inversable());
+        interp.simplify_inplace(6).unwrap();
+        assert_eq!(interp.fp, vec![0, 10, 30]);
+        assert!(interp.is_inversable());
+        assert_eq!(interp.inverse(10, Method::None), Ok(2));
+
+        let mut interp = Interp::new(vec![0, 2, 1], vec![0.0, 1.0, 2.0]);
+        assert_eq!(
+            interp.simplify_inplace(1.0),
+            Err(InterpError::NotStrictlyIncreasing)
+        );
+        assert_eq!(interp.xp, vec![0, 2, 1]);
+        let mut interp = Interp::new(vec![1, 0], vec![0.0, 1.0]);
         assert_eq!(
-            interp.inverse(31, Method::None),
-            Err(InterpError::OutOfBounds)
+            interp.simplify_inplace(1.0),
+            Err(InterpError::NotStrictlyIncreasing)
         );
+        let mut interp = Interp::<u64, f64>::new(vec![], vec![]);
+        interp.simplify_inplace(1.0).unwrap();
+        assert!(interp.is_empty());
     }
 
     #[test]
-    fn test_inverse_round_unsigned() {
-        let xp: Vec<u64> = vec![0, 5];
-        let fp: Vec<u64> = vec![20, 30];
-        let interp = Interp::new(xp, fp);
-        assert_eq!(interp.inverse(19, Method::Nearest), Ok(0));
-        assert_eq!(interp.inverse(20, Method::Nearest), Ok(0));
-        assert_eq!(interp.inverse(21, Method::Nearest), Ok(0));
-        assert_eq!(interp.inverse(22, Method::Nearest), Ok(1));
-        assert_eq!(interp.inverse(23, Method::Nearest), Ok(2));
-        assert_eq!(interp.inverse(24, Method::Nearest), Ok(2));
-        assert_eq!(interp.inverse(25, Method::Nearest), Ok(2));
-        assert_eq!(interp.inverse(26, Method::Nearest), Ok(3));
-        assert_eq!(interp.inverse(30, Method::Nearest), Ok(5));
-        assert_eq!(interp.inverse(31, Method::Nearest), Ok(5));
+    fn test_simplify_ties() {
+        // Symmetric values: the three interior data points tie on the first chord and the
+        // smallest index is kept, the result being asymmetric.
+        let interp = Interp::new((0..5).collect(), vec![0i64, 6, 6, 6, 0]);
+        assert_eq!(interp.simplify(3).unwrap().xp, vec![0, 1, 3, 4]);
+        assert_eq!(interp.simplify(4).unwrap().xp, vec![0, 1, 4]);
+        assert_eq!(interp.simplify(5).unwrap().xp, vec![0, 1, 4]);
+        assert_eq!(interp.simplify(6).unwrap().xp, vec![0, 4]);
+        let reversed = Interp::new((0..5).collect(), vec![0i64, -6, -6, -6, 0]);
+        assert_eq!(reversed.simplify(4).unwrap().xp, vec![0, 1, 4]);
+
+        // Ties on the deviation at every level.
+        let interp = Interp::new((0..5).collect(), vec![0i64, 6, 0, 6, 0]);
+        assert_eq!(interp.simplify(3).unwrap().xp, vec![0, 1, 2, 3, 4]);
+        assert_eq!(interp.simplify(6).unwrap().xp, vec![0, 4]);
+
+        // Deviations equal to epsilon are dropped, with floats as well.
+        let interp = Interp::new(vec![0, 2, 4], vec![0.0, 1.5, 0.0]);
+        assert_eq!(interp.simplify(1.5).unwrap().xp, vec![0, 4]);
+        assert_eq!(interp.simplify(1.25).unwrap().xp, vec![0, 2, 4]);
+
+        // Evaluation order does not matter.
+        let deviation = |position: usize| Some([1, 3, 2, 3, 3][position]);
+        assert_eq!(farthest(0..5, deviation, &0), Some(1));
+        assert_eq!(farthest((0..5).rev(), deviation, &0), Some(1));
+        assert_eq!(farthest((0..5).rev(), deviation, &3), None);
+        let deviation = |position: usize| [Some(1), None, Some(2), None][position];
+        assert_eq!(farthest((0..4).rev(), deviation, &0), Some(1));
+    }
+
+    /// Alternating values of growing amplitude: the farthest data point from any chord is next
+    /// to its end, so that each step of the Douglas-Peucker recursion only peels off one data
+    /// point.
+    fn zigzag(len: u64) -> Interp<u64, f64> {
+        let fp = (0..len)
+            .map(|x| if x % 2 == 0 { x as f64 } else { -(x as f64) })
+            .collect();
+        Interp::new((0..len).collect(), fp)
     }
 
     #[test]
-    fn test_inverse_ffill_unsigned() {
-        let xp: Vec<u64> = vec![0, 5];
-        let fp: Vec<u64> = vec![20, 30];
-        let interp = Interp::new(xp, fp);
+    fn test_simplify_adversarial() {
+        let interp = zigzag(4000);
+        for epsilon in [0.0, 1000.0, 3000.0, 1e9] {
+            let simplified = interp.simplify(epsilon).unwrap();
+            assert_eq!(simplified.xp.first(), Some(&0));
+            assert_eq!(simplified.xp.last(), Some(&3999));
+            for (x, f) in interp.xp.iter().zip(&interp.fp) {
+                assert!((simplified.forward(*x).unwrap() - f).abs() <= epsilon);
+            }
+        }
+        assert_eq!(interp.simplify(0.0).unwrap().xp.len(), 4000);
+        assert!(interp.simplify(1000.0).unwrap().xp.len() < 4000);
+        assert_eq!(interp.simplify(1e9).unwrap().xp, vec![0, 3999]);
+    }
+
+    #[test]
+    fn test_epsilon_sweep() {
+        /// Runs `simplify` for each tolerance and measures the result.
+        fn independent(interp: &Interp<u64, i64>, epsilons: &[u64]) -> Vec<(u64, usize, u64)> {
+            epsilons
+                .iter()
+                .map(|epsilon| {
+                    let simplified = interp.simplify(*epsilon).unwrap();
+                    let error = interp
+                        .iter()
+                        .map(|(x, f)| simplified.forward(*x).unwrap().abs_diff(*f))
+                        .max()
+                        .unwrap();
+                    (*epsilon, simplified.len(), error)
+                })
+                .collect()
+        }
+        let interp = Interp::new(vec![0, 1, 2, 3, 4, 5], vec![0i64, 2, 4, 7, 8, 10]);
+        let sweep = interp.epsilon_sweep(&[0, 1, 2, 10]).unwrap();
+        assert_eq!(sweep, vec![(0, 4, 0), (1, 2, 1), (2, 2, 1), (10, 2, 1)]);
+        let interp = Interp::new((0..5).collect(), vec![0i64, 6, 6, 6, 0]);
+        let epsilons: Vec<u64> = (0..8).rev().collect();
         assert_eq!(
-            interp.inverse(19, Method::ForwardFill),
-            Err(InterpError::OutOfBounds)
+            interp.epsilon_sweep(&epsilons).unwrap(),
+            independent(&interp, &epsilons)
+        );
+
+        let mut rng = Rng(0x9e3779b97f4a7c15);
+        let epsilons = [0, 1, 2, 5, 10, 20, 50, 100, 1000];
+        for _ in 0..100 {
+            let len = 2 + rng.below(200);
+            let mut value = 0i64;
+            let fp = (0..len)
+                .map(|_| {
+                    value += rng.below(41) as i64 - 20;
+                    value
+                })
+                .collect();
+            let interp = Interp::new((0..len).map(|x| 3 * x).collect(), fp);
+            assert_eq!(
+                interp.epsilon_sweep(&epsilons).unwrap(),
+                independent(&interp, &epsilons)
+            );
+        }
+
+        // Beyond the work limit, the sweep agrees with the greedy fallback of simplify.
+        let interp = zigzag(300);
+        let sweep = interp.epsilon_sweep(&[0.0, 100.0, 1e9]).unwrap();
+        for (epsilon, count, error) in sweep {
+            let simplified = interp.simplify(epsilon).unwrap();
+            assert_eq!(count, simplified.len());
+            let deviations = interp
+                .iter()
+                .map(|(x, f)| (simplified.forward(*x).unwrap() - f).abs());
+            assert_eq!(Some(error), deviations.reduce(f64::max));
+        }
+
+        let interp = Interp::new(vec![0, 10], vec![0.0, 1.0]);
+        assert_eq!(interp.epsilon_sweep(&[0.5]), Ok(vec![(0.5, 2, 0.0)]));
+        assert_eq!(interp.epsilon_sweep(&[]), Ok(vec![]));
+        let empty = Interp::<u64, f64>::new(vec![], vec![]);
+        assert_eq!(empty.epsilon_sweep(&[0.5]), Err(InterpError::OutOfBounds));
+        let unsorted = Interp::new(vec![10, 0], vec![0.0, 1.0]);
+        assert_eq!(
+            unsorted.epsilon_sweep(&[0.5]),
+            Err(InterpError::NotStrictlyIncreasing)
         );
-        assert_eq!(interp.inverse(20, Method::ForwardFill), Ok(0));
-        assert_eq!(interp.inverse(21, Method::ForwardFill), Ok(0));
-        assert_eq!(interp.inverse(22, Method::ForwardFill), Ok(1));
-        assert_eq!(interp.inverse(23, Method::ForwardFill), Ok(1));
-        assert_eq!(interp.inverse(24, Method::ForwardFill), Ok(2));
-        assert_eq!(interp.inverse(25, Method::ForwardFill), Ok(2));
-        assert_eq!(interp.inverse(26, Method::ForwardFill), Ok(3));
-        assert_eq!(interp.inverse(30, Method::ForwardFill), Ok(5));
-        assert_eq!(interp.inverse(31, Method::ForwardFill), Ok(5));
     }
 
     #[test]
-    fn test_inverse_bfill_unsigned() {
-        let xp: Vec<u64> = vec![0, 5];
-        let fp: Vec<u64> = vec![20, 30];
-        let interp = Interp::new(xp, fp);
-        assert_eq!(interp.inverse(19, Method::BackwardFill), Ok(0));
-        assert_eq!(interp.inverse(20, Method::BackwardFill), Ok(0));
-        assert_eq!(interp.inverse(21, Method::BackwardFill), Ok(1));
-        assert_eq!(interp.inverse(22, Method::BackwardFill), Ok(1));
-        assert_eq!(interp.inverse(23, Method::BackwardFill), Ok(2));
-        assert_eq!(interp.inverse(24, Method::BackwardFill), Ok(2));
-        assert_eq!(interp.inverse(25, Method::BackwardFill), Ok(3));
-        assert_eq!(interp.inverse(26, Method::BackwardFill), Ok(3));
-        assert_eq!(interp.inverse(30, Method::BackwardFill), Ok(5));
-        assert_eq!(
-            interp.inverse(31, Method::BackwardFill),
-            Err(InterpError::OutOfBounds)
+    #[ignore = "benchmark, run with --release --ignored"]
+    fn bench_simplify_adversarial() {
+        // The plain recursion needs about 5e9 evaluations on the large input, 100 times more
+        // than on the small one, whereas n log n grows about 12.5 times.
+        let small = zigzag(10_000);
+        let start = std::time::Instant::now();
+        small.simplify(1000.0).unwrap();
+        let small_elapsed = start.elapsed();
+        let interp = zigzag(100_000);
+        let start = std::time::Instant::now();
+        let simplified = interp.simplify(1000.0).unwrap();
+        let elapsed = start.elapsed();
+        assert!(simplified.xp.len() < interp.xp.len());
+        assert!(
+            elapsed < small_elapsed * 30,
+            "{elapsed:?} vs {small_elapsed:?}"
         );
     }
 
     #[test]
-    fn test_inverse_exact_signed() {
-        let xp: Vec<u64> = vec![0, 5];
-        let fp: Vec<i64> = vec![-30, -20];
-        let interp = Interp::new(xp, fp);
+    fn test_decimate() {
+        let interp = Interp::new(vec![0u64, 1, 2, 3, 4, 5, 6], vec![0i64, 1, 0, 1, 0, 1, 0]);
+        assert_eq!(interp.decimate(1).unwrap(), interp);
+        assert_eq!(interp.decimate(2).unwrap().xp(), &[0, 2, 4, 6]);
+        assert_eq!(interp.max_error(&interp.decimate(2).unwrap()), Ok((1, 1)));
+        // The last data point is kept even off the step.
+        assert_eq!(interp.decimate(4).unwrap().xp(), &[0, 4, 6]);
+        assert_eq!(interp.decimate(4).unwrap().fp(), &[0, 0, 0]);
+        assert_eq!(interp.decimate(6).unwrap().xp(), &[0, 6]);
+        assert_eq!(interp.decimate(100).unwrap().xp(), &[0, 6]);
+        let single = Interp::new(vec![3], vec![1.5]);
+        assert_eq!(single.decimate(5).unwrap(), single);
+        let empty: Interp<u64, f64> = Interp::new(vec![], vec![]);
+        assert_eq!(empty.decimate(2).unwrap(), empty);
         assert_eq!(
-            interp.inverse(-31, Method::None),
-            Err(InterpError::OutOfBounds)
+            interp.decimate(0),
+            Err(InterpError::InvalidArgument("k must be positive"))
         );
-        assert_eq!(interp.inverse(-30, Method::None), Ok(0));
+
+        assert_eq!(interp.decimate_to(7).unwrap(), interp);
+        assert_eq!(interp.decimate_to(100).unwrap(), interp);
+        assert_eq!(interp.decimate_to(6).unwrap().xp(), &[0, 2, 4, 6]);
+        assert_eq!(interp.decimate_to(4).unwrap().xp(), &[0, 2, 4, 6]);
+        assert_eq!(interp.decimate_to(3).unwrap().xp(), &[0, 3, 6]);
+        assert_eq!(interp.decimate_to(2).unwrap().xp(), &[0, 6]);
         assert_eq!(
-            interp.inverse(-29, Method::None),
-            Err(InterpError::NotFound)
+            interp.decimate_to(1),
+            Err(InterpError::InvalidArgument("n must be at least 2"))
         );
-        assert_eq!(interp.inverse(-28, Method::None), Ok(1));
+        for n in 2..=7 {
+            assert!(interp.decimate_to(n).unwrap().xp().len() <= n);
+        }
+    }
+
+    #[test]
+    fn test_simplify2() {
+        // A shallow segment with a point off by 0.1 in value but 10 in index, followed by a
+        // steep segment with a point off by 40 in value but less than 0.5 in index.
+        let xp: Vec<u64> = vec![0, 50, 100, 110, 120];
+        let fp: Vec<f64> = vec![0.0, 0.6, 1.0, 1041.0, 2001.0];
+        let interp = Interp::new(xp, fp);
         assert_eq!(
-            interp.inverse(-27, Method::None),
-            Err(InterpError::NotFound)
+            interp.simplify2(5, 100.0).unwrap().xp,
+            vec![0, 50, 100, 120]
         );
-        assert_eq!(interp.inverse(-26, Method::None), Ok(2));
         assert_eq!(
-            interp.inverse(-25, Method::None),
-            Err(InterpError::NotFound)
+            interp.simplify2(20, 10.0).unwrap().xp,
+            vec![0, 100, 110, 120]
         );
-        assert_eq!(interp.inverse(-24, Method::None), Ok(3));
-        assert_eq!(interp.inverse(-20, Method::None), Ok(5));
+        assert_eq!(interp.simplify2(20, 100.0).unwrap().xp, vec![0, 100, 120]);
         assert_eq!(
-            interp.inverse(-19, Method::None),
-            Err(InterpError::OutOfBounds)
+            interp.simplify2(5, 10.0).unwrap().xp,
+            vec![0, 50, 100, 110, 120]
         );
-    }
+        assert_eq!(interp.simplify(10.0).unwrap().xp, vec![0, 100, 110, 120]);
 
-    #[test]
-    fn test_inverse_round_signed() {
-        let xp: Vec<u64> = vec![0, 5];
-        let fp: Vec<i64> = vec![-30, -20];
+        // Decreasing values and values outside the range of the chord.
+        let xp: Vec<u64> = vec![0, 5, 10, 15, 20];
+        let fp: Vec<i64> = vec![100, 70, 50, 25, 0];
         let interp = Interp::new(xp, fp);
-        assert_eq!(interp.inverse(-31, Method::Nearest), Ok(0));
-        assert_eq!(interp.inverse(-30, Method::Nearest), Ok(0));
-        assert_eq!(interp.inverse(-29, Method::Nearest), Ok(0));
-        assert_eq!(interp.inverse(-28, Method::Nearest), Ok(1));
-        assert_eq!(interp.inverse(-27, Method::Nearest), Ok(2));
-        assert_eq!(interp.inverse(-26, Method::Nearest), Ok(2));
-        assert_eq!(interp.inverse(-25, Method::Nearest), Ok(2));
-        assert_eq!(interp.inverse(-24, Method::Nearest), Ok(3));
-        assert_eq!(interp.inverse(-20, Method::Nearest), Ok(5));
-        assert_eq!(interp.inverse(-19, Method::Nearest), Ok(5));
+        assert_eq!(interp.simplify2(0, 10).unwrap().xp, vec![0, 5, 10, 20]);
+        assert_eq!(interp.simplify2(1, 10).unwrap().xp, vec![0, 20]);
+        let xp: Vec<u64> = vec![0, 5, 10];
+        let fp: Vec<i64> = vec![0, 20, 10];
+        let interp = Interp::new(xp, fp);
+        assert_eq!(interp.simplify2(100, 100).unwrap().xp, vec![0, 5, 10]);
     }
 
     #[test]
-    fn test_inverse_ffill_signed() {
-        let xp: Vec<u64> = vec![0, 5];
-        let fp: Vec<i64> = vec![-30, -20];
-        let interp = Interp::new(xp, fp);
-        assert_eq!(
-            interp.inverse(-31, Method::ForwardFill),
-            Err(InterpError::OutOfBounds)
-        );
-        assert_eq!(interp.inverse(-30, Method::ForwardFill), Ok(0));
-        assert_eq!(interp.inverse(-29, Method::ForwardFill), Ok(0));
-        assert_eq!(interp.inverse(-28, Method::ForwardFill), Ok(1));
-        assert_eq!(interp.inverse(-27, Method::ForwardFill), Ok(1));
-        assert_eq!(interp.inverse(-26, Method::ForwardFill), Ok(2));
-        assert_eq!(interp.inverse(-25, Method::ForwardFill), Ok(2));
-        assert_eq!(interp.inverse(-24, Method::ForwardFill), Ok(3));
-        assert_eq!(interp.inverse(-20, Method::ForwardFill), Ok(5));
-        assert_eq!(interp.inverse(-19, Method::ForwardFill), Ok(5));
+    fn test_simplify_robust() {
+        let xp: Vec<u64> = (0..=20).collect();
+        let clean: Vec<i64> = (0..=20)
+            .map(|x| if x <= 10 { 2 * x } else { 20 + 5 * (x - 10) })
+            .collect();
+        let interp = Interp::new(xp.clone(), clean.clone());
+        assert_eq!(interp.simplify(0).unwrap().xp, vec![0, 10, 20]);
+        let (simplified, outliers) = interp.simplify_robust(0, 3).unwrap();
+        assert_eq!(simplified.xp, vec![0, 10, 20]);
+        assert!(outliers.is_empty());
+
+        let mut spiked = clean.clone();
+        spiked[5] += 100;
+        spiked[15] -= 50;
+        let interp = Interp::new(xp.clone(), spiked);
+        assert_eq!(interp.simplify(0).unwrap().xp.len(), 9);
+        let (simplified, outliers) = interp.simplify_robust(0, 3).unwrap();
+        assert_eq!(simplified.xp, vec![0, 10, 20]);
+        assert_eq!(simplified.fp, vec![0, 20, 70]);
+        assert_eq!(outliers, vec![5, 15]);
+        let (simplified, outliers) = interp.simplify_robust(0, 1).unwrap();
+        assert_eq!(simplified.xp, vec![0, 10, 14, 15, 16, 20]);
+        assert_eq!(outliers, vec![5]);
+        let (simplified, outliers) = interp.simplify_robust(0, 0).unwrap();
+        assert_eq!(simplified.xp.len(), 9);
+        assert!(outliers.is_empty());
     }
 
     #[test]
-    fn test_inverse_bfill_signed() {
-        let xp: Vec<u64> = vec![0, 5];
-        let fp: Vec<i64> = vec![-30, -20];
+    fn test_inverse_with_stats() {
+        let xp: Vec<u64> = vec![0, 10];
+        let fp: Vec<i64> = vec![0, 3];
         let interp = Interp::new(xp, fp);
-        assert_eq!(interp.inverse(-31, Method::BackwardFill), Ok(0));
-        assert_eq!(interp.inverse(-30, Method::BackwardFill), Ok(0));
-        assert_eq!(interp.inverse(-29, Method::BackwardFill), Ok(1));
-        assert_eq!(interp.inverse(-28, Method::BackwardFill), Ok(1));
-        assert_eq!(interp.inverse(-27, Method::BackwardFill), Ok(2));
-        assert_eq!(interp.inverse(-26, Method::BackwardFill), Ok(2));
-        assert_eq!(interp.inverse(-25, Method::BackwardFill), Ok(3));
-        assert_eq!(interp.inverse(-24, Method::BackwardFill), Ok(3));
-        assert_eq!(interp.inverse(-20, Method::BackwardFill), Ok(5));
+        let (x, stats) = interp
+            .inverse_with_stats(&[0, 1, 2, 3], Method::Nearest)
+            .unwrap();
+        assert_eq!(x, vec![0, 3, 7, 10]);
+        assert_eq!(stats.exact_count, 2);
+        assert_eq!(stats.rounded_count, 2);
+        assert_eq!(stats.max_abs_residual, Some(0));
+        let (_, stats) = interp.inverse_with_stats(&[], Method::Nearest).unwrap();
+        assert_eq!(stats.exact_count + stats.rounded_count, 0);
+        assert_eq!(stats.max_abs_residual, None);
         assert_eq!(
-            interp.inverse(-19, Method::BackwardFill),
-            Err(InterpError::OutOfBounds)
+            interp.inverse_with_stats(&[1], Method::None).err(),
+            Some(InterpError::NotFound)
         );
+
+        let xp: Vec<u64> = vec![0, 2];
+        let fp: Vec<f64> = vec![0.0, 10.0];
+        let interp = Interp::new(xp, fp);
+        let (x, stats) = interp
+            .inverse_with_stats(&[5.0, 6.0, 9.0], Method::ForwardFill)
+            .unwrap();
+        assert_eq!(x, vec![1, 1, 1]);
+        assert_eq!(stats.exact_count, 1);
+        assert_eq!(stats.rounded_count, 2);
+        assert_eq!(stats.max_abs_residual, Some(4.0));
     }
 
     #[test]
-    fn test_inverse_round_float() {
-        let xp: Vec<u64> = vec![0, 5];
-        let fp: Vec<f64> = vec![20.0, 30.0];
-        let interp = Interp::new(xp, fp);
-        assert_eq!(interp.inverse(19.9, Method::Nearest), Ok(0));
-        assert_eq!(interp.inverse(20.0, Method::Nearest), Ok(0));
-        assert_eq!(interp.inverse(20.1, Method::Nearest), Ok(0));
-        assert_eq!(interp.inverse(20.9, Method::Nearest), Ok(0));
-        assert_eq!(interp.inverse(21.1, Method::Nearest), Ok(1));
-        assert_eq!(interp.inverse(22.0, Method::Nearest), Ok(1));
-        assert_eq!(interp.inverse(29.9, Method::Nearest), Ok(5));
-        assert_eq!(interp.inverse(30.0, Method::Nearest), Ok(5));
-        assert_eq!(interp.inverse(30.1, Method::Nearest), Ok(5));
-        assert_eq!(interp.inverse(21.0, Method::Nearest), Ok(0));
-        assert_eq!(interp.inverse(23.0, Method::Nearest), Ok(2));
-        assert_eq!(interp.inverse(25.0, Method::Nearest), Ok(2));
-        assert_eq!(interp.inverse(27.0, Method::Nearest), Ok(4));
-        assert_eq!(interp.inverse(29.0, Method::Nearest), Ok(4));
+    fn test_forward_detailed() {
+        let interp = Interp::new(vec![0, 4, 7], vec![0.0, 1.0, 2.0]);
+        assert_eq!(interp.forward_detailed(4), Ok((1.0, true)));
+        assert_eq!(interp.forward_detailed(2), Ok((0.5, true)));
+        assert_eq!(interp.forward_detailed(1), Ok((0.25, true)));
+        let (value, exact) = interp.forward_detailed(5).unwrap();
+        assert!(!exact);
+        assert_eq!(Ok(value), interp.forward(5));
+        assert_eq!(interp.forward_detailed(8), Err(InterpError::OutOfBounds));
+        let interp = Interp::new(vec![0, 2], vec![1.0, 1.0 + f64::EPSILON]);
+        assert!(!interp.forward_detailed(1).unwrap().1);
     }
 
     #[test]
-    fn test_inverse_ffill_float() {
-        let xp: Vec<u64> = vec![0, 5];
-        let fp: Vec<f64> = vec![20.0, 30.0];
-        let interp = Interp::new(xp, fp);
+    fn test_verify() {
+        let values: Vec<i64> = (0..100)
+            .map(|x| 1_700_000_000_000_000_000 + 7 * x)
+            .collect();
+        let interp = Interp::new(vec![0, 40, 99], vec![values[0], values[40], values[99]]);
+        let report = interp.verify(&values, 0).unwrap();
+        assert_eq!(report.violations, 0);
+        assert_eq!(report.max_deviation, Some(0));
+        let mut noisy = values.clone();
+        noisy[17] += 5;
+        noisy[63] -= 3;
+        noisy[64] += 5;
+        let report = interp.verify(&noisy, 3).unwrap();
+        assert_eq!(report.violations, 2);
+        assert_eq!(report.max_deviation, Some(5));
+        assert_eq!(report.argmax, Some(17));
+        assert_eq!(interp.verify(&[], 0).unwrap().argmax, None);
         assert_eq!(
-            interp.inverse(19.9, Method::ForwardFill),
+            interp.verify(&[values[0]; 101], 0),
             Err(InterpError::OutOfBounds)
         );
-        assert_eq!(interp.inverse(20.0, Method::ForwardFill), Ok(0));
-        assert_eq!(interp.inverse(20.1, Method::ForwardFill), Ok(0));
-        assert_eq!(interp.inverse(20.9, Method::ForwardFill), Ok(0));
-        assert_eq!(interp.inverse(21.1, Method::ForwardFill), Ok(0));
-        assert_eq!(interp.inverse(22.0, Method::ForwardFill), Ok(1));
-        assert_eq!(interp.inverse(29.9, Method::ForwardFill), Ok(4));
-        assert_eq!(interp.inverse(30.0, Method::ForwardFill), Ok(5));
-        assert_eq!(interp.inverse(30.1, Method::ForwardFill), Ok(5));
-        assert_eq!(interp.inverse(21.0, Method::ForwardFill), Ok(0));
-        assert_eq!(interp.inverse(23.0, Method::ForwardFill), Ok(1));
-        assert_eq!(interp.inverse(25.0, Method::ForwardFill), Ok(2));
-        assert_eq!(interp.inverse(27.0, Method::ForwardFill), Ok(3));
-        assert_eq!(interp.inverse(29.0, Method::ForwardFill), Ok(4));
+        let interp = Interp::new(vec![2, 99], vec![values[2], values[99]]);
+        assert_eq!(interp.verify(&values, 0), Err(InterpError::OutOfBounds));
+
+        let values: Vec<f64> = (0..10).map(|x| 0.5 * x as f64).collect();
+        let interp = Interp::new(vec![0, 9], vec![0.0, 4.5]);
+        let mut noisy = values.clone();
+        noisy[4] = f64::NAN;
+        noisy[6] += 0.25;
+        let report = interp.verify(&noisy, 0.1).unwrap();
+        assert_eq!(report.violations, 2);
+        assert_eq!(report.max_deviation, Some(0.25));
+        assert_eq!(report.argmax, Some(6));
+        let interp = interp.with_closed(Closed::Left);
+        assert_eq!(interp.verify(&values, 0.0), Err(InterpError::OutOfBounds));
+        assert!(interp.verify(&values[..9], 0.0).is_ok());
     }
 
     #[test]
-    fn test_inverse_bfill_float() {
+    fn test_closed() {
         let xp: Vec<u64> = vec![0, 5];
-        let fp: Vec<f64> = vec![20.0, 30.0];
-        let interp = Interp::new(xp, fp);
-        assert_eq!(interp.inverse(19.9, Method::BackwardFill), Ok(0));
-        assert_eq!(interp.inverse(20.0, Method::BackwardFill), Ok(0));
-        assert_eq!(interp.inverse(20.1, Method::BackwardFill), Ok(1));
-        assert_eq!(interp.inverse(20.9, Method::BackwardFill), Ok(1));
-        assert_eq!(interp.inverse(21.1, Method::BackwardFill), Ok(1));
-        assert_eq!(interp.inverse(22.0, Method::BackwardFill), Ok(1));
-        assert_eq!(interp.inverse(29.9, Method::BackwardFill), Ok(5));
-        assert_eq!(interp.inverse(30.0, Method::BackwardFill), Ok(5));
+        let fp: Vec<u64> = vec![20, 30];
+        let interp = Interp::new(xp.clone(), fp.clone());
+        assert_eq!(interp.forward(0), Ok(20));
+        assert_eq!(interp.forward(5), Ok(30));
+        assert_eq!(interp.inverse(20, Method::None), Ok(0));
+        assert_eq!(interp.inverse(20, Method::Nearest), Ok(0));
+        assert_eq!(interp.inverse(20, Method::ForwardFill), Ok(0));
+        assert_eq!(interp.inverse(20, Method::BackwardFill), Ok(0));
+        assert_eq!(interp.inverse(30, Method::None), Ok(5));
+        assert_eq!(interp.inverse(30, Method::Nearest), Ok(5));
+        assert_eq!(interp.inverse(30, Method::ForwardFill), Ok(5));
+        assert_eq!(interp.inverse(30, Method::BackwardFill), Ok(5));
+
+        let interp = Interp::new(xp.clone(), fp.clone()).with_closed(Closed::Left);
+        assert_eq!(interp.forward(0), Ok(20));
+        assert_eq!(interp.forward(4), Ok(28));
+        assert_eq!(interp.forward(5), Err(InterpError::OutOfBounds));
+        assert_eq!(interp.inverse(20, Method::None), Ok(0));
+        assert_eq!(interp.inverse(20, Method::Nearest), Ok(0));
+        assert_eq!(interp.inverse(20, Method::ForwardFill), Ok(0));
+        assert_eq!(interp.inverse(20, Method::BackwardFill), Ok(0));
         assert_eq!(
-            interp.inverse(30.1, Method::BackwardFill),
+            interp.inverse(30, Method::None),
+            Err(InterpError::OutOfBounds)
+        );
+        assert_eq!(interp.inverse(30, Method::Nearest), Ok(5));
+        assert_eq!(interp.inverse(30, Method::ForwardFill), Ok(5));
+        assert_eq!(
+            interp.inverse(30, Method::BackwardFill),
+            Err(InterpError::OutOfBounds)
+        );
+        assert_eq!(interp.inverse(29, Method::BackwardFill), Ok(5));
+        assert_eq!(interp.inverse_fractional(30), Err(InterpError::OutOfBounds));
+
+        let interp = Interp::new(xp.clone(), fp.clone()).with_closed(Closed::Right);
+        assert_eq!(interp.forward(0), Err(InterpError::OutOfBounds));
+        assert_eq!(interp.forward(1), Ok(22));
+        assert_eq!(interp.forward(5), Ok(30));
+        assert_eq!(
+            interp.inverse(20, Method::None),
+            Err(InterpError::OutOfBounds)
+        );
+        assert_eq!(interp.inverse(20, Method::Nearest), Ok(0));
+        assert_eq!(
+            interp.inverse(20, Method::ForwardFill),
+            Err(InterpError::OutOfBounds)
+        );
+        assert_eq!(interp.inverse(20, Method::BackwardFill), Ok(0));
+        assert_eq!(interp.inverse(21, Method::ForwardFill), Ok(0));
+        assert_eq!(interp.inverse(30, Method::None), Ok(5));
+        assert_eq!(interp.inverse(30, Method::Nearest), Ok(5));
+        assert_eq!(interp.inverse(30, Method::ForwardFill), Ok(5));
+        assert_eq!(interp.inverse(30, Method::BackwardFill), Ok(5));
+        assert_eq!(interp.inverse_fractional(20), Err(InterpError::OutOfBounds));
+        assert_eq!(interp.inverse_fractional(25), Ok(2.5));
+
+        let float = Interp::new(xp.clone(), vec![20.0, 30.0]);
+        assert_eq!(
+            float.clone().with_closed(Closed::Left).forward_at(5.0),
+            Err(InterpError::OutOfBounds)
+        );
+        assert_eq!(
+            float.clone().with_closed(Closed::Left).forward_at(4.5),
+            Ok(29.0)
+        );
+        assert_eq!(
+            float.clone().with_closed(Closed::Right).forward_at(0.0),
+            Err(InterpError::OutOfBounds)
+        );
+        assert_eq!(float.with_closed(Closed::Right).forward_at(0.5), Ok(21.0));
+
+        let fp: Vec<u64> = vec![30, 20];
+        let interp = Interp::new(xp, fp).with_closed(Closed::Left);
+        assert_eq!(interp.inverse(30, Method::None), Ok(0));
+        assert_eq!(
+            interp.inverse(20, Method::None),
             Err(InterpError::OutOfBounds)
         );
-        assert_eq!(interp.inverse(21.0, Method::BackwardFill), Ok(1));
-        assert_eq!(interp.inverse(23.0, Method::BackwardFill), Ok(2));
-        assert_eq!(interp.inverse(25.0, Method::BackwardFill), Ok(3));
-        assert_eq!(interp.inverse(27.0, Method::BackwardFill), Ok(4));
-        assert_eq!(interp.inverse(29.0, Method::BackwardFill), Ok(5));
     }
 
     #[test]
@@ -471,6 +6345,21 @@ mod tests {
         assert_eq!(interp.inverse(0, Method::ForwardFill), Ok(u64::MAX / 2 + 1));
     }
 
+    #[test]
+    fn test_inverse_full_span_big_numbers() {
+        let interp = Interp::new(vec![0, 3], vec![i64::MIN, i64::MAX]);
+        assert_eq!(interp.inverse(0, Method::None), Err(InterpError::NotFound));
+        assert_eq!(interp.inverse(0, Method::Nearest), Ok(2));
+        assert_eq!(interp.inverse(0, Method::ForwardFill), Ok(1));
+        assert_eq!(interp.inverse(0, Method::BackwardFill), Ok(2));
+        assert_eq!(interp.inverse(i64::MAX - 1, Method::ForwardFill), Ok(2));
+        let interp = Interp::new(vec![0, 3], vec![i64::MAX, i64::MIN]);
+        assert_eq!(interp.inverse(-1, Method::Nearest), Ok(2));
+        assert_eq!(interp.inverse(-1, Method::ForwardFill), Ok(1));
+        assert_eq!(interp.inverse(-1, Method::BackwardFill), Ok(2));
+        assert_eq!(interp.inverse(i64::MIN, Method::None), Ok(3));
+    }
+
     #[test]
     fn test_inverse_bfill_big_numbers() {
         let interp = Interp::new(vec![0, u64::MAX], vec![i64::MIN, i64::MAX]);
@@ -0,0 +1,368 @@
+//! Piecewise linear interpolation with exact rational slopes.
+//!
+//! Each segment between two consecutive data points stores its slope as an irreducible fraction
+//! of a value difference over an index difference. Forward and inverse interpolation are
+//! computed from the fraction with integer arithmetic only: the results are the same as the ones
+//! of `Interp`, but the segments can be inspected and certified, an exact forward result always
+//! maps back to the same index and converting back and forth with `Interp` never drifts.
+//!
+//! Only u64 indices and i64 values are supported, with the default settings of `Interp` (closed
+//! on both sides, out of bounds queries fail).
+
+use crate::divop::{DivOp, Method};
+use crate::piecewise::{Interp, InterpError, Order};
+
+/// Slope of a segment as an irreducible fraction, the index difference being positive.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Slope {
+    pub df: i64,
+    pub dx: u64,
+}
+
+impl Slope {
+    /// Reduces the slope between two data points to an irreducible fraction.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the Slope.
+    /// Otherwise, returns `NotStrictlyIncreasing` if the indices are not strictly increasing or
+    /// `Overflow` if the reduced value difference does not fit in an i64.
+    pub fn between(start: (u64, i64), end: (u64, i64)) -> Result<Slope, InterpError> {
+        let ((x0, f0), (x1, f1)) = (start, end);
+        if x0 >= x1 {
+            return Err(InterpError::NotStrictlyIncreasing);
+        }
+        let df = f1 as i128 - f0 as i128;
+        let dx = (x1 - x0) as u128;
+        let divisor = gcd(df.unsigned_abs(), dx);
+        Ok(Slope {
+            df: i64::try_from(df / divisor as i128).map_err(|_| InterpError::Overflow)?,
+            dx: (dx / divisor) as u64,
+        })
+    }
+}
+
+/// Structure for performing forward and inverse interpolation with exact rational slopes.
+#[derive(Clone, PartialEq, Debug)]
+pub struct RationalInterp {
+    xp: Vec<u64>,
+    fp: Vec<i64>,
+    slopes: Vec<Slope>,
+    order: Order,
+}
+
+impl RationalInterp {
+    /// Creates a new RationalInterp instance from the given data points.
+    ///
+    /// # Arguments
+    ///
+    /// * `xp` - Vector of indices, must be strictly increasing.
+    /// * `fp` - Vector of corresponding values.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the RationalInterp.
+    /// Otherwise, returns `LengthMismatch` if the lengths of `xp` and `fp` are not equal or an
+    /// error from `Slope::between` if a segment cannot be represented.
+    pub fn new(xp: Vec<u64>, fp: Vec<i64>) -> Result<RationalInterp, InterpError> {
+        if xp.len() != fp.len() {
+            return Err(InterpError::LengthMismatch(xp.len(), fp.len()));
+        }
+        let slopes = xp
+            .windows(2)
+            .zip(fp.windows(2))
+            .map(|(x, f)| Slope::between((x[0], f[0]), (x[1], f[1])))
+            .collect::<Result<Vec<Slope>, InterpError>>()?;
+        let order = if slopes.iter().all(|slope| slope.df > 0) {
+            Order::Increasing
+        } else if slopes.iter().all(|slope| slope.df < 0) {
+            Order::Decreasing
+        } else {
+            Order::Unordered
+        };
+        Ok(RationalInterp {
+            xp,
+            fp,
+            slopes,
+            order,
+        })
+    }
+    /// Creates a new RationalInterp instance from the data points of an Interp, its settings
+    /// being dropped.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the RationalInterp.
+    /// Otherwise, returns an error from `new`.
+    pub fn from_interp(interp: &Interp<u64, i64>) -> Result<RationalInterp, InterpError> {
+        RationalInterp::new(interp.xp().to_vec(), interp.fp().to_vec())
+    }
+    /// Returns an Interp with the same data points and the default settings.
+    pub fn to_interp(&self) -> Interp<u64, i64> {
+        Interp::new(self.xp.clone(), self.fp.clone())
+    }
+    /// Returns the indices of the data points.
+    pub fn xp(&self) -> &[u64] {
+        &self.xp
+    }
+    /// Returns the values of the data points.
+    pub fn fp(&self) -> &[i64] {
+        &self.fp
+    }
+    /// Returns the slopes of the segments, one less than data points (or none at all).
+    pub fn slopes(&self) -> &[Slope] {
+        &self.slopes
+    }
+    /// Performs forward interpolation at the given index.
+    ///
+    /// # Arguments
+    ///
+    /// * `rhs` - The index for forward interpolation.
+    /// * `method` - The rounding method to use in case of inexact values.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the interpolated value.
+    /// Otherwise, returns `OutOfBounds` if the index is beyond the data points or `NotFound`
+    /// if the value is inexact with `Method::None`.
+    pub fn forward(&self, rhs: u64, method: Method) -> Result<i64, InterpError> {
+        let index = match self.xp.binary_search(&rhs) {
+            Ok(index) => return Ok(self.fp[index]),
+            Err(index) if index == 0 || index == self.xp.len() => {
+                return Err(InterpError::OutOfBounds)
+            }
+            Err(index) => index - 1,
+        };
+        let (x0, f0, slope) = (self.xp[index], self.fp[index], self.slopes[index]);
+        // The parity of f0 is carried into the fraction so that ties are rounded to the even
+        // value, as the division of the whole value would do.
+        let parity = f0.rem_euclid(2);
+        let num = (parity as i128) * (slope.dx as i128) + (slope.df as i128) * ((rhs - x0) as i128);
+        let step = num
+            .div(slope.dx as i128, method)
+            .ok_or(InterpError::NotFound)?;
+        i64::try_from((f0 - parity) as i128 + step).map_err(|_| InterpError::Overflow)
+    }
+    /// Performs inverse interpolation at the given value, with the semantics of
+    /// `Interp::inverse`: the rounding methods refer to the direction of the indices and out of
+    /// bounds values are snapped to the extreme indices by the methods pointing inwards.
+    ///
+    /// # Arguments
+    ///
+    /// * `rhs` - The value for inverse interpolation.
+    /// * `method` - The rounding method to use in case of inexact matching.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the interpolated index.
+    /// Otherwise, returns `NotStrictlyMonotonic` if the values are not strictly monotonic or an
+    /// error indicating the reason for failure.
+    pub fn inverse(&self, rhs: i64, method: Method) -> Result<u64, InterpError> {
+        let search = match self.order {
+            Order::Increasing => self.fp.binary_search(&rhs),
+            Order::Decreasing => self.fp.binary_search_by(|f| rhs.cmp(f)),
+            Order::Unordered => return Err(InterpError::NotStrictlyMonotonic),
+        };
+        let index = match search {
+            Ok(index) => return Ok(self.xp[index]),
+            Err(0) => {
+                return match method {
+                    Method::None | Method::ForwardFill => Err(InterpError::OutOfBounds),
+                    Method::Nearest | Method::BackwardFill => {
+                        self.xp.first().copied().ok_or(InterpError::OutOfBounds)
+                    }
+                }
+            }
+            Err(len) if len == self.xp.len() => {
+                return match method {
+                    Method::None | Method::BackwardFill => Err(InterpError::OutOfBounds),
+                    Method::Nearest | Method::ForwardFill => Ok(self.xp[len - 1]),
+                }
+            }
+            Err(index) => index - 1,
+        };
+        let (x0, f0, slope) = (self.xp[index], self.fp[index], self.slopes[index]);
+        // Same parity trick as forward, the rounding applying to the index.
+        let parity = x0 % 2;
+        let df = slope.df.unsigned_abs() as u128;
+        let num = (parity as u128) * df + (rhs.abs_diff(f0) as u128) * (slope.dx as u128);
+        let step = num.div(df, method).ok_or(InterpError::NotFound)?;
+        u64::try_from((x0 - parity) as u128 + step).map_err(|_| InterpError::Overflow)
+    }
+}
+
+impl TryFrom<&Interp<u64, i64>> for RationalInterp {
+    type Error = InterpError;
+
+    fn try_from(interp: &Interp<u64, i64>) -> Result<RationalInterp, InterpError> {
+        RationalInterp::from_interp(interp)
+    }
+}
+
+impl From<&RationalInterp> for Interp<u64, i64> {
+    fn from(interp: &RationalInterp) -> Interp<u64, i64> {
+        interp.to_interp()
+    }
+}
+
+/// Computes the greatest common divisor of two integers, the other one if either is zero.
+fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+
+    const METHODS: [Method; 4] = [
+        Method::None,
+        Method::Nearest,
+        Method::ForwardFill,
+        Method::BackwardFill,
+    ];
+
+    /// Random strictly increasing indices and either monotonic or arbitrary values.
+    fn random_points(rng: &mut Rng, scale: u64) -> (Vec<u64>, Vec<i64>) {
+        let len = 2 + rng.below(8) as usize;
+        let sign = [1, -1, 0][rng.below(3) as usize];
+        let mut x = rng.below(scale);
+        let mut f = rng.below(scale) as i64 - (scale / 2) as i64;
+        let (mut xp, mut fp) = (vec![x], vec![f]);
+        for _ in 1..len {
+            x += 1 + rng.below(scale);
+            f += match sign {
+                0 => rng.below(scale) as i64 - (scale / 2) as i64,
+                sign => sign * (1 + rng.below(scale) as i64),
+            };
+            xp.push(x);
+            fp.push(f);
+        }
+        (xp, fp)
+    }
+
+    #[test]
+    fn test_agrees_with_interp() {
+        let mut rng = Rng(0x9e3779b97f4a7c15);
+        for scale in [10, 1_000, 1 << 40, 1 << 58] {
+            for _ in 0..200 {
+                let (xp, fp) = random_points(&mut rng, scale);
+                let interp = Interp::new(xp.clone(), fp.clone());
+                let rational = RationalInterp::new(xp.clone(), fp.clone()).unwrap();
+                let (first, last) = (xp[0], xp[xp.len() - 1]);
+                for _ in 0..20 {
+                    let x = first.saturating_sub(1) + rng.below(last - first + 3);
+                    for method in METHODS {
+                        assert_eq!(
+                            rational.forward(x, method),
+                            interp.forward_with(x, method),
+                            "{xp:?} {fp:?} {x} {method:?}"
+                        );
+                    }
+                    let f = fp[0] + rng.below(scale) as i64 - (scale / 2) as i64;
+                    for method in METHODS {
+                        assert_eq!(
+                            rational.inverse(f, method),
+                            interp.inverse(f, method),
+                            "{xp:?} {fp:?} {f} {method:?}"
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut rng = Rng(0x2545f4914f6cdd1d);
+        for scale in [10, 1_000, 1 << 40, 1 << 58] {
+            for _ in 0..200 {
+                let (xp, fp) = random_points(&mut rng, scale);
+                let rational = RationalInterp::new(xp.clone(), fp).unwrap();
+                let interp = rational.to_interp();
+                assert_eq!(RationalInterp::from_interp(&interp), Ok(rational.clone()));
+                if !interp.is_inversable() {
+                    continue;
+                }
+                for x in xp[0]..=xp[0] + 100.min(xp[xp.len() - 1] - xp[0]) {
+                    if let Ok(f) = rational.forward(x, Method::None) {
+                        assert_eq!(rational.inverse(f, Method::None), Ok(x));
+                    }
+                }
+                for _ in 0..20 {
+                    let x = xp[0] + rng.below(xp[xp.len() - 1] - xp[0] + 1);
+                    if let Ok(f) = rational.forward(x, Method::None) {
+                        assert_eq!(rational.inverse(f, Method::None), Ok(x));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_slopes() {
+        let rational = RationalInterp::new(vec![0, 10, 16, 19], vec![5, 9, 0, 0]).unwrap();
+        assert_eq!(
+            rational.slopes(),
+            &[
+                Slope { df: 2, dx: 5 },
+                Slope { df: -3, dx: 2 },
+                Slope { df: 0, dx: 1 }
+            ]
+        );
+        assert_eq!(rational.forward(5, Method::None), Ok(7));
+        assert_eq!(
+            rational.forward(3, Method::None),
+            Err(InterpError::NotFound)
+        );
+        assert_eq!(rational.forward(18, Method::None), Ok(0));
+        assert_eq!(
+            rational.inverse(7, Method::None),
+            Err(InterpError::NotStrictlyMonotonic)
+        );
+        let interp: Interp<u64, i64> = (&rational).into();
+        assert_eq!(RationalInterp::try_from(&interp), Ok(rational));
+    }
+
+    #[test]
+    fn test_new() {
+        assert_eq!(
+            RationalInterp::new(vec![0, 1], vec![0]),
+            Err(InterpError::LengthMismatch(2, 1))
+        );
+        assert_eq!(
+            RationalInterp::new(vec![1, 1], vec![0, 1]),
+            Err(InterpError::NotStrictlyIncreasing)
+        );
+        assert_eq!(
+            RationalInterp::new(vec![0, 1], vec![i64::MIN, i64::MAX]),
+            Err(InterpError::Overflow)
+        );
+        let wide = RationalInterp::new(vec![0, 2], vec![i64::MIN, i64::MAX - 1]).unwrap();
+        assert_eq!(
+            wide.slopes(),
+            &[Slope {
+                df: i64::MAX,
+                dx: 1
+            }]
+        );
+        assert_eq!(wide.forward(1, Method::None), Ok(-1));
+        assert_eq!(wide.inverse(-1, Method::None), Ok(1));
+        let single = RationalInterp::new(vec![5], vec![3]).unwrap();
+        assert_eq!(single.forward(5, Method::None), Ok(3));
+        assert_eq!(single.inverse(3, Method::None), Ok(5));
+        assert_eq!(single.inverse(4, Method::Nearest), Ok(5));
+        let empty = RationalInterp::new(vec![], vec![]).unwrap();
+        assert_eq!(
+            empty.forward(0, Method::None),
+            Err(InterpError::OutOfBounds)
+        );
+        assert_eq!(
+            empty.inverse(0, Method::Nearest),
+            Err(InterpError::OutOfBounds)
+        );
+    }
+}
@@ -1,5 +1,6 @@
 //! Forward and backward linear interpolation schemes between two points (x0, f0) and (x1, f1) for
-//! different data types (x is u64, f is either i64 or f64).
+//! different data types (x is u64, f is either i64, f64 or F80), and bilinear interpolation
+//! within a cell of four points.
 //!
 //! When the values are integers, operations are performed with u128 integers to avoid overflow.
 //! Signed integers are mapped on positive values to avoid potential subtraction overflows
@@ -7,47 +8,196 @@
 //!
 //! When the values are floats, operations are performed with extended-precision floats to
 //! avoid big numbers inacurracies (integers above 2^53 cannot accurately be represented by f64
-//! which is problematic when using nanosecond datetime64 timestamps).
+//! which is problematic when using nanosecond datetime64 timestamps). Extended-precision values
+//! are not Copy, hence the inverse and distance traits only require Clone.
 
-use crate::divop::{DivOp, Method};
+use std::ops::Range;
+
+use crate::divop::{round_quotient, DivOp, Method};
 use crate::extended::F80;
 
 /// Implements forward scheme from index to value.
 pub trait Forward<F>: Copy + Ord {
-    /// Estimate f at index x between two points (x0, f0) and (x1, f1)
-    fn forward(self, x0: Self, x1: Self, f0: F, f1: F) -> F;
+    /// Estimate f at index x between two points (x0, f0) and (x1, f1). Integer values are
+    /// rounded according to `method` while floating values ignore it.
+    fn forward(self, x0: Self, x1: Self, f0: F, f1: F, method: Method) -> Option<F>;
 }
 impl Forward<u64> for u64 {
-    fn forward(self, x0: u64, x1: u64, f0: u64, f1: u64) -> u64 {
+    fn forward(self, x0: u64, x1: u64, f0: u64, f1: u64, method: Method) -> Option<u64> {
         let num = (f0 as u128) * ((x1 - self) as u128) + (f1 as u128) * ((self - x0) as u128);
         let den = (x1 - x0) as u128;
-        num.div(den, Method::Nearest).unwrap() as u64
+        num.div(den, method).map(|f| f as u64)
     }
 }
 impl Forward<i64> for u64 {
-    fn forward(self, x0: u64, x1: u64, f0: i64, f1: i64) -> i64 {
-        self.forward(x0, x1, f0.to_unsigned(), f1.to_unsigned())
-            .to_signed()
+    fn forward(self, x0: u64, x1: u64, f0: i64, f1: i64, method: Method) -> Option<i64> {
+        self.forward(x0, x1, f0.to_unsigned(), f1.to_unsigned(), method)
+            .map(|f| f.to_signed())
     }
 }
 impl Forward<f64> for u64 {
-    fn forward(self, x0: u64, x1: u64, f0: f64, f1: f64) -> f64 {
+    fn forward(self, x0: u64, x1: u64, f0: f64, f1: f64, _method: Method) -> Option<f64> {
         let x = F80::from(self);
         let x0 = F80::from(x0);
         let x1 = F80::from(x1);
+        let f0 = F80::from_finite(f0)?;
+        let f1 = F80::from_finite(f1)?;
+        f0.mul(&x1.sub(&x))
+            .add(&f1.mul(&x.sub(&x0)))
+            .checked_div(&x1.sub(&x0))
+            .map(|f| f.into())
+    }
+}
+impl Forward<F80> for u64 {
+    fn forward(self, x0: u64, x1: u64, f0: F80, f1: F80, _method: Method) -> Option<F80> {
+        let x = F80::from(self);
+        let x0 = F80::from(x0);
+        let x1 = F80::from(x1);
+        f0.mul(&x1.sub(&x))
+            .add(&f1.mul(&x.sub(&x0)))
+            .checked_div(&x1.sub(&x0))
+    }
+}
+
+/// Estimate f at index x between two points (x0, f0) and (x1, f1) as `Forward<f64>` does, also
+/// returning whether the result is exact: no extended-precision operation had to round and the
+/// result is exactly representable as f64. None if the segment is degenerate (`x0 == x1`) or if
+/// a value is not finite.
+pub fn forward_checked(x: u64, x0: u64, x1: u64, f0: f64, f1: f64) -> Option<(f64, bool)> {
+    if x1 == x0 {
+        return None;
+    }
+    let x = F80::from(x);
+    let x0 = F80::from(x0);
+    let x1 = F80::from(x1);
+    let f0 = F80::from_finite(f0)?;
+    let f1 = F80::from_finite(f1)?;
+    let (left, left_exact) = f0.mul_checked(&x1.sub(&x));
+    let (right, right_exact) = f1.mul_checked(&x.sub(&x0));
+    let (num, num_exact) = left.add_checked(&right);
+    let (f, f_exact) = num.div_checked(&x1.sub(&x0));
+    let out: f64 = f.clone().into();
+    let exact = left_exact
+        && right_exact
+        && num_exact
+        && f_exact
+        && F80::from_finite(out).is_some_and(|out| out == f);
+    Some((out, exact))
+}
+
+/// Implements forward scheme from real-valued index to value.
+pub trait ForwardAt<F> {
+    /// Estimate f at real-valued index x between two points (x0, f0) and (x1, f1). Integer
+    /// values are rounded according to `method` while floating values ignore it. None if x or
+    /// a value is not finite.
+    fn forward_at(self, x0: u64, x1: u64, f0: F, f1: F, method: Method) -> Option<F>;
+}
+impl ForwardAt<u64> for f64 {
+    fn forward_at(self, x0: u64, x1: u64, f0: u64, f1: u64, method: Method) -> Option<u64> {
+        let x = F80::from_finite(self)?;
+        let x0 = F80::from(x0);
+        let x1 = F80::from(x1);
         let f0 = F80::from(f0);
         let f1 = F80::from(f1);
+        let f = f0
+            .mul(&x1.sub(&x))
+            .add(&f1.mul(&x.sub(&x0)))
+            .checked_div(&x1.sub(&x0))?;
+        round_with(&f, method).map(|f| f.into())
+    }
+}
+impl ForwardAt<i64> for f64 {
+    fn forward_at(self, x0: u64, x1: u64, f0: i64, f1: i64, method: Method) -> Option<i64> {
+        self.forward_at(x0, x1, f0.to_unsigned(), f1.to_unsigned(), method)
+            .map(|f| f.to_signed())
+    }
+}
+impl ForwardAt<f64> for f64 {
+    fn forward_at(self, x0: u64, x1: u64, f0: f64, f1: f64, _method: Method) -> Option<f64> {
+        let x = F80::from_finite(self)?;
+        let x0 = F80::from(x0);
+        let x1 = F80::from(x1);
+        let f0 = F80::from_finite(f0)?;
+        let f1 = F80::from_finite(f1)?;
         f0.mul(&x1.sub(&x))
             .add(&f1.mul(&x.sub(&x0)))
-            .div(&x1.sub(&x0))
-            .into()
+            .checked_div(&x1.sub(&x0))
+            .map(f64::from)
+    }
+}
+
+/// Implements bilinear scheme from a pair of indices to a value.
+pub trait Bilinear: Sized {
+    /// Estimate f inside a cell given the values at its corners `[[f00, f01], [f10, f11]]` (rows
+    /// first), `i` and `j` being the offsets from the first corner along with the sizes of the
+    /// cell along rows and columns. Integer values are rounded to the nearest integer, None if
+    /// the weighted sum of the corners overflows or if a float corner is not finite.
+    fn bilinear(corners: [[Self; 2]; 2], i: (u64, u64), j: (u64, u64)) -> Option<Self>;
+}
+impl Bilinear for u64 {
+    fn bilinear(
+        [[f00, f01], [f10, f11]]: [[u64; 2]; 2],
+        (di, ni): (u64, u64),
+        (dj, nj): (u64, u64),
+    ) -> Option<u64> {
+        let (di, ni, dj, nj) = (di as u128, ni as u128, dj as u128, nj as u128);
+        let base = f00.min(f01).min(f10).min(f11);
+        let terms = [
+            (f00, (ni - di) * (nj - dj)),
+            (f01, (ni - di) * dj),
+            (f10, di * (nj - dj)),
+            (f11, di * dj),
+        ];
+        let mut num: u128 = 0;
+        for (f, weight) in terms {
+            num = num.checked_add(((f - base) as u128).checked_mul(weight)?)?;
+        }
+        let delta = num.div(ni * nj, Method::Nearest)?;
+        base.checked_add(u64::try_from(delta).ok()?)
+    }
+}
+impl Bilinear for i64 {
+    fn bilinear(corners: [[i64; 2]; 2], i: (u64, u64), j: (u64, u64)) -> Option<i64> {
+        let corners = corners.map(|row| row.map(|f| f.to_unsigned()));
+        u64::bilinear(corners, i, j).map(|f| f.to_signed())
+    }
+}
+impl Bilinear for f64 {
+    fn bilinear(corners: [[f64; 2]; 2], i: (u64, u64), j: (u64, u64)) -> Option<f64> {
+        let [[f00, f01], [f10, f11]] = corners;
+        let corners = [
+            [F80::from_finite(f00)?, F80::from_finite(f01)?],
+            [F80::from_finite(f10)?, F80::from_finite(f11)?],
+        ];
+        F80::bilinear(corners, i, j).map(f64::from)
+    }
+}
+impl Bilinear for F80 {
+    fn bilinear(
+        [[f00, f01], [f10, f11]]: [[F80; 2]; 2],
+        (di, ni): (u64, u64),
+        (dj, nj): (u64, u64),
+    ) -> Option<F80> {
+        let (di, ni, dj, nj) = (F80::from(di), F80::from(ni), F80::from(dj), F80::from(nj));
+        let (ri, rj) = (ni.sub(&di), nj.sub(&dj));
+        let num = f00
+            .mul(&ri.mul(&rj))
+            .add(&f01.mul(&ri.mul(&dj)))
+            .add(&f10.mul(&di.mul(&rj)))
+            .add(&f11.mul(&di.mul(&dj)));
+        num.checked_div(&ni.mul(&nj))
     }
 }
 
 /// Implements inverse scheme from value to index.
-pub trait Inverse<X>: Copy + PartialOrd {
-    /// Estimate x at values f between two points (x0, f0) and (x1, f1)
+pub trait Inverse<X>: Clone + PartialOrd {
+    /// Estimate x at values f between two points (x0, f0) and (x1, f1), None if no index
+    /// matches with the given method or if a float value is not finite.
     fn inverse(self, x0: X, x1: X, f0: Self, f1: Self, method: Method) -> Option<X>;
+    /// Estimate the real-valued x at value f between two points (x0, f0) and (x1, f1), None if
+    /// the segment is degenerate (`f0 == f1`) and f differs from f0 or if a float value is not
+    /// finite.
+    fn inverse_fractional(self, x0: X, x1: X, f0: Self, f1: Self) -> Option<f64>;
 }
 impl Inverse<u64> for u64 {
     fn inverse(self, x0: u64, x1: u64, f0: u64, f1: u64, method: Method) -> Option<u64> {
@@ -55,37 +205,396 @@ impl Inverse<u64> for u64 {
         let den = (f1 - f0) as u128;
         num.div(den, method).map(|x| x as u64)
     }
+    fn inverse_fractional(self, x0: u64, x1: u64, f0: u64, f1: u64) -> Option<f64> {
+        if self == f0 {
+            return Some(x0 as f64);
+        }
+        fractional(
+            F80::from(self),
+            F80::from(x0),
+            F80::from(x1),
+            F80::from(f0),
+            F80::from(f1),
+        )
+    }
 }
 impl Inverse<u64> for i64 {
     fn inverse(self, x0: u64, x1: u64, f0: i64, f1: i64, method: Method) -> Option<u64> {
         self.to_unsigned()
             .inverse(x0, x1, f0.to_unsigned(), f1.to_unsigned(), method)
     }
+    fn inverse_fractional(self, x0: u64, x1: u64, f0: i64, f1: i64) -> Option<f64> {
+        self.to_unsigned()
+            .inverse_fractional(x0, x1, f0.to_unsigned(), f1.to_unsigned())
+    }
 }
 impl Inverse<u64> for f64 {
     fn inverse(self, x0: u64, x1: u64, f0: f64, f1: f64, method: Method) -> Option<u64> {
-        let f = F80::from(self);
+        let f = F80::from_finite(self)?;
+        let f0 = F80::from_finite(f0)?;
+        let f1 = F80::from_finite(f1)?;
+        if method == Method::None {
+            return exact_inverse(&f, x0, x1, &f0, &f1);
+        }
         let x0 = F80::from(x0);
         let x1 = F80::from(x1);
-        let f0 = F80::from(f0);
-        let f1 = F80::from(f1);
         let x = x0
             .mul(&f1.sub(&f))
             .add(&x1.mul(&f.sub(&f0)))
-            .div(&f1.sub(&f0));
-        match method {
-            Method::None => {
-                let out = x.floor();
-                if out == x {
-                    Some(out.into())
+            .checked_div(&f1.sub(&f0))?;
+        x.to_u64(method)
+    }
+    fn inverse_fractional(self, x0: u64, x1: u64, f0: f64, f1: f64) -> Option<f64> {
+        if self == f0 {
+            return Some(x0 as f64);
+        }
+        fractional(
+            F80::from_finite(self)?,
+            F80::from(x0),
+            F80::from(x1),
+            F80::from_finite(f0)?,
+            F80::from_finite(f1)?,
+        )
+    }
+}
+impl Inverse<u64> for F80 {
+    fn inverse(self, x0: u64, x1: u64, f0: F80, f1: F80, method: Method) -> Option<u64> {
+        if method == Method::None {
+            return exact_inverse(&self, x0, x1, &f0, &f1);
+        }
+        let x0 = F80::from(x0);
+        let x1 = F80::from(x1);
+        let x = x0.add(&x1.sub(&x0).mul(&self.sub(&f0)).checked_div(&f1.sub(&f0))?);
+        round_with(&x, method).map(|x| x.into())
+    }
+    fn inverse_fractional(self, x0: u64, x1: u64, f0: F80, f1: F80) -> Option<f64> {
+        if self == f0 {
+            return Some(x0 as f64);
+        }
+        fractional(self, F80::from(x0), F80::from(x1), f0, f1)
+    }
+}
+/// Common type of values of mixed signedness (e.g. i64 queries against u64 values), in which both
+/// convert exactly. Interpolation is invariant by translation, hence the segment is shifted to
+/// start at zero where it fits in u64 since it joins two values of a 64-bit type.
+impl Inverse<u64> for i128 {
+    fn inverse(self, x0: u64, x1: u64, f0: i128, f1: i128, method: Method) -> Option<u64> {
+        let (f, f0, f1) = shifted(self, f0, f1)?;
+        f.inverse(x0, x1, f0, f1, method)
+    }
+    fn inverse_fractional(self, x0: u64, x1: u64, f0: i128, f1: i128) -> Option<f64> {
+        let (f, f0, f1) = shifted(self, f0, f1)?;
+        f.inverse_fractional(x0, x1, f0, f1)
+    }
+}
+
+/// Implements the arithmetic progressions of values inverted by `Interp::inverse_range`.
+pub trait Progression: Inverse<u64> {
+    /// Difference between consecutive values.
+    type Step: Copy;
+    /// Returns the value `start + k * step`, None if it is not representable.
+    fn nth(start: &Self, step: Self::Step, k: usize) -> Option<Self>;
+    /// Inverts the values `start + k * step` for k in `ks` as long as they lie strictly between
+    /// the values of the segment `[(x0, f0), (x1, f1)]` (f0 < f1), pushing to `out` the indices
+    /// that `inverse` gives. Returns the first k left uninverted, or Err(k) at the first value
+    /// that no index matches with the given method.
+    fn inverse_run(
+        start: &Self,
+        step: Self::Step,
+        ks: Range<usize>,
+        segment: [(u64, &Self); 2],
+        method: Method,
+        out: &mut Vec<u64>,
+    ) -> Result<usize, usize> {
+        let [(x0, f0), (x1, f1)] = segment;
+        for k in ks.clone() {
+            match Self::nth(start, step, k) {
+                Some(f) if *f0 < f && f < *f1 => {
+                    let x = f.inverse(x0, x1, f0.clone(), f1.clone(), method);
+                    out.push(x.ok_or(k)?);
+                }
+                _ => return Ok(k),
+            }
+        }
+        Ok(ks.end)
+    }
+}
+/// The first value of a run is divided, then the quotient and remainder of the numerator of
+/// `inverse` are stepped by `step * (x1 - x0)`, which avoids a u128 division per value.
+impl Progression for u64 {
+    type Step = i64;
+    fn nth(start: &u64, step: i64, k: usize) -> Option<u64> {
+        let value = (*start as i128).checked_add((k as i128).checked_mul(step as i128)?)?;
+        u64::try_from(value).ok()
+    }
+    fn inverse_run(
+        start: &u64,
+        step: i64,
+        ks: Range<usize>,
+        segment: [(u64, &u64); 2],
+        method: Method,
+        out: &mut Vec<u64>,
+    ) -> Result<usize, usize> {
+        let [(x0, &f0), (x1, &f1)] = segment;
+        let within = |k| u64::nth(start, step, k).filter(|f| f0 < *f && *f < f1);
+        let Some(f) = within(ks.start) else {
+            return Ok(ks.start);
+        };
+        let num = (x0 as u128) * ((f1 - f) as u128) + (x1 as u128) * ((f - f0) as u128);
+        let den = (f1 - f0) as u128;
+        let (mut div, mut rem) = (num / den, num % den);
+        let delta = (step.unsigned_abs() as u128) * (x1.abs_diff(x0) as u128);
+        let (delta_div, delta_rem) = (delta / den, delta % den);
+        let ascending = (step >= 0) == (x1 >= x0);
+        for k in ks.clone() {
+            if k > ks.start {
+                if within(k).is_none() {
+                    return Ok(k);
+                }
+                if ascending {
+                    div += delta_div;
+                    rem += delta_rem;
+                    if rem >= den {
+                        div += 1;
+                        rem -= den;
+                    }
                 } else {
-                    None
+                    div -= delta_div;
+                    if rem < delta_rem {
+                        div -= 1;
+                        rem += den;
+                    }
+                    rem -= delta_rem;
                 }
             }
-            Method::Nearest => Some(x.round().into()),
-            Method::ForwardFill => Some(x.floor().into()),
-            Method::BackwardFill => Some(x.ceil().into()),
+            let x = round_quotient(div, rem, den, method).ok_or(k)?;
+            out.push(x as u64);
         }
+        Ok(ks.end)
+    }
+}
+/// Runs on the unsigned translation of the values, in which the progression is unchanged.
+impl Progression for i64 {
+    type Step = i64;
+    fn nth(start: &i64, step: i64, k: usize) -> Option<i64> {
+        let value = (*start as i128).checked_add((k as i128).checked_mul(step as i128)?)?;
+        i64::try_from(value).ok()
+    }
+    fn inverse_run(
+        start: &i64,
+        step: i64,
+        ks: Range<usize>,
+        segment: [(u64, &i64); 2],
+        method: Method,
+        out: &mut Vec<u64>,
+    ) -> Result<usize, usize> {
+        let [(x0, f0), (x1, f1)] = segment;
+        let (f0, f1) = (f0.to_unsigned(), f1.to_unsigned());
+        u64::inverse_run(
+            &start.to_unsigned(),
+            step,
+            ks,
+            [(x0, &f0), (x1, &f1)],
+            method,
+            out,
+        )
+    }
+}
+/// The values are computed in extended precision and rounded once to the nearest f64.
+impl Progression for f64 {
+    type Step = f64;
+    fn nth(start: &f64, step: f64, k: usize) -> Option<f64> {
+        let value = F80::from_finite(step)?.mul(&F80::from(k as u64));
+        let value: f64 = F80::from_finite(*start)?.add(&value).into();
+        value.is_finite().then_some(value)
+    }
+}
+
+/// Translates a value and the values of its segment by the lowest of the latter, None if any of
+/// them does not fit in u64.
+fn shifted(f: i128, f0: i128, f1: i128) -> Option<(u64, u64, u64)> {
+    let low = f0.min(f1);
+    let shift = |value: i128| u64::try_from(value.checked_sub(low)?).ok();
+    Some((shift(f)?, shift(f0)?, shift(f1)?))
+}
+
+/// Finds the index between (x0, f0) and (x1, f1), in any order, whose value is exactly f, as
+/// required by `Method::None`. The offset from x0 is estimated in extended precision, which is
+/// accurate to a few units in the last place, and the integers within that error are verified by
+/// checking that f is exactly their weighted mean of f0 and f1, without any rounding.
+fn exact_inverse(f: &F80, x0: u64, x1: u64, f0: &F80, f1: &F80) -> Option<u64> {
+    if x1 < x0 {
+        return exact_inverse(f, x1, x0, f1, f0);
+    }
+    let span = x1 - x0;
+    let offset = F80::from(span).mul(&f.sub(f0)).checked_div(&f1.sub(f0))?;
+    let margin = offset.abs().add(F80::one()).div(&F80::from(1u64 << 61));
+    let lo = offset
+        .sub(&margin)
+        .to_u64(Method::BackwardFill)
+        .unwrap_or(0);
+    let hi = match offset.add(&margin).to_u64(Method::ForwardFill) {
+        Some(hi) => hi.min(span),
+        None if offset > *F80::zero() => span,
+        None => return None,
+    };
+    (lo..=hi)
+        .map(|offset| x0 + offset)
+        .find(|x| f.is_weighted_mean(f0, x1 - x, f1, x - x0))
+}
+
+/// Rounds an extended-precision value to an integer according to the given method. Returns None
+/// if `Method::None` is chosen and the value is not an integer.
+pub(crate) fn round_with(value: &F80, method: Method) -> Option<F80> {
+    match method {
+        Method::None => {
+            let out = value.floor();
+            if out == *value {
+                Some(out)
+            } else {
+                None
+            }
+        }
+        Method::Nearest => Some(value.round()),
+        Method::ForwardFill => Some(value.floor()),
+        Method::BackwardFill => Some(value.ceil()),
+    }
+}
+
+/// Computes the real-valued position of f between (x0, f0) and (x1, f1) in extended precision.
+/// The result is only rounded once when converted to f64, hence it is accurate to half an f64
+/// ulp as long as the F80 intermediate rounding (2^-64 relative) stays negligible. None if the
+/// segment is degenerate (`f0 == f1`).
+fn fractional(f: F80, x0: F80, x1: F80, f0: F80, f1: F80) -> Option<f64> {
+    let ratio = f.sub(&f0).checked_div(&f1.sub(&f0))?;
+    Some(x0.add(&x1.sub(&x0).mul(&ratio)).into())
+}
+
+/// Implements lossless conversion of values to extended precision and back. Used by the fitting
+/// algorithms that need to perform arithmetic on any value type.
+pub trait Extended: Copy {
+    /// Margin to subtract from error bounds to absorb the rounding of the fitted values and of
+    /// their interpolation (one for integers, zero for floats).
+    const MARGIN: u64;
+    /// Converts the value to extended precision, None if a float is not finite.
+    fn to_extended(self) -> Option<F80>;
+    /// Converts back from extended precision, rounding integers to the nearest.
+    fn from_extended(value: F80) -> Self;
+}
+impl Extended for u64 {
+    const MARGIN: u64 = 1;
+    fn to_extended(self) -> Option<F80> {
+        Some(F80::from(self))
+    }
+    fn from_extended(value: F80) -> u64 {
+        value.round().into()
+    }
+}
+impl Extended for i64 {
+    const MARGIN: u64 = 1;
+    fn to_extended(self) -> Option<F80> {
+        Some(F80::from(self))
+    }
+    fn from_extended(value: F80) -> i64 {
+        value.round().into()
+    }
+}
+impl Extended for f64 {
+    const MARGIN: u64 = 0;
+    fn to_extended(self) -> Option<F80> {
+        F80::from_finite(self)
+    }
+    fn from_extended(value: F80) -> f64 {
+        value.into()
+    }
+}
+
+/// Implements the absolute difference between two values. Used to compare deviations with
+/// tolerances.
+///
+/// Implementations must be symmetric (`a.distance(b) == b.distance(a)`), non-negative and zero
+/// between equal values. The output must be comparable with the tolerances given by the caller
+/// (e.g. the `epsilon` of `Interp::simplify`), hence integers use the unsigned type of the same
+/// width so that no distance overflows, and floats their own type. NaN distances are not
+/// comparable and are treated as exceeding any tolerance.
+pub trait Distance: Clone {
+    /// The type of the distance, unsigned for integers.
+    type Output: Clone + PartialOrd;
+    /// Returns the absolute difference between the two values.
+    fn distance(self, other: Self) -> Self::Output;
+}
+impl Distance for u64 {
+    type Output = u64;
+    fn distance(self, other: u64) -> u64 {
+        self.abs_diff(other)
+    }
+}
+impl Distance for i64 {
+    type Output = u64;
+    fn distance(self, other: i64) -> u64 {
+        self.abs_diff(other)
+    }
+}
+impl Distance for i128 {
+    type Output = u128;
+    fn distance(self, other: i128) -> u128 {
+        self.abs_diff(other)
+    }
+}
+impl Distance for f64 {
+    type Output = f64;
+    fn distance(self, other: f64) -> f64 {
+        (self - other).abs()
+    }
+}
+impl Distance for F80 {
+    type Output = F80;
+    fn distance(self, other: F80) -> F80 {
+        self.clone().max(other.clone()).sub(&self.min(other))
+    }
+}
+
+/// Implements overflow-checked sum and difference of values. Used to combine interpolations
+/// pointwise. Float operations are correctly rounded, results that are not finite being
+/// overflows.
+pub trait Combine: Sized {
+    /// Returns the sum of the two values, None on overflow.
+    fn checked_add(self, other: Self) -> Option<Self>;
+    /// Returns the difference of the two values, None on overflow.
+    fn checked_sub(self, other: Self) -> Option<Self>;
+}
+impl Combine for u64 {
+    fn checked_add(self, other: u64) -> Option<u64> {
+        u64::checked_add(self, other)
+    }
+    fn checked_sub(self, other: u64) -> Option<u64> {
+        u64::checked_sub(self, other)
+    }
+}
+impl Combine for i64 {
+    fn checked_add(self, other: i64) -> Option<i64> {
+        i64::checked_add(self, other)
+    }
+    fn checked_sub(self, other: i64) -> Option<i64> {
+        i64::checked_sub(self, other)
+    }
+}
+impl Combine for f64 {
+    fn checked_add(self, other: f64) -> Option<f64> {
+        let out = self + other;
+        out.is_finite().then_some(out)
+    }
+    fn checked_sub(self, other: f64) -> Option<f64> {
+        let out = self - other;
+        out.is_finite().then_some(out)
+    }
+}
+impl Combine for F80 {
+    fn checked_add(self, other: F80) -> Option<F80> {
+        Some(self.add(&other))
+    }
+    fn checked_sub(self, other: F80) -> Option<F80> {
+        Some(self.sub(&other))
     }
 }
 
@@ -111,3 +620,118 @@ impl ToSigned<i64> for u64 {
         self.wrapping_add(i64::MIN as u64) as i64
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+
+    #[test]
+    fn test_combine() {
+        assert_eq!(Combine::checked_add(i64::MAX - 1, 1), Some(i64::MAX));
+        assert_eq!(Combine::checked_add(i64::MAX, 1), None);
+        assert_eq!(Combine::checked_sub(i64::MIN, 1), None);
+        assert_eq!(Combine::checked_sub(3u64, 4), None);
+        assert_eq!(Combine::checked_add(0.1f64, 0.2), Some(0.1 + 0.2));
+        assert_eq!(Combine::checked_add(f64::MAX, f64::MAX), None);
+        assert_eq!(Combine::checked_sub(1.5f64, 2.0), Some(-0.5));
+    }
+
+    #[test]
+    fn test_degenerate() {
+        // Segments with equal indices (forward) or equal values (inverse) cannot be interpolated.
+        let f80 = |value: f64| F80::try_from(value).unwrap();
+        for method in [Method::None, Method::Nearest] {
+            assert_eq!(5u64.forward(5, 5, 1u64, 2, method), None);
+            assert_eq!(5u64.forward(5, 5, -1i64, 2, method), None);
+            assert_eq!(5u64.forward(5, 5, 1.0, 2.0, method), None);
+            assert_eq!(5u64.forward(5, 5, f80(1.0), f80(2.0), method), None);
+            assert_eq!(5.5f64.forward_at(5, 5, 1u64, 2, method), None);
+            assert_eq!(5.5f64.forward_at(5, 5, 1.0, 2.0, method), None);
+            assert_eq!(3u64.inverse(0, 10, 3, 3, method), None);
+            assert_eq!(3i64.inverse(0, 10, 3, 3, method), None);
+            assert_eq!(3.0f64.inverse(0, 10, 3.0, 3.0, method), None);
+            assert_eq!(f80(3.0).inverse(0, 10, f80(3.0), f80(3.0), method), None);
+        }
+        assert_eq!(forward_checked(5, 5, 5, 1.0, 2.0), None);
+        assert_eq!(forward_checked(5, 0, 10, 1.0, 2.0), Some((1.5, true)));
+        assert_eq!(2.0f64.inverse_fractional(0, 10, 3.0, 3.0), None);
+        assert_eq!(3.0f64.inverse_fractional(0, 10, 3.0, 3.0), Some(0.0));
+        assert_eq!(2u64.inverse_fractional(0, 10, 3, 3), None);
+        assert_eq!(f80(2.0).inverse_fractional(0, 10, f80(3.0), f80(3.0)), None);
+        assert_eq!(
+            f64::bilinear([[1.0, 2.0], [3.0, 4.0]], (0, 0), (0, 1)),
+            None
+        );
+        assert_eq!(
+            f64::bilinear([[1.0, 2.0], [3.0, 4.0]], (1, 2), (1, 2)),
+            Some(2.5)
+        );
+    }
+
+    #[test]
+    fn test_inverse_exact() {
+        // Values exactly on a segment near 2^63, where the extended-precision inverse is off by
+        // far more than the distance to the nearest integer.
+        let (x0, x1) = (9169336205395631494u64, 9169336205397525773u64);
+        let (f0, scale) = (2517398.5, 2.0f64.powi(-18));
+        let f1 = f0 + (x1 - x0) as f64 * scale;
+        let x = x0 + 195083;
+        let f = f0 + (x - x0) as f64 * scale;
+        assert_eq!(f.inverse(x0, x1, f0, f1, Method::None), Some(x));
+        assert_eq!(f.inverse(x1, x0, f1, f0, Method::None), Some(x));
+        let next = f64::from_bits(f.to_bits() + 1);
+        assert_eq!(next.inverse(x0, x1, f0, f1, Method::None), None);
+
+        let mut rng = Rng(0x1234_5678_9ABC_DEF1);
+        for _ in 0..2000 {
+            let x0 = rng.next() >> 1;
+            let span = (rng.next() >> 40) + 2;
+            let x1 = x0 + span;
+            let scale = 2.0f64.powi((rng.next() % 40) as i32 - 20);
+            // multiples of the scale below 2^53 times the scale, hence exact
+            let f0 = (rng.next() >> 40) as f64 * scale;
+            let f1 = f0 + span as f64 * scale;
+            let x = x0 + rng.next() % span;
+            let f = f0 + (x - x0) as f64 * scale;
+            assert_eq!(f.inverse(x0, x1, f0, f1, Method::None), Some(x));
+            let (f80, f0, f1) = (
+                F80::try_from(f).unwrap(),
+                F80::try_from(f0).unwrap(),
+                F80::try_from(f1).unwrap(),
+            );
+            assert_eq!(
+                f80.inverse(x0, x1, f0.clone(), f1.clone(), Method::None),
+                Some(x)
+            );
+            // a value one ulp away lies less than one index step away: it is never exact
+            let next = f64::from_bits(f.to_bits() + 1);
+            if next - f < scale {
+                assert_eq!(
+                    next.inverse(x0, x1, f64::from(f0), f64::from(f1), Method::None),
+                    None
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_distance() {
+        assert_eq!(3u64.distance(u64::MAX), u64::MAX - 3);
+        assert_eq!(u64::MAX.distance(3), u64::MAX - 3);
+        assert_eq!(7u64.distance(7), 0);
+        assert_eq!(i64::MIN.distance(i64::MAX), u64::MAX);
+        assert_eq!(i64::MAX.distance(i64::MIN), u64::MAX);
+        assert_eq!(i128::MIN.distance(i128::MAX), u128::MAX);
+        assert_eq!((-5i128).distance(5), 10);
+        assert_eq!(5i128.distance(-5), 10);
+        assert_eq!(1.5f64.distance(-1.0), 2.5);
+        assert_eq!((-1.0f64).distance(1.5), 2.5);
+        assert!(f64::NAN.distance(0.0).partial_cmp(&1.0).is_none());
+        let (a, b) = (F80::from(u64::MAX), F80::from(-1i64));
+        let expected = F80::from(u64::MAX).add(F80::one());
+        assert_eq!(a.clone().distance(b.clone()), expected);
+        assert_eq!(b.distance(a.clone()), expected);
+        assert_eq!(a.clone().distance(a), *F80::zero());
+    }
+}
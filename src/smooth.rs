@@ -0,0 +1,168 @@
+//! Centered moving-window smoothing of values.
+//!
+//! The window is symmetric around each value and shrinks near the edges so that the first and
+//! last values are kept unchanged and the domain does not shrink. Integer means are accumulated
+//! exactly with i128 integers and rounded to the nearest, floating means are accumulated with
+//! extended-precision floats.
+
+use crate::divop::{DivOp, Method};
+use crate::extended::F80;
+use crate::piecewise::InterpError;
+
+/// Kind of smoothing applied over the window.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Smoothing {
+    Mean,
+    Median,
+}
+
+/// Implements the mean of a window of values.
+pub trait Smooth: Copy + PartialOrd {
+    /// Computes the mean of a non-empty window of values.
+    fn mean(values: &[Self]) -> Self;
+}
+impl Smooth for i64 {
+    fn mean(values: &[i64]) -> i64 {
+        let sum: i128 = values.iter().map(|value| *value as i128).sum();
+        sum.div(values.len() as i128, Method::Nearest)
+            .expect("nearest division is always defined") as i64
+    }
+}
+impl Smooth for f64 {
+    fn mean(values: &[f64]) -> f64 {
+        let sum = values
+            .iter()
+            .try_fold(F80::clone(F80::zero()), |sum, value| {
+                Some(sum.add(&F80::from_finite(*value)?))
+            });
+        match sum {
+            Some(sum) => sum.div(&F80::from(values.len() as u64)).into(),
+            // infinities and NaNs propagate as with f64 arithmetic
+            None => values.iter().sum::<f64>() / values.len() as f64,
+        }
+    }
+}
+
+/// Smooths the values with a centered moving window.
+///
+/// # Arguments
+///
+/// * `fp` - The values to smooth.
+/// * `window` - The width of the window. Even widths are rounded down to the previous odd width.
+/// * `kind` - Whether to take the mean or the median over the window.
+///
+/// # Returns
+///
+/// If successful, returns the smoothed values, same length as `fp`. Near the edges, the window
+/// shrinks to the largest symmetric window that fits. NaN values sort after the others with
+/// `Median`.
+/// Otherwise, returns `InvalidArgument` if `window` is zero.
+pub fn smooth<F: Smooth>(fp: &[F], window: usize, kind: Smoothing) -> Result<Vec<F>, InterpError> {
+    if window == 0 {
+        return Err(InterpError::InvalidArgument("window must be positive"));
+    }
+    let half = (window - 1) / 2;
+    let mut buffer = Vec::with_capacity(2 * half + 1);
+    Ok((0..fp.len())
+        .map(|index| {
+            let reach = half.min(index).min(fp.len() - 1 - index);
+            let values = &fp[index - reach..=index + reach];
+            match kind {
+                Smoothing::Mean => F::mean(values),
+                Smoothing::Median => {
+                    buffer.clear();
+                    buffer.extend_from_slice(values);
+                    let is_nan = |value: &F| value.partial_cmp(value).is_none();
+                    buffer.sort_by(|a, b| {
+                        a.partial_cmp(b)
+                            .unwrap_or_else(|| is_nan(a).cmp(&is_nan(b)))
+                    });
+                    buffer[reach]
+                }
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mean() {
+        let fp: Vec<i64> = vec![0, 3, 6, 10, 11, 20];
+        assert_eq!(
+            smooth(&fp, 3, Smoothing::Mean).unwrap(),
+            vec![0, 3, 6, 9, 14, 20]
+        );
+        assert_eq!(
+            smooth(&fp, 4, Smoothing::Mean).unwrap(),
+            vec![0, 3, 6, 9, 14, 20]
+        );
+        assert_eq!(
+            smooth(&fp, 5, Smoothing::Mean).unwrap(),
+            vec![0, 3, 6, 10, 14, 20]
+        );
+        assert_eq!(smooth(&fp, 1, Smoothing::Mean).unwrap(), fp);
+        let fp: Vec<f64> = vec![0.0, 1.0, 5.0, 6.0];
+        assert_eq!(
+            smooth(&fp, 3, Smoothing::Mean).unwrap(),
+            vec![0.0, 2.0, 4.0, 6.0]
+        );
+        assert!(smooth::<f64>(&[], 3, Smoothing::Mean).unwrap().is_empty());
+        assert_eq!(
+            smooth(&fp, 0, Smoothing::Mean),
+            Err(InterpError::InvalidArgument("window must be positive"))
+        );
+    }
+
+    #[test]
+    fn test_median() {
+        let fp: Vec<i64> = vec![0, 1, 100, 3, 4, -50, 6];
+        assert_eq!(
+            smooth(&fp, 3, Smoothing::Median).unwrap(),
+            vec![0, 1, 3, 4, 3, 4, 6]
+        );
+        let fp: Vec<f64> = vec![0.0, 1.0, 100.0, 3.0, 4.0];
+        assert_eq!(
+            smooth(&fp, 5, Smoothing::Median).unwrap(),
+            vec![0.0, 1.0, 3.0, 4.0, 4.0]
+        );
+        let fp: Vec<f64> = vec![0.0, f64::NAN, 2.0, 3.0];
+        assert_eq!(
+            smooth(&fp, 3, Smoothing::Median).unwrap(),
+            vec![0.0, 2.0, 3.0, 3.0]
+        );
+        let fp: Vec<f64> = vec![0.0, f64::INFINITY, 2.0];
+        assert_eq!(
+            smooth(&fp, 3, Smoothing::Mean).unwrap(),
+            vec![0.0, f64::INFINITY, 2.0]
+        );
+    }
+
+    #[test]
+    fn test_precision() {
+        let start = 1_700_000_000_000_000_000i64;
+        let fp: Vec<i64> = vec![start, start + 1, start + 5, start + 6, start + 8];
+        assert_eq!(
+            smooth(&fp, 3, Smoothing::Mean).unwrap(),
+            vec![start, start + 2, start + 4, start + 6, start + 8]
+        );
+        let fp: Vec<i64> = vec![i64::MAX, i64::MAX - 1, i64::MAX - 2];
+        assert_eq!(smooth(&fp, 3, Smoothing::Mean).unwrap(), fp);
+    }
+
+    #[test]
+    fn test_monotonic() {
+        let noise = |index: i64| (index * 7919) % 13;
+        let fp: Vec<i64> = (0..1000)
+            .map(|index| 1_700_000_000_000_000_000 + 1000 * index + noise(index))
+            .collect();
+        for window in [3, 10, 51] {
+            let smoothed = smooth(&fp, window, Smoothing::Mean).unwrap();
+            assert!(smoothed.windows(2).all(|pair| pair[0] <= pair[1]));
+            assert_eq!(smoothed[0], fp[0]);
+            assert_eq!(smoothed[999], fp[999]);
+        }
+    }
+}
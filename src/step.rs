@@ -0,0 +1,270 @@
+//! Piecewise constant functions, e.g. the derivative of a piecewise linear function.
+//!
+//! A step function holds strictly increasing breakpoints and one value per segment between two
+//! consecutive breakpoints. Segments include their first breakpoint but not the next one, except
+//! for the last segment which includes both: the value at an inner breakpoint is the one of the
+//! segment starting there. Out of bounds queries follow the same options as `Interp`.
+//!
+//! Step functions of rates per index step can be integrated back into an `Interp` of cumulative
+//! values with `antiderivative`, the inverse of `Interp::derivative`.
+
+use crate::extended::F80;
+use crate::piecewise::{Bounds, Closed, Interp, InterpError, SidedBounds};
+use crate::schemes::{Forward, Inverse};
+
+/// Structure for looking up the values of a piecewise constant function.
+#[derive(Clone, PartialEq, Debug)]
+pub struct StepFn<X, V> {
+    xp: Vec<X>,
+    values: Vec<V>,
+    closed: Closed,
+    bounds: SidedBounds,
+}
+
+impl<X, V> StepFn<X, V>
+where
+    X: Copy + Ord,
+    V: Clone,
+{
+    /// Creates a new StepFn instance, closed on both sides and failing out of bounds.
+    ///
+    /// # Arguments
+    ///
+    /// * `xp` - The breakpoints, must be strictly increasing.
+    /// * `values` - The value of each segment, one less than breakpoints (or none at all).
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the StepFn.
+    /// Otherwise, returns `LengthMismatch` with the expected and actual number of values if they
+    /// do not match the breakpoints or `NotStrictlyIncreasing` if the breakpoints are not
+    /// strictly increasing.
+    pub fn new(xp: Vec<X>, values: Vec<V>) -> Result<StepFn<X, V>, InterpError> {
+        let expected = xp.len().saturating_sub(1);
+        if values.len() != expected {
+            return Err(InterpError::LengthMismatch(expected, values.len()));
+        }
+        if !xp.windows(2).all(|pair| pair[0] < pair[1]) {
+            return Err(InterpError::NotStrictlyIncreasing);
+        }
+        Ok(StepFn {
+            xp,
+            values,
+            closed: Closed::Both,
+            bounds: Bounds::Error.into(),
+        })
+    }
+    /// Sets the inclusivity of the extreme breakpoints (closed on both sides by default).
+    pub fn with_closed(mut self, closed: Closed) -> StepFn<X, V> {
+        self.closed = closed;
+        self
+    }
+    /// Sets the behavior of out of bounds queries, either on both sides or on each side (error
+    /// by default).
+    pub fn with_bounds(mut self, bounds: impl Into<SidedBounds>) -> StepFn<X, V> {
+        self.bounds = bounds.into();
+        self
+    }
+    /// Returns the breakpoints.
+    pub fn xp(&self) -> &[X] {
+        &self.xp
+    }
+    /// Returns the values of the segments.
+    pub fn values(&self) -> &[V] {
+        &self.values
+    }
+    /// Iterates over the segments as (start, value) pairs.
+    pub fn iter(&self) -> impl Iterator<Item = (&X, &V)> {
+        self.xp.iter().zip(&self.values)
+    }
+    /// Looks up the value of the segment containing the given index.
+    ///
+    /// # Arguments
+    ///
+    /// * `rhs` - The index to look up.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the value of the segment.
+    /// Otherwise, returns `OutOfBounds` if the index is beyond the breakpoints (or on an open
+    /// extreme breakpoint) and out of bounds queries are not clamped on that side.
+    pub fn forward(&self, rhs: X) -> Result<V, InterpError> {
+        let (Some(first), Some(last)) = (self.xp.first(), self.xp.last()) else {
+            return Err(InterpError::OutOfBounds);
+        };
+        let before = rhs < *first || (rhs == *first && self.closed == Closed::Right);
+        let after = rhs > *last || (rhs == *last && self.closed == Closed::Left);
+        match (before, after) {
+            (true, _) if self.bounds.left == Bounds::Clamp => {
+                self.values.first().cloned().ok_or(InterpError::OutOfBounds)
+            }
+            (_, true) if self.bounds.right == Bounds::Clamp => {
+                self.values.last().cloned().ok_or(InterpError::OutOfBounds)
+            }
+            (true, _) | (_, true) => Err(InterpError::OutOfBounds),
+            (false, false) => {
+                let count = self.xp.partition_point(|x| *x <= rhs);
+                count
+                    .min(self.values.len())
+                    .checked_sub(1)
+                    .map(|index| self.values[index].clone())
+                    .ok_or(InterpError::OutOfBounds)
+            }
+        }
+    }
+}
+
+impl StepFn<u64, i64> {
+    /// Computes the running integral of the function, e.g. cumulative counts from rates per
+    /// index step. The integral of a piecewise constant function is piecewise linear with the
+    /// same breakpoints, hence it is exactly represented by an Interp keeping the options of
+    /// this StepFn. Integrals are accumulated exactly with i128 integers.
+    ///
+    /// # Arguments
+    ///
+    /// * `c0` - The value of the integral at the first breakpoint.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the Interp of the integral.
+    /// Otherwise, returns `Overflow` if a value of the integral does not fit in an i64.
+    pub fn antiderivative(&self, c0: i64) -> Result<Interp<u64, i64>, InterpError> {
+        let mut acc = c0 as i128;
+        let mut fp = Vec::with_capacity(self.xp.len());
+        if !self.xp.is_empty() {
+            fp.push(c0);
+        }
+        for (pair, value) in self.xp.windows(2).zip(&self.values) {
+            let area = (*value as i128)
+                .checked_mul((pair[1] - pair[0]) as i128)
+                .ok_or(InterpError::Overflow)?;
+            acc = acc.checked_add(area).ok_or(InterpError::Overflow)?;
+            fp.push(i64::try_from(acc).map_err(|_| InterpError::Overflow)?);
+        }
+        Ok(self.integral(fp))
+    }
+}
+
+impl StepFn<u64, f64> {
+    /// Computes the running integral of the function, e.g. cumulative counts from rates per
+    /// index step. The integral of a piecewise constant function is piecewise linear with the
+    /// same breakpoints, hence it is represented by an Interp keeping the options of this
+    /// StepFn. Integrals are accumulated with extended-precision floats and rounded to f64.
+    ///
+    /// # Arguments
+    ///
+    /// * `c0` - The value of the integral at the first breakpoint.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the Interp of the integral.
+    /// Otherwise, returns `Overflow` if `c0`, a value of the step function or a value of the
+    /// integral is not finite.
+    pub fn antiderivative(&self, c0: f64) -> Result<Interp<u64, f64>, InterpError> {
+        let mut acc = F80::from_finite(c0).ok_or(InterpError::Overflow)?;
+        let mut fp = Vec::with_capacity(self.xp.len());
+        if !self.xp.is_empty() {
+            fp.push(c0);
+        }
+        for (pair, value) in self.xp.windows(2).zip(&self.values) {
+            let value = F80::from_finite(*value).ok_or(InterpError::Overflow)?;
+            acc = acc.add(&value.mul(&F80::from(pair[1] - pair[0])));
+            let value: f64 = acc.clone().into();
+            if !value.is_finite() {
+                return Err(InterpError::Overflow);
+            }
+            fp.push(value);
+        }
+        Ok(self.integral(fp))
+    }
+}
+
+impl<V> StepFn<u64, V> {
+    /// Builds the Interp of an integral from its values at the breakpoints.
+    fn integral<F>(&self, fp: Vec<F>) -> Interp<u64, F>
+    where
+        u64: Forward<F>,
+        F: Inverse<u64>,
+    {
+        Interp::builder(self.xp.clone(), fp)
+            .closed(self.closed)
+            .bounds(self.bounds)
+            .assume_sorted(true)
+            .build()
+            .expect("one value per breakpoint")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_forward() {
+        let step = StepFn::new(vec![0u64, 10, 20], vec![1.0, 2.0]).unwrap();
+        assert_eq!(step.forward(0), Ok(1.0));
+        assert_eq!(step.forward(9), Ok(1.0));
+        assert_eq!(step.forward(10), Ok(2.0));
+        assert_eq!(step.forward(20), Ok(2.0));
+        assert_eq!(step.forward(21), Err(InterpError::OutOfBounds));
+        let pairs: Vec<(u64, f64)> = step.iter().map(|(x, v)| (*x, *v)).collect();
+        assert_eq!(pairs, vec![(0, 1.0), (10, 2.0)]);
+
+        let step = step.with_closed(Closed::Left);
+        assert_eq!(step.forward(20), Err(InterpError::OutOfBounds));
+        let step = step.with_bounds(Bounds::Clamp);
+        assert_eq!(step.forward(20), Ok(2.0));
+        let step = StepFn::new(vec![5u64, 10], vec![3]).unwrap();
+        let step = step.with_bounds(Bounds::Clamp);
+        assert_eq!(step.forward(0), Ok(3));
+        assert_eq!(step.forward(100), Ok(3));
+    }
+
+    #[test]
+    fn test_antiderivative() {
+        let rates = StepFn::new(vec![0u64, 10, 14, 22], vec![10i64, -5, 3]).unwrap();
+        let counts = rates.antiderivative(100).unwrap();
+        assert_eq!(counts.xp(), &[0, 10, 14, 22]);
+        assert_eq!(counts.fp(), &[100, 200, 180, 204]);
+        assert_eq!(counts.forward(5), Ok(150));
+        let derivative = counts.derivative().unwrap();
+        assert_eq!(derivative.values(), &[10.0, -5.0, 3.0]);
+
+        let rates = StepFn::new(vec![0u64, 4, 12], vec![0.25, -1.5]).unwrap();
+        let counts = rates.antiderivative(1.0).unwrap();
+        assert_eq!(counts.fp(), &[1.0, 2.0, -10.0]);
+        assert_eq!(counts.derivative().unwrap(), rates);
+
+        let rates = StepFn::new(vec![0u64, 2], vec![i64::MAX]).unwrap();
+        assert_eq!(rates.antiderivative(0), Err(InterpError::Overflow));
+        let rates = StepFn::new(vec![0u64, u64::MAX], vec![i64::MAX]).unwrap();
+        assert_eq!(rates.antiderivative(0), Err(InterpError::Overflow));
+        let rates = StepFn::new(vec![0u64, 2], vec![f64::MAX]).unwrap();
+        assert_eq!(rates.antiderivative(0.0), Err(InterpError::Overflow));
+        let rates = StepFn::new(vec![0u64, 2], vec![f64::NAN]).unwrap();
+        assert_eq!(rates.antiderivative(0.0), Err(InterpError::Overflow));
+        let rates = StepFn::new(vec![0u64, 2], vec![1.0]).unwrap();
+        assert_eq!(
+            rates.antiderivative(f64::INFINITY),
+            Err(InterpError::Overflow)
+        );
+        let empty = StepFn::<u64, i64>::new(vec![], vec![]).unwrap();
+        assert!(empty.antiderivative(0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_new() {
+        assert_eq!(
+            StepFn::new(vec![0u64, 10], vec![1, 2]),
+            Err(InterpError::LengthMismatch(1, 2))
+        );
+        assert_eq!(
+            StepFn::new(vec![10u64, 0], vec![1]),
+            Err(InterpError::NotStrictlyIncreasing)
+        );
+        let single = StepFn::<u64, i64>::new(vec![5], vec![]).unwrap();
+        assert_eq!(single.forward(5), Err(InterpError::OutOfBounds));
+        let empty = StepFn::<u64, i64>::new(vec![], vec![]).unwrap();
+        assert_eq!(empty.forward(5), Err(InterpError::OutOfBounds));
+    }
+}
@@ -0,0 +1,179 @@
+//! Instrumentation of batch operations, fallbacks and validation failures, behind the `tracing`
+//! feature.
+//!
+//! When a conversion is slow, the events tell whether it fell back from a fast path to a slower
+//! one and how many queries were out of bounds. Spans and events are emitted through the
+//! `tracing` crate, so they reach whichever subscriber the application installs, with the
+//! operation in their `operation` field. Without the feature, the instrumentation is compiled
+//! out.
+
+use tracing::span::EnteredSpan;
+
+/// Enters the span of a batch operation with its number of queries, exited on drop.
+pub(crate) fn batch(name: &'static str, len: usize) -> EnteredSpan {
+    tracing::debug_span!("batch", operation = name, len).entered()
+}
+
+/// Reports that an operation falls back from its fast path to a slower one.
+pub(crate) fn fallback(name: &'static str, reason: &'static str) {
+    tracing::info!(operation = name, reason, "fallback");
+}
+
+/// Reports the number of queries of a batch operation that are out of bounds.
+pub(crate) fn out_of_bounds(name: &'static str, count: usize) {
+    tracing::debug!(operation = name, count, "out of bounds");
+}
+
+/// Reports that the data points fail the validation of an operation.
+pub(crate) fn invalid(name: &'static str, reason: &'static str) {
+    tracing::debug!(operation = name, reason, "invalid data points");
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::divop::Method;
+    use crate::piecewise::Interp;
+    use std::fmt;
+    use std::sync::{Arc, Mutex};
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    /// Formats the fields of a span or an event as `name key=value...`.
+    struct Fields(String);
+
+    impl Visit for Fields {
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.0 += &format!(" {}={value}", field.name());
+        }
+        fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+            match field.name() {
+                "message" => self.0 += &format!(" {value:?}"),
+                name => self.0 += &format!(" {name}={value:?}"),
+            }
+        }
+    }
+
+    /// Subscriber recording the spans it enters and exits and the events it receives.
+    #[derive(Default)]
+    struct Capture {
+        spans: Mutex<Vec<String>>,
+        records: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl Capture {
+        fn push(&self, record: String) {
+            self.records.lock().unwrap().push(record);
+        }
+        fn span(&self, id: &Id) -> String {
+            self.spans.lock().unwrap()[id.into_u64() as usize - 1].clone()
+        }
+    }
+
+    impl Subscriber for Capture {
+        fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+            true
+        }
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            let mut fields = Fields(span.metadata().name().to_string());
+            span.record(&mut fields);
+            let mut spans = self.spans.lock().unwrap();
+            spans.push(fields.0);
+            Id::from_u64(spans.len() as u64)
+        }
+        fn record(&self, _span: &Id, _values: &Record<'_>) {}
+        fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+        fn event(&self, event: &Event<'_>) {
+            let mut fields = Fields(event.metadata().level().to_string());
+            event.record(&mut fields);
+            self.push(fields.0);
+        }
+        fn enter(&self, span: &Id) {
+            self.push(format!("enter {}", self.span(span)));
+        }
+        fn exit(&self, span: &Id) {
+            self.push(format!("exit {}", self.span(span)));
+        }
+    }
+
+    /// Runs `f` with a capturing subscriber and returns what it recorded.
+    fn capture(f: impl FnOnce()) -> Vec<String> {
+        let subscriber = Capture::default();
+        let records = Arc::clone(&subscriber.records);
+        tracing::subscriber::with_default(subscriber, f);
+        let records = records.lock().unwrap().clone();
+        records
+    }
+
+    #[test]
+    fn test_forward_batch() {
+        let interp = Interp::new(vec![0u64, 10], vec![0.0, 1.0]);
+        let records = capture(|| {
+            let _ = interp.forward_batch_masked(&[0, 5, 11, 12], Method::None);
+        });
+        assert_eq!(
+            records,
+            vec![
+                "enter batch operation=forward_batch_masked len=4",
+                "DEBUG out of bounds operation=forward_batch_masked count=2",
+                "exit batch operation=forward_batch_masked len=4",
+            ]
+        );
+        let records = capture(|| {
+            let _ = interp.forward_batch(&[5], Method::None);
+        });
+        assert_eq!(records.len(), 2);
+        let unsorted = Interp::new(vec![0u64, 10, 5], vec![0.0, 1.0, 2.0]);
+        let records = capture(|| {
+            let _ = unsorted.forward_batch(&[5], Method::None);
+        });
+        assert!(records.contains(
+            &"DEBUG invalid data points operation=forward_batch \
+              reason=xp must be strictly increasing"
+                .to_string()
+        ));
+        assert!(capture(|| ()).is_empty());
+    }
+
+    #[test]
+    fn test_fallback() {
+        // Alternating values of growing amplitude peel off one data point per split, which
+        // exceeds the work limit of the Douglas-Peucker recursion.
+        let fp = (0..300i64)
+            .map(|x| if x % 2 == 0 { x } else { -x })
+            .collect();
+        let interp = Interp::new((0..300u64).collect(), fp);
+        let records = capture(|| {
+            interp.simplify(0).unwrap();
+        });
+        assert_eq!(
+            records,
+            vec!["INFO fallback operation=simplify reason=work limit exceeded"]
+        );
+        let records = capture(|| {
+            interp.epsilon_sweep(&[0]).unwrap();
+        });
+        assert_eq!(
+            records[0],
+            "INFO fallback operation=epsilon_sweep reason=work limit exceeded"
+        );
+        let records = capture(|| {
+            Interp::new(vec![0u64, 1, 2], vec![0i64, 5, 0])
+                .simplify(0)
+                .unwrap();
+        });
+        assert!(records.is_empty());
+    }
+
+    #[test]
+    fn test_nested_subscribers() {
+        let interp = Interp::new(vec![0u64, 10], vec![0i64, 10]);
+        let outer = capture(|| {
+            let inner = capture(|| {
+                let _ = interp.forward_batch(&[1], Method::None);
+            });
+            assert_eq!(inner.len(), 2);
+        });
+        assert!(outer.is_empty());
+    }
+}
@@ -0,0 +1,275 @@
+//! Several interpolations covering disjoint pieces of a single axis.
+//!
+//! Multi-file datasets come with one set of data points per file. The pieces are ordered and
+//! do not overlap, neither along the indices nor along the values, but there may be gaps
+//! between them. Queries are dispatched to the piece containing them by binary search over the
+//! bounds of the pieces, and queries falling in a gap fail with `InterpError::InGap`.
+
+use crate::divop::Method;
+use crate::piecewise::{Interp, InterpError, Order};
+use crate::schemes::{Forward, Inverse};
+
+/// Structure for performing forward and inverse interpolation over ordered pieces.
+#[derive(Clone, PartialEq, Debug)]
+pub struct InterpUnion<X, F> {
+    pieces: Vec<Interp<X, F>>,
+    ranges: Vec<(F, F)>,
+    order: Order,
+}
+
+impl<X, F> InterpUnion<X, F>
+where
+    X: Forward<F> + Sync,
+    F: Inverse<X>,
+{
+    /// Creates a new InterpUnion instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `pieces` - The pieces, ordered along the indices.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the InterpUnion.
+    /// Otherwise, returns `NotStrictlyIncreasing` if the index ranges of the pieces overlap, are
+    /// not in increasing order or if a piece has no data points, and `NotStrictlyMonotonic` if
+    /// the value ranges of the pieces overlap or are not consistently ordered.
+    pub fn new(pieces: Vec<Interp<X, F>>) -> Result<InterpUnion<X, F>, InterpError> {
+        let mut ranges = Vec::with_capacity(pieces.len());
+        for piece in &pieces {
+            ranges.push(range(piece.fp()).ok_or(InterpError::NotStrictlyIncreasing)?);
+        }
+        let ordered = pieces
+            .windows(2)
+            .all(|pair| pair[0].xp().last() < pair[1].xp().first());
+        if !ordered {
+            return Err(InterpError::NotStrictlyIncreasing);
+        }
+        let increasing = ranges.windows(2).all(|pair| pair[0].1 < pair[1].0);
+        let decreasing = ranges.windows(2).all(|pair| pair[0].0 > pair[1].1);
+        let order = match (increasing, decreasing) {
+            (true, _) => Order::Increasing,
+            (false, true) => Order::Decreasing,
+            (false, false) => return Err(InterpError::NotStrictlyMonotonic),
+        };
+        Ok(InterpUnion {
+            pieces,
+            ranges,
+            order,
+        })
+    }
+    /// Returns the pieces.
+    pub fn pieces(&self) -> &[Interp<X, F>] {
+        &self.pieces
+    }
+    /// Returns the pieces, consuming the InterpUnion.
+    pub fn into_pieces(self) -> Vec<Interp<X, F>> {
+        self.pieces
+    }
+    /// Finds the piece whose indices contain the given index.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the position of the piece.
+    /// Otherwise, returns `InGap` if the index falls between two pieces or `OutOfBounds` if it
+    /// lies before the first piece or after the last one.
+    pub fn piece_of(&self, rhs: X) -> Result<usize, InterpError> {
+        let index = self
+            .pieces
+            .partition_point(|piece| piece.xp().last().is_some_and(|x| *x < rhs));
+        match self.pieces.get(index) {
+            None => Err(InterpError::OutOfBounds),
+            Some(piece) if piece.xp().first().is_some_and(|x| *x <= rhs) => Ok(index),
+            Some(_) if index == 0 => Err(InterpError::OutOfBounds),
+            Some(_) => Err(InterpError::InGap),
+        }
+    }
+    /// Finds the piece whose values contain the given value.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the position of the piece.
+    /// Otherwise, returns `InGap` if the value falls between two pieces or `OutOfBounds` if it
+    /// lies beyond the values of the extreme pieces.
+    pub fn piece_of_value(&self, rhs: &F) -> Result<usize, InterpError> {
+        let index = match self.order {
+            Order::Decreasing => self.ranges.partition_point(|(min, _)| min > rhs),
+            _ => self.ranges.partition_point(|(_, max)| max < rhs),
+        };
+        match self.ranges.get(index) {
+            Some((min, max)) if min <= rhs && rhs <= max => Ok(index),
+            Some(_) if index > 0 => Err(InterpError::InGap),
+            _ => Err(InterpError::OutOfBounds),
+        }
+    }
+    /// Performs forward interpolation with the piece containing the given index.
+    ///
+    /// # Arguments
+    ///
+    /// * `rhs` - The index for forward interpolation.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the interpolated value.
+    /// Otherwise, returns an error indicating the reason for failure (see `piece_of`).
+    pub fn forward(&self, rhs: X) -> Result<F, InterpError> {
+        self.pieces[self.piece_of(rhs)?].forward(rhs)
+    }
+    /// Performs inverse interpolation with the piece containing the given value.
+    ///
+    /// # Arguments
+    ///
+    /// * `rhs` - The value for inverse interpolation.
+    /// * `method` - The rounding method to use in case of inexact matching, the default method
+    ///   of the piece if None.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the interpolated index.
+    /// Otherwise, returns an error indicating the reason for failure (see `piece_of_value`).
+    pub fn inverse(&self, rhs: F, method: impl Into<Option<Method>>) -> Result<X, InterpError> {
+        self.pieces[self.piece_of_value(&rhs)?].inverse(rhs, method)
+    }
+    /// Restricts the pieces to the given closed interval of indices. Pieces partially covered
+    /// are cropped (see `Interp::crop`) and the ones outside are dropped. The ends of the
+    /// interval may fall in gaps or beyond the pieces.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The first index of the interval.
+    /// * `end` - The last index of the interval.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the InterpUnion of the affected pieces, empty if none.
+    /// Otherwise, returns `OutOfBounds` if the interval is reversed.
+    pub fn slice(&self, start: X, end: X) -> Result<InterpUnion<X, F>, InterpError> {
+        if start > end {
+            return Err(InterpError::OutOfBounds);
+        }
+        let mut pieces = Vec::new();
+        for piece in &self.pieces {
+            let (Some(first), Some(last)) = (piece.xp().first(), piece.xp().last()) else {
+                continue;
+            };
+            if *last < start || end < *first {
+                continue;
+            }
+            pieces.push(piece.crop(start.max(*first), end.min(*last))?);
+        }
+        InterpUnion::new(pieces)
+    }
+}
+
+/// Computes the minimum and maximum of the values, None if there are none.
+fn range<F: Clone + PartialOrd>(fp: &[F]) -> Option<(F, F)> {
+    let (first, rest) = fp.split_first()?;
+    let (mut min, mut max) = (first.clone(), first.clone());
+    for f in rest {
+        if *f < min {
+            min = f.clone();
+        }
+        if *f > max {
+            max = f.clone();
+        }
+    }
+    Some((min, max))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pieces() -> InterpUnion<u64, i64> {
+        InterpUnion::new(vec![
+            Interp::new(vec![0, 10], vec![100, 200]),
+            Interp::new(vec![20, 30], vec![300, 400]),
+            Interp::new(vec![31, 41], vec![410, 510]),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_forward() {
+        let union = pieces();
+        assert_eq!(union.forward(5), Ok(150));
+        assert_eq!(union.forward(20), Ok(300));
+        assert_eq!(union.forward(31), Ok(410));
+        assert_eq!(union.forward(41), Ok(510));
+        assert_eq!(union.forward(15), Err(InterpError::InGap));
+        assert_eq!(union.forward(42), Err(InterpError::OutOfBounds));
+        assert_eq!(union.piece_of(10), Ok(0));
+        assert_eq!(union.piece_of(25), Ok(1));
+        assert_eq!(union.piece_of(35), Ok(2));
+        let union = InterpUnion::new(vec![Interp::new(vec![5, 10], vec![0.0, 1.0])]).unwrap();
+        assert_eq!(union.forward(4), Err(InterpError::OutOfBounds));
+    }
+
+    #[test]
+    fn test_inverse() {
+        let union = pieces();
+        assert_eq!(union.inverse(150, Method::None), Ok(5));
+        assert_eq!(union.inverse(350, Method::None), Ok(25));
+        assert_eq!(union.inverse(250, None), Err(InterpError::InGap));
+        assert_eq!(
+            union.inverse(405, Method::ForwardFill),
+            Err(InterpError::InGap)
+        );
+        assert_eq!(union.inverse(99, None), Err(InterpError::OutOfBounds));
+        assert_eq!(union.inverse(511, None), Err(InterpError::OutOfBounds));
+        assert_eq!(union.piece_of_value(&420), Ok(2));
+
+        let union = InterpUnion::new(vec![
+            Interp::new(vec![0, 10], vec![1.0, 0.5]),
+            Interp::new(vec![20, 30], vec![0.25, 0.0]),
+        ])
+        .unwrap();
+        assert_eq!(union.inverse(0.75, Method::None), Ok(5));
+        assert_eq!(union.inverse(0.125, Method::None), Ok(25));
+        assert_eq!(union.inverse(0.4, None), Err(InterpError::InGap));
+        assert_eq!(union.inverse(1.5, None), Err(InterpError::OutOfBounds));
+    }
+
+    #[test]
+    fn test_new() {
+        let overlapping: Vec<Interp<u64, i64>> = vec![
+            Interp::new(vec![0, 10], vec![0, 1]),
+            Interp::new(vec![10, 20], vec![2, 3]),
+        ];
+        assert_eq!(
+            InterpUnion::new(overlapping),
+            Err(InterpError::NotStrictlyIncreasing)
+        );
+        let overlapping: Vec<Interp<u64, i64>> = vec![
+            Interp::new(vec![0, 10], vec![0, 2]),
+            Interp::new(vec![11, 20], vec![1, 3]),
+        ];
+        assert_eq!(
+            InterpUnion::new(overlapping),
+            Err(InterpError::NotStrictlyMonotonic)
+        );
+        let empty = vec![Interp::new(vec![], vec![])];
+        assert_eq!(
+            InterpUnion::<u64, i64>::new(empty),
+            Err(InterpError::NotStrictlyIncreasing)
+        );
+        let union = InterpUnion::<u64, i64>::new(vec![]).unwrap();
+        assert_eq!(union.forward(0), Err(InterpError::OutOfBounds));
+        assert_eq!(union.inverse(0, None), Err(InterpError::OutOfBounds));
+    }
+
+    #[test]
+    fn test_slice() {
+        let union = pieces();
+        let sliced = union.slice(5, 35).unwrap();
+        let xp: Vec<&[u64]> = sliced.pieces().iter().map(|piece| piece.xp()).collect();
+        assert_eq!(xp, vec![&[5, 10][..], &[20, 30], &[31, 35]]);
+        assert_eq!(sliced.forward(35), Ok(450));
+        let sliced = union.slice(12, 18).unwrap();
+        assert!(sliced.pieces().is_empty());
+        let sliced = union.slice(15, 25).unwrap();
+        assert_eq!(sliced.pieces().len(), 1);
+        assert_eq!(sliced.pieces()[0].xp(), &[20, 25]);
+        assert_eq!(union.slice(25, 15), Err(InterpError::OutOfBounds));
+    }
+}
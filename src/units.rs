@@ -0,0 +1,650 @@
+//! Conversions between floating values and integer ticks, e.g. seconds and nanoseconds.
+//!
+//! Multiplying f64 seconds by 1e9 in f64 rounds the product before it is rounded to an integer,
+//! which loses nanoseconds as soon as the timestamps exceed 2^53 ns (about 104 days). Here, the
+//! f64 is decomposed into its integer mantissa and binary exponent so that the exact product is
+//! rounded only once, with the given method. Conversely, ticks are divided exactly and the
+//! quotient is rounded once to the nearest f64.
+//!
+//! Durations written for humans (e.g. "250us") are parsed with integer arithmetic only, so that
+//! they convert exactly to the ticks of a numpy datetime64 unit or fail.
+//!
+//! `UnitInterp` builds on these conversions to keep the values of an interpolation as exact
+//! integer ticks while exchanging f64 values with its users.
+
+use std::ops::Range;
+
+use crate::divop::{DivOp, Method};
+use crate::piecewise::{Interp, InterpError};
+
+const NS_PER_SECOND: u64 = 1_000_000_000;
+
+/// Errors of the conversions between seconds and nanoseconds.
+#[derive(PartialEq, Debug)]
+pub enum UnitError {
+    /// The number of seconds is NaN or infinite.
+    NotFinite,
+    /// The number of nanoseconds does not fit in an i64.
+    OutOfRange,
+    /// The number of nanoseconds is not an integer while no rounding was requested.
+    Inexact,
+    /// The number of ticks per unit of value is zero.
+    InvalidScale,
+}
+impl std::fmt::Display for UnitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UnitError::NotFinite => write!(f, "non-finite number of seconds"),
+            UnitError::OutOfRange => write!(f, "out of the nanosecond range"),
+            UnitError::Inexact => write!(f, "not an integer number of nanoseconds"),
+            UnitError::InvalidScale => write!(f, "scale must be positive"),
+        }
+    }
+}
+impl std::error::Error for UnitError {}
+
+/// Errors of the parsing of durations.
+#[derive(PartialEq, Debug)]
+pub enum DurationError {
+    /// The text is not a decimal number followed by a unit, or the unit is unknown.
+    Invalid,
+    /// The unit `m` could mean minutes or months.
+    Ambiguous,
+    /// The duration is not an integer number of ticks of the target unit.
+    Inexact,
+    /// The number of ticks does not fit in an i64.
+    OutOfRange,
+}
+impl std::fmt::Display for DurationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DurationError::Invalid => write!(f, "invalid duration"),
+            DurationError::Ambiguous => write!(f, "ambiguous unit 'm', use 'min' for minutes"),
+            DurationError::Inexact => write!(f, "not an integer number of the unit"),
+            DurationError::OutOfRange => write!(f, "out of the range of the unit"),
+        }
+    }
+}
+impl std::error::Error for DurationError {}
+
+/// Parses a duration into an integer number of ticks of a numpy timedelta64 unit.
+///
+/// The duration is a decimal number, optionally signed, followed by one of the units `W`, `D`
+/// (or `d`), `h`, `min`, `s`, `ms`, `us` (or `µs`), `ns`, `ps`, `fs` and `as`, possibly
+/// separated by spaces, e.g. "250us" or "0.5 s".
+///
+/// # Arguments
+///
+/// * `text` - The duration to parse.
+/// * `unit` - The numpy code of the target unit, e.g. `ns` (`m` being minutes).
+///
+/// # Returns
+///
+/// If successful, returns the number of ticks of the target unit.
+/// Otherwise, returns `Invalid` if the text or the target unit cannot be parsed, `Ambiguous`
+/// for the unit `m`, `Inexact` if the duration is not a multiple of the target unit and
+/// `OutOfRange` if the ticks do not fit in an i64.
+pub fn parse_duration(text: &str, unit: &str) -> Result<i64, DurationError> {
+    let target = attoseconds(unit).ok_or(DurationError::Invalid)?;
+    let (negative, rest) = match text.trim().as_bytes() {
+        [b'-', rest @ ..] => (true, rest),
+        [b'+', rest @ ..] => (false, rest),
+        rest => (false, rest),
+    };
+    let integer = rest.iter().take_while(|byte| byte.is_ascii_digit()).count();
+    let (integer, rest) = rest.split_at(integer);
+    let (fraction, rest) = match rest.split_first() {
+        Some((b'.', rest)) => {
+            let len = rest.iter().take_while(|byte| byte.is_ascii_digit()).count();
+            rest.split_at(len)
+        }
+        _ => (&rest[..0], rest),
+    };
+    if integer.is_empty() && fraction.is_empty() {
+        return Err(DurationError::Invalid);
+    }
+    let suffix = std::str::from_utf8(rest)
+        .map_err(|_| DurationError::Invalid)?
+        .trim_start();
+    let source = match suffix {
+        "m" => return Err(DurationError::Ambiguous),
+        "min" => attoseconds("m"),
+        "d" => attoseconds("D"),
+        "µs" => attoseconds("us"),
+        suffix => attoseconds(suffix),
+    }
+    .ok_or(DurationError::Invalid)?;
+    // Trailing zeros of the fraction are dropped so that they cannot overflow the power of ten.
+    let zeros = fraction
+        .iter()
+        .rev()
+        .take_while(|byte| **byte == b'0')
+        .count();
+    let fraction = &fraction[..fraction.len() - zeros];
+    // The last digit of the fraction is nonzero, hence the digits lack either the factor 2 or the
+    // factor 5 and the source unit must supply one of them as many times as there are digits:
+    // longer fractions are inexact whatever their digits, and would overflow otherwise.
+    let fives = (0..).take_while(|k| source % 5i128.pow(k + 1) == 0).count();
+    if fraction.len() > fives.max(source.trailing_zeros() as usize) {
+        return Err(DurationError::Inexact);
+    }
+    let digits = integer
+        .iter()
+        .chain(fraction)
+        .try_fold(0i128, |acc, digit| {
+            acc.checked_mul(10)?.checked_add((digit - b'0') as i128)
+        })
+        .ok_or(DurationError::OutOfRange)?;
+    let total = digits
+        .checked_mul(source)
+        .ok_or(DurationError::OutOfRange)?;
+    // Any duration is an integer number of attoseconds, the finest unit.
+    let power = 10i128.pow(fraction.len() as u32);
+    if total % power != 0 || (total / power) % target != 0 {
+        return Err(DurationError::Inexact);
+    }
+    let ticks = total / power / target;
+    let ticks = if negative { -ticks } else { ticks };
+    i64::try_from(ticks).map_err(|_| DurationError::OutOfRange)
+}
+
+/// The number of attoseconds of a numpy timedelta64 unit of fixed length, None for years,
+/// months or unknown units.
+fn attoseconds(unit: &str) -> Option<i128> {
+    let seconds = 1_000_000_000_000_000_000;
+    let ticks = match unit {
+        "W" => 604_800 * seconds,
+        "D" => 86_400 * seconds,
+        "h" => 3_600 * seconds,
+        "m" => 60 * seconds,
+        "s" => seconds,
+        "ms" => 1_000_000_000_000_000,
+        "us" => 1_000_000_000_000,
+        "ns" => 1_000_000_000,
+        "ps" => 1_000_000,
+        "fs" => 1_000,
+        "as" => 1,
+        _ => return None,
+    };
+    Some(ticks)
+}
+
+/// Converts a number of seconds into nanoseconds.
+///
+/// # Arguments
+///
+/// * `seconds` - The number of seconds.
+/// * `method` - The rounding method to use in case of fractional nanoseconds.
+///
+/// # Returns
+///
+/// If successful, returns the number of nanoseconds.
+/// Otherwise, returns an error from `to_ticks`.
+pub fn seconds_to_ns(seconds: f64, method: Method) -> Result<i64, UnitError> {
+    scale_up(seconds, NS_PER_SECOND, method)
+}
+
+/// Converts a number of nanoseconds into seconds, correctly rounded to the nearest f64 (ties to
+/// even).
+pub fn ns_to_seconds(ns: i64) -> f64 {
+    scale_down(ns, NS_PER_SECOND)
+}
+
+/// Converts a value into an integer number of ticks, the exact product of the value by the
+/// scale being rounded once.
+///
+/// # Arguments
+///
+/// * `value` - The value to convert.
+/// * `scale` - The number of ticks per unit of value (e.g. 1e9 for seconds to nanoseconds).
+/// * `method` - The rounding method to use in case of fractional ticks.
+///
+/// # Returns
+///
+/// If successful, returns the number of ticks.
+/// Otherwise, returns `InvalidScale` if the scale is zero, `NotFinite` for NaN or infinite
+/// values, `OutOfRange` if the ticks do not fit in an i64 and `Inexact` if they are fractional
+/// with `Method::None`.
+pub fn to_ticks(value: f64, scale: u64, method: Method) -> Result<i64, UnitError> {
+    if scale == 0 {
+        return Err(UnitError::InvalidScale);
+    }
+    scale_up(value, scale, method)
+}
+
+/// Converts a value into ticks like `to_ticks`, for a positive scale.
+fn scale_up(value: f64, scale: u64, method: Method) -> Result<i64, UnitError> {
+    if !value.is_finite() {
+        return Err(UnitError::NotFinite);
+    }
+    let (mantissa, exponent) = decompose(value);
+    let num = mantissa * scale as i128;
+    let ticks = if exponent >= 64 {
+        return Err(UnitError::OutOfRange);
+    } else if exponent >= 0 {
+        num.checked_mul(1 << exponent)
+            .ok_or(UnitError::OutOfRange)?
+    } else {
+        // Below 2^-120, the quotient of a product smaller than 2^117 lies strictly between 0
+        // and half a tick in magnitude, as does the one of its sign: all methods round them
+        // alike.
+        let (num, shift) = if exponent < -120 {
+            (num.signum(), 120)
+        } else {
+            (num, -exponent)
+        };
+        num.div(1 << shift, method).ok_or(UnitError::Inexact)?
+    };
+    i64::try_from(ticks).map_err(|_| UnitError::OutOfRange)
+}
+
+/// Converts a number of ticks into a value, the exact quotient of the ticks by the scale being
+/// correctly rounded to the nearest f64 (ties to even).
+///
+/// # Returns
+///
+/// If successful, returns the value.
+/// Otherwise, returns `InvalidScale` if the scale is zero.
+pub fn from_ticks(ticks: i64, scale: u64) -> Result<f64, UnitError> {
+    if scale == 0 {
+        return Err(UnitError::InvalidScale);
+    }
+    Ok(scale_down(ticks, scale))
+}
+
+/// Converts ticks into a value like `from_ticks`, for a positive scale.
+fn scale_down(ticks: i64, scale: u64) -> f64 {
+    let magnitude = ticks.unsigned_abs() as u128;
+    if magnitude == 0 {
+        return 0.0;
+    }
+    // Shift the dividend so that the quotient has 64 or 65 bits: the 53 bits of the mantissa
+    // followed by the rounding bits, the remainder telling whether the rest is zero. The
+    // shifted dividend stays below 2^128.
+    let scale = scale as u128;
+    let shift = 64 + bit_length(scale) - bit_length(magnitude);
+    let shifted = magnitude << shift;
+    let quotient = shifted / scale;
+    let sticky = !shifted.is_multiple_of(scale);
+    let extra = bit_length(quotient) - 53;
+    let mut mantissa = quotient >> extra;
+    let rest = quotient & ((1 << extra) - 1);
+    let half = 1 << (extra - 1);
+    if rest > half || (rest == half && (sticky || mantissa % 2 == 1)) {
+        mantissa += 1;
+    }
+    // The mantissa fits in 54 bits and the power of two is a normal f64: both are exact.
+    let power = f64::from_bits(((1023 + extra - shift) as u64) << 52);
+    let value = mantissa as f64 * power;
+    if ticks < 0 {
+        -value
+    } else {
+        value
+    }
+}
+
+/// Returns the number of significant bits of an integer.
+fn bit_length(value: u128) -> i32 {
+    (128 - value.leading_zeros()) as i32
+}
+
+/// Structure for performing interpolation on integer ticks (e.g. nanoseconds) with values
+/// exchanged in a floating unit (e.g. seconds).
+///
+/// The values are stored and interpolated exactly as i64 ticks. Each value crossing the
+/// boundary is rounded exactly once: queried values are converted to ticks with the rounding
+/// method of the UnitInterp (see `to_ticks`) and resulting ticks are converted back to the
+/// nearest f64 (see `from_ticks`). Hence no error accumulates inside: interpolated values are
+/// the correctly rounded f64 of the exact integer results.
+#[derive(Clone, PartialEq, Debug)]
+pub struct UnitInterp {
+    interp: Interp<u64, i64>,
+    scale: u64,
+    rounding: Method,
+}
+
+impl UnitInterp {
+    /// Creates a new UnitInterp instance, queried values being rounded to the nearest tick.
+    ///
+    /// # Arguments
+    ///
+    /// * `interp` - The interpolation of the values in ticks.
+    /// * `scale` - The number of ticks per unit of value (e.g. 1e9 for nanoseconds exchanged
+    ///   as seconds).
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the UnitInterp.
+    /// Otherwise, returns `InvalidScale` if the scale is zero.
+    pub fn new(interp: Interp<u64, i64>, scale: u64) -> Result<UnitInterp, UnitError> {
+        if scale == 0 {
+            return Err(UnitError::InvalidScale);
+        }
+        Ok(UnitInterp {
+            interp,
+            scale,
+            rounding: Method::Nearest,
+        })
+    }
+    /// Sets the rounding method of queried values to ticks (to the nearest tick by default).
+    pub fn with_rounding(mut self, rounding: Method) -> UnitInterp {
+        self.rounding = rounding;
+        self
+    }
+    /// Returns the interpolation of the values in ticks.
+    pub fn interp(&self) -> &Interp<u64, i64> {
+        &self.interp
+    }
+    /// Returns the number of ticks per unit of value.
+    pub fn scale(&self) -> u64 {
+        self.scale
+    }
+    /// Converts a value into ticks with the rounding method of this UnitInterp.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the number of ticks.
+    /// Otherwise, returns `NotFound` if the value is not finite or inexact without rounding and
+    /// `OutOfBounds` if the ticks do not fit in an i64.
+    pub fn to_ticks(&self, value: f64) -> Result<i64, InterpError> {
+        // The scale was checked at construction.
+        scale_up(value, self.scale, self.rounding).map_err(|err| match err {
+            UnitError::NotFinite | UnitError::Inexact | UnitError::InvalidScale => {
+                InterpError::NotFound
+            }
+            UnitError::OutOfRange => InterpError::OutOfBounds,
+        })
+    }
+    /// Converts ticks into the nearest value.
+    pub fn from_ticks(&self, ticks: i64) -> f64 {
+        scale_down(ticks, self.scale)
+    }
+    /// Performs forward interpolation at the given index, ticks being rounded with the default
+    /// method of the interpolation.
+    ///
+    /// # Arguments
+    ///
+    /// * `rhs` - The index for forward interpolation.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the interpolated value.
+    /// Otherwise, returns an error indicating the reason for failure.
+    pub fn forward(&self, rhs: u64) -> Result<f64, InterpError> {
+        self.interp.forward(rhs).map(|ticks| self.from_ticks(ticks))
+    }
+    /// Performs forward interpolation at the given index with the given rounding method of
+    /// the ticks.
+    ///
+    /// # Arguments
+    ///
+    /// * `rhs` - The index for forward interpolation.
+    /// * `method` - The rounding method to use in case of inexact ticks.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the interpolated value.
+    /// Otherwise, returns an error indicating the reason for failure.
+    pub fn forward_with(&self, rhs: u64, method: Method) -> Result<f64, InterpError> {
+        self.interp
+            .forward_with(rhs, method)
+            .map(|ticks| self.from_ticks(ticks))
+    }
+    /// Performs inverse interpolation at the given value, converted to ticks first.
+    ///
+    /// # Arguments
+    ///
+    /// * `rhs` - The value for inverse interpolation.
+    /// * `method` - The rounding method to use in case of inexact matching, None for the
+    ///   default method of the interpolation.
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the interpolated index.
+    /// Otherwise, returns an error from `to_ticks` or from `Interp::inverse`.
+    pub fn inverse(&self, rhs: f64, method: impl Into<Option<Method>>) -> Result<u64, InterpError> {
+        self.interp.inverse(self.to_ticks(rhs)?, method)
+    }
+    /// Selects the data points in the given range of positions (see `Interp::slice`).
+    ///
+    /// # Returns
+    ///
+    /// The UnitInterp of the selected data points, None if the range is out of bounds or
+    /// reversed.
+    pub fn slice(&self, range: Range<usize>) -> Option<UnitInterp> {
+        let interp = self.interp.slice(range)?;
+        Some(UnitInterp { interp, ..*self })
+    }
+    /// Restricts the function to the given closed interval of indices (see `Interp::crop`).
+    ///
+    /// # Returns
+    ///
+    /// If successful, returns the cropped UnitInterp.
+    /// Otherwise, returns an error indicating the reason for failure.
+    pub fn crop(&self, start: u64, end: u64) -> Result<UnitInterp, InterpError> {
+        let interp = self.interp.crop(start, end)?;
+        Ok(UnitInterp { interp, ..*self })
+    }
+}
+
+/// Decomposes a finite f64 into an integer mantissa and a binary exponent.
+fn decompose(value: f64) -> (i128, i32) {
+    let bits = value.to_bits();
+    let biased = ((bits >> 52) & 0x7ff) as i32;
+    let fraction = (bits & ((1 << 52) - 1)) as i128;
+    let (mantissa, exponent) = if biased == 0 {
+        (fraction, -1074)
+    } else {
+        (fraction | (1 << 52), biased - 1075)
+    };
+    if value.is_sign_negative() {
+        (-mantissa, exponent)
+    } else {
+        (mantissa, exponent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seconds_to_ns() {
+        assert_eq!(seconds_to_ns(1.5, Method::None), Ok(1_500_000_000));
+        assert_eq!(seconds_to_ns(-0.25, Method::None), Ok(-250_000_000));
+        assert_eq!(seconds_to_ns(0.0, Method::None), Ok(0));
+        assert_eq!(seconds_to_ns(-0.0, Method::None), Ok(0));
+        assert_eq!(seconds_to_ns(1e-10, Method::None), Err(UnitError::Inexact));
+        assert_eq!(seconds_to_ns(1e-10, Method::Nearest), Ok(0));
+        assert_eq!(seconds_to_ns(1e-10, Method::BackwardFill), Ok(1));
+        assert_eq!(seconds_to_ns(-1e-10, Method::ForwardFill), Ok(-1));
+        assert_eq!(seconds_to_ns(5e-324, Method::BackwardFill), Ok(1));
+        assert_eq!(seconds_to_ns(-5e-324, Method::Nearest), Ok(0));
+        assert_eq!(seconds_to_ns(0.0009765625, Method::Nearest), Ok(976_562));
+        assert_eq!(seconds_to_ns(0.0029296875, Method::Nearest), Ok(2_929_688));
+        assert_eq!(seconds_to_ns(2.5e-9, Method::Nearest), Ok(3));
+        assert_eq!(
+            seconds_to_ns(f64::NAN, Method::None),
+            Err(UnitError::NotFinite)
+        );
+        assert_eq!(
+            seconds_to_ns(f64::NEG_INFINITY, Method::None),
+            Err(UnitError::NotFinite)
+        );
+        assert_eq!(
+            seconds_to_ns(1e10, Method::None),
+            Err(UnitError::OutOfRange)
+        );
+        assert_eq!(
+            seconds_to_ns(1e300, Method::None),
+            Err(UnitError::OutOfRange)
+        );
+        assert_eq!(
+            seconds_to_ns(-9.3e9, Method::Nearest),
+            Err(UnitError::OutOfRange)
+        );
+        assert_eq!(
+            seconds_to_ns(9223372036.0, Method::None),
+            Ok(9_223_372_036_000_000_000)
+        );
+    }
+
+    #[test]
+    fn test_precision_cliff() {
+        // 2^53 + 1 ns is the first integer not representable as f64 nanoseconds: the naive
+        // product rounds to 2^53 while the seconds themselves carry the extra nanosecond.
+        let ns = (1i64 << 53) + 1;
+        let seconds = 9007199.254740993;
+        assert_eq!(seconds * 1e9, 9007199254740992.0);
+        assert_eq!(seconds_to_ns(seconds, Method::Nearest), Ok(ns));
+        assert_eq!(ns_to_seconds(ns), seconds);
+        // Timestamps of today are well above the cliff: each f64 of seconds is a few hundred
+        // nanoseconds from the next one and round trips through nanoseconds are exact.
+        let mut seconds = 1_760_486_400.123_456_7_f64;
+        for _ in 0..1000 {
+            let ns = seconds_to_ns(seconds, Method::Nearest).unwrap();
+            assert_eq!(ns_to_seconds(ns), seconds);
+            assert_eq!(seconds_to_ns(ns_to_seconds(ns), Method::Nearest), Ok(ns));
+            seconds = f64::from_bits(seconds.to_bits() + 1);
+        }
+        let exact = seconds_to_ns(1_760_486_400.123_456_7, Method::None);
+        assert_eq!(exact, Err(UnitError::Inexact));
+        let floor = seconds_to_ns(1_760_486_400.123_456_7, Method::ForwardFill).unwrap();
+        let ceil = seconds_to_ns(1_760_486_400.123_456_7, Method::BackwardFill).unwrap();
+        assert_eq!(
+            (floor, ceil),
+            (1_760_486_400_123_456_716, 1_760_486_400_123_456_717)
+        );
+    }
+
+    #[test]
+    fn test_ns_to_seconds() {
+        assert_eq!(ns_to_seconds(0), 0.0);
+        assert_eq!(ns_to_seconds(1_500_000_000), 1.5);
+        assert_eq!(ns_to_seconds(-250_000_000), -0.25);
+        assert_eq!(ns_to_seconds(1), 1e-9);
+        assert_eq!(ns_to_seconds(i64::MAX), 9_223_372_036.854_776);
+        assert_eq!(ns_to_seconds(i64::MIN), -9_223_372_036.854_776);
+        // Below 2^53, both operands are exact and a single f64 division is correctly rounded.
+        let mut ns = 1i64;
+        while ns < 1 << 53 {
+            for delta in [-1, 0, 1] {
+                assert_eq!(ns_to_seconds(ns + delta), (ns + delta) as f64 / 1e9);
+                assert_eq!(ns_to_seconds(-ns - delta), -(ns + delta) as f64 / 1e9);
+            }
+            ns = ns * 3 + 7;
+        }
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("1ms", "ns"), Ok(1_000_000));
+        assert_eq!(parse_duration("250us", "ns"), Ok(250_000));
+        assert_eq!(parse_duration("250 µs", "us"), Ok(250));
+        assert_eq!(parse_duration("2s", "ms"), Ok(2_000));
+        assert_eq!(parse_duration("0.5s", "ns"), Ok(500_000_000));
+        assert_eq!(parse_duration(".5s", "ms"), Ok(500));
+        assert_eq!(parse_duration("-1.250ms", "us"), Ok(-1_250));
+        assert_eq!(parse_duration("1.5min", "s"), Ok(90));
+        assert_eq!(parse_duration("1d", "h"), Ok(24));
+        assert_eq!(parse_duration("2W", "D"), Ok(14));
+        assert_eq!(parse_duration("90s", "m"), Err(DurationError::Inexact));
+        assert_eq!(parse_duration("120s", "m"), Ok(2));
+        assert_eq!(parse_duration("0.3ns", "ns"), Err(DurationError::Inexact));
+        assert_eq!(parse_duration("0.3ns", "ps"), Ok(300));
+        assert_eq!(
+            parse_duration("1.000000000000000000000000000000000000000s", "s"),
+            Ok(1)
+        );
+        assert_eq!(
+            parse_duration("1.000000000000000000000000000000000000001s", "s"),
+            Err(DurationError::Inexact)
+        );
+        // A minute is 2^20 * 3 * 5^19 attoseconds: the digit 5 is exact up to the 20th place.
+        assert_eq!(parse_duration("0.00000000000000000005min", "as"), Ok(3));
+        assert_eq!(
+            parse_duration("0.000000000000000000005min", "as"),
+            Err(DurationError::Inexact)
+        );
+        assert_eq!(parse_duration("1m", "ns"), Err(DurationError::Ambiguous));
+        assert_eq!(
+            parse_duration("10000000000s", "ns"),
+            Err(DurationError::OutOfRange)
+        );
+        assert_eq!(parse_duration("1e3s", "s"), Err(DurationError::Invalid));
+        for text in ["", "s", "1", "1 ", ".s", "1.2.3s", "1Y", "1 sec", "--1s"] {
+            assert_eq!(
+                parse_duration(text, "ns"),
+                Err(DurationError::Invalid),
+                "{text}"
+            );
+        }
+        assert_eq!(parse_duration("1s", "Y"), Err(DurationError::Invalid));
+    }
+
+    #[test]
+    fn test_scales() {
+        assert_eq!(to_ticks(2.5, 1, Method::None), Err(UnitError::Inexact));
+        assert_eq!(to_ticks(2.5, 1, Method::Nearest), Ok(2));
+        assert_eq!(to_ticks(2f64.powi(62), 1, Method::None), Ok(1 << 62));
+        assert_eq!(
+            to_ticks(2f64.powi(63), 1, Method::None),
+            Err(UnitError::OutOfRange)
+        );
+        assert_eq!(to_ticks(-(2f64.powi(63)), 1, Method::None), Ok(i64::MIN));
+        assert_eq!(to_ticks(1.25, 4, Method::None), Ok(5));
+        assert_eq!(to_ticks(1e-300, u64::MAX, Method::BackwardFill), Ok(1));
+        assert_eq!(to_ticks(1.0, 0, Method::None), Err(UnitError::InvalidScale));
+        assert_eq!(from_ticks(i64::MAX, 1), Ok(2f64.powi(63)));
+        assert_eq!(from_ticks(5, 4), Ok(1.25));
+        assert_eq!(from_ticks(1, u64::MAX), Ok(1.0 / u64::MAX as f64));
+        assert_eq!(from_ticks(i64::MIN, u64::MAX), Ok(-0.5));
+        assert_eq!(from_ticks(1, 0), Err(UnitError::InvalidScale));
+    }
+
+    #[test]
+    fn test_unit_interp() {
+        let interp = Interp::new(vec![0, 10], vec![1_000_000_000i64, 3_000_000_000]);
+        assert_eq!(
+            UnitInterp::new(interp.clone(), 0),
+            Err(UnitError::InvalidScale)
+        );
+        let unit = UnitInterp::new(interp, 1_000_000_000).unwrap();
+        assert_eq!(unit.forward(5), Ok(2.0));
+        assert_eq!(unit.forward(11), Err(InterpError::OutOfBounds));
+        assert_eq!(unit.inverse(2.0, Method::None), Ok(5));
+        assert_eq!(unit.inverse(2.1, Method::None), Err(InterpError::NotFound));
+        assert_eq!(unit.inverse(2.09, Method::Nearest), Ok(5));
+        assert_eq!(unit.inverse(f64::NAN, None), Err(InterpError::NotFound));
+        assert_eq!(unit.inverse(1e20, None), Err(InterpError::OutOfBounds));
+        let unit = unit.with_rounding(Method::None);
+        assert_eq!(unit.inverse(2.0000000001, None), Err(InterpError::NotFound));
+        let cropped = unit.crop(2, 4).unwrap();
+        assert_eq!(cropped.interp().fp(), &[1_400_000_000, 1_800_000_000]);
+        assert_eq!(cropped.scale(), 1_000_000_000);
+        assert_eq!(unit.slice(1..2).unwrap().forward(10), Ok(3.0));
+    }
+
+    #[test]
+    fn test_unit_interp_precision() {
+        // Timestamps of today sampled every 300 ns, while f64 seconds are 238 ns apart: each
+        // sample is still found back from its f64 value. A pure f64 implementation rounds the
+        // data points too and accumulates enough error to pick neighboring samples.
+        let t0 = 1_760_486_400_123_456_789i64;
+        let interp = Interp::new(vec![0, 1_000_000], vec![t0, t0 + 300_000_000]);
+        let unit = UnitInterp::new(interp, NS_PER_SECOND).unwrap();
+        let (f0, f1) = (ns_to_seconds(t0), ns_to_seconds(t0 + 300_000_000));
+        let mut mismatches = 0;
+        for x in 0..1000 {
+            let ns = t0 + 300 * x as i64;
+            let seconds = unit.forward(x).unwrap();
+            assert_eq!(seconds, ns_to_seconds(ns));
+            assert_eq!(unit.inverse(seconds, Method::Nearest), Ok(x));
+            let naive = ((seconds - f0) / (f1 - f0) * 1e6).round() as u64;
+            if naive != x {
+                mismatches += 1;
+            }
+        }
+        assert!(mismatches > 0);
+    }
+}
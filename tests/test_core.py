@@ -1,7 +1,35 @@
 import numpy as np
 import pytest
 
-from xinterp import forward, inverse
+from xinterp import (
+    Interp,
+    Method,
+    check_monotonic,
+    decimate,
+    derivative,
+    detect_outliers,
+    dumps,
+    epsilon_sweep,
+    fingerprint,
+    forward,
+    forward_2d,
+    forward_multi,
+    forward_range,
+    from_dict,
+    inverse,
+    inverse_multi,
+    load_npy_dense,
+    load_npy_pair,
+    loads,
+    ns_to_seconds,
+    rust,
+    sample,
+    seconds_to_ns,
+    smooth,
+    summary,
+    to_dict,
+    verify,
+)
 
 
 class TestForward:
@@ -61,6 +89,29 @@ class TestForward:
         with pytest.raises(IndexError, match="x out of bounds"):
             forward([3], [1, 2], [3, 5])
 
+    def test_errors_mask(self):
+        out = forward([0, 1, 3, 2], [0, 2], [3.0, 7.0], errors="mask")
+        assert isinstance(out, np.ma.MaskedArray)
+        assert list(out.mask) == [False, False, True, False]
+        assert list(out.compressed()) == [3.0, 5.0, 7.0]
+        out = forward([0, 1], [0, 2], [3, 7], errors="mask")
+        assert not isinstance(out, np.ma.MaskedArray)
+        out = forward([0, 2], [0, 2], [3, 7], closed="left", errors="mask")
+        assert list(out.mask) == [False, True]
+        with pytest.raises(ValueError, match="xp must be strictly increasing"):
+            forward([0], [0, 2, 1], [3, 7, 5], errors="mask")
+
+    def test_closed(self):
+        assert forward(2, [1, 2], [3, 5], closed="right") == 5
+        with pytest.raises(IndexError, match="x out of bounds"):
+            forward(2, [1, 2], [3, 5], closed="left")
+        with pytest.raises(IndexError, match="x out of bounds"):
+            forward(1, [1, 2], [3.0, 5.0], closed="right")
+        out = rust.forward_float_at([1.0, 1.5], [1, 2], [3.0, 5.0], closed="left")
+        assert list(out) == [3.0, 4.0]
+        with pytest.raises(IndexError, match="x out of bounds"):
+            rust.forward_float_at([1.0], [1, 2], [3.0, 5.0], closed="right")
+
     def test_type_handling(self):
         assert forward([1], [0, 2], [3, 5]) == 4
         assert forward([1], [0, 2], [3, 5]).dtype in ["i4", "i8"]
@@ -152,9 +203,29 @@ class TestInverse:
         inverse([4.0], [0, 2], np.array([3, 5], dtype="M8[s]")) == 1
         inverse([4], [0, 2], np.array([3, 5], dtype="M8[s]")) == 1
 
-    def test_raises_not_strictly_incresing(self):
-        with pytest.raises(ValueError, match="fp must be strictly increasing"):
-            inverse([4], [0, 2], [5, 3])
+    def test_raises_not_strictly_monotonic(self):
+        with pytest.raises(ValueError, match="fp must be strictly monotonic"):
+            inverse([4], [0, 2, 4], [3, 5, 3])
+
+    def test_closed(self):
+        assert inverse(5, [0, 2], [3, 5], closed="left", method="nearest") == 2
+        with pytest.raises(KeyError, match="f out of bounds"):
+            inverse(5, [0, 2], [3, 5], closed="left")
+        with pytest.raises(KeyError, match="f out of bounds"):
+            inverse(3, [0, 2], [3, 5], closed="right")
+        with pytest.raises(ValueError, match="closed must be"):
+            inverse(3, [0, 2], [3, 5], closed="none")
+        out = rust.inverse_int_fractional([3, 4], [0, 2], [3, 5], closed="right")
+        assert list(out) == [0.0, 1.0]
+        with pytest.raises(KeyError, match="f out of bounds"):
+            rust.inverse_int_fractional([5], [0, 2], [3, 5], closed="left")
+        with pytest.raises(KeyError, match="f out of bounds"):
+            rust.inverse_float_fractional([3.0], [0, 2], [3.0, 5.0], closed="right")
+
+    def test_decreasing(self):
+        assert inverse(4, [0, 2], [5, 3]) == 1
+        assert inverse(4.5, [0, 2], [5, 3], method="ffill") == 0
+        assert inverse(4.5, [0, 2], [5, 3], method="bfill") == 1
 
     def test_raises_out_of_bounds(self):
         with pytest.raises(KeyError, match="f out of bounds"):
@@ -189,6 +260,87 @@ class TestInverse:
         ):
             inverse([4], [0, 2], [3, 5], method="non_existing_method")
 
+    def test_method_enum(self):
+        xp = [0, 8]
+        fp = [0, 80]
+        for string, member in [
+            (None, Method.NONE),
+            ("nearest", Method.NEAREST),
+            ("ffill", Method.FFILL),
+            ("bfill", Method.BFILL),
+        ]:
+            f = [0, 80] if member == Method.NONE else [14, 16]
+            assert np.array_equal(
+                inverse(f, xp, fp, method=string), inverse(f, xp, fp, method=member)
+            )
+        assert list(inverse([14, 16], xp, fp, method=Method.NEAREST)) == [1, 2]
+        assert list(inverse([14, 16], xp, fp, method=Method.FFILL)) == [1, 1]
+        assert list(inverse([14, 16], xp, fp, method=Method.BFILL)) == [2, 2]
+
+    def test_return_stats(self):
+        x, stats = inverse([0, 1, 2, 3], [0, 10], [0, 3], "nearest", return_stats=True)
+        assert list(x) == [0, 3, 7, 10]
+        assert stats.exact_count == 2
+        assert stats.rounded_count == 2
+        assert stats.max_abs_residual == 0
+        x, stats = inverse(6.0, [0, 2], [0.0, 10.0], "ffill", return_stats=True)
+        assert x == 1
+        assert stats.exact_count == 0
+        assert stats.rounded_count == 1
+        assert stats.max_abs_residual == 1.0
+        x = inverse([5.0], [0, 2], [0.0, 10.0])
+        assert isinstance(x, np.ndarray)
+
+    def test_method_members(self):
+        assert Method.members() == [
+            Method.NONE,
+            Method.NEAREST,
+            Method.FFILL,
+            Method.BFILL,
+        ]
+        assert Method.NEAREST == Method.NEAREST
+        assert Method.NEAREST != Method.FFILL
+        assert repr(Method.FFILL) == "Method.FFILL"
+        names = {Method.NEAREST: "nearest", Method.FFILL: "ffill"}
+        assert names[Method.FFILL] == "ffill"
+
+    def test_errors_mask(self):
+        # 2 and 8 are out of bounds, 4 and 6 fall between indices (0.5 and 1.5)
+        out = inverse([2, 3, 4, 6, 8], [0, 2], [3, 7], errors="mask")
+        assert isinstance(out, np.ma.MaskedArray)
+        assert list(out.mask) == [True, False, True, True, True]
+        assert list(out.compressed()) == [0]
+        out = inverse([3, 5, 7], [0, 2], [3, 7], errors="mask")
+        assert not isinstance(out, np.ma.MaskedArray)
+        assert list(out) == [0, 1, 2]
+        with pytest.raises(ValueError, match="fp must be strictly monotonic"):
+            inverse([3], [0, 1, 2], [3, 7, 5], errors="mask")
+        with pytest.raises(ValueError, match="errors must be either"):
+            inverse([3], [0, 2], [3, 7], errors="ignore")
+
+    def test_iso_strings(self):
+        xp = [0, 1000]
+        fp = np.array(["2024-03-01T12:00:00", "2024-03-01T12:00:01"], dtype="M8[ns]")
+        assert inverse("2024-03-01T12:00:00.000000123", xp, fp, method="ffill") == 0
+        assert inverse("2024-03-01T12:00:00.000999999", xp, fp, method="ffill") == 0
+        assert inverse("2024-03-01T12:00:00.001000000", xp, fp, method="ffill") == 1
+        f = ["2024-03-01T12:00:00.5", "2024-03-01T13:00:01+01:00"]
+        assert list(inverse(f, xp, fp)) == [500, 1000]
+        assert list(inverse(np.array(f), xp, fp)) == [500, 1000]
+        fp = np.array(["1969-12-31T23:59:59", "1970-01-01T00:00:01"], dtype="M8[s]")
+        assert inverse("1969-12-31T23:59:59.5", xp, fp) == 250
+        assert inverse("1969-12-31T23:59:59.500000001", xp, fp, method="bfill") == 251
+
+    def test_raises_invalid_iso_strings(self):
+        xp = [0, 1000]
+        fp = np.array(["2024-03-01T12:00:00", "2024-03-01T12:00:01"], dtype="M8[ns]")
+        with pytest.raises(ValueError, match="invalid ISO 8601 datetime '2024-03-32'"):
+            inverse("2024-03-32", xp, fp)
+        with pytest.raises(ValueError, match="out of the nanosecond range"):
+            inverse(["2024-03-01", "3000-01-01"], xp, fp)
+        with pytest.raises(ValueError, match="fp has datetime64 dtype"):
+            inverse("2024-03-01", xp, [0, 1])
+
     def test_type_handling(self):
         assert inverse([4], [0, 2], [3, 5]) == 1
         assert inverse([4.0], [0, 2], [3.0, 5.0]) == 1
@@ -288,3 +440,1401 @@ class TestInverse:
         cases = [(1, 21), (2, 23), (3, 25), (4, 27), (5, 29), (6, 31), (7, 33), (8, 35)]
         for x, f in cases:
             assert inverse([float(f)], xp, fp, method="bfill")[0] == x
+
+
+class TestCheckMonotonic:
+    def test_kinds(self):
+        assert check_monotonic([0, 1, 2]) == {
+            "kind": "strict",
+            "violations": [],
+            "nans": [],
+        }
+        assert check_monotonic([0, 1, 1]) == {
+            "kind": "non-decreasing",
+            "violations": [2],
+            "nans": [],
+        }
+        assert check_monotonic([2.0, 1.0, np.nan]) == {
+            "kind": "none",
+            "violations": [1],
+            "nans": [2],
+        }
+
+
+class TestSmooth:
+    def test_mean(self):
+        fp = [0, 3, 6, 10, 11, 20]
+        assert np.array_equal(smooth(fp, 3), [0, 3, 6, 9, 14, 20])
+        assert np.array_equal(smooth(np.array(fp, dtype="f8"), 1), fp)
+
+    def test_median(self):
+        fp = [0.0, 1.0, 100.0, 3.0, 4.0]
+        assert np.array_equal(smooth(fp, 3, kind="median"), [0.0, 1.0, 3.0, 4.0, 4.0])
+
+    def test_datetime(self):
+        fp = np.array(["2023-01-01T00:00:00.000000000"], dtype="datetime64[ns]") + [
+            0,
+            1,
+            5,
+            6,
+            8,
+        ]
+        result = smooth(fp, 3)
+        assert result.dtype == fp.dtype
+        assert np.array_equal(result - fp[0], np.array([0, 2, 4, 6, 8], "m8[ns]"))
+
+    def test_raises(self):
+        with pytest.raises(ValueError, match="window must be positive"):
+            smooth([0, 1, 2], 0)
+        with pytest.raises(ValueError, match="kind must be"):
+            smooth([0, 1, 2], 3, kind="mode")
+        with pytest.raises(ValueError, match="fp must be 1D"):
+            smooth([[0, 1, 2]], 3)
+
+
+class TestDetectOutliers:
+    def test_spikes(self):
+        xp = np.arange(20)
+        fp = 10 * np.arange(20)
+        fp[7] += 100
+        fp[13] -= 100
+        assert np.array_equal(detect_outliers(xp, fp, 5, 60), [7, 13])
+        assert np.array_equal(detect_outliers(xp, fp.astype("f8"), 5, 60.0), [7, 13])
+
+    def test_datetime(self):
+        xp = np.arange(10)
+        fp = np.datetime64("2023-01-01T00:00:00") + np.arange(10) * np.timedelta64(1, "s")
+        fp = fp.astype("datetime64[ns]")
+        fp[0] += np.timedelta64(500, "ms")
+        assert np.array_equal(
+            detect_outliers(xp, fp, 5, np.timedelta64(300, "ms")), [0]
+        )
+
+    def test_raises_not_strictly_increasing(self):
+        with pytest.raises(ValueError, match="xp must be strictly increasing"):
+            detect_outliers([0, 2, 1], [0, 1, 2], 3, 0)
+
+
+class TestVerify:
+    def test_integers(self):
+        values = 10**18 + 7 * np.arange(100)
+        xp = [0, 40, 99]
+        fp = values[xp]
+        report = verify(values, xp, fp, 0)
+        assert report.violations == 0
+        assert report.max_deviation == 0
+        values[17] += 5
+        values[63] -= 2
+        report = verify(values, xp, fp, 3)
+        assert report.violations == 1
+        assert report.max_deviation == 5
+        assert report.argmax == 17
+
+    def test_floats(self):
+        values = 0.5 * np.arange(10)
+        values[6] += 0.25
+        report = verify(values, [0, 9], [0.0, 4.5], 0.1)
+        assert (report.violations, report.max_deviation, report.argmax) == (1, 0.25, 6)
+
+    def test_datetime(self):
+        start = np.datetime64("2023-01-01T00:00:00", "ns")
+        values = start + np.arange(10) * np.timedelta64(1, "s")
+        fp = values[[0, 9]]
+        values[4] += np.timedelta64(2, "ms")
+        report = verify(values, [0, 9], fp, np.timedelta64(1, "ms"))
+        assert report.violations == 1
+        assert report.max_deviation == 2_000_000
+        assert report.argmax == 4
+
+    def test_empty(self):
+        report = verify(np.array([], dtype="i8"), [0, 1], [0, 1], 0)
+        assert report.violations == 0
+        assert report.max_deviation is None
+        assert report.argmax is None
+
+    def test_raises_out_of_bounds(self):
+        with pytest.raises(IndexError, match="x out of bounds"):
+            verify(np.arange(5), [1, 4], [1, 4], 0)
+
+
+class TestMulti:
+    @pytest.mark.parametrize("parallel", [False, True])
+    def test_forward(self, parallel):
+        x_list = [[0, 5, 10], [0, 11], [1]]
+        xp_list = [[0, 10], [0, 10], [0, 2]]
+        fp_list = [np.array([100, 200]), np.array([100, 200]), np.array([-4, 0])]
+        results, errors = forward_multi(x_list, xp_list, fp_list, parallel=parallel)
+        assert list(results[0]) == [100, 150, 200]
+        assert results[1] is None
+        assert list(results[2]) == [-2]
+        assert list(errors) == [1]
+        assert isinstance(errors[1], IndexError)
+        assert str(errors[1]) == "x[1] out of bounds"
+
+    def test_forward_datetime(self):
+        fp = np.array(["2023-01-01T00:00:00", "2023-01-01T00:00:10"], dtype="M8[ns]")
+        results, errors = forward_multi([[5]] * 3, [[0, 10]] * 3, [fp] * 3)
+        assert not errors
+        assert all(out.dtype == fp.dtype for out in results)
+        assert all(out[0] == np.datetime64("2023-01-01T00:00:05") for out in results)
+
+    def test_inverse(self):
+        f_list = [[1.0, 1.3], [1.0]]
+        xp_list = [[0, 4], [0, 4]]
+        fp_list = [[1.0, 2.0], [1.0, 1.0]]
+        results, errors = inverse_multi(f_list, xp_list, fp_list, method="nearest")
+        assert list(results[0]) == [0, 1]
+        assert results[1] is None
+        assert isinstance(errors[1], ValueError)
+        results, errors = inverse_multi(f_list, xp_list, fp_list)
+        assert isinstance(errors[0], KeyError)
+
+    def test_many_pairs(self):
+        xp_list = [np.array([0, 10]) for _ in range(1000)]
+        fp_list = [np.array([0, 10 * k]) for k in range(1000)]
+        x_list = [np.array([5]) for _ in range(1000)]
+        results, errors = forward_multi(x_list, xp_list, fp_list, parallel=True)
+        assert not errors
+        assert [out[0] for out in results] == [5 * k for k in range(1000)]
+
+    def test_raises_invalid_input(self):
+        with pytest.raises(ValueError, match="must have the same length"):
+            forward_multi([[0]], [[0, 1], [0, 1]], [[0, 1], [0, 1]])
+        with pytest.raises(ValueError, match=r"xp_list\[0\] and fp_list\[0\]"):
+            forward_multi([[0]], [[0, 1, 2]], [[0, 1]])
+        with pytest.raises(
+            OverflowError, match=r"x_list\[0\]\[1\] = -1 cannot be negative"
+        ):
+            forward_multi([[0, -1]], [[0, 1]], [[0, 1]])
+        with pytest.raises(ValueError, match="same dtype"):
+            forward_multi([[0], [0]], [[0, 1], [0, 1]], [[0, 1], [0.0, 1.0]])
+
+
+class TestSequences:
+    def test_lists_and_tuples(self):
+        out = rust.forward_int([0, 5, 9], [0, 10], [100, 200])
+        assert isinstance(out, np.ndarray)
+        assert list(out) == [100, 150, 190]
+        out = rust.forward_int((0, 5, 9), (0, 10), (100, 200))
+        assert list(out) == [100, 150, 190]
+        out = rust.inverse_float([1.5, 2], [0, 2], [1, 3.0], "nearest")
+        assert list(out) == [0, 1]
+
+    def test_ranges(self):
+        out = rust.forward_float(range(3), range(0, 4, 2), [0.0, 1.0])
+        assert list(out) == [0.0, 0.5, 1.0]
+
+    def test_raises_generators(self):
+        with pytest.raises(TypeError, match="generators are not supported"):
+            rust.forward_int((x for x in range(3)), [0, 10], [100, 200])
+
+    def test_raises_overflow(self):
+        with pytest.raises(OverflowError, match=r"x\[1\] = -1 cannot be negative"):
+            rust.forward_int([0, -1], [0, 10], [100, 200])
+        with pytest.raises(TypeError, match="argument 'x': element 0 has an invalid type"):
+            rust.forward_int([0.5], [0, 10], [100, 200])
+
+
+class TestConversion:
+    def test_negative_to_unsigned(self):
+        x = np.arange(40) - 2
+        with pytest.raises(OverflowError, match=r"x\[0\] = -2 cannot be negative"):
+            rust.forward_int(x, [0, 10], [100, 200])
+        x = list(range(40))
+        x[37] = -1
+        with pytest.raises(OverflowError, match=r"x\[37\] = -1 cannot be negative"):
+            rust.forward_int(x, [0, 10], [100, 200])
+        with pytest.raises(OverflowError, match=r"xp\[0\] = -10 cannot be negative"):
+            rust.inverse_int([150], [-10, 10], [100, 200], None)
+
+    def test_too_large(self):
+        with pytest.raises(
+            OverflowError,
+            match=r"x\[1\] = 18446744073709551616 exceeds the maximum of 18446744073709551615",
+        ):
+            rust.forward_int([0, 2**64], [0, 10], [100, 200])
+        with pytest.raises(
+            OverflowError,
+            match=r"fp\[0\] = -9223372036854775809 is below the minimum of -9223372036854775808",
+        ):
+            rust.forward_int([0], [0, 10], [-(2**63) - 1, 0])
+        with pytest.raises(OverflowError, match=r"f\[0\] = 9223372036854775808 exceeds"):
+            rust.inverse_int([2**63], [0, 10], [100, 200], None)
+
+    def test_in_range(self):
+        x = np.array([0, 10], dtype="i8")
+        assert list(rust.forward_int(x, [0, 10], [100, 200])) == [100, 200]
+        assert list(rust.forward_int([2**64 - 1], [0, 2**64 - 1], [0, 1])) == [1]
+
+
+@pytest.mark.skipif(
+    np.finfo(np.longdouble).nmant != 63, reason="long double is not x87 extended"
+)
+class TestLongDouble:
+    def test_decimate(self):
+        fp = np.array([0, 1, 0, 1, 0], dtype=np.longdouble) + np.longdouble(2**63)
+        _, out_fp, error = decimate([0, 1, 2, 3, 4], fp, k=2)
+        assert out_fp.dtype == np.longdouble and error.dtype == np.longdouble
+        assert np.array_equal(out_fp, fp[[0, 2, 4]]) and error == 1
+
+    def test_forward(self):
+        start = 10**18 + 1
+        fp = np.array([start, start + 2], dtype=np.longdouble)
+        out = forward([1], [0, 2], fp)
+        assert out.dtype == np.longdouble
+        assert out[0] == np.longdouble(start + 1)
+        lossy = forward([1], [0, 2], fp.astype("f8"))
+        assert not lossy[0] == np.longdouble(start + 1)
+
+    def test_inverse(self):
+        start = 10**18 + 1
+        fp = np.array([start, start + 8], dtype=np.longdouble)
+        f = np.array([start + 2, start + 3], dtype=np.longdouble)
+        assert list(inverse(f, [0, 4], fp, method="ffill")) == [1, 1]
+        assert list(inverse(f, [0, 4], fp, method="bfill")) == [1, 2]
+        with pytest.raises(KeyError, match="f not found"):
+            inverse(f, [0, 4], fp)
+
+    def test_raises_unsupported(self):
+        fp = np.array([0, 1], dtype=np.longdouble)
+        with pytest.raises(ValueError, match="closed is not supported"):
+            forward([0], [0, 1], fp, closed="left")
+
+
+class TestPromotion:
+    @pytest.mark.parametrize(
+        "dtype", ["u1", "u2", "u4", "u8", "i1", "i2", "i4", "i8"]
+    )
+    def test_accept_integers(self, dtype):
+        x = np.array([0, 5, 9], dtype=dtype)
+        xp = np.array([0, 10], dtype=dtype)
+        assert list(rust.forward_int(x, xp, [100, 200])) == [100, 150, 190]
+        fp = np.array([10, 20], dtype=dtype)
+        assert list(rust.forward_int([5], [0, 10], fp)) == [15]
+        if fp.itemsize < 8:
+            assert list(rust.forward_float([5], [0, 10], fp)) == [15.0]
+
+    @pytest.mark.parametrize("dtype", ["f4", "f8"])
+    def test_accept_floats(self, dtype):
+        fp = np.array([1.5, 2.5], dtype=dtype)
+        assert list(rust.forward_float([1], [0, 2], fp)) == [2.0]
+
+    def test_reject_negative_indices(self):
+        with pytest.raises(OverflowError, match=r"x\[0\] = -1 cannot be negative"):
+            rust.forward_int(np.array([-1, 0]), [0, 10], [100, 200])
+
+    def test_reject_large_unsigned_values(self):
+        fp = np.array([0, 2**63], dtype="u8")
+        with pytest.raises(
+            OverflowError,
+            match=r"fp\[1\] = 9223372036854775808 exceeds the maximum of 9223372036854775807",
+        ):
+            rust.forward_int([0], [0, 10], fp)
+
+    @pytest.mark.parametrize("dtype", ["f4", "f8"])
+    def test_reject_float_to_integer(self, dtype):
+        with pytest.raises(TypeError, match=f"from dtype float{8 * int(dtype[1])}"):
+            rust.forward_int(np.array([0, 5], dtype=dtype), [0, 10], [100, 200])
+        with pytest.raises(TypeError, match="argument 'fp'"):
+            rust.forward_int([0], [0, 10], np.array([1.0, 2.0], dtype=dtype))
+
+    @pytest.mark.parametrize("dtype", ["i8", "u8"])
+    def test_reject_wide_integer_to_float(self, dtype):
+        with pytest.raises(TypeError, match="to dtype float64"):
+            rust.forward_float([0], [0, 10], np.array([1, 2], dtype=dtype))
+
+    def test_reject_multidimensional(self):
+        with pytest.raises(TypeError, match="expected a one-dimensional array"):
+            rust.forward_int(np.zeros((2, 2), dtype="u8"), [0, 10], [100, 200])
+
+
+class TestThreads:
+    def test_concurrent_calls(self):
+        from concurrent.futures import ThreadPoolExecutor
+
+        xp = np.arange(0, 100_000, 10, dtype="u8")
+        fp = 1_700_000_000_000_000_000 + 7 * np.arange(xp.size, dtype="i8")
+        chunks = [np.arange(start, 99_990, 8, dtype="u8") for start in range(8)]
+
+        def task(x):
+            f = forward(x, xp, fp)
+            return f, inverse(f, xp, fp, method="ffill")
+
+        serial = [task(x) for x in chunks]
+        with ThreadPoolExecutor(max_workers=8) as executor:
+            concurrent = list(executor.map(task, chunks))
+        for (f, x), (expected_f, expected_x) in zip(concurrent, serial):
+            assert np.array_equal(f, expected_f)
+            assert np.array_equal(x, expected_x)
+
+    def test_shared_instance(self):
+        from concurrent.futures import ThreadPoolExecutor
+
+        xp = np.arange(0, 100_000, 10, dtype="u8")
+        fp = 1_700_000_000_000_000_000 + 7 * np.arange(xp.size, dtype="i8")
+        interp = Interp(xp, fp)
+        chunks = [np.arange(start, 99_990, 8, dtype="u8") for start in range(8)]
+
+        def task(x):
+            f = interp.forward(x)
+            return f, interp.inverse(f, method="ffill")
+
+        serial = [task(x) for x in chunks]
+        assert np.array_equal(serial[0][0], forward(chunks[0], xp, fp))
+        with ThreadPoolExecutor(max_workers=8) as executor:
+            concurrent = list(executor.map(task, chunks * 16))
+        for (f, x), (expected_f, expected_x) in zip(concurrent, serial * 16):
+            assert np.array_equal(f, expected_f)
+            assert np.array_equal(x, expected_x)
+
+
+class TestInterp:
+    def test_int(self):
+        interp = Interp([0, 10, 20], [100, 200, 400])
+        assert np.array_equal(interp.forward([0, 5, 15]), [100, 150, 300])
+        assert np.array_equal(interp.forward([1], method="ffill"), [110])
+        assert np.array_equal(interp.inverse([150, 400]), [5, 20])
+        assert np.array_equal(interp.inverse([101], method="ffill"), [0])
+        with pytest.raises(KeyError, match="f out of bounds"):
+            interp.inverse([500])
+        f, mask = interp.forward([5, 25], errors="mask")
+        assert np.array_equal(mask, [False, True])
+
+    def test_float(self):
+        interp = Interp(np.array([0, 10], dtype="u8"), np.array([0.0, 1.0]))
+        assert np.array_equal(interp.forward([5]), [0.5])
+        assert np.array_equal(interp.inverse([0.25], method="nearest"), [2])
+        interp = Interp([0, 10], [0.0, 1.0], closed="left")
+        with pytest.raises(IndexError, match="x out of bounds"):
+            interp.forward([10])
+
+    def test_raises(self):
+        with pytest.raises(ValueError, match="xp must be strictly increasing"):
+            Interp([0, 10, 5], [0, 1, 2])
+        with pytest.raises(ValueError, match="xp and fp must have the same length"):
+            Interp([0, 10], [0, 1, 2])
+        with pytest.raises(OverflowError, match=r"xp\[0\] = -1 cannot be negative"):
+            Interp([-1, 10], [0, 1])
+
+    def test_sequence(self):
+        interp = Interp([0, 10, 20, 30], [1.0, 2.0, 4.0, 8.0])
+        assert len(interp) == 4
+        assert interp[0] == (0, 1.0)
+        assert interp[-1] == (30, 8.0)
+        assert interp[-4] == (0, 1.0)
+        for index in [4, -5]:
+            with pytest.raises(IndexError, match="Interp index out of range"):
+                interp[index]
+        assert list(interp) == [(0, 1.0), (10, 2.0), (20, 4.0), (30, 8.0)]
+        assert dict(Interp([5, 6], [-1, 1])) == {5: -1, 6: 1}
+
+    def test_arrays(self):
+        interp = Interp([0, 10], [100, 200])
+        assert interp.xp.dtype == np.uint64 and np.array_equal(interp.xp, [0, 10])
+        assert interp.fp.dtype == np.int64 and np.array_equal(interp.fp, [100, 200])
+        interp.xp[0] = 5
+        assert interp[0] == (0, 100)
+        assert Interp([0, 10], [0.5, 1.5]).fp.dtype == np.float64
+
+    def test_repr(self):
+        assert repr(Interp([0, 10], [100, 200])) == "Interp(xp=[0, 10], fp=[100, 200])"
+        interp = Interp(np.arange(8), np.arange(8) / 2)
+        assert repr(interp) == (
+            "Interp(xp=[0, 1, 2, ..., 5, 6, 7], fp=[0.0, 0.5, 1.0, ..., 2.5, 3.0, 3.5])"
+        )
+
+    def test_slice(self):
+        interp = Interp([0, 10, 20, 30], [100, 200, 400, 800], closed="left")
+        sliced = interp[1:3]
+        assert isinstance(sliced, Interp)
+        assert list(sliced) == [(10, 200), (20, 400)]
+        assert np.array_equal(sliced.forward([15]), [300])
+        with pytest.raises(IndexError, match="x out of bounds"):
+            sliced.forward([20])
+        assert list(interp[-2:]) == [(20, 400), (30, 800)]
+        assert len(interp[3:1]) == 0
+        assert len(interp[:10]) == 4
+        with pytest.raises(ValueError, match="slice step must be 1"):
+            interp[::2]
+
+
+class TestSample:
+    def test_endpoints(self):
+        x, f = sample([10, 20, 24], [0, 100, 400], 3)
+        assert np.array_equal(x, [10, 17, 24])
+        assert np.array_equal(f, [0, 70, 400])
+        x, f = sample([10, 20, 24], [0, 100, 400], 15)
+        assert np.array_equal(x, np.arange(10, 25))
+        assert f[0] == 0 and f[-1] == 400
+
+    def test_single(self):
+        x, f = sample([10, 20], [1.0, 2.0], 1)
+        assert np.array_equal(x, [10])
+        assert np.array_equal(f, [1.0])
+
+    def test_datetime(self):
+        fp = np.array(["2024-01-01", "2024-01-03"], dtype="datetime64[D]")
+        x, f = sample([0, 2], fp, 3)
+        assert f.dtype == fp.dtype
+        assert f[1] == np.datetime64("2024-01-02")
+
+    def test_raises_zero(self):
+        with pytest.raises(ValueError, match="n must be positive"):
+            sample([0, 10], [0, 1], 0)
+
+
+class TestDerivative:
+    def test_slopes(self):
+        starts, slopes = derivative([0, 10, 14, 22], [100, 200, 180, 182])
+        assert np.array_equal(starts, [0, 10, 14])
+        assert np.array_equal(slopes, [10.0, -5.0, 0.25])
+
+    def test_datetime(self):
+        fp = np.array(["2024-01-01T00:00:00", "2024-01-01T00:00:10"], dtype="M8[ms]")
+        starts, slopes = derivative([0, 1000], fp)
+        assert np.array_equal(starts, [0])
+        assert np.array_equal(slopes, [10.0])
+
+
+class TestSummary:
+    def test_statistics(self):
+        out = summary([0, 10, 20, 25, 35], [100, 200, 300, 450, 440])
+        assert out["points"] == 5
+        assert out["domain"] == (0, 35)
+        assert out["range"] == (100, 450)
+        assert out["slopes"] == (-1.0, 10.0, 30.0)
+        assert out["lengths"] == (5, 10)
+        assert out["irregular"] == 2
+
+    def test_datetime(self):
+        fp = np.array(["2024-01-01", "2024-01-03"], dtype="datetime64[D]")
+        out = summary([0, 2], fp)
+        assert out["range"] == (fp[0], fp[1])
+        assert out["slopes"] == (1.0, 1.0, 1.0)
+        assert out["irregular"] == 0
+
+
+class TestForward2D:
+    def test_bilinear(self):
+        fp = np.array([[0, 1000], [10, 1010], [30, 1030]])
+        out = forward_2d([0, 5, 15, 20], [0, 50, 1, 100], [0, 10, 20], [0, 100], fp)
+        assert np.array_equal(out, [0, 505, 30, 1030])
+        out = forward_2d([3], [2], [2, 4], [0, 4], [[1.0, 2.0], [3.0, 5.0]])
+        assert np.array_equal(out, [2.75])
+
+    def test_datetime(self):
+        fp = np.array([["2024-01-01", "2024-01-03"], ["2024-01-05", "2024-01-07"]])
+        fp = fp.astype("datetime64[D]")
+        out = forward_2d([1], [1], [0, 2], [0, 2], fp)
+        assert out.dtype == fp.dtype
+        assert out[0] == np.datetime64("2024-01-04")
+
+    def test_errors(self):
+        fp = [[0, 1], [2, 3]]
+        with pytest.raises(IndexError, match="out of bounds"):
+            forward_2d([3], [0], [0, 2], [0, 2], fp)
+        out = forward_2d([1, 3], [0, 0], [0, 2], [0, 2], fp, errors="mask")
+        assert np.array_equal(out.mask, [False, True])
+        with pytest.raises(ValueError, match="fp must have shape"):
+            forward_2d([0], [0], [0, 2], [0, 2, 4], fp)
+
+
+class TestSerialization:
+    @pytest.mark.parametrize("dtype", ["i8", "f8"])
+    def test_round_trip(self, dtype):
+        xp = np.array([0, 10, 20], dtype="u8")
+        fp = np.array([-5, 7, 1_000_000], dtype=dtype)
+        for checksum in [True, False]:
+            out_xp, out_fp = loads(dumps(xp, fp, checksum=checksum))
+            assert np.array_equal(out_xp, xp)
+            assert np.array_equal(out_fp, fp)
+            assert out_fp.dtype == fp.dtype
+
+    def test_corrupted(self):
+        data = dumps([0, 10, 20], [1, 2, 3])
+        with pytest.raises(ValueError, match="truncated buffer"):
+            loads(data[:-1])
+        corrupted = bytearray(data)
+        corrupted[30] ^= 1
+        with pytest.raises(ValueError, match="checksum mismatch"):
+            loads(bytes(corrupted))
+        with pytest.raises(ValueError, match="not a buffer of data points"):
+            loads(b"0" * 32)
+
+
+class TestUnits:
+    @pytest.mark.parametrize("fp_unit", ["s", "ms", "us", "ns"])
+    @pytest.mark.parametrize("f_unit", ["s", "ms", "us", "ns"])
+    def test_inverse(self, fp_unit, f_unit):
+        xp = [0, 1000]
+        fp = np.array(["2024-01-01T00:00:00", "2024-01-01T00:16:40"], f"M8[{fp_unit}]")
+        f = np.array(["2024-01-01T00:00:01", "2024-01-01T00:16:40"], f"M8[{f_unit}]")
+        assert np.array_equal(inverse(f, xp, fp), [1, 1000])
+
+    def test_finer_query(self):
+        xp = [0, 1000]
+        fp = np.array(["2024-01-01T00:00:00", "2024-01-01T00:00:01"], "M8[s]")
+        f = np.datetime64("2024-01-01T00:00:00.500", "ms")
+        assert inverse(f, xp, fp) == 500
+        f = np.datetime64("2024-01-01T00:00:00.500300", "us")
+        assert inverse(f, xp, fp, method="nearest") == 500
+        with pytest.raises(KeyError):
+            inverse(f, xp, fp)
+
+    def test_forward(self):
+        xp = [0, 1000]
+        fp = np.array(["2024-01-01T00:00:00", "2024-01-01T00:00:01"], "M8[us]")
+        out = forward([500], xp, fp)
+        assert out.dtype == np.dtype("M8[us]")
+        assert out[0] == np.datetime64("2024-01-01T00:00:00.500", "ms")
+
+    def test_multi(self):
+        xp = [0, 1000]
+        fp = np.array(["2024-01-01T00:00:00", "2024-01-01T00:00:01"], "M8[s]")
+        f = np.array(["2024-01-01T00:00:00.250"], "M8[ms]")
+        out, errors = inverse_multi([f], [xp], [fp])
+        assert not errors
+        assert np.array_equal(out[0], [250])
+
+    def test_overflow(self):
+        xp = [0, 1]
+        fp = np.array(["2262-04-11", "2262-04-12"], "M8[us]")
+        f = np.datetime64("2262-04-11T00:00:00.000000000", "ns")
+        with pytest.raises(OverflowError, match="out of the range"):
+            inverse(f, xp, fp)
+        with pytest.raises(OverflowError, match="out of the range"):
+            inverse("2262-04-11", xp, fp)
+        fp = np.array(["2262-04-10", "2262-04-11"], "M8[us]")
+        assert inverse(f, xp, fp) == 1
+
+    def test_lossy_threshold(self):
+        xp = [0, 1, 2]
+        fp = np.array(["2024-01-01", "2024-01-02", "2024-01-03"], "M8[s]")
+        assert np.array_equal(detect_outliers(xp, fp, 3, np.timedelta64(1000, "ms")), [])
+        with pytest.raises(ValueError, match="without loss"):
+            detect_outliers(xp, fp, 3, np.timedelta64(1500, "ms"))
+        with pytest.raises(ValueError, match="without loss"):
+            verify(fp, xp, fp, np.timedelta64(1, "ns"))
+
+
+class TestGaps:
+    xp = [0, 10, 11, 21]
+    fp = [0, 100, 1000, 1100]
+
+    def test_raise(self):
+        f = [50, 100, 1000, 1050]
+        assert np.array_equal(inverse(f, self.xp, self.fp, max_gap=50), [5, 10, 11, 16])
+        with pytest.raises(KeyError, match="in a gap"):
+            inverse(600, self.xp, self.fp, method="nearest", max_gap_factor=5)
+        out = inverse(
+            [50, 600], self.xp, self.fp, method="nearest", max_gap=50, errors="mask"
+        )
+        assert np.array_equal(out.mask, [False, True])
+        assert inverse(600, self.xp, self.fp, method="nearest") == 11
+
+    def test_snap(self):
+        for method, expected in [("nearest", 10), ("ffill", 10), ("bfill", 11)]:
+            out = inverse(400, self.xp, self.fp, method=method, max_gap=50, gaps="snap")
+            assert out == expected
+        with pytest.raises(KeyError):
+            inverse(400, self.xp, self.fp, max_gap=50, gaps="snap")
+
+    def test_datetime(self):
+        fp = np.array(self.fp, "M8[s]")
+        f = np.datetime64(600, "s")
+        with pytest.raises(KeyError):
+            inverse(f, self.xp, fp, method="nearest", max_gap=np.timedelta64(1, "m"))
+        out = inverse(f, self.xp, fp, method="nearest", max_gap=np.timedelta64(15, "m"))
+        assert out == 11
+
+    def test_invalid(self):
+        with pytest.raises(ValueError, match="cannot be both provided"):
+            inverse(50, self.xp, self.fp, max_gap=50, max_gap_factor=5)
+        with pytest.raises(ValueError, match="gaps must be"):
+            inverse(50, self.xp, self.fp, max_gap=50, gaps="ignore")
+
+
+class TestLimit:
+    xp = [10, 20, 30]
+    fp = [100, 200, 300]
+
+    def test_edges(self):
+        assert inverse(350, self.xp, self.fp, method="ffill", limit=5) == 30
+        with pytest.raises(KeyError):
+            inverse(351, self.xp, self.fp, method="ffill", limit=5)
+        assert inverse(50, self.xp, self.fp, method="bfill", limit=5) == 10
+        with pytest.raises(KeyError):
+            inverse(49, self.xp, self.fp, method="bfill", limit=5)
+        out = inverse(
+            [340, 360], self.xp, self.fp, method="ffill", limit=5, errors="mask"
+        )
+        assert np.array_equal(out.mask, [False, True])
+
+    def test_inside(self):
+        assert inverse(255, self.xp, self.fp, method="ffill", limit=1) == 25
+        with pytest.raises(KeyError):
+            inverse(255, self.xp, self.fp, method="ffill", limit=0)
+        assert inverse(200, self.xp, self.fp, method="bfill", limit=0) == 20
+        assert inverse(350, self.xp, self.fp, method="nearest", limit=0) == 30
+
+
+class TestUnitConversions:
+    def test_precision_cliff(self):
+        ns = 2**53 + 1
+        seconds = 9007199.254740993
+        assert round(seconds * 1e9) == 2**53
+        assert seconds_to_ns(seconds) == ns
+        assert ns_to_seconds(ns) == seconds
+
+    def test_arrays(self):
+        out = seconds_to_ns([1.5, -0.25, 0.0009765625])
+        assert out.dtype == np.int64
+        assert np.array_equal(out, [1_500_000_000, -250_000_000, 976_562])
+        assert np.array_equal(ns_to_seconds(out), [1.5, -0.25, 0.000976562])
+        assert ns_to_seconds(np.timedelta64(3, "s")) == 3.0
+
+    def test_methods(self):
+        assert seconds_to_ns(1e-10, method="bfill") == 1
+        assert seconds_to_ns(-1e-10, method=Method.FFILL) == -1
+        with pytest.raises(ValueError, match="not an integer number of nanoseconds"):
+            seconds_to_ns([0.5, 1e-10], method=None)
+
+    def test_raises(self):
+        with pytest.raises(ValueError, match=r"seconds\[1\] = NaN: non-finite"):
+            seconds_to_ns([0.0, np.nan])
+        with pytest.raises(OverflowError, match="out of the nanosecond range"):
+            seconds_to_ns(1e10)
+
+
+class TestFingerprint:
+    def test_stable(self):
+        xp, fp = [0, 10, 20], [0.5, 1.5, 2.5]
+        assert fingerprint(xp, fp) == 10550901362686441908
+        assert fingerprint(np.array(xp), np.array(fp)) == fingerprint(xp, fp)
+        assert hash(fingerprint(xp, fp)) == hash(fingerprint(xp, fp))
+
+    def test_sensitive(self):
+        xp, fp = [0, 10, 20], np.array([0.5, 1.5, 2.5])
+        other = fp.copy()
+        other[1] = np.nextafter(other[1], np.inf)
+        assert fingerprint(xp, fp) != fingerprint(xp, other)
+        assert fingerprint(xp, [0, 1, 2]) != fingerprint(xp, [0.0, 1.0, 2.0])
+        assert fingerprint(xp[:2], fp[:2]) != fingerprint(xp, fp)
+
+    def test_datetime_unit(self):
+        xp, fp = [0, 10], np.array([0, 1])
+        ns = fingerprint(xp, fp.astype("datetime64[ns]"))
+        us = fingerprint(xp, fp.astype("datetime64[us]"))
+        assert ns != us
+        assert ns != fingerprint(xp, fp)
+
+
+class TestForwardRange:
+    def test_matches_arange(self):
+        xp = [10, 20, 24, 1000]
+        for fp in ([0, 100, 400, -3], [0.0, 0.5, 2.0, -3.0]):
+            for start, stop, step in [(10, 1001, 1), (13, 900, 100), (24, 25, 3)]:
+                expected = forward(np.arange(start, stop, step), xp, fp)
+                f = forward_range(start, stop, step, xp, fp)
+                assert np.array_equal(f, expected)
+
+    def test_datetime(self):
+        xp = [0, 10]
+        fp = np.array(["2024-01-01", "2024-01-11"], dtype="datetime64[D]")
+        f = forward_range(0, 10, 5, xp, fp)
+        assert f.dtype == fp.dtype
+        assert np.array_equal(f, np.array(["2024-01-01", "2024-01-06"], dtype=fp.dtype))
+
+    def test_raises(self):
+        assert forward_range(5, 5, 1, [0, 10], [0, 1]).size == 0
+        with pytest.raises(IndexError, match="out of bounds"):
+            forward_range(0, 20, 5, [0, 10], [0.0, 1.0])
+        with pytest.raises(ValueError, match="step must be positive"):
+            forward_range(0, 10, 0, [0, 10], [0.0, 1.0])
+
+
+class TestWrapNegative:
+    def test_forward(self):
+        xp, fp = [0, 10, 20], [0.0, 10.0, 40.0]
+        assert forward(-1, xp, fp, wrap_negative=True) == 40.0
+        assert forward(-21, xp, fp, wrap_negative=True) == 0.0
+        f = forward([-1, 5, -6], xp, fp, wrap_negative=True)
+        assert np.array_equal(f, [40.0, 5.0, 25.0])
+        with pytest.raises(IndexError, match=r"x\[1\] = -22 out of bounds"):
+            forward([1, -22], xp, fp, wrap_negative=True)
+        with pytest.raises(ValueError, match="x values must be positive"):
+            forward(-1, xp, fp)
+
+    def test_samples(self):
+        n = 5
+        xp, fp = [0, n - 1], [0, 4 * (n - 1)]
+        assert forward(-1, xp, fp, wrap_negative=True) == 4 * (n - 1)
+        assert forward(-n, xp, fp, wrap_negative=True) == 0
+        with pytest.raises(IndexError, match="x = -6 out of bounds"):
+            forward(-(n + 1), xp, fp, wrap_negative=True)
+
+    def test_forward_range(self):
+        xp, fp = [0, 10], [0, 100]
+        f = forward_range(-5, -1, 2, xp, fp, wrap_negative=True)
+        assert np.array_equal(f, [60, 80])
+        with pytest.raises(IndexError, match="start = -12 out of bounds"):
+            forward_range(-12, 5, 1, xp, fp, wrap_negative=True)
+
+    def test_inverse_unaffected(self):
+        assert inverse(-6.0, [0, 10], [-10.0, 10.0]) == 2
+
+
+class TestPacked:
+    def test_negative_scale_factor(self):
+        xp, fp = [0, 10, 20], np.array([100, 200, 400], dtype="i4")
+        kwargs = {"scale_factor": -0.5, "add_offset": 10.0}
+        assert forward(5, xp, fp, **kwargs) == -65.0
+        f = forward([0, 15], xp, fp, **kwargs)
+        assert f.dtype == np.float64
+        assert np.array_equal(f, [-40.0, -140.0])
+        assert inverse(-65.0, xp, fp, **kwargs) == 5
+        assert inverse(-66.0, xp, fp, method="ffill", **kwargs) == 5
+        assert inverse(-66.0, xp, fp, method="bfill", **kwargs) == 6
+        assert inverse(-65.8, xp, fp, method="bfill", **kwargs) == 6
+        with pytest.raises(KeyError):
+            inverse(-66.0, xp, fp, **kwargs)
+        result = inverse([-30.0, -65.0], xp, fp, errors="mask", **kwargs)
+        assert np.ma.is_masked(result)
+        assert result[1] == 5
+
+    def test_large_offset(self):
+        xp = [0, 1000]
+        fp = np.array([0, np.iinfo("i4").max], dtype="i4")
+        kwargs = {"scale_factor": 1e-3, "add_offset": 1.7e9}
+        f = forward([0, 1, 999, 1000], xp, fp, **kwargs)
+        assert f[-1] == 1_702_147_483.647
+        x = inverse(f, xp, fp, method="nearest", **kwargs)
+        assert np.array_equal(x, [0, 1, 999, 1000])
+
+    def test_errors(self):
+        with pytest.raises(ValueError, match="integer dtype"):
+            forward(5, [0, 10], [0.0, 1.0], scale_factor=2.0)
+        with pytest.raises(ValueError, match="scale_factor must be finite and nonzero"):
+            forward(5, [0, 10], [0, 1], scale_factor=0.0)
+        with pytest.raises(ValueError, match="strictly monotonic"):
+            inverse(1.7e9, [0, 10], [0, 1], scale_factor=1e-9, add_offset=1.7e9)
+        with pytest.raises(ValueError, match="limit is not supported"):
+            inverse(1.0, [0, 10], [0, 10], limit=2, add_offset=1.0)
+
+
+class TestEpsilonSweep:
+    def test_int(self):
+        xp, fp = [0, 1, 2, 3, 4, 5], [0, 2, 4, 7, 8, 10]
+        out = epsilon_sweep(xp, fp, [0, 1, 2, 10])
+        assert out.dtype.names == ("epsilon", "count", "max_error")
+        assert np.array_equal(out["count"], [4, 2, 2, 2])
+        assert np.array_equal(out["max_error"], [0, 1, 1, 1])
+        assert out["max_error"].dtype == np.uint64
+        with pytest.raises(ValueError, match="epsilons must be non-negative"):
+            epsilon_sweep(xp, fp, -1)
+
+    def test_float(self):
+        xp, fp = [0, 2, 4], [0.0, 1.5, 0.0]
+        out = epsilon_sweep(xp, fp, 1.25)
+        assert out["epsilon"][0] == 1.25
+        assert out["count"][0] == 3
+        out = epsilon_sweep(xp, fp, [1.5, 2.0])
+        assert np.array_equal(out["count"], [2, 2])
+        assert np.array_equal(out["max_error"], [1.5, 1.5])
+
+    def test_datetime(self):
+        xp = [0, 1, 2]
+        fp = np.datetime64("2024-01-01", "ms") + np.array([0, 3000, 4000], "m8[ms]")
+        out = epsilon_sweep(xp, fp, [np.timedelta64(1, "s"), np.timedelta64(500, "ms")])
+        assert out["max_error"].dtype == np.dtype("m8[ms]")
+        assert np.array_equal(out["count"], [2, 3])
+        assert out["max_error"][0] == np.timedelta64(1000, "ms")
+
+
+class TestMaskedQueries:
+    def test_forward(self):
+        xp, fp = [0, 10], [0.0, 100.0]
+        x = np.ma.MaskedArray([2, 999, 5], mask=[False, True, False])
+        out = forward(x, xp, fp)
+        assert isinstance(out, np.ma.MaskedArray)
+        assert np.array_equal(out.mask, [False, True, False])
+        assert np.array_equal(out.compressed(), [20.0, 50.0])
+        x = np.ma.MaskedArray([2, 999, 50], mask=[False, True, False])
+        out = forward(x, xp, fp, errors="mask")
+        assert np.array_equal(out.mask, [False, True, True])
+        with pytest.raises(IndexError):
+            forward(x, xp, fp)
+        assert forward(np.ma.masked, xp, fp) is np.ma.masked
+
+    def test_inverse(self):
+        xp, fp = [0, 10], [0, 100]
+        f = np.ma.MaskedArray([20, -1, 50, 1000], mask=[False, True, False, False])
+        out = inverse(f, xp, fp, errors="mask")
+        assert out.dtype == np.asarray(xp).dtype
+        assert np.array_equal(out.mask, [False, True, False, True])
+        assert np.array_equal(out.compressed(), [2, 5])
+        out, stats = inverse(f[:3], xp, fp, method="nearest", return_stats=True)
+        assert np.array_equal(out.mask, [False, True, False])
+        assert stats.exact_count == 2
+
+    def test_fully_masked(self):
+        # The tie points are not even validated.
+        xp, fp = [10, 0], [0.0, 0.0]
+        x = np.ma.masked_all(3, dtype="i8")
+        out = forward(x, xp, fp)
+        assert out.mask.all() and out.shape == (3,)
+        assert out.dtype == np.float64
+        out = inverse(np.ma.masked_all(2, dtype="f8"), xp, fp)
+        assert out.mask.all() and out.dtype == np.asarray(xp).dtype
+
+
+class TestOutputDtype:
+    def test_forward(self):
+        xp, fp = [0, 10], [0.0, 1.0]
+        out = forward([0, 5, 10], xp, fp, dtype="float32")
+        assert out.dtype == np.float32
+        assert np.array_equal(out, np.array([0.0, 0.5, 1.0], dtype="f4"))
+        assert forward(3, xp, fp, dtype=np.float32) == np.float32(0.3)
+        assert forward(3, xp, fp, dtype="float64").dtype == np.float64
+        out = forward([5, 20], xp, fp, dtype="float32", errors="mask")
+        assert out.dtype == np.float32 and np.array_equal(out.mask, [False, True])
+        with pytest.raises(OverflowError):
+            forward(5, xp, [0.0, 1e300], dtype="float32")
+        with pytest.raises(ValueError, match="dtype must be either float32 or float64"):
+            forward(5, xp, fp, dtype="int64")
+        with pytest.raises(ValueError, match="only supported for float fp"):
+            forward(5, xp, [0, 10], dtype="float32")
+
+    def test_inverse(self):
+        xp = np.array([0, 10], dtype="u8")
+        out = inverse([0.0, 0.5], xp, [0.0, 1.0], dtype="int64")
+        assert out.dtype == np.int64
+        assert np.array_equal(out, [0, 5])
+        out = inverse([0, 5], [0, 10], [0, 10], dtype="uint64")
+        assert out.dtype == np.uint64
+        out, stats = inverse([5], [0, 10], [0, 10], return_stats=True, dtype="int64")
+        assert out.dtype == np.int64 and stats.exact_count == 1
+        big = np.array([0, 2**63 + 10], dtype="u8")
+        assert inverse(0.0, big, [0.0, 1.0], dtype="uint64") == 0
+        with pytest.raises(OverflowError):
+            inverse(1.0, big, [0.0, 1.0], dtype="int64")
+        with pytest.raises(ValueError, match="dtype must be either int64 or uint64"):
+            inverse(1.0, big, [0.0, 1.0], dtype="float64")
+
+
+class TestSidedBounds:
+    policies = ["raise", "clip"]
+
+    @pytest.mark.parametrize("errors", ["raise", "mask"])
+    @pytest.mark.parametrize("low", policies)
+    @pytest.mark.parametrize("high", policies)
+    def test_forward(self, low, high, errors):
+        xp, fp = [10, 20], [100.0, 200.0]
+        kwargs = dict(bounds=(low, high), errors=errors)
+        for x, side, extreme in [(5, low, 100.0), (25, high, 200.0)]:
+            if side == "clip":
+                assert forward(x, xp, fp, **kwargs) == extreme
+            elif errors == "mask":
+                out = forward([x, 15], xp, fp, **kwargs)
+                assert out.mask.tolist() == [True, False]
+            else:
+                with pytest.raises(IndexError):
+                    forward(x, xp, fp, **kwargs)
+        assert forward(15, xp, fp, **kwargs) == 150.0
+
+    @pytest.mark.parametrize("errors", ["raise", "mask"])
+    @pytest.mark.parametrize("low", policies)
+    @pytest.mark.parametrize("high", policies)
+    @pytest.mark.parametrize("fp", [[100, 200], [200, 100]])
+    def test_inverse(self, low, high, errors, fp):
+        xp = [10, 20]
+        kwargs = dict(bounds=(low, high), errors=errors)
+        # the sides follow the indices whether fp is increasing or decreasing
+        beyond = [2 * fp[0] - fp[1], 2 * fp[1] - fp[0]]
+        for f, side, extreme in zip(beyond, [low, high], xp):
+            if side == "clip":
+                assert inverse(f, xp, fp, **kwargs) == extreme
+            elif errors == "mask":
+                out = inverse([f, 150], xp, fp, **kwargs)
+                assert out.mask.tolist() == [True, False]
+            else:
+                with pytest.raises(KeyError):
+                    inverse(f, xp, fp, **kwargs)
+        assert inverse(150, xp, fp, **kwargs) == 15
+
+    def test_shorthand(self):
+        xp, fp = [10, 20], [100, 200]
+        assert np.array_equal(forward([0, 30], xp, fp, bounds="clip"), [100, 200])
+        assert np.array_equal(inverse([0, 300], xp, fp, bounds="clip"), [10, 20])
+        assert forward(0, xp, fp, bounds=("clip", "raise"), wrap_negative=True) == 100
+        assert inverse(50.0, xp, fp, scale_factor=2.0, bounds=("clip", "raise")) == 10
+        assert forward(30, xp, fp, scale_factor=2.0, bounds=("raise", "clip")) == 400.0
+        with pytest.raises(ValueError, match="bounds must be either"):
+            forward(15, xp, fp, bounds="extrapolate")
+
+
+class TestPeriodic:
+    def test_forward(self):
+        xp, fp = [0, 90, 180, 270], [0.0, 10.0, 20.0, 30.0]
+        assert forward(45, xp, fp, period=360) == 5.0
+        assert forward(315, xp, fp, period=360) == 15.0
+        # exactly on the wrap, just above the period and just below 0
+        assert forward(360, xp, fp, period=360) == 0.0
+        assert forward(361, xp, fp, period=360) == forward(1, xp, fp)
+        assert forward(-1, xp, fp, period=360) == forward(359, xp, fp, period=360)
+        out = forward([-360, -45, 0, 405, 720], xp, fp, period=360)
+        assert np.array_equal(out, [0.0, 15.0, 0.0, 5.0, 0.0])
+        assert forward(3, [0, 2], [0, 10], period=4) == 5
+        assert forward(-1, [10, 12], [0, 10], period=4) == 5
+        datetimes = np.array(["2024-01-01", "2024-01-03"], dtype="M8[D]")
+        out = forward(-1, [0, 2], datetimes, period=4)
+        assert out == np.datetime64("2024-01-02")
+        x = np.ma.MaskedArray([-1, 5], mask=[False, True])
+        out = forward(x, [0, 2], [0.0, 10.0], period=4)
+        assert np.array_equal(out.mask, [False, True]) and out[0] == 5.0
+
+    def test_errors(self):
+        xp, fp = [0, 90, 180, 270], [0.0, 10.0, 20.0, 30.0]
+        with pytest.raises(ValueError, match="period must exceed the span of xp"):
+            forward(0, xp, fp, period=270)
+        with pytest.raises(ValueError, match="period must be a positive integer"):
+            forward(0, xp, fp, period=360.5)
+        with pytest.raises(ValueError, match="closed is not supported with period"):
+            forward(0, xp, fp, period=360, closed="left")
+        with pytest.raises(ValueError, match="wrap_negative is not supported"):
+            forward(0, xp, fp, period=360, wrap_negative=True)
+        with pytest.raises(ValueError, match="not supported for inverse"):
+            inverse(5.0, xp, fp, period=360)
+
+
+class TestSort:
+    def test_forward(self):
+        xp, fp = [30, 0, 20, 10], [30.0, 0.0, 15.0, 5.0]
+        with pytest.raises(ValueError, match="strictly increasing"):
+            forward(15, xp, fp)
+        assert forward(15, xp, fp, sort=True) == 10.0
+        assert forward(25, xp[::-1], fp[::-1], sort=True) == 22.5
+        assert np.array_equal(forward([0, 30], xp, fp, sort=True), [0.0, 30.0])
+        datetimes = np.array(["2024-01-03", "2024-01-01"], dtype="M8[D]")
+        out = forward(5, [10, 0], datetimes, sort=True)
+        assert out == np.datetime64("2024-01-02")
+        assert forward(5, [10, 0], [10, 0], sort=True, scale_factor=0.5) == 2.5
+
+    def test_inverse(self):
+        xp, fp = np.array([20, 0, 10], dtype="u8"), [15, 0, 5]
+        out = inverse([0, 10], xp, fp, method="nearest", sort=True)
+        assert out.dtype == np.uint64
+        assert np.array_equal(out, [0, 15])
+
+    def test_duplicates(self):
+        xp, fp = [10, 0, 10, 20], [5.0, 0.0, 6.0, 20.0]
+        with pytest.raises(ValueError, match="xp has duplicate values"):
+            forward(5, xp, fp, sort=True)
+        assert forward(10, xp, fp, sort=True, duplicates="first") == 5.0
+        assert forward(10, xp[::-1], fp[::-1], sort=True, duplicates="first") == 6.0
+        with pytest.raises(ValueError, match="duplicates must be either"):
+            forward(5, xp, fp, sort=True, duplicates="last")
+        with pytest.raises(ValueError, match="xp values must be positive"):
+            forward(5, [-1, 0], fp[:2], sort=True)
+
+
+class TestNpy:
+    def test_dense(self, tmp_path):
+        path = tmp_path / "dense.npy"
+        np.save(path, np.array([0.0, 1.0, 2.0, 3.0, 5.0, 7.0, 9.0, 9.5]))
+        xp, fp = load_npy_dense(path, 0.0)
+        assert np.array_equal(xp, [0, 3, 6, 7])
+        assert np.array_equal(fp, [0.0, 3.0, 9.0, 9.5])
+        assert xp.dtype == np.uint64 and fp.dtype == np.float64
+        xp, fp = load_npy_dense(path, 1.0)
+        assert np.all(np.abs(forward(np.arange(8), xp, fp) - np.load(path)) <= 1.0)
+        np.save(path, np.array([0, 10, 20, 40], dtype="i8"))
+        xp, fp = load_npy_dense(str(path), 0)
+        assert np.array_equal(xp, [0, 2, 3]) and fp.dtype == np.int64
+        with pytest.raises(ValueError, match="epsilon must be non-negative"):
+            load_npy_dense(path, -1)
+
+    def test_pair(self, tmp_path):
+        np.save(tmp_path / "xp.npy", np.array([0, 10, 20], dtype="u8"))
+        np.save(tmp_path / "fp.npy", np.array([0.0, 5.0, 20.0]))
+        xp, fp = load_npy_pair(tmp_path / "xp.npy", tmp_path / "fp.npy")
+        assert np.array_equal(xp, [0, 10, 20])
+        assert np.array_equal(fp, [0.0, 5.0, 20.0])
+        np.save(tmp_path / "fp.npy", np.array([0.0, 5.0]))
+        with pytest.raises(ValueError):
+            load_npy_pair(tmp_path / "xp.npy", tmp_path / "fp.npy")
+
+    def test_errors(self, tmp_path):
+        path = tmp_path / "data.npy"
+        with pytest.raises(FileNotFoundError):
+            load_npy_dense(path, 0.0)
+        np.save(path, np.zeros(4, dtype="f4"))
+        with pytest.raises(ValueError, match="unsupported dtype"):
+            load_npy_dense(path, 0.0)
+        np.save(path, np.zeros((2, 2)))
+        with pytest.raises(ValueError):
+            load_npy_dense(path, 0.0)
+        np.save(path, np.arange(4.0))
+        path.write_bytes(path.read_bytes()[:-3])
+        with pytest.raises(ValueError, match="truncated"):
+            load_npy_dense(path, 0.0)
+
+
+class TestUint64:
+    def test_unsigned_fp(self):
+        fp = np.array([0, 2**63 - 1, 2**64 - 1], dtype="u8")
+        xp = [0, 10, 20]
+        assert inverse(0, xp, fp) == 0
+        assert inverse(2**63 - 1, xp, fp) == 10
+        assert inverse(np.uint64(2**64 - 1), xp, fp) == 20
+        f = np.array([0, 2**63 - 1], dtype="i8")
+        assert np.array_equal(inverse(f, xp, fp), [0, 10])
+        with pytest.raises(KeyError):
+            inverse(np.int64(-1), xp, fp)
+        assert inverse(np.int64(-1), xp, fp, method="nearest") == 0
+        assert inverse(np.iinfo("i8").min, xp, fp, bounds="clip") == 0
+        out = inverse(np.array([-1, 0], dtype="i8"), xp, fp, errors="mask")
+        assert np.ma.is_masked(out[0]) and out[1] == 0
+
+    def test_unsigned_f(self):
+        fp = np.array([-(2**63), 0, 2**63 - 1], dtype="i8")
+        xp = np.array([0, 10, 20], dtype="u8")
+        f = np.array([0, 2**63 - 1, 2**63, 2**64 - 1], dtype="u8")
+        with pytest.raises(KeyError):
+            inverse(f, xp, fp)
+        out = inverse(f, xp, fp, bounds=("raise", "clip"), dtype="int64")
+        assert out.dtype == np.int64
+        assert np.array_equal(out, [10, 20, 20, 20])
+
+    def test_unsupported(self):
+        fp = np.array([0, 2**64 - 1], dtype="u8")
+        with pytest.raises(ValueError, match="limit is not supported with uint64"):
+            inverse(5, [0, 10], fp, method="ffill", limit=1)
+        with pytest.raises(ValueError, match="fp must be strictly monotonic"):
+            inverse(5, [0, 10], np.array([10, 10], dtype="u8"))
+
+    def test_int64_range(self):
+        # uint64 values within the int64 range keep the options of the int64 path
+        xp = [0, 10, 11, 21]
+        fp = np.array([0, 100, 1000, 1100], dtype="u8")
+        assert inverse(np.uint64(55), xp, fp, method="ffill", limit=5) == 5
+        with pytest.raises(KeyError, match="in a gap"):
+            inverse(np.uint64(600), xp, fp, method="nearest", max_gap=50)
+        out, stats = inverse(np.array([50, 60], dtype="u8"), xp, fp, return_stats=True)
+        assert np.array_equal(out, [5, 6]) and stats.exact_count == 2
+        assert inverse(np.int64(-1), xp, fp, method="nearest") == 0
+
+
+class TestNonFinite:
+    def test_load_npy_dense(self, tmp_path):
+        path = tmp_path / "dense.npy"
+        np.save(path, np.array([0.0, np.nan, 1.0]))
+        with pytest.raises(ValueError):
+            load_npy_dense(path, 0.0)
+        np.save(path, np.array([0.0, 1.0]))
+        with pytest.raises(ValueError):
+            load_npy_dense(path, np.inf)
+
+    def test_max_gap_factor(self):
+        xp, fp = [0, 10, 20, 30], [0.0, 10.0, 20.0, 1020.0]
+        assert inverse(520.0, xp, fp, max_gap_factor=np.inf) == 25
+        with pytest.raises(KeyError):
+            inverse(520.0, xp, fp, max_gap_factor=2.0)
+
+
+def swapped(arr):
+    """Returns a copy of the array in non-native byte order, with the same values."""
+    arr = np.asarray(arr)
+    return arr.astype(arr.dtype.newbyteorder("S"))
+
+
+class TestByteOrder:
+    @pytest.mark.parametrize("dtype", ["u8", "i8", "u4", "i4", "f8", "f4", "M8[ns]"])
+    def test_forward_inverse(self, dtype):
+        xp = np.array([0, 10, 20], dtype="u8")
+        fp = np.array([0, 100, 400]).astype(dtype)
+        x = np.array([0, 5, 15, 20], dtype="u8")
+        f = np.array([0, 50, 250, 400]).astype(dtype)
+        assert not swapped(fp).dtype.isnative
+        out = forward(swapped(x), swapped(xp), swapped(fp))
+        assert out.dtype == fp.dtype
+        assert np.array_equal(out, forward(x, xp, fp))
+        out = inverse(swapped(f), swapped(xp), swapped(fp))
+        assert np.array_equal(out, inverse(f, xp, fp))
+
+    def test_uint64(self):
+        xp = np.array([0, 10], dtype="u8")
+        fp = np.array([0, 2**64 - 1], dtype="u8")
+        f = np.array([2**63, 2**64 - 1], dtype="u8")
+        out = inverse(swapped(f), swapped(xp), swapped(fp), method="nearest")
+        assert np.array_equal(out, [5, 10])
+
+    def test_functions(self):
+        xp = np.array([0, 10, 20, 30], dtype="u8")
+        fp = np.array([0.0, 10.0, 20.0, 35.0])
+        args = (swapped(xp), swapped(fp))
+        out = forward_range(0, 30, 7, *args)
+        assert np.array_equal(out, forward_range(0, 30, 7, xp, fp))
+        for a, b in zip(derivative(*args), derivative(xp, fp)):
+            assert np.array_equal(a, b)
+        assert summary(*args) == summary(xp, fp)
+        assert dumps(*args) == dumps(xp, fp)
+        assert check_monotonic(swapped(fp)) == check_monotonic(fp)
+        assert np.array_equal(smooth(swapped(fp), 3), smooth(fp, 3))
+        out = detect_outliers(*args, 3, 1.0)
+        assert np.array_equal(out, detect_outliers(xp, fp, 3, 1.0))
+        out = forward_multi([swapped(xp)], [swapped(xp)], [swapped(fp)])
+        assert np.array_equal(out[0], fp)
+        assert np.array_equal(seconds_to_ns(swapped(fp)), seconds_to_ns(fp))
+        ij, grid = swapped([0, 10]), swapped(np.eye(2))
+        assert np.array_equal(forward_2d(ij, ij, ij, ij, grid), [1.0, 1.0])
+
+    def test_extension(self):
+        ij, grid = np.array([0, 10], dtype="u8"), np.eye(2)
+        expected = rust.forward_2d_float(ij, ij, ij, ij, grid, errors="raise")
+        out = rust.forward_2d_float(
+            swapped(ij), swapped(ij), swapped(ij), swapped(ij), grid, errors="raise"
+        )
+        assert np.array_equal(out, expected)
+
+
+class TestDict:
+    # coordinates as serialized by xdas
+    TIME = {
+        "dim": "time",
+        "data": {
+            "tie_indices": [0, 29999, 30000, 59999],
+            "tie_values": [
+                "2023-01-01T00:00:00.000000000",
+                "2023-01-01T00:01:59.996000000",
+                "2023-01-01T00:02:00.100000000",
+                "2023-01-01T00:04:00.096000000",
+            ],
+        },
+        "dtype": "datetime64[ns]",
+    }
+    DISTANCE = {
+        "dim": "distance",
+        "data": {"tie_indices": [0, 9999], "tie_values": [0.0, 20397.958]},
+        "dtype": "float64",
+    }
+
+    def test_round_trip(self):
+        for fixture in (self.TIME, self.DISTANCE):
+            xp, fp = from_dict(fixture)
+            assert xp.dtype == np.uint64
+            assert fp.dtype == np.dtype(fixture["dtype"])
+            assert np.array_equal(xp, fixture["data"]["tie_indices"])
+            assert to_dict(xp, fp, dim=fixture["dim"]) == fixture
+        xp, fp = from_dict(self.TIME)
+        assert forward(29999, xp, fp) == np.datetime64("2023-01-01T00:01:59.996")
+        assert inverse("2023-01-01T00:02:00.100", xp, fp) == 30000
+
+    def test_int_datetimes(self):
+        xp, fp = from_dict(self.TIME)
+        out = to_dict(xp, fp, dim="time", datetime="int")
+        assert out["data"]["tie_values"] == fp.view("i8").tolist()
+        assert out["dtype"] == "datetime64[ns]"
+        xp_int, fp_int = from_dict(out)
+        assert np.array_equal(xp_int, xp) and np.array_equal(fp_int, fp)
+        xp_ms, fp_ms = from_dict(to_dict(xp, fp.astype("M8[ms]"), datetime="int"))
+        assert fp_ms.dtype == np.dtype("M8[ms]")
+        assert np.array_equal(fp_ms, fp)
+
+    def test_data_only(self):
+        xp, fp = from_dict(self.DISTANCE["data"])
+        assert np.array_equal(fp, [0.0, 20397.958])
+        data = dict(self.TIME["data"], dtype="datetime64[ns]")
+        assert from_dict(data)[1].dtype == np.dtype("M8[ns]")
+
+    def test_malformed(self):
+        data = self.DISTANCE["data"]
+        with pytest.raises(ValueError, match="expected a dict"):
+            from_dict([0, 1])
+        with pytest.raises(ValueError, match="'data' must be a dict"):
+            from_dict({"data": [0, 1]})
+        with pytest.raises(ValueError, match="missing key 'tie_values'"):
+            from_dict({"tie_indices": [0, 1]})
+        with pytest.raises(ValueError, match="invalid dtype"):
+            from_dict(dict(data, dtype="nonsense"))
+        with pytest.raises(ValueError, match="tie_indices must be a list of integers"):
+            from_dict({"tie_indices": [0.0, 1.0], "tie_values": [0.0, 1.0]})
+        with pytest.raises(ValueError, match="invalid tie_values"):
+            from_dict(dict(self.TIME["data"], tie_values=["soon", "later"]))
+        with pytest.raises(ValueError, match="tie_values must be a list of numbers"):
+            from_dict({"tie_indices": [0, 1], "tie_values": ["a", "b"]})
+        with pytest.raises(ValueError, match="xp and fp must have the same length"):
+            from_dict({"tie_indices": [0, 1, 2], "tie_values": [0.0, 1.0]})
+        with pytest.raises(ValueError, match="xp must be strictly increasing"):
+            from_dict({"tie_indices": [1, 0], "tie_values": [0.0, 1.0]})
+        with pytest.raises(ValueError, match="datetime must be either"):
+            to_dict([0, 1], [0.0, 1.0], datetime="str")
+
+
+class TestNaT:
+    XP = [0, 10, 20]
+    FP = np.array(
+        ["2023-01-01T00:00:00", "2023-01-01T00:00:10", "2023-01-01T00:00:20"],
+        dtype="M8[s]",
+    )
+    NAT = np.datetime64("NaT", "s")
+
+    def with_nat(self, position):
+        fp = self.FP.copy()
+        fp[position] = self.NAT
+        return fp
+
+    def test_fp(self):
+        for position in (0, 1, 2):
+            fp = self.with_nat(position)
+            with pytest.raises(ValueError, match=f"fp\\[{position}\\] is NaT"):
+                forward(5, self.XP, fp)
+            with pytest.raises(ValueError, match=f"fp\\[{position}\\] is NaT"):
+                inverse(self.FP[0], self.XP, fp)
+            with pytest.raises(ValueError, match=f"fp\\[{position}\\] is NaT"):
+                smooth(fp, 3)
+            with pytest.raises(ValueError, match="is NaT"):
+                forward_2d([0], [0], [0, 10], [0, 20], np.stack([fp[:2], fp[1:]]))
+        with pytest.raises(ValueError, match="fp\\[0\\] is NaT"):
+            forward(5, self.XP, np.full(3, self.NAT))
+        # reported at its position before sorting
+        with pytest.raises(ValueError, match="fp\\[0\\] is NaT"):
+            forward(5, [20, 10, 0], self.with_nat(0), sort=True)
+
+    def test_f(self):
+        f = np.array([self.FP[0], self.NAT, self.FP[2]])
+        with pytest.raises(ValueError, match="f\\[1\\] is NaT"):
+            inverse(f, self.XP, self.FP)
+        out = inverse(f, self.XP, self.FP, errors="mask")
+        assert np.ma.isMaskedArray(out)
+        assert out.mask.tolist() == [False, True, False]
+        assert out[0] == 0 and out[2] == 20
+        out = inverse(np.full(2, self.NAT), self.XP, self.FP, errors="mask")
+        assert out.mask.all()
+
+    def test_forward_never_nat(self):
+        out = forward([0, 5, 20, 30], self.XP, self.FP, errors="mask")
+        assert not np.isnat(np.ma.getdata(out)).any()
+        assert out.mask.tolist() == [False, False, False, True]
+
+    def test_multi(self):
+        with pytest.raises(ValueError, match="fp_list\\[1\\]\\[1\\] is NaT"):
+            forward_multi([[5], [5]], [self.XP, self.XP], [self.FP, self.with_nat(1)])
+        with pytest.raises(ValueError, match="f_list\\[0\\]\\[0\\] is NaT"):
+            inverse_multi([np.array([self.NAT])], [self.XP], [self.FP])
+
+    def test_check_monotonic(self):
+        report = check_monotonic(self.with_nat(1))
+        assert report["kind"] == "none"
+        assert report["nans"] == [1]
+        assert check_monotonic(np.full(3, self.NAT))["nans"] == [0, 1, 2]
+        assert check_monotonic(self.FP)["kind"] == "strict"
+
+
+class TestDuration:
+    xp = [0, 1, 2]
+    fp = np.datetime64("2024-01-01", "ns") + np.array([0, 3, 4], "m8[s]")
+
+    def test_epsilon_forms(self):
+        expected = epsilon_sweep(self.xp, self.fp, np.timedelta64(1, "s"))
+        for epsilon in ["1s", "1000ms", "1 s", 1.0, np.float32(1.0), 1_000_000_000]:
+            out = epsilon_sweep(self.xp, self.fp, epsilon)
+            assert np.array_equal(out, expected)
+        out = epsilon_sweep(self.xp, self.fp, ["250us", "0.5s", "2s"])
+        assert out["epsilon"].tolist() == [
+            np.timedelta64(250_000, "ns"),
+            np.timedelta64(500_000_000, "ns"),
+            np.timedelta64(2_000_000_000, "ns"),
+        ]
+        out = epsilon_sweep(self.xp, self.fp, [0.001, 1e-9])
+        assert out["epsilon"].view("i8").tolist() == [1_000_000, 1]
+
+    def test_sub_resolution(self):
+        with pytest.raises(ValueError, match="not an integer number of the unit"):
+            epsilon_sweep(self.xp, self.fp, "0.3ns")
+        with pytest.raises(ValueError, match="not an integer number of the unit"):
+            epsilon_sweep(self.xp, self.fp, 1e-10)
+        fp = self.fp.astype("M8[s]")
+        with pytest.raises(ValueError, match="not an integer number of the unit"):
+            epsilon_sweep(self.xp, fp, "1ms")
+        out = epsilon_sweep(self.xp, fp, "0.5min")
+        assert out["epsilon"][0] == np.timedelta64(30, "s")
+
+    def test_invalid(self):
+        with pytest.raises(ValueError, match="ambiguous unit 'm'"):
+            epsilon_sweep(self.xp, self.fp, "1m")
+        for epsilon in ["1", "ms", "1e3ms", "1 sec"]:
+            with pytest.raises(ValueError, match="invalid duration"):
+                epsilon_sweep(self.xp, self.fp, epsilon)
+        with pytest.raises(ValueError, match="epsilons must be finite"):
+            epsilon_sweep(self.xp, self.fp, np.nan)
+        with pytest.raises(OverflowError):
+            epsilon_sweep(self.xp, self.fp, "300000D")
+
+    def test_other_tolerances(self):
+        values = self.fp.copy()
+        values[1] += np.timedelta64(2, "ms")
+        xp = [0, 1, 2]
+        assert verify(values, xp, self.fp, "1ms").violations == 1
+        assert verify(values, xp, self.fp, "2ms").violations == 0
+        assert verify(values, xp, self.fp, 0.002).violations == 0
+        assert np.array_equal(detect_outliers(xp, self.fp, 3, "3s"), [])
+        fp = np.array(TestGaps.fp, "M8[s]")
+        f = np.datetime64(600, "s")
+        with pytest.raises(KeyError):
+            inverse(f, TestGaps.xp, fp, method="nearest", max_gap="1min")
+        assert inverse(f, TestGaps.xp, fp, method="nearest", max_gap="15min") == 11
+        assert inverse(f, TestGaps.xp, fp, method="nearest", max_gap=900.0) == 11
+
+
+class TestDecimate:
+    xp = [0, 1, 2, 3, 4, 5, 6]
+    fp = [0.0, 1.0, 0.0, 1.0, 0.0, 1.0, 0.0]
+
+    def test_step(self):
+        xp, fp, error = decimate(self.xp, self.fp, k=2)
+        assert np.array_equal(xp, [0, 2, 4, 6])
+        assert np.array_equal(fp, [0.0, 0.0, 0.0, 0.0])
+        assert error == 1.0
+        xp, fp, error = decimate(self.xp, self.fp, k=1)
+        assert np.array_equal(xp, self.xp) and np.array_equal(fp, self.fp)
+        assert error == 0.0
+
+    def test_keep_last(self):
+        xp, fp, _ = decimate(self.xp, self.fp, k=4)
+        assert np.array_equal(xp, [0, 4, 6])
+        xp, _, _ = decimate(self.xp, self.fp, k=100)
+        assert np.array_equal(xp, [0, 6])
+
+    def test_count(self):
+        cases = [(2, [0, 6]), (3, [0, 3, 6]), (4, [0, 2, 4, 6]), (7, self.xp)]
+        for n, expected in cases:
+            xp, _, _ = decimate(self.xp, self.fp, n=n)
+            assert np.array_equal(xp, expected)
+
+    def test_dtypes(self):
+        xp = np.array(self.xp, dtype="u4")
+        fp = np.datetime64("2024-01-01", "ms") + np.array(self.fp, "m8[s]")
+        out_xp, out_fp, error = decimate(xp, fp, k=3)
+        assert out_xp.dtype == np.dtype("u4") and out_fp.dtype == fp.dtype
+        assert np.array_equal(out_fp, fp[[0, 3, 6]])
+        assert error == np.timedelta64(1000, "ms")
+        _, out_fp, error = decimate(self.xp, [0, 10, 20, 30, 40, 50, 61], k=3)
+        # 50 + 2 * 31 / 3 is rounded to 51
+        assert np.array_equal(out_fp, [0, 30, 61]) and error == 1
+
+    def test_uint64(self):
+        fp = np.array([0, 1, 0, 1, 0, 1, 0], dtype="u8") + np.uint64(2**63)
+        _, out_fp, error = decimate(self.xp, fp, k=2)
+        assert out_fp.dtype == np.dtype("u8")
+        assert np.array_equal(out_fp, fp[[0, 2, 4, 6]]) and error == 1
+
+    def test_invalid(self):
+        with pytest.raises(ValueError, match="k must be positive"):
+            decimate(self.xp, self.fp, k=0)
+        with pytest.raises(ValueError, match="n must be at least 2"):
+            decimate(self.xp, self.fp, n=1)
+        with pytest.raises(ValueError, match="either k or n must be provided"):
+            decimate(self.xp, self.fp)
+        with pytest.raises(ValueError, match="either k or n must be provided"):
+            decimate(self.xp, self.fp, k=2, n=3)
@@ -1 +1,28 @@
-from .core import forward, inverse
+from .core import (
+    Interp,
+    Method,
+    check_monotonic,
+    decimate,
+    derivative,
+    detect_outliers,
+    dumps,
+    epsilon_sweep,
+    fingerprint,
+    forward,
+    forward_2d,
+    forward_multi,
+    forward_range,
+    from_dict,
+    inverse,
+    inverse_multi,
+    load_npy_dense,
+    load_npy_pair,
+    loads,
+    ns_to_seconds,
+    sample,
+    seconds_to_ns,
+    smooth,
+    summary,
+    to_dict,
+    verify,
+)
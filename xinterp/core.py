@@ -1,102 +1,1653 @@
+import os
+
 import numpy as np
 
 from . import rust
+from .rust import Interp, Method
 
 
-def forward(x, xp, fp):
+def forward(
+    x,
+    xp,
+    fp,
+    closed="both",
+    errors="raise",
+    wrap_negative=False,
+    scale_factor=None,
+    add_offset=None,
+    dtype=None,
+    bounds="raise",
+    period=None,
+    sort=False,
+    duplicates="raise",
+):
     """
     One-dimensional linear interpolation from indices to values.
 
     Parameters
     ----------
     x : 1-D sequence or scalar of positive integers
-        The indices at which to evaluate the interpolated values.
+        The indices at which to evaluate the interpolated values. Masked elements of a
+        masked array are skipped and stay masked in the returned array.
     xp : 1-D sequence of positive integers
         The indices of the data points, must be strictly increasing.
     fp : 1-D sequence of floats, integers or datetime64s
-        The values of the data points, same length as `xp`.
+        The values of the data points, same length as `xp`. Long double values are
+        interpolated without loss of precision where long double is the x87 extended
+        format.
+    closed : {"both", "left", "right"}, optional
+        Which extreme breakpoints of `xp` are included in the valid range. Defaults to
+        "both".
+    errors : {"raise", "mask"}, optional
+        Whether to raise on values of `x` outside the `xp` range or to mask them in the
+        returned array. Defaults to "raise".
+    wrap_negative : bool, optional
+        Whether to interpret negative values of `x` as offsets from the end as numpy
+        indexing does: -1 is the last index of `xp`, -2 the one before... Defaults to
+        False.
+    scale_factor, add_offset : float or None, optional
+        The CF packing attributes of integer `fp`: the values are interpolated from the
+        packed integers and returned unpacked as `packed * scale_factor + add_offset`
+        floats, computed in extended precision. Defaults to None, no packing.
+    dtype : {"float32", "float64"} or None, optional
+        The dtype of the returned values, only for float `fp`. Values are rounded to the
+        nearest float32 (ties to even) as they are interpolated, values beyond the float32
+        range raising OverflowError instead of saturating to infinity. Defaults to None,
+        the dtype of `fp`.
+    bounds : {"raise", "clip"} or tuple of two of them, optional
+        Whether values of `x` outside the `xp` range raise (or are masked, following
+        `errors`) or are clipped to the first or last value of `fp`. A (low, high) tuple
+        sets the policy separately before the first index of `xp` and after the last one,
+        e.g. ("raise", "clip") for a recording still being acquired. Defaults to "raise".
+    period : int or None, optional
+        The period of the indices, as in `np.interp`, for axes that wrap around (e.g.
+        azimuths or circular buffers). `x` may then hold any integers, negative ones
+        included: they are reduced modulo `period` and values beyond the last index of
+        `xp` are interpolated across the wrap, towards the first value of `fp` at
+        ``xp[0] + period``. `period` must exceed the span of `xp`. Not supported with
+        `closed`, `errors`, `wrap_negative`, `scale_factor`, `add_offset`, `dtype` or
+        `bounds`, nor with long double values. Defaults to None, no period.
+    sort : bool, optional
+        Whether to sort the data points by index first, e.g. when assembled from
+        unordered metadata records. The sort is stable and skipped if `xp` is already
+        sorted. Defaults to False.
+    duplicates : {"raise", "first"}, optional
+        Whether duplicate values of `xp` raise or keep the first data point in the input
+        order, only with `sort`. Defaults to "raise".
 
     Returns
     -------
     1-D array or scalar of floats, integers or datetime64s.
-        The interpolated values, same shape as `x`.
+        The interpolated values, same shape as `x`. A masked array if `x` is a masked
+        array, or if `errors` is "mask" and any value is masked.
 
     Raises
     ------
     IndexError
-        If any value of `x` is outside the `xp` range.
+        If any value of `x` is outside the `xp` range on a side where `bounds` is
+        "raise" and `errors` is "raise", or if any negative value of `x` is still
+        negative once wrapped.
+    OverflowError
+        If any value exceeds the range of `dtype`.
     """
-    return _forward(xp, fp, x=x)
+    packing = scale_factor is not None or add_offset is not None
+    if np.ma.isMaskedArray(x):
+        if dtype is not None:
+            out = np.dtype(dtype)
+        elif packing:
+            out = np.dtype("f8")
+        else:
+            out = native(fp).dtype
+        return masked(
+            forward,
+            x,
+            out,
+            xp=xp,
+            fp=fp,
+            closed=closed,
+            errors=errors,
+            wrap_negative=wrap_negative,
+            scale_factor=scale_factor,
+            add_offset=add_offset,
+            dtype=dtype,
+            bounds=bounds,
+            period=period,
+            sort=sort,
+            duplicates=duplicates,
+        )
+    if sort:
+        xp, fp = sort_points(xp, fp, duplicates)
+    if period is not None:
+        return periodic(
+            xp,
+            fp,
+            period,
+            x,
+            closed=closed,
+            errors=errors,
+            wrap_negative=wrap_negative,
+            scale_factor=scale_factor,
+            add_offset=add_offset,
+            dtype=dtype,
+            bounds=bounds,
+        )
+    if wrap_negative:
+        x = wrap(x, xp, "x")
+    if packing:
+        if dtype is not None:
+            raise ValueError("dtype is not supported for packed values")
+        return packed(
+            xp,
+            fp,
+            scale_factor,
+            add_offset,
+            x=x,
+            closed=closed,
+            errors=errors,
+            bounds=bounds,
+        )
+    return _forward(
+        xp, fp, x=x, closed=closed, errors=errors, dtype=dtype, bounds=bounds
+    )
 
 
-def inverse(f, xp, fp, method=None):
+def inverse(
+    f,
+    xp,
+    fp,
+    method=None,
+    closed="both",
+    return_stats=False,
+    errors="raise",
+    max_gap=None,
+    max_gap_factor=None,
+    gaps="raise",
+    limit=None,
+    scale_factor=None,
+    add_offset=None,
+    dtype=None,
+    bounds="raise",
+    period=None,
+    sort=False,
+    duplicates="raise",
+):
     """
     One-dimensional linear interpolation from values to indices.
 
     Parameters
     ----------
-    f : 1-D sequence or scalar of floats, integers or datetime64s
-        The values at which to evaluate the interpolated indices.
+    f : 1-D sequence or scalar of floats, integers, datetime64s or strings
+        The values at which to evaluate the interpolated indices. If `fp` has datetime64
+        dtype, ISO 8601 strings such as "2024-03-01T12:00:00.000000123" are parsed with
+        nanosecond precision whatever the unit of `fp`. Offsets such as "Z" or "+01:00"
+        are honored by converting to UTC, strings without offset are taken as UTC.
+        Datetime64 values may have another unit than `fp`, both are then converted
+        exactly to the finer unit. Masked elements of a masked array are skipped and stay
+        masked in the returned array. If `f` or `fp` has uint64 dtype, integers of either
+        signedness are compared exactly, values beyond the range of the dtype of `fp`
+        (e.g. negative ones against uint64 `fp`) being out of bounds. This is not
+        supported with `return_stats`, `max_gap`, `max_gap_factor`, `gaps` or `limit`.
     xp : 1-D sequence of positive integers
         The indices of the data points, same length as `fp`.
     fp : 1-D sequence of floats, integers or datetime64s
-        The values of the data points, must be strictly increasing.
-    method : str or None, optional
+        The values of the data points, must be strictly increasing or decreasing.
+    method : str, Method or None, optional
         The method to use for inexact matches:
-        - None (default): exact match, raises otherwise
-        - "nearest": nearest match
-        - "ffill": propagate previous index forward
-        - "bfill": propagate next index backward
+        - None or Method.NONE (default): exact match, raises otherwise
+        - "nearest" or Method.NEAREST: nearest match
+        - "ffill" or Method.FFILL: propagate previous index forward
+        - "bfill" or Method.BFILL: propagate next index backward
+        Previous and next always refer to the order of the indices, whether `fp` is
+        increasing or decreasing.
+    closed : {"both", "left", "right"}, optional
+        Which extreme breakpoints of `fp` (first and last) are included in the valid range.
+        Defaults to "both".
+    return_stats : bool, optional
+        Whether to also return statistics on the exactness of the results. Defaults to
+        False. Not supported with `errors="mask"`.
+    errors : {"raise", "mask"}, optional
+        Whether to raise on values of `f` outside the `fp` range (or not found) or to mask
+        them in the returned array. Defaults to "raise".
+    max_gap : float, int, timedelta64, str or None, optional
+        The steepness (absolute difference of `fp` values per index step) beyond which a
+        segment is an acquisition gap, in units of `fp`. For datetime64 `fp`, floats are
+        seconds and strings durations such as "10min", converted exactly to the unit of
+        `fp`. Values of `f` strictly inside a gap correspond to no real sample and are
+        handled according to `gaps`. Defaults to None, no gap.
+    max_gap_factor : float or None, optional
+        The same threshold as a multiple of the median steepness of the segments. Cannot be
+        provided along with `max_gap`. Defaults to None.
+    gaps : {"raise", "snap"}, optional
+        Whether values of `f` inside a gap raise (or are masked) or snap to a breakpoint of
+        the gap: the nearest one with "nearest", the previous one with "ffill" and the next
+        one with "bfill", exact matching still raising. Defaults to "raise".
+    limit : int or None, optional
+        The maximum number of indices between the position of a value of `f` and the index
+        it is filled to with "ffill" or "bfill", as in pandas. Beyond the `fp` range, the
+        position is extrapolated along the extreme segment, so that values slightly out of
+        range are filled up to the limit. Values filled farther raise (or are masked).
+        Cannot be provided along with `max_gap`. Defaults to None, no limit.
+    scale_factor, add_offset : float or None, optional
+        The CF packing attributes of integer `fp`: `f` holds unpacked floats, compared to
+        `packed * scale_factor + add_offset` in extended precision, which must be strictly
+        monotonic (a negative `scale_factor` reverses the order of `fp`). Not supported
+        with `return_stats`, `max_gap`, `max_gap_factor`, `gaps` or `limit`. Defaults to
+        None, no packing.
+    dtype : {"int64", "uint64"} or None, optional
+        The dtype of the returned indices, converted as they are interpolated. Indices
+        beyond the int64 range raise OverflowError. Defaults to None, the dtype of `xp`.
+    bounds : {"raise", "clip"} or tuple of two of them, optional
+        Whether values of `f` outside the `fp` range raise (or are masked, following
+        `errors`) or are clipped to the first or last index of `xp`, `method` being ignored.
+        A (low, high) tuple sets the policy separately for values beyond the first and the
+        last element of `fp`, i.e. along the indices whether `fp` is increasing or
+        decreasing. Defaults to "raise".
+    period : None, optional
+        Not supported: a periodic function takes each value once per period, so that the
+        inverse is ill-posed. Providing a period raises ValueError.
+    sort : bool, optional
+        Whether to sort the data points by index first, e.g. when assembled from
+        unordered metadata records. The sort is stable and skipped if `xp` is already
+        sorted. Defaults to False.
+    duplicates : {"raise", "first"}, optional
+        Whether duplicate values of `xp` raise or keep the first data point in the input
+        order, only with `sort`. Defaults to "raise".
 
     Returns
     -------
     1-D array or scalar of positive integers.
-        The interpolated indices, same shape as `f`.
+        The interpolated indices, same shape as `f`. A masked array if `f` is a masked
+        array, or if `errors` is "mask" and any value is masked.
+    InverseStats
+        Only if `return_stats` is True. The number of values exactly attained
+        (`exact_count`), the number of values whose index was rounded (`rounded_count`)
+        and the maximum distance between the values and the function at the returned
+        indices (`max_abs_residual`, in units of `fp`, or of `f` if finer).
 
     Raises
     ------
     KeyError
-        If any value of `f` is outside the `fp` range.
+        If any value of `f` is outside the `fp` range on a side where `bounds` is "raise",
+        inside a gap or filled beyond the limit and `errors` is "raise".
+    ValueError
+        If any string of `f` is not a valid ISO 8601 datetime.
+    OverflowError
+        If the datetime64 values of `fp` do not fit in the finer unit (e.g. beyond the
+        years 1677 to 2262 for nanoseconds), or if any index exceeds the range of `dtype`.
+    """
+    if period is not None:
+        raise ValueError("period is not supported for inverse interpolation")
+    if errors == "mask" and np.asarray(f).dtype.kind == "M":
+        nat = np.isnat(np.ma.getdata(f))
+        if nat.any():
+            f = np.ma.masked_array(f, mask=np.ma.getmaskarray(f) | nat)
+    if np.ma.isMaskedArray(f):
+        return masked(
+            inverse,
+            f,
+            native(xp).dtype if dtype is None else np.dtype(dtype),
+            xp=xp,
+            fp=fp,
+            method=method,
+            closed=closed,
+            return_stats=return_stats,
+            errors=errors,
+            max_gap=max_gap,
+            max_gap_factor=max_gap_factor,
+            gaps=gaps,
+            limit=limit,
+            scale_factor=scale_factor,
+            add_offset=add_offset,
+            dtype=dtype,
+            bounds=bounds,
+            sort=sort,
+            duplicates=duplicates,
+        )
+    if sort:
+        xp, fp = sort_points(xp, fp, duplicates)
+    if scale_factor is not None or add_offset is not None:
+        if dtype is not None:
+            raise ValueError("dtype is not supported for packed values")
+        return packed(
+            xp,
+            fp,
+            scale_factor,
+            add_offset,
+            f=f,
+            method=method,
+            closed=closed,
+            return_stats=return_stats,
+            errors=errors,
+            max_gap=max_gap,
+            max_gap_factor=max_gap_factor,
+            gaps=gaps,
+            limit=limit,
+            bounds=bounds,
+        )
+    if is_uint64(f, fp):
+        return uint64(
+            xp,
+            fp,
+            f,
+            method=method,
+            closed=closed,
+            return_stats=return_stats,
+            errors=errors,
+            max_gap=max_gap,
+            max_gap_factor=max_gap_factor,
+            gaps=gaps,
+            limit=limit,
+            dtype=dtype,
+            bounds=bounds,
+        )
+    return _inverse(
+        xp,
+        fp,
+        f=f,
+        method=method,
+        closed=closed,
+        return_stats=return_stats,
+        errors=errors,
+        max_gap=max_gap,
+        max_gap_factor=max_gap_factor,
+        gaps=gaps,
+        limit=limit,
+        dtype=dtype,
+        bounds=bounds,
+    )
+
+
+def forward_2d(i, j, xi, xj, fp, errors="raise"):
+    """
+    Bilinear interpolation on a grid of data points.
+
+    Parameters
+    ----------
+    i, j : 1-D sequences of positive integers
+        The row and column indices at which to evaluate the interpolated values, same
+        length.
+    xi : 1-D sequence of positive integers
+        The row indices of the data points, must be strictly increasing.
+    xj : 1-D sequence of positive integers
+        The column indices of the data points, must be strictly increasing.
+    fp : 2-D array of floats, integers or datetime64s
+        The values of the data points, of shape `(len(xi), len(xj))`. Integer values are
+        interpolated exactly and rounded to the nearest integer.
+    errors : {"raise", "mask"}, optional
+        Whether to raise on queries outside the grid or to mask them in the returned
+        array. Defaults to "raise".
+
+    Returns
+    -------
+    1-D array of floats, integers or datetime64s.
+        The interpolated values, same length as `i`. A masked array if `errors` is "mask"
+        and any value is masked.
+
+    Raises
+    ------
+    IndexError
+        If any query is outside the grid and `errors` is "raise".
+    """
+    i = native(i)
+    j = native(j)
+    fp = native(fp)
+    if not (i.ndim == 1 and j.ndim == 1 and len(i) == len(j)):
+        raise ValueError("i and j must be 1D with the same length")
+    if not fp.ndim == 2:
+        raise ValueError("fp must be 2D")
+    if not fp.shape == (len(xi), len(xj)):
+        raise ValueError("fp must have shape (len(xi), len(xj))")
+    check_nat(fp.reshape(-1), "fp")
+    if np.issubdtype(fp.dtype, np.integer) or np.issubdtype(fp.dtype, np.datetime64):
+        out = rust.forward_2d_int(i, j, xi, xj, fp.astype("i8"), errors=errors)
+    elif np.issubdtype(fp.dtype, np.floating):
+        out = rust.forward_2d_float(i, j, xi, xj, fp.astype("f8"), errors=errors)
+    else:
+        raise ValueError("fp dtype must be either integer, floating or datetime")
+    if isinstance(out, tuple):
+        out, mask = out
+        out = out.astype(fp.dtype)
+        return np.ma.MaskedArray(out, mask=mask) if mask.any() else out
+    return out.astype(fp.dtype)
+
+
+def forward_multi(x_list, xp_list, fp_list, parallel=False):
+    """
+    Forward interpolation over many pairs of data points in a single call.
+
+    Parameters
+    ----------
+    x_list : sequence of 1-D sequences of positive integers
+        The indices at which to evaluate each pair.
+    xp_list : sequence of 1-D sequences of positive integers
+        The indices of the data points of each pair, must be strictly increasing.
+    fp_list : sequence of 1-D sequences of floats, integers or datetime64s
+        The values of the data points of each pair, all with the same dtype.
+    parallel : bool, optional
+        Whether to spread the pairs over the available threads. Defaults to False.
+
+    Returns
+    -------
+    list of 1-D arrays or None
+        The interpolated values of each pair, None for failing pairs.
+    dict
+        The exception of each failing pair by pair index (e.g. IndexError if any index is
+        out of bounds).
+    """
+    return multi(
+        rust.forward_multi_int,
+        rust.forward_multi_float,
+        xp_list,
+        fp_list,
+        x_list=x_list,
+        parallel=parallel,
+    )
+
+
+def inverse_multi(f_list, xp_list, fp_list, method=None, parallel=False):
+    """
+    Inverse interpolation over many pairs of data points in a single call.
+
+    Parameters
+    ----------
+    f_list : sequence of 1-D sequences of floats, integers or datetime64s
+        The values at which to evaluate each pair. Datetime64 values and data points are
+        converted exactly to the finest unit of all of them.
+    xp_list : sequence of 1-D sequences of positive integers
+        The indices of the data points of each pair.
+    fp_list : sequence of 1-D sequences of floats, integers or datetime64s
+        The values of the data points of each pair, must be strictly increasing or
+        decreasing and all with the same dtype.
+    method : str, Method or None, optional
+        The method to use for inexact matches, see `inverse`.
+    parallel : bool, optional
+        Whether to spread the pairs over the available threads. Defaults to False.
+
+    Returns
+    -------
+    list of 1-D arrays or None
+        The interpolated indices of each pair, None for failing pairs.
+    dict
+        The exception of each failing pair by pair index (e.g. KeyError if any value is not
+        found).
+    """
+    return multi(
+        rust.inverse_multi_int,
+        rust.inverse_multi_float,
+        xp_list,
+        fp_list,
+        f_list=f_list,
+        method=method,
+        parallel=parallel,
+    )
+
+
+def sample(xp, fp, n):
+    """
+    Evaluate the interpolation at evenly spaced indices, e.g. for plotting.
+
+    Parameters
+    ----------
+    xp : 1-D sequence of positive integers
+        The indices of the data points, must be strictly increasing.
+    fp : 1-D sequence of floats, integers or datetime64s
+        The values of the data points, same length as `xp`.
+    n : int
+        The number of samples, must be positive.
+
+    Returns
+    -------
+    x : 1-D array of integers
+        The `n` indices, evenly spaced from the first to the last index of `xp` inclusive
+        and rounded to the nearest integer. A single sample is the first index.
+    f : 1-D array of floats, integers or datetime64s
+        The interpolated values at `x`.
+    """
+    xp, fp, x, _, _ = check(xp, fp, x=xp)
+    if not n > 0:
+        raise ValueError("n must be positive")
+    if np.issubdtype(fp.dtype, np.integer) or np.issubdtype(fp.dtype, np.datetime64):
+        x, f = rust.sample_int(xp.astype("u8"), fp.astype("i8"), n)
+    elif np.issubdtype(fp.dtype, np.floating):
+        x, f = rust.sample_float(xp.astype("u8"), fp.astype("f8"), n)
+    else:
+        raise ValueError("fp dtype must be either integer, floating or datetime")
+    return x.astype(xp.dtype), f.astype(fp.dtype)
+
+
+def forward_range(start, stop, step, xp, fp, wrap_negative=False):
+    """
+    Interpolate at evenly spaced indices without building them, as
+    `forward(np.arange(start, stop, step), xp, fp)` does.
+
+    Parameters
+    ----------
+    start : int
+        The first index, included.
+    stop : int
+        The end of the range, excluded.
+    step : int
+        The spacing between consecutive indices, must be positive.
+    xp : 1-D sequence of positive integers
+        The indices of the data points, must be strictly increasing.
+    fp : 1-D sequence of floats, integers or datetime64s
+        The values of the data points, same length as `xp`.
+    wrap_negative : bool, optional
+        Whether to interpret negative `start` and `stop` as offsets from the end as
+        Python slices do (see `forward`). Defaults to False.
+
+    Returns
+    -------
+    1-D array of floats, integers or datetime64s.
+        The interpolated values, empty if `start` is not below `stop`.
+
+    Raises
+    ------
+    IndexError
+        If any index of the range is outside the `xp` range, or if a negative `start` or
+        `stop` is still negative once wrapped.
+    """
+    if wrap_negative:
+        start = int(wrap(start, xp, "start"))
+        stop = int(wrap(stop, xp, "stop"))
+    xp, fp, _, _, _ = check(xp, fp, x=xp)
+    if not step > 0:
+        raise ValueError("step must be positive")
+    if not (start >= 0 and stop >= 0):
+        raise ValueError("start and stop must be positive")
+    if np.issubdtype(fp.dtype, np.integer) or np.issubdtype(fp.dtype, np.datetime64):
+        f = rust.forward_range_int(
+            start, stop, step, xp.astype("u8"), fp.astype("i8")
+        )
+    elif np.issubdtype(fp.dtype, np.floating):
+        f = rust.forward_range_float(
+            start, stop, step, xp.astype("u8"), fp.astype("f8")
+        )
+    else:
+        raise ValueError("fp dtype must be either integer, floating or datetime")
+    return f.astype(fp.dtype)
+
+
+def derivative(xp, fp):
+    """
+    Compute the slope of each segment between consecutive data points.
+
+    Slopes are computed in extended precision and rounded to float64 at the end, hence they
+    are accurate to about one float64 ulp.
+
+    Parameters
+    ----------
+    xp : 1-D sequence of positive integers
+        The indices of the data points, must be strictly increasing.
+    fp : 1-D sequence of floats, integers or datetime64s
+        The values of the data points, same length as `xp`.
+
+    Returns
+    -------
+    starts : 1-D array of integers
+        The first index of each segment, i.e. `xp` without its last element.
+    slopes : 1-D array of floats
+        The slope of each segment, in units of `fp` (or of its datetime64 unit) per index
+        step.
+    """
+    xp, fp, _, _, _ = check(xp, fp, x=xp)
+    if np.issubdtype(fp.dtype, np.integer) or np.issubdtype(fp.dtype, np.datetime64):
+        starts, slopes = rust.derivative_int(xp.astype("u8"), fp.astype("i8"))
+    elif np.issubdtype(fp.dtype, np.floating):
+        starts, slopes = rust.derivative_float(xp.astype("u8"), fp.astype("f8"))
+    else:
+        raise ValueError("fp dtype must be either integer, floating or datetime")
+    return starts.astype(xp.dtype), slopes
+
+
+def seconds_to_ns(seconds, method="nearest"):
+    """
+    Convert floating seconds into integer nanoseconds.
+
+    The exact product of each float by 1e9 is rounded once, hence nanoseconds are not lost
+    above 2**53 ns (about 104 days) as they are with float multiplication.
+
+    Parameters
+    ----------
+    seconds : 1-D sequence or scalar of floats
+        The numbers of seconds.
+    method : {None, "nearest", "ffill", "bfill"} or Method, optional
+        How to round fractional nanoseconds: to the nearest (ties to even), toward minus or
+        plus infinity, or not at all. Defaults to "nearest".
+
+    Returns
+    -------
+    1-D array or scalar of integers.
+        The numbers of nanoseconds, same shape as `seconds`.
+
+    Raises
+    ------
+    ValueError
+        If any value is NaN or infinite, or fractional and `method` is None.
+    OverflowError
+        If any number of nanoseconds does not fit in 64-bit integers.
+    """
+    seconds = native(seconds)
+    if seconds.ndim > 1:
+        raise ValueError("seconds must be 1D or scalar")
+    out = rust.seconds_to_ns_array(np.atleast_1d(seconds).astype("f8"), method)
+    return out[0] if seconds.ndim == 0 else out
+
+
+def ns_to_seconds(ns):
+    """
+    Convert integer nanoseconds into floating seconds.
+
+    Each result is the float nearest to the exact quotient (ties to even).
+
+    Parameters
+    ----------
+    ns : 1-D sequence or scalar of integers or timedelta64s
+        The numbers of nanoseconds. Timedeltas are converted to nanoseconds first.
+
+    Returns
+    -------
+    1-D array or scalar of floats.
+        The numbers of seconds, same shape as `ns`.
+    """
+    ns = native(ns)
+    if ns.ndim > 1:
+        raise ValueError("ns must be 1D or scalar")
+    if np.issubdtype(ns.dtype, np.timedelta64):
+        ns = to_unit(ns, "ns").view("i8")
+    out = rust.ns_to_seconds_array(np.atleast_1d(ns))
+    return out[0] if ns.ndim == 0 else out
+
+
+def summary(xp, fp, factor=2.0):
+    """
+    Descriptive statistics of the data points, e.g. for logging.
+
+    Parameters
+    ----------
+    xp : 1-D sequence of positive integers
+        The indices of the data points, must be strictly increasing.
+    fp : 1-D sequence of floats, integers or datetime64s
+        The values of the data points, same length as `xp`.
+    factor : float, optional
+        The factor of deviation from the median slope beyond which segments are
+        irregular. Defaults to 2.
+
+    Returns
+    -------
+    dict
+        The number of data points (`points`), the first and last indices (`domain`), the
+        minimum and maximum values (`range`, same dtype as `fp`), the minimum, median and
+        maximum slopes in units of `fp` per index (`slopes`, as floats), the minimum and
+        maximum lengths of the segments in indices (`lengths`) and the number of irregular
+        segments (`irregular`).
+    """
+    xp, fp, _, _, _ = check(xp, fp, x=xp)
+    if not factor >= 1:
+        raise ValueError("factor must be at least one")
+    if np.issubdtype(fp.dtype, np.integer) or np.issubdtype(fp.dtype, np.datetime64):
+        out = rust.summary_int(xp.astype("u8"), fp.astype("i8"), factor)
+    elif np.issubdtype(fp.dtype, np.floating):
+        out = rust.summary_float(xp.astype("u8"), fp.astype("f8"), factor)
+    else:
+        raise ValueError("fp dtype must be either integer, floating or datetime")
+    out["range"] = tuple(np.array(out["range"]).astype(fp.dtype))
+    return out
+
+
+def dumps(xp, fp, checksum=True):
+    """
+    Serialize data points into a compact binary format.
+
+    Parameters
+    ----------
+    xp : 1-D sequence of positive integers
+        The indices of the data points, must be strictly increasing.
+    fp : 1-D sequence of floats, integers or datetime64s
+        The values of the data points, same length as `xp`. Integer and datetime64 values
+        are stored as 64-bit integers, losing the datetime64 unit.
+    checksum : bool, optional
+        Whether to append a checksum verified by `loads`. Defaults to True.
+
+    Returns
+    -------
+    bytes
+        The serialized data points.
+    """
+    xp, fp, _, _, _ = check(xp, fp, x=xp)
+    if is_extended(fp.dtype):
+        return rust.dumps_longdouble(xp.astype("u8"), to_bytes(fp), checksum)
+    if np.issubdtype(fp.dtype, np.integer) or np.issubdtype(fp.dtype, np.datetime64):
+        return rust.dumps_int(xp.astype("u8"), fp.astype("i8"), checksum)
+    elif np.issubdtype(fp.dtype, np.floating):
+        return rust.dumps_float(xp.astype("u8"), fp.astype("f8"), checksum)
+    else:
+        raise ValueError("fp dtype must be either integer, floating or datetime")
+
+
+def loads(data):
+    """
+    Deserialize data points serialized by `dumps`.
+
+    Parameters
+    ----------
+    data : bytes
+        The serialized data points.
+
+    Returns
+    -------
+    xp : 1-D array of integers
+        The indices of the data points.
+    fp : 1-D array of floats or integers
+        The values of the data points.
+
+    Raises
+    ------
+    ValueError
+        If the data is truncated, corrupted or not serialized data points.
+    """
+    xp, fp = rust.loads(data)
+    if fp.ndim == 2:
+        fp = from_bytes(fp)
+    return xp, fp
+
+
+def to_dict(xp, fp, dim=None, datetime="iso"):
+    """
+    Convert data points into the coordinate dict of xdas.
+
+    Parameters
+    ----------
+    xp : 1-D sequence of positive integers
+        The indices of the data points, must be strictly increasing.
+    fp : 1-D sequence of floats, integers or datetime64s
+        The values of the data points, same length as `xp`.
+    dim : str or None, optional
+        The dimension of the coordinate. Defaults to None.
+    datetime : {"iso", "int"}, optional
+        Whether datetime64 values are encoded as ISO 8601 strings or as integers counted in
+        the unit of the dtype. Defaults to "iso".
+
+    Returns
+    -------
+    dict
+        The dimension (`dim`), the data points (`data`, a dict of the `tie_indices` and
+        `tie_values` lists) and the dtype of the values (`dtype`, as a string).
+    """
+    xp, fp, _, _, _ = check(xp, fp, x=xp)
+    if datetime not in ("iso", "int"):
+        raise ValueError("datetime must be either 'iso' or 'int'")
+    if np.issubdtype(fp.dtype, np.datetime64):
+        values = fp.astype(str) if datetime == "iso" else fp.view("i8")
+    else:
+        values = fp
+    data = {"tie_indices": xp.tolist(), "tie_values": values.tolist()}
+    return {"dim": dim, "data": data, "dtype": str(fp.dtype)}
+
+
+def from_dict(data):
+    """
+    Convert the coordinate dict of xdas into data points.
+
+    Parameters
+    ----------
+    data : dict
+        Either a coordinate dict as returned by `to_dict` or its `data` dict alone, i.e. the
+        `tie_indices` and `tie_values` lists, along with an optional `dtype`. Datetime64
+        values are either ISO 8601 strings or integers counted in the unit of the dtype.
+
+    Returns
+    -------
+    xp : 1-D array of uint64
+        The indices of the data points.
+    fp : 1-D array of floats, integers or datetime64s
+        The values of the data points, of the given dtype.
+
+    Raises
+    ------
+    ValueError
+        If the dict is malformed or if the data points are invalid.
+    """
+    if not isinstance(data, dict):
+        raise ValueError("malformed dict: expected a dict")
+    dtype = data.get("dtype")
+    if "data" in data:
+        data = data["data"]
+        if not isinstance(data, dict):
+            raise ValueError("malformed dict: 'data' must be a dict")
+        dtype = data.get("dtype", dtype)
+    for key in ("tie_indices", "tie_values"):
+        if key not in data:
+            raise ValueError(f"malformed dict: missing key '{key}'")
+    try:
+        dtype = None if dtype is None else np.dtype(dtype)
+    except TypeError:
+        raise ValueError(f"malformed dict: invalid dtype {dtype!r}") from None
+    xp = np.asarray(data["tie_indices"])
+    if not (xp.ndim == 1 and (xp.size == 0 or np.issubdtype(xp.dtype, np.integer))):
+        raise ValueError("malformed dict: tie_indices must be a list of integers")
+    try:
+        fp = np.asarray(data["tie_values"], dtype=dtype)
+    except (TypeError, ValueError) as error:
+        raise ValueError(f"malformed dict: invalid tie_values ({error})") from None
+    if not (fp.ndim == 1 and fp.dtype.kind in "iufM"):
+        raise ValueError("malformed dict: tie_values must be a list of numbers")
+    check(xp, fp, x=xp)
+    return xp.astype("u8"), fp
+
+
+def fingerprint(xp, fp):
+    """
+    Compute a fingerprint of data points, e.g. to key caches of derived products.
+
+    The fingerprint hashes the dtypes, the number and the raw bytes of the data points as
+    serialized by `dumps`, and the datetime64 unit of the values if any, hence it is stable
+    across processes and platforms.
+
+    Parameters
+    ----------
+    xp : 1-D sequence of positive integers
+        The indices of the data points, must be strictly increasing.
+    fp : 1-D sequence of floats, integers or datetime64s
+        The values of the data points, same length as `xp`.
+
+    Returns
+    -------
+    int
+        The fingerprint, a non-negative integer below 2**64 that can be used as a
+        dictionary key or returned by `__hash__` (Python reduces it).
+    """
+    fp = np.asarray(fp)
+    unit = str(fp.dtype) if np.issubdtype(fp.dtype, np.datetime64) else ""
+    return rust.fingerprint(dumps(xp, fp, checksum=False), unit)
+
+
+def load_npy_dense(path, epsilon):
+    """
+    Load data points simplified from the dense values of a .npy file.
+
+    The file is memory-mapped and streamed once to keep the data points needed to
+    reproduce every value within `epsilon`, hence files larger than memory can be
+    simplified without loading them.
+
+    Parameters
+    ----------
+    path : str or path-like
+        The path of a 1-D .npy file of little-endian int64, uint64 or float64 values, the
+        value at index i being the i-th element.
+    epsilon : int or float
+        The maximum absolute error of the forward interpolation at every index, must be
+        positive.
+
+    Returns
+    -------
+    xp : 1-D array of integers
+        The indices of the data points.
+    fp : 1-D array of floats or integers
+        The values of the data points.
+
+    Raises
+    ------
+    FileNotFoundError
+        If the file does not exist.
+    ValueError
+        If the file is not a valid .npy file of a supported dtype or is empty.
+    """
+    return rust.load_npy_dense(os.fspath(path), epsilon)
+
+
+def load_npy_pair(xp_path, fp_path):
+    """
+    Load data points stored in two .npy files.
+
+    Parameters
+    ----------
+    xp_path : str or path-like
+        The path of a 1-D .npy file of positive, strictly increasing int64 or uint64
+        indices.
+    fp_path : str or path-like
+        The path of a 1-D .npy file of int64, uint64 or float64 values, same length as the
+        indices.
+
+    Returns
+    -------
+    xp : 1-D array of integers
+        The indices of the data points.
+    fp : 1-D array of floats or integers
+        The values of the data points.
+
+    Raises
+    ------
+    FileNotFoundError
+        If a file does not exist.
+    ValueError
+        If a file is not a valid .npy file of a supported dtype or if the data points are
+        invalid.
+    """
+    return rust.load_npy_pair(os.fspath(xp_path), os.fspath(fp_path))
+
+
+def check_monotonic(arr):
+    """
+    Diagnose whether a 1-D array is strictly increasing.
+
+    Parameters
+    ----------
+    arr : 1-D sequence of floats, integers or datetime64s
+        The array to check.
+
+    Returns
+    -------
+    dict
+        The diagnostics with keys:
+        - "kind": "strict", "non-decreasing" or "none"
+        - "violations": positions of the elements not strictly greater than their
+          predecessor (at most 100)
+        - "nans": positions of the NaN (or NaT) values (at most 100)
+    """
+    arr = native(arr)
+    if not arr.ndim == 1:
+        raise ValueError("arr must be 1D")
+    if np.issubdtype(arr.dtype, np.unsignedinteger):
+        return rust.check_monotonic_uint(arr.astype("u8"))
+    elif np.issubdtype(arr.dtype, np.datetime64):
+        return rust.check_monotonic_datetime(arr.view("i8"))
+    elif np.issubdtype(arr.dtype, np.integer):
+        return rust.check_monotonic_int(arr.astype("i8"))
+    elif np.issubdtype(arr.dtype, np.floating):
+        return rust.check_monotonic_float(arr.astype("f8"))
+    else:
+        raise ValueError("arr dtype must be either integer, floating or datetime")
+
+
+def smooth(fp, window, kind="mean"):
     """
-    return _inverse(xp, fp, f=f, method=method)
+    Smooth values with a centered moving window.
 
+    The window shrinks near the edges so that the first and last values are kept unchanged.
+    Integer and datetime means are computed exactly and rounded to the nearest integer.
 
-def wraps(func_int, func_float):
+    Parameters
+    ----------
+    fp : 1-D sequence of floats, integers or datetime64s
+        The values to smooth.
+    window : int
+        The width of the window, even widths are rounded down to the previous odd width.
+    kind : {"mean", "median"}, optional
+        The statistic computed over the window. Defaults to "mean".
+
+    Returns
+    -------
+    1-D array of floats, integers or datetime64s.
+        The smoothed values, same shape and dtype as `fp`.
+    """
+    fp = native(fp)
+    if not fp.ndim == 1:
+        raise ValueError("fp must be 1D")
+    if not window > 0:
+        raise ValueError("window must be positive")
+    check_nat(fp, "fp")
+    if np.issubdtype(fp.dtype, np.integer) or np.issubdtype(fp.dtype, np.datetime64):
+        return rust.smooth_int(fp.astype("i8"), window, kind).astype(fp.dtype)
+    elif np.issubdtype(fp.dtype, np.floating):
+        if not np.all(np.isfinite(fp)):
+            raise ValueError("fp values must be finite")
+        return rust.smooth_float(fp.astype("f8"), window, kind).astype(fp.dtype)
+    else:
+        raise ValueError("fp dtype must be either integer, floating or datetime")
+
+
+def detect_outliers(xp, fp, window, threshold):
+    """
+    Find the data points deviating from the local linear trend.
+
+    Each value is compared to the linear interpolation between the endpoints of a centered
+    window of data points, the window shrinking near the edges. The first and last values are
+    compared to the extrapolation of the nearest window endpoints on the other side.
+
+    Parameters
+    ----------
+    xp : 1-D sequence of positive integers
+        The indices of the data points, must be strictly increasing.
+    fp : 1-D sequence of floats, integers or datetime64s
+        The values of the data points, same length as `xp`.
+    window : int
+        The width of the window, even widths are rounded down to the previous odd width.
+    threshold : float, int, timedelta64 or str
+        The maximum deviation from the trend, in the units of `fp`. For datetime64 `fp`,
+        floats are seconds and strings durations such as "250us", which must convert
+        exactly to the unit of `fp` as timedelta64 values.
+
+    Returns
+    -------
+    1-D array of integers
+        The sorted positions of the outliers.
+    """
+    xp = native(xp)
+    fp = native(fp)
+    if not (xp.ndim == 1 and fp.ndim == 1):
+        raise ValueError("xp and fp must be 1D")
+    if not (len(xp) == len(fp)):
+        raise ValueError("xp and fp must have the same length")
+    if not np.issubdtype(xp.dtype, np.integer):
+        raise ValueError("xp must have integer dtype")
+    if not np.all(xp >= 0):
+        raise ValueError("xp values must be positive")
+    if not np.all(np.isfinite(fp)):
+        raise ValueError("fp values must be finite")
+    if np.issubdtype(fp.dtype, np.datetime64):
+        unit, _ = np.datetime_data(fp.dtype)
+        threshold = to_duration(threshold, unit, "threshold").astype("i8")
+    if np.issubdtype(fp.dtype, np.integer) or np.issubdtype(fp.dtype, np.datetime64):
+        return rust.detect_outliers_int(
+            xp.astype("u8"), fp.astype("i8"), window, int(threshold)
+        )
+    elif np.issubdtype(fp.dtype, np.floating):
+        return rust.detect_outliers_float(
+            xp.astype("u8"), fp.astype("f8"), window, float(threshold)
+        )
+    else:
+        raise ValueError("fp dtype must be either integer, floating or datetime")
+
+
+def epsilon_sweep(xp, fp, epsilons):
+    """
+    Measure the simplification of the data points for several tolerances.
+
+    The data points are simplified with the Douglas-Peucker algorithm, dropping those whose
+    value deviates from the chord joining the kept neighbors by at most the tolerance. All
+    tolerances are answered from a single pass, with the same results as independent
+    simplifications.
+
+    Parameters
+    ----------
+    xp : 1-D sequence of positive integers
+        The indices of the data points, must be strictly increasing.
+    fp : 1-D sequence of floats, integers or datetime64s
+        The values of the data points, same length as `xp`.
+    epsilons : 1-D sequence or scalar of floats, integers, timedelta64s or strs
+        The tolerances, in the units of `fp`. For datetime64 `fp`, floats are seconds and
+        strings durations such as "1ms" or "0.5s", which must convert exactly to the unit
+        of `fp` as timedelta64 values (e.g. "0.3ns" raises ValueError for nanoseconds).
+
+    Returns
+    -------
+    1-D structured array
+        One record per tolerance with the tolerance (`epsilon`), the number of kept data
+        points (`count`) and the largest deviation of the dropped data points from the
+        simplified function (`max_error`, zero if none is dropped). Deviations are unsigned
+        integers for integer `fp` and timedelta64s for datetime64 `fp`.
+    """
+    xp, fp, _, _, _ = check(xp, fp, x=xp)
+    epsilons = np.atleast_1d(native(epsilons))
+    if np.issubdtype(fp.dtype, np.datetime64):
+        unit, _ = np.datetime_data(fp.dtype)
+        epsilons = to_duration(epsilons, unit, "epsilons")
+        dtype = epsilons.dtype
+        epsilons = epsilons.view("i8")
+    if np.issubdtype(fp.dtype, np.integer) or np.issubdtype(fp.dtype, np.datetime64):
+        if np.any(epsilons < 0):
+            raise ValueError("epsilons must be non-negative")
+        if not np.issubdtype(fp.dtype, np.datetime64):
+            dtype = np.dtype("u8")
+        epsilons = epsilons.astype("u8")
+        counts, errors = rust.epsilon_sweep_int(xp.astype("u8"), fp.astype("i8"), epsilons)
+    elif np.issubdtype(fp.dtype, np.floating):
+        dtype = np.dtype("f8")
+        epsilons = epsilons.astype("f8")
+        counts, errors = rust.epsilon_sweep_float(
+            xp.astype("u8"), fp.astype("f8"), epsilons
+        )
+    else:
+        raise ValueError("fp dtype must be either integer, floating or datetime")
+    fields = [("epsilon", dtype), ("count", "i8"), ("max_error", dtype)]
+    out = np.zeros(len(counts), dtype=fields)
+    out["epsilon"] = epsilons.view(dtype)
+    out["count"] = counts
+    out["max_error"] = errors.view(dtype)
+    return out
+
+
+def decimate(xp, fp, k=None, n=None):
+    """
+    Reduce the data points by keeping every k-th one.
+
+    The data points at positions 0, k, 2k, ... are kept, and always the last one, whatever
+    the values. Cheaper and more predictable than a simplification within a tolerance, e.g.
+    before plotting.
+
+    Parameters
+    ----------
+    xp : 1-D sequence of positive integers
+        The indices of the data points, must be strictly increasing.
+    fp : 1-D sequence of floats, integers or datetime64s
+        The values of the data points, same length as `xp`.
+    k : int, optional
+        The step between the positions of the kept data points, 1 to keep all of them.
+    n : int, optional
+        The maximum number of kept data points, at least 2, the smallest step leaving at
+        most `n` of them being chosen. Exactly one of `k` and `n` must be provided.
+
+    Returns
+    -------
+    1-D array of positive integers
+        The indices of the kept data points, same dtype as `xp`.
+    1-D array of floats, integers or datetime64s
+        The values of the kept data points, same dtype as `fp`.
+    float, int or timedelta64
+        The largest deviation of the original data points from the decimated function
+        (integer interpolations being rounded to the nearest integer), in the units of `fp`.
+    """
+    xp, fp, _, _, _ = check(xp, fp, x=xp)
+    if is_extended(fp.dtype):
+        out_xp, out_fp, error = rust.decimate_longdouble(
+            xp.astype("u8"), to_bytes(fp), k=k, n=n
+        )
+        out_fp, error = from_bytes(out_fp), from_bytes(error)[0]
+    elif np.issubdtype(fp.dtype, np.unsignedinteger):
+        out_xp, out_fp, error = rust.decimate_uint(
+            xp.astype("u8"), fp.astype("u8"), k=k, n=n
+        )
+    elif np.issubdtype(fp.dtype, np.integer) or np.issubdtype(fp.dtype, np.datetime64):
+        out_xp, out_fp, error = rust.decimate_int(
+            xp.astype("u8"), fp.astype("i8"), k=k, n=n
+        )
+        if np.issubdtype(fp.dtype, np.datetime64):
+            unit, _ = np.datetime_data(fp.dtype)
+            error = np.timedelta64(error, unit)
+    elif np.issubdtype(fp.dtype, np.floating):
+        out_xp, out_fp, error = rust.decimate_float(
+            xp.astype("u8"), fp.astype("f8"), k=k, n=n
+        )
+    else:
+        raise ValueError("fp dtype must be either integer, floating or datetime")
+    return out_xp.astype(xp.dtype), out_fp.astype(fp.dtype), error
+
+
+def verify(values, xp, fp, atol):
+    """
+    Check that data points reproduce densely sampled values within a tolerance.
+
+    The i-th value is compared to the interpolation at index i. Integer and datetime
+    interpolations are rounded to the nearest integer.
+
+    Parameters
+    ----------
+    values : 1-D sequence of floats, integers or datetime64s
+        The densely sampled values, e.g. the coordinate the data points were fitted to.
+    xp : 1-D sequence of positive integers
+        The indices of the data points, must be strictly increasing and cover the indices of
+        `values`.
+    fp : 1-D sequence of floats, integers or datetime64s
+        The values of the data points, same length as `xp` and same dtype as `values`.
+    atol : float, int, timedelta64 or str
+        The maximum absolute deviation, in the units of `fp`. For datetime64 `fp`, floats
+        are seconds and strings durations such as "1ms", which must convert exactly to the
+        unit of `fp` as timedelta64 values.
+
+    Returns
+    -------
+    Verification
+        The number of values deviating by more than `atol` (`violations`), the maximum
+        deviation (`max_deviation`, in the units of `fp`) and its index (`argmax`). NaN
+        values count as violations. Both `max_deviation` and `argmax` are None if `values`
+        is empty.
+
+    Raises
+    ------
+    IndexError
+        If the indices of `values` extend beyond the `xp` range.
+    """
+    values = native(values)
+    xp, fp, _, _, _ = check(xp, fp, x=np.arange(len(values)))
+    if not values.ndim == 1:
+        raise ValueError("values must be 1D")
+    if not values.dtype == fp.dtype:
+        raise ValueError("values and fp must have the same dtype")
+    check_nat(values, "values")
+    if np.issubdtype(fp.dtype, np.datetime64):
+        unit, _ = np.datetime_data(fp.dtype)
+        atol = to_duration(atol, unit, "atol").astype("i8")
+    if np.issubdtype(fp.dtype, np.integer) or np.issubdtype(fp.dtype, np.datetime64):
+        return rust.verify_int(
+            values.astype("i8"), xp.astype("u8"), fp.astype("i8"), int(atol)
+        )
+    elif np.issubdtype(fp.dtype, np.floating):
+        return rust.verify_float(
+            values.astype("f8"), xp.astype("u8"), fp.astype("f8"), float(atol)
+        )
+    else:
+        raise ValueError("fp dtype must be either integer, floating or datetime")
+
+
+def wraps(func_int, func_float, func_longdouble):
     def func(xp, fp, *, x=None, f=None, **kwargs):
         xp, fp, x, f, isscalar = check(xp, fp, x, f)
+        dtype = kwargs.pop("dtype", None)
+        if dtype is not None:
+            dtype = np.dtype(dtype)
+            if x is not None and not np.issubdtype(fp.dtype, np.floating):
+                raise ValueError("dtype is only supported for float fp")
+            kwargs["dtype"] = dtype.name
+        if kwargs.get("max_gap") is not None and np.issubdtype(fp.dtype, np.datetime64):
+            unit, _ = np.datetime_data(fp.dtype)
+            max_gap = to_duration(kwargs["max_gap"], unit, "max_gap")
+            kwargs["max_gap"] = int(max_gap.view("i8"))
+        if is_extended(fp.dtype):
+            out = longdouble(func_longdouble, xp, fp, x, f, **kwargs)
+            return out[0] if isscalar else out
         if np.issubdtype(fp.dtype, np.integer) or np.issubdtype(
             fp.dtype, np.datetime64
         ):
             if x is not None:
                 out = func_int(
                     x.astype("u8"), xp.astype("u8"), fp.astype("i8"), **kwargs
-                ).astype(fp.dtype)
+                )
             if f is not None:
                 out = func_int(
-                    f.astype("i8"), xp.astype("u8"), fp.astype("i8"), **kwargs
-                ).astype(xp.dtype)
+                    f if is_text(f) else f.astype("i8"),
+                    xp.astype("u8"),
+                    fp.astype("i8"),
+                    **kwargs,
+                )
         elif np.issubdtype(fp.dtype, np.floating):
             if x is not None:
                 out = func_float(
                     x.astype("u8"), xp.astype("u8"), fp.astype("f8"), **kwargs
-                ).astype(fp.dtype)
+                )
             if f is not None:
                 out = func_float(
                     f.astype("f8"), xp.astype("u8"), fp.astype("f8"), **kwargs
-                ).astype(xp.dtype)
+                )
         else:
             raise ValueError("fp dtype must be either integer, floating or datetime")
+        mask = None
+        stats = None
+        if isinstance(out, tuple):
+            if kwargs.get("errors") == "mask":
+                out, mask = out
+            else:
+                out, stats = out
+        if dtype is None:
+            out = out.astype(fp.dtype if x is not None else xp.dtype)
+        if mask is not None and mask.any():
+            out = np.ma.MaskedArray(out, mask=mask)
         if isscalar:
-            return out[0]
-        else:
+            out = out[0]
+        if stats is None:
             return out
+        else:
+            return out, stats
 
     return func
 
 
+def multi(
+    func_int, func_float, xp_list, fp_list, *, x_list=None, f_list=None, **kwargs
+):
+    xp_list = [native(xp) for xp in xp_list]
+    fp_list = [native(fp) for fp in fp_list]
+    for k, fp in enumerate(fp_list):
+        check_nat(fp, f"fp_list[{k}]")
+    dtypes = {fp.dtype for fp in fp_list}
+    if len(dtypes) > 1:
+        raise ValueError("fp arrays must all have the same dtype")
+    dtype = dtypes.pop() if dtypes else np.dtype("i8")
+    if x_list is not None:
+        queries = [native(x) for x in x_list]
+    elif np.issubdtype(dtype, np.datetime64):
+        queries = [native(f) for f in f_list]
+        queries = [f if f.dtype.kind == "M" else f.astype(dtype) for f in queries]
+        for k, f in enumerate(queries):
+            check_nat(f, f"f_list[{k}]")
+        unit = finest_unit(dtype, *(f.dtype for f in queries))
+        fp_list = [to_unit(fp, unit) for fp in fp_list]
+        queries = [to_unit(f, unit) for f in queries]
+    else:
+        queries = [np.asarray(f).astype(dtype) for f in f_list]
+    if np.issubdtype(dtype, np.datetime64):
+        fp_list = [fp.view("i8") for fp in fp_list]
+        if f_list is not None:
+            queries = [f.view("i8") for f in queries]
+    if np.issubdtype(dtype, np.integer) or np.issubdtype(dtype, np.datetime64):
+        results, errors = func_int(queries, xp_list, fp_list, **kwargs)
+    elif np.issubdtype(dtype, np.floating):
+        results, errors = func_float(queries, xp_list, fp_list, **kwargs)
+    else:
+        raise ValueError("fp dtype must be either integer, floating or datetime")
+    results = [
+        None if out is None else out.astype(dtype if x_list is not None else xp.dtype)
+        for out, xp in zip(results, xp_list)
+    ]
+    return results, errors
+
+
+UNITS = ["Y", "M", "W", "D", "h", "m", "s", "ms", "us", "ns", "ps", "fs", "as"]
+
+
+def masked(func, query, dtype, **kwargs):
+    """
+    Apply `func` to the unmasked elements of the masked array `query` only.
+
+    The results are returned as a masked array with the mask of `query`, combined with the
+    mask of the results if any. A fully masked `query` returns a fully masked array of
+    `dtype` without calling `func`, unless statistics are requested.
+    """
+    mask = np.ma.getmaskarray(query)
+    if query.ndim == 0:
+        return np.ma.masked if mask else func(np.ma.getdata(query), **kwargs)
+    if mask.all() and not kwargs.get("return_stats"):
+        return np.ma.masked_all(query.shape, dtype=dtype)
+    out = func(query.compressed(), **kwargs)
+    stats = None
+    if kwargs.get("return_stats"):
+        out, stats = out
+    result = np.ma.MaskedArray(np.zeros(query.shape, dtype=out.dtype), mask=mask.copy())
+    result.data[~mask] = np.ma.getdata(out)
+    result.mask[~mask] = np.ma.getmaskarray(out)
+    if stats is None:
+        return result
+    else:
+        return result, stats
+
+
+def wrap(x, xp, name):
+    """
+    Interpret negative indices as offsets from the end of `xp`, -1 being its last index.
+
+    Raises IndexError if a wrapped index is still negative.
+    """
+    x = native(x)
+    if not np.issubdtype(x.dtype, np.signedinteger):
+        return x
+    negative = x < 0
+    if not np.any(negative):
+        return x
+    last = np.uint64(np.asarray(xp)[-1])
+    back = (-(np.minimum(x, -1) + 1)).astype("u8")
+    beyond = negative & (back > last)
+    if np.any(beyond):
+        if x.ndim == 0:
+            raise IndexError(f"{name} = {x} out of bounds")
+        position = np.flatnonzero(beyond)[0]
+        raise IndexError(f"{name}[{position}] = {x[position]} out of bounds")
+    return np.where(negative, last - np.minimum(back, last), x.astype("u8"))
+
+
+def finest_unit(*dtypes):
+    """The finest unit of the datetime64 or timedelta64 dtypes."""
+    units = [np.datetime_data(dtype)[0] for dtype in dtypes]
+    return max(units, key=UNITS.index)
+
+
+def to_unit(arr, unit):
+    """
+    Convert datetime64 or timedelta64 values to the unit, exactly.
+
+    Raises OverflowError if the converted values do not fit in 64-bit integers and ValueError
+    if the conversion to a coarser unit loses precision. NaT values are kept.
+    """
+    dtype = np.dtype(f"{arr.dtype.kind}8[{unit}]")
+    if arr.dtype == dtype:
+        return arr
+    valid = arr.view("i8")[~np.isnat(arr)]
+    source, count = np.datetime_data(arr.dtype)
+    if source not in ("Y", "M") and unit not in ("Y", "M"):
+        factor = int(np.timedelta64(count, source) // np.timedelta64(1, unit))
+        if factor > 1 and np.any(np.abs(valid) > np.iinfo(np.int64).max // factor):
+            raise OverflowError(f"values out of the range of {dtype} dtype")
+    out = arr.astype(dtype)
+    if not np.array_equal(out.astype(arr.dtype).view("i8")[~np.isnat(arr)], valid):
+        raise ValueError(f"values cannot be converted to {dtype} dtype without loss")
+    return out
+
+
+def to_duration(values, unit, name):
+    """
+    Convert tolerances on datetime64 values to timedelta64s of the unit, exactly.
+
+    Timedelta64s are converted with `to_unit`, strings are durations such as "250us"
+    parsed by `rust.duration_to_ticks`, floats are seconds and integers are counts of the
+    unit. Raises ValueError for other dtypes and inexact or ambiguous durations.
+    """
+    values = native(values)
+    if values.dtype.kind == "m":
+        return to_unit(values, unit)
+    elif values.dtype.kind in "iu":
+        return values.astype(f"m8[{unit}]")
+    elif values.dtype.kind == "f":
+        if not np.all(np.isfinite(values)):
+            raise ValueError(f"{name} must be finite")
+        texts = [
+            np.format_float_positional(value, trim="-") + "s"
+            for value in values.reshape(-1)
+        ]
+    elif values.dtype.kind == "U":
+        texts = [str(value) for value in values.reshape(-1)]
+    else:
+        raise ValueError(f"{name} must be timedelta64s, durations, seconds or integers")
+    ticks = [rust.duration_to_ticks(text, unit) for text in texts]
+    return np.array(ticks, dtype="i8").reshape(values.shape).view(f"m8[{unit}]")
+
+
+def native(values):
+    """Converts the values to an array in native byte order, copying only if needed."""
+    arr = np.asarray(values)
+    if arr.dtype.isnative:
+        return arr
+    return arr.astype(arr.dtype.newbyteorder("="))
+
+
+def check_nat(arr, name):
+    """Raise if datetime64 values hold NaT, stored as the minimum of int64."""
+    if np.issubdtype(arr.dtype, np.datetime64):
+        positions = np.flatnonzero(np.isnat(arr))
+        if len(positions):
+            raise ValueError(f"{name}[{positions[0]}] is NaT")
+
+
+def is_text(values):
+    """Whether the values are strings, parsed as ISO 8601 datetimes."""
+    return isinstance(values, str) or np.asarray(values).dtype.kind == "U"
+
+
+def is_extended(dtype):
+    """Whether the dtype is a long double in the x87 80-bit extended format."""
+    if not dtype == np.longdouble:
+        return False
+    nmant = np.finfo(np.longdouble).nmant
+    if nmant == 52:
+        return False  # long double is a double, handled as float64
+    if nmant == 63:
+        return True
+    raise ValueError("long double format of this platform is not supported")
+
+
+def to_bytes(arr):
+    return np.ascontiguousarray(arr).view(np.uint8).reshape(len(arr), -1)
+
+
+def from_bytes(arr):
+    out = np.zeros(len(arr), dtype=np.longdouble)
+    to_bytes(out)[:, :10] = arr
+    return out
+
+
+def longdouble(func, xp, fp, x, f, **kwargs):
+    method = kwargs.pop("method", None)
+    defaults = {
+        "closed": "both",
+        "errors": "raise",
+        "return_stats": False,
+        "max_gap": None,
+        "max_gap_factor": None,
+        "gaps": "raise",
+        "limit": None,
+        "dtype": None,
+        "bounds": "raise",
+    }
+    for key, value in kwargs.items():
+        if not value == defaults[key]:
+            raise ValueError(f"{key} is not supported for long double values")
+    if x is not None:
+        return from_bytes(func(x.astype("u8"), xp.astype("u8"), to_bytes(fp)))
+    else:
+        return func(to_bytes(f), xp.astype("u8"), to_bytes(fp), method).astype(xp.dtype)
+
+
+def packed(xp, fp, scale_factor, add_offset, x=None, f=None, **kwargs):
+    fp = native(fp)
+    if not np.issubdtype(fp.dtype, np.integer):
+        raise ValueError("fp must have integer dtype when packed")
+    scale_factor = 1.0 if scale_factor is None else float(scale_factor)
+    add_offset = 0.0 if add_offset is None else float(add_offset)
+    if x is not None:
+        xp, _, x, _, isscalar = check(xp, fp, x=x)
+        out = rust.forward_packed(
+            x.astype("u8"),
+            xp.astype("u8"),
+            fp.astype("i8"),
+            scale_factor,
+            add_offset,
+            **kwargs,
+        )
+    else:
+        method = kwargs.pop("method", None)
+        defaults = {
+            "return_stats": False,
+            "max_gap": None,
+            "max_gap_factor": None,
+            "gaps": "raise",
+            "limit": None,
+        }
+        for key, value in defaults.items():
+            if not kwargs.pop(key) == value:
+                raise ValueError(f"{key} is not supported for packed values")
+        # the unpacked monotonicity is checked by the extension
+        xp, _, _, f, isscalar = check(
+            xp, np.arange(len(fp), dtype="f8"), f=np.asarray(f, dtype="f8")
+        )
+        out = rust.inverse_packed(
+            f,
+            xp.astype("u8"),
+            fp.astype("i8"),
+            scale_factor,
+            add_offset,
+            method,
+            **kwargs,
+        )
+    mask = None
+    if isinstance(out, tuple):
+        out, mask = out
+    out = out.astype("f8" if x is not None else xp.dtype)
+    if mask is not None and mask.any():
+        out = np.ma.MaskedArray(out, mask=mask)
+    return out[0] if isscalar else out
+
+
+def sort_points(xp, fp, duplicates):
+    xp = native(xp)
+    fp = native(fp)
+    # invalid data points are left to check
+    if not (xp.ndim == 1 and fp.ndim == 1 and len(xp) == len(fp)):
+        return xp, fp
+    if not (np.issubdtype(xp.dtype, np.integer) and np.all(xp >= 0)):
+        return xp, fp
+    # NaT would be reported at its sorted position
+    check_nat(fp, "fp")
+    if np.issubdtype(fp.dtype, np.integer) or np.issubdtype(fp.dtype, np.datetime64):
+        out = rust.sort_int(xp.astype("u8"), fp.astype("i8"), duplicates)
+    elif np.issubdtype(fp.dtype, np.floating) and not is_extended(fp.dtype):
+        out = rust.sort_float(xp.astype("u8"), fp.astype("f8"), duplicates)
+    else:
+        raise ValueError("sort is not supported for this fp dtype")
+    return out[0].astype(xp.dtype), out[1].astype(fp.dtype)
+
+
+def periodic(xp, fp, period, x, **kwargs):
+    defaults = {
+        "closed": "both",
+        "errors": "raise",
+        "wrap_negative": False,
+        "scale_factor": None,
+        "add_offset": None,
+        "dtype": None,
+        "bounds": "raise",
+    }
+    for key, value in kwargs.items():
+        if not value == defaults[key]:
+            raise ValueError(f"{key} is not supported with period")
+    if not (isinstance(period, (int, np.integer)) and period > 0):
+        raise ValueError("period must be a positive integer")
+    x = native(x)
+    if not np.issubdtype(x.dtype, np.integer):
+        raise ValueError("x must have integer dtype")
+    # x is checked apart since negative values are valid
+    xp, fp, _, _, _ = check(xp, fp, x=np.zeros(0, dtype=native(xp).dtype))
+    isscalar = x.ndim == 0
+    if x.ndim > 1:
+        raise ValueError("x must be 1D or scalar")
+    x = x.reshape(-1).astype("i8")
+    if is_extended(fp.dtype):
+        raise ValueError("period is not supported for long double values")
+    if np.issubdtype(fp.dtype, np.integer) or np.issubdtype(fp.dtype, np.datetime64):
+        out = rust.forward_periodic_int(x, xp.astype("u8"), fp.astype("i8"), period)
+    elif np.issubdtype(fp.dtype, np.floating):
+        out = rust.forward_periodic_float(x, xp.astype("u8"), fp.astype("f8"), period)
+    else:
+        raise ValueError("fp dtype must be either integer, floating or datetime")
+    out = out.astype(fp.dtype)
+    return out[0] if isscalar else out
+
+
+def is_uint64(f, fp):
+    f = native(f)
+    fp = native(fp)
+    if not (np.issubdtype(f.dtype, np.integer) and np.issubdtype(fp.dtype, np.integer)):
+        return False
+    # uint64 values within the int64 range take the int64 path, which has all the options
+    limit = np.iinfo(np.int64).max
+    return any(
+        arr.dtype == np.uint64 and arr.size > 0 and arr.max() > limit for arr in (f, fp)
+    )
+
+
+def uint64(xp, fp, f, dtype=None, **kwargs):
+    defaults = {
+        "return_stats": False,
+        "max_gap": None,
+        "max_gap_factor": None,
+        "gaps": "raise",
+        "limit": None,
+    }
+    for key, default in defaults.items():
+        if not kwargs.pop(key) == default:
+            raise ValueError(f"{key} is not supported with uint64 values")
+    f = native(f)
+    # f is checked apart since it is compared exactly with fp whatever its signedness
+    xp, fp, _, _, _ = check(xp, fp, f=np.zeros(0, dtype=native(fp).dtype))
+    isscalar = f.ndim == 0
+    if f.ndim > 1:
+        raise ValueError("f must be 1D or scalar")
+    f = f.reshape(-1)
+    f = f.astype("u8" if f.dtype == np.uint64 else "i8")
+    fp = fp.astype("u8" if fp.dtype == np.uint64 else "i8")
+    if dtype is not None:
+        kwargs["dtype"] = np.dtype(dtype).name
+    out = rust.inverse_cross(f, xp.astype("u8"), fp, **kwargs)
+    mask = None
+    if kwargs.get("errors") == "mask":
+        out, mask = out
+    if dtype is None:
+        out = out.astype(xp.dtype)
+    if mask is not None and mask.any():
+        out = np.ma.MaskedArray(out, mask=mask)
+    return out[0] if isscalar else out
+
+
 def check(xp, fp, x=None, f=None):
-    xp = np.asarray(xp)
-    fp = np.asarray(fp)
+    xp = native(xp)
+    fp = native(fp)
     if not (xp.ndim == 1 and fp.ndim == 1):
         raise ValueError("xp and fp must be 1D")
     if not (len(xp) == len(fp)):
@@ -107,12 +1658,13 @@ def check(xp, fp, x=None, f=None):
         raise ValueError("xp must have integer dtype")
     if not np.all(xp >= 0):
         raise ValueError("xp values must be positive")
+    check_nat(fp, "fp")
     if not np.all(np.isfinite(fp)):
         raise ValueError("fp values must be finite")
     if (x is None) == (f is None):
         raise ValueError("either x or f must be provided")
     if x is not None:
-        x = np.asarray(x).astype(xp.dtype)
+        x = native(x).astype(xp.dtype)
         if x.ndim == 0:
             x = x.reshape(1)
             isscalar = True
@@ -127,7 +1679,18 @@ def check(xp, fp, x=None, f=None):
         if not np.all(xp[1:] > xp[:-1]):
             raise ValueError("xp must be strictly increasing")
     if f is not None:
-        f = np.asarray(f).astype(fp.dtype)
+        text = is_text(f)
+        if text:
+            if not np.issubdtype(fp.dtype, np.datetime64):
+                raise ValueError("f can only be strings if fp has datetime64 dtype")
+            fp = to_unit(fp, "ns")
+            f = np.asarray(f, dtype=str)
+        elif np.issubdtype(fp.dtype, np.datetime64) and np.asarray(f).dtype.kind == "M":
+            f = native(f)
+            unit = finest_unit(fp.dtype, f.dtype)
+            fp, f = to_unit(fp, unit), to_unit(f, unit)
+        else:
+            f = native(f).astype(fp.dtype)
         if f.ndim == 0:
             f = f.reshape(1)
             isscalar = True
@@ -135,14 +1698,15 @@ def check(xp, fp, x=None, f=None):
             isscalar = False
         else:
             raise ValueError("f must be 1D or scalar")
-        if not f.dtype == fp.dtype:
+        if not text and not f.dtype == fp.dtype:
             raise ValueError("f and fp must have the same dtype")
-        if not np.all(np.isfinite(f)):
+        check_nat(f, "f")
+        if not text and not np.all(np.isfinite(f)):
             raise ValueError("f values must be finite")
-        if not np.all(fp[1:] > fp[:-1]):
-            raise ValueError("fp must be strictly increasing")
+        if not (np.all(fp[1:] > fp[:-1]) or np.all(fp[1:] < fp[:-1])):
+            raise ValueError("fp must be strictly monotonic")
     return xp, fp, x, f, isscalar
 
 
-_forward = wraps(rust.forward_int, rust.forward_float)
-_inverse = wraps(rust.inverse_int, rust.inverse_float)
+_forward = wraps(rust.forward_int, rust.forward_float, rust.forward_longdouble)
+_inverse = wraps(rust.inverse_int, rust.inverse_float, rust.inverse_longdouble)